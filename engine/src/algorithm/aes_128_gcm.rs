@@ -0,0 +1,37 @@
+//! SealVault AES-128-GCM 加解密算法
+//!
+//! 仅用于与只支持 128 位 AES 密钥的对端互通：密钥取 32 字节 KDF 输出的前 16 字节，
+//! 安全余量低于 AES-256-GCM（暴力搜索密钥约 2^128，且无法抵御未来量子计算的 Grover 搜索），
+//! 没有互通需求时应使用默认算法。nonce 构造与 AES-256-GCM 相同。
+
+use aes_gcm::aes::Aes128;
+
+use super::gcm;
+
+pub const NONCE_SIZE: usize = super::NONCE_96_SIZE;
+
+/// AES-128 密钥长度（字节）
+pub const KEY_SIZE: usize = 16;
+
+/// 加密单个 chunk；`key` 为 `KEY_SIZE` 字节的 AEAD 密钥（调用方按 `AeadAlgorithm::key_len` 截取 KDF 输出），
+/// 长度不符时返回 `InvalidInput`
+pub fn encrypt_chunk(
+    key: &[u8],
+    base_nonce: &[u8; 24],
+    chunk_index: u64,
+    plaintext: &[u8],
+    aad: &[u8],
+) -> std::io::Result<Vec<u8>> {
+    gcm::encrypt_chunk::<Aes128>(key, base_nonce, chunk_index, plaintext, aad)
+}
+
+/// 解密单个 chunk；`key` 的用法同 `encrypt_chunk`
+pub fn decrypt_chunk(
+    key: &[u8],
+    base_nonce: &[u8; 24],
+    chunk_index: u64,
+    ciphertext: &[u8],
+    aad: &[u8],
+) -> std::io::Result<Vec<u8>> {
+    gcm::decrypt_chunk::<Aes128>(key, base_nonce, chunk_index, ciphertext, aad)
+}
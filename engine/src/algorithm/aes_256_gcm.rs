@@ -1,11 +1,10 @@
 //! SealVault AES-256-GCM 加解密算法
 
-use aes_gcm::{
-    Aes256Gcm, Nonce,
-    aead::{Aead, KeyInit, Payload},
-};
+use aes_gcm::aes::Aes256;
 
-pub const NONCE_SIZE: usize = 12;
+use super::gcm;
+
+pub const NONCE_SIZE: usize = super::NONCE_96_SIZE;
 
 pub fn encrypt_chunk(
     key: &[u8; 32],
@@ -14,20 +13,7 @@ pub fn encrypt_chunk(
     plaintext: &[u8],
     aad: &[u8],
 ) -> std::io::Result<Vec<u8>> {
-    let cipher = Aes256Gcm::new_from_slice(key)
-        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid key length"))?;
-    let nonce_bytes = derive_nonce(base_nonce, chunk_index);
-    let nonce = Nonce::from_slice(&nonce_bytes);
-
-    cipher
-        .encrypt(
-            nonce,
-            Payload {
-                msg: plaintext,
-                aad,
-            },
-        )
-        .map_err(|_| std::io::Error::other("AEAD encrypt failed"))
+    gcm::encrypt_chunk::<Aes256>(key, base_nonce, chunk_index, plaintext, aad)
 }
 
 pub fn decrypt_chunk(
@@ -37,35 +23,5 @@ pub fn decrypt_chunk(
     ciphertext: &[u8],
     aad: &[u8],
 ) -> std::io::Result<Vec<u8>> {
-    let cipher = Aes256Gcm::new_from_slice(key)
-        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid key length"))?;
-    let nonce_bytes = derive_nonce(base_nonce, chunk_index);
-    let nonce = Nonce::from_slice(&nonce_bytes);
-
-    cipher
-        .decrypt(
-            nonce,
-            Payload {
-                msg: ciphertext,
-                aad,
-            },
-        )
-        .map_err(|_| {
-            std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "AEAD authentication failed",
-            )
-        })
-}
-
-fn derive_nonce(base: &[u8; 24], index: u64) -> [u8; NONCE_SIZE] {
-    let mut nonce = [0u8; NONCE_SIZE];
-    nonce.copy_from_slice(&base[..NONCE_SIZE]);
-
-    let idx_bytes = index.to_be_bytes();
-    for i in 0..8 {
-        nonce[4 + i] ^= idx_bytes[i];
-    }
-
-    nonce
+    gcm::decrypt_chunk::<Aes256>(key, base_nonce, chunk_index, ciphertext, aad)
 }
@@ -0,0 +1,74 @@
+//! SealVault ChaCha20-Poly1305（IETF，96-bit nonce）加解密算法
+//!
+//! 用于与只提供标准 ChaCha20-Poly1305 的客户端互通。
+//!
+//! Nonce 推导与 AES-GCM 共用 `derive_nonce_96`：取 base_nonce 前 12 字节，
+//! 低 8 字节与 chunk_index（大端）异或。
+//!
+//! 碰撞界：
+//! - 同一文件内 chunk_index 各不相同，nonce 必然唯一；chunk 数上限见 `AeadAlgorithm::max_chunks`，
+//!   超出即拒绝，低 8 字节不会回绕
+//! - 每个文件使用独立随机 salt 派生 key，只有 salt 碰撞（128-bit，约 2^64 个文件）时
+//!   不同文件才会共用 key
+//! - 即使 key 相同，nonce 的高 4 字节是固定的随机值，低 8 字节是随机值与 chunk_index 的异或：
+//!   两个各含 n 个 chunk 的 stream 只有在高 4 字节相同（2^-32）且低 8 字节的随机值相差不足
+//!   约 n（约 n / 2^64）时才会出现 nonce 重叠，概率约为 n / 2^96；
+//!   共用 key 的 stream 越多，该概率按 stream 对数累加，远不如 XChaCha20 的 192-bit nonce 宽裕
+
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, Nonce,
+    aead::{Aead, KeyInit, Payload},
+};
+
+use super::derive_nonce_96;
+
+pub const NONCE_SIZE: usize = super::NONCE_96_SIZE;
+
+pub fn encrypt_chunk(
+    key: &[u8; 32],
+    base_nonce: &[u8; 24],
+    chunk_index: u64,
+    plaintext: &[u8],
+    aad: &[u8],
+) -> std::io::Result<Vec<u8>> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce_bytes = derive_nonce_96(base_nonce, chunk_index);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    cipher
+        .encrypt(
+            nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| std::io::Error::other("AEAD encrypt failed"))
+}
+
+pub fn decrypt_chunk(
+    key: &[u8; 32],
+    base_nonce: &[u8; 24],
+    chunk_index: u64,
+    ciphertext: &[u8],
+    aad: &[u8],
+) -> std::io::Result<Vec<u8>> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce_bytes = derive_nonce_96(base_nonce, chunk_index);
+    let nonce = Nonce::from_slice(&nonce_bytes);
+
+    cipher
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "AEAD authentication failed",
+            )
+        })
+}
@@ -0,0 +1,70 @@
+//! AES-GCM（96-bit nonce）通用的 chunk 加解密
+//!
+//! AES-256-GCM 与 AES-128-GCM 只在分组密码（即密钥长度）上不同，
+//! 构造与 nonce 推导共用此处的实现。
+
+use aes_gcm::{
+    AesGcm,
+    aead::{Aead, KeyInit, Nonce, Payload, consts::U12},
+};
+
+use super::derive_nonce_96;
+
+/// 加密单个 chunk；`key` 长度必须与分组密码一致，否则返回 `InvalidInput`
+pub(crate) fn encrypt_chunk<Aes>(
+    key: &[u8],
+    base_nonce: &[u8; 24],
+    chunk_index: u64,
+    plaintext: &[u8],
+    aad: &[u8],
+) -> std::io::Result<Vec<u8>>
+where
+    AesGcm<Aes, U12>: Aead + KeyInit,
+{
+    let cipher = AesGcm::<Aes, U12>::new_from_slice(key)
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid key length"))?;
+    let nonce_bytes = derive_nonce_96(base_nonce, chunk_index);
+    let nonce = Nonce::<AesGcm<Aes, U12>>::from_slice(&nonce_bytes);
+
+    cipher
+        .encrypt(
+            nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| std::io::Error::other("AEAD encrypt failed"))
+}
+
+/// 解密单个 chunk；`key` 的用法同 `encrypt_chunk`
+pub(crate) fn decrypt_chunk<Aes>(
+    key: &[u8],
+    base_nonce: &[u8; 24],
+    chunk_index: u64,
+    ciphertext: &[u8],
+    aad: &[u8],
+) -> std::io::Result<Vec<u8>>
+where
+    AesGcm<Aes, U12>: Aead + KeyInit,
+{
+    let cipher = AesGcm::<Aes, U12>::new_from_slice(key)
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, "invalid key length"))?;
+    let nonce_bytes = derive_nonce_96(base_nonce, chunk_index);
+    let nonce = Nonce::<AesGcm<Aes, U12>>::from_slice(&nonce_bytes);
+
+    cipher
+        .decrypt(
+            nonce,
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "AEAD authentication failed",
+            )
+        })
+}
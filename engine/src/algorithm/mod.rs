@@ -1,37 +1,151 @@
 //! SealVault AEAD 算法模块。
 //!
 //! 统一管理可选算法与算法标识，具体实现见子模块。
+//!
+//! `AeadAlgorithm` 标记为 `#[non_exhaustive]`，新增算法不会破坏下游代码：
+//! crate 外的 `match` 必须带通配分支。crate 内部的 `match` 仍保持穷尽（不写通配分支），
+//! 新增变体时编译器会逐一指出需要补充的位置（ID、密钥长度、chunk 上限、名称、加解密分派），
+//! 并需同时加入 `AeadAlgorithm::ALL`。文件中的未知算法 ID 一律报告 `UnsupportedAlgorithm`。
 
+pub mod aes_128_gcm;
 pub mod aes_256_gcm;
+pub mod chacha20_poly1305;
+mod gcm;
 pub mod xchacha20_poly1305;
 
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
 /// 支持的 AEAD 算法。
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum AeadAlgorithm {
     XChaCha20Poly1305,
     Aes256Gcm,
+    ChaCha20Poly1305,
+    /// 仅用于互通，安全余量较低，见 `aes_128_gcm` 模块文档
+    Aes128Gcm,
 }
 
 impl AeadAlgorithm {
     pub const XCHACHA20_POLY1305_ID: u8 = 1;
     pub const AES_256_GCM_ID: u8 = 2;
+    pub const CHACHA20_POLY1305_ID: u8 = 3;
+    pub const AES_128_GCM_ID: u8 = 4;
+
+    /// 全部支持的算法，按算法 ID 排序
+    pub const ALL: [Self; 4] = [
+        Self::XChaCha20Poly1305,
+        Self::Aes256Gcm,
+        Self::ChaCha20Poly1305,
+        Self::Aes128Gcm,
+    ];
 
+    /// Header 中的算法 ID（写入文件的稳定编号，不随枚举顺序变化）
     pub fn to_u8(self) -> u8 {
         match self {
             Self::XChaCha20Poly1305 => Self::XCHACHA20_POLY1305_ID,
             Self::Aes256Gcm => Self::AES_256_GCM_ID,
+            Self::ChaCha20Poly1305 => Self::CHACHA20_POLY1305_ID,
+            Self::Aes128Gcm => Self::AES_128_GCM_ID,
         }
     }
 
+    /// 由 Header 中的算法 ID 得到算法，未知 ID 返回 `None`
     pub fn from_u8(value: u8) -> Option<Self> {
         match value {
             Self::XCHACHA20_POLY1305_ID => Some(Self::XChaCha20Poly1305),
             Self::AES_256_GCM_ID => Some(Self::Aes256Gcm),
+            Self::CHACHA20_POLY1305_ID => Some(Self::ChaCha20Poly1305),
+            Self::AES_128_GCM_ID => Some(Self::Aes128Gcm),
             _ => None,
         }
     }
+
+    /// AEAD 密钥长度（字节）
+    ///
+    /// KDF 始终输出 32 字节；密钥较短的算法取其前缀。
+    pub fn key_len(self) -> usize {
+        match self {
+            Self::Aes128Gcm => aes_128_gcm::KEY_SIZE,
+            Self::XChaCha20Poly1305 | Self::Aes256Gcm | Self::ChaCha20Poly1305 => 32,
+        }
+    }
+
+    /// 单个 stream（同一 key 与 base_nonce）允许的最大 chunk 数，含 final chunk
+    ///
+    /// chunk_index 以异或方式写入 nonce 的低 8 字节，超出上限的 chunk 一律拒绝，不会静默回绕：
+    /// - AES-256-GCM / AES-128-GCM / ChaCha20-Poly1305（12 字节 nonce）：2^32，对应 NIST SP 800-38D
+    ///   对同一 key 随机 nonce 调用次数的建议上限；默认 64 KiB chunk 时单文件约 256 TiB
+    /// - XChaCha20-Poly1305（24 字节 nonce）：2^64 - 1，高 16 字节始终保持随机
+    pub fn max_chunks(self) -> u64 {
+        match self {
+            Self::Aes256Gcm | Self::Aes128Gcm | Self::ChaCha20Poly1305 => 1 << 32,
+            Self::XChaCha20Poly1305 => u64::MAX,
+        }
+    }
+
+    /// 规范名称，即 `Display` 的输出
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::XChaCha20Poly1305 => "XChaCha20-Poly1305",
+            Self::Aes256Gcm => "AES-256-GCM",
+            Self::ChaCha20Poly1305 => "ChaCha20-Poly1305",
+            Self::Aes128Gcm => "AES-128-GCM",
+        }
+    }
+}
+
+impl fmt::Display for AeadAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// 无法识别的算法名称
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("unsupported algorithm: {0}")]
+pub struct ParseAlgorithmError(pub String);
+
+impl FromStr for AeadAlgorithm {
+    type Err = ParseAlgorithmError;
+
+    /// 解析算法名称（不区分大小写）
+    ///
+    /// 接受 CLI 使用的别名（`xchacha20`、`aes256gcm`、`chacha20` 等）以及 `Display` 输出的规范名称。
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "xchacha20" | "xchacha20poly1305" | "xchacha20-poly1305" => Ok(Self::XChaCha20Poly1305),
+            "aes256gcm" | "aes-256-gcm" => Ok(Self::Aes256Gcm),
+            "chacha20" | "chacha20poly1305" | "chacha20-poly1305" => Ok(Self::ChaCha20Poly1305),
+            "aes128gcm" | "aes-128-gcm" => Ok(Self::Aes128Gcm),
+            _ => Err(ParseAlgorithmError(s.to_owned())),
+        }
+    }
+}
+
+/// 96-bit nonce 长度（字节），AES-GCM 与 ChaCha20-Poly1305 共用
+pub const NONCE_96_SIZE: usize = 12;
+
+/// 由 base_nonce 与 chunk_index 推导 96-bit nonce
+///
+/// 取 base_nonce 前 12 字节，低 8 字节与 chunk_index（大端）异或。
+/// chunk_index 各不相同即保证同一 stream 内 nonce 唯一，上限见 `AeadAlgorithm::max_chunks`。
+pub(crate) fn derive_nonce_96(base: &[u8; 24], index: u64) -> [u8; NONCE_96_SIZE] {
+    let mut nonce = [0u8; NONCE_96_SIZE];
+    nonce.copy_from_slice(&base[..NONCE_96_SIZE]);
+
+    let idx_bytes = index.to_be_bytes();
+    for i in 0..8 {
+        nonce[4 + i] ^= idx_bytes[i];
+    }
+
+    nonce
 }
 
-// #[allow(clippy::unus)]
 /// 默认算法：XChaCha20-Poly1305。
-pub const _DEFAULT_AEAD_ALGORITHM: AeadAlgorithm = AeadAlgorithm::XChaCha20Poly1305;
+///
+/// 最早的 v1 文件没有 algorithm 字节，解密时按此算法处理。
+pub const DEFAULT_AEAD_ALGORITHM: AeadAlgorithm = AeadAlgorithm::XChaCha20Poly1305;
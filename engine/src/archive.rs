@@ -0,0 +1,237 @@
+//! SealVault 目录归档加密/解密
+//!
+//! 设计要点：
+//! - 整个目录树打包为一条归档明文流（格式见 `format::archive`），
+//!   再用现有 StreamEncryptor 加密为单个 .svlt 文件。
+//! - 全程流式处理，不把文件内容读入内存。
+//! - 空目录与非 UTF-8 文件名原样保留；符号链接与特殊文件不跟随、不打包。
+//! - 解出时复用目录解密的路径校验，防止路径穿越写出到目标目录之外。
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use walkdir::WalkDir;
+use zeroize::Zeroizing;
+
+use crate::algorithm::AeadAlgorithm;
+use crate::compression::Codec;
+use crate::decrypt::open_for_decrypt;
+use crate::encrypt::open_for_encrypt;
+use crate::error::SealVaultError;
+use crate::folder::{
+    ensure_output_outside_input, safe_join, validate_relative_path, walkdir_to_io,
+};
+use crate::format::archive::{self, ArchiveEntry};
+use crate::format::stream::{StreamDecryptor, StreamEncryptor};
+use crate::fs::atomic::write_atomic;
+
+/// 将目录打包并加密为单个归档文件。
+///
+/// - 输出原子写入，失败时不会留下不完整的归档。
+/// - 归档中只有相对路径、目录与文件内容，不记录权限与修改时间。
+/// - 输出文件不能位于输入目录内部。
+pub fn encrypt_folder_to_archive(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    algorithm: AeadAlgorithm,
+) -> io::Result<()> {
+    if !input_path.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "input_path 不是目录",
+        ));
+    }
+
+    ensure_output_outside_input(input_path, output_path)?;
+
+    write_atomic(output_path, |file| {
+        let mut writer = BufWriter::new(file);
+        let (_header, encryptor) = open_for_encrypt(&mut writer, password, algorithm)?;
+
+        let mut plaintext = ChunkWriter::new(encryptor, &mut writer);
+        archive::write_start(&mut plaintext)?;
+
+        for entry in WalkDir::new(input_path).follow_links(false) {
+            let entry = entry.map_err(walkdir_to_io)?;
+            let source_path = entry.path();
+
+            let rel = source_path.strip_prefix(input_path).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("无法计算相对路径: {e}"))
+            })?;
+
+            let safe_rel = validate_relative_path(rel)?;
+            if safe_rel.as_os_str().is_empty() {
+                // 输入根目录本身
+                continue;
+            }
+
+            if entry.file_type().is_dir() {
+                ArchiveEntry::Dir { path: safe_rel }.write(&mut plaintext)?;
+            } else if entry.file_type().is_file() {
+                let mut source = File::open(source_path)?;
+                let len = source.metadata()?.len();
+                ArchiveEntry::File {
+                    path: safe_rel,
+                    len,
+                }
+                .write(&mut plaintext)?;
+
+                // 条目头已写入长度，文件在打包期间变短只能整体失败
+                let copied = io::copy(&mut (&mut source).take(len), &mut plaintext)?;
+                if copied != len {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("文件在打包期间被截断: {source_path:?}"),
+                    ));
+                }
+            }
+        }
+
+        archive::write_end(&mut plaintext)?;
+        plaintext.finish()?;
+        writer.flush()
+    })
+}
+
+/// 解密归档文件，并在目标目录中还原目录树。
+///
+/// - 每个文件单独原子写入；中途失败时已还原的文件会保留。
+/// - 归档必须以结束标记收尾，且结束标记之后紧接 final chunk，否则视为损坏。
+pub fn decrypt_archive_to_folder(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+) -> io::Result<()> {
+    let mut reader = BufReader::new(File::open(input_path)?);
+    let (header, decryptor) = open_for_decrypt(&mut reader, password)?;
+
+    if header.codec != Codec::None {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "compressed archives are not supported",
+        ));
+    }
+
+    let mut plaintext = ChunkReader::new(decryptor, reader);
+    archive::read_start(&mut plaintext)?;
+
+    std::fs::create_dir_all(output_path)?;
+
+    while let Some(entry) = ArchiveEntry::read(&mut plaintext)? {
+        match entry {
+            ArchiveEntry::Dir { path } => {
+                let target_dir = safe_join(output_path, &validate_relative_path(&path)?)?;
+                std::fs::create_dir_all(target_dir)?;
+            }
+            ArchiveEntry::File { path, len } => {
+                let target_file_path = safe_join(output_path, &validate_relative_path(&path)?)?;
+                if let Some(parent) = target_file_path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+
+                write_atomic(&target_file_path, |output| {
+                    let copied = io::copy(&mut (&mut plaintext).take(len), output)?;
+                    if copied != len {
+                        return Err(corrupted());
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+    }
+
+    if plaintext.read(&mut [0u8; 1])? != 0 {
+        return Err(corrupted());
+    }
+
+    Ok(())
+}
+
+fn corrupted() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, SealVaultError::CorruptedData)
+}
+
+/// 把写入的明文攒成完整 chunk 后交给 StreamEncryptor
+struct ChunkWriter<W: Write> {
+    encryptor: StreamEncryptor,
+    writer: W,
+    buffer: Zeroizing<Vec<u8>>,
+}
+
+impl<W: Write> ChunkWriter<W> {
+    fn new(encryptor: StreamEncryptor, writer: W) -> Self {
+        let buffer = Zeroizing::new(Vec::with_capacity(encryptor.chunk_size()));
+        Self {
+            encryptor,
+            writer,
+            buffer,
+        }
+    }
+
+    /// 写出剩余的不满一个 chunk 的数据，并写入 final chunk
+    fn finish(mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.encryptor.write_chunk(&self.buffer, &mut self.writer)?;
+        }
+        self.encryptor.finish(&mut self.writer)
+    }
+}
+
+impl<W: Write> Write for ChunkWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let room = self.encryptor.chunk_size() - self.buffer.len();
+        let n = room.min(buf.len());
+        self.buffer.extend_from_slice(&buf[..n]);
+
+        if self.buffer.len() == self.encryptor.chunk_size() {
+            self.encryptor.write_chunk(&self.buffer, &mut self.writer)?;
+            self.buffer.clear();
+        }
+        Ok(n)
+    }
+
+    /// 不满一个 chunk 的数据留到下次写入或 `finish`，这里只刷新底层 writer
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// 以 Read 形式逐 chunk 读出已通过认证的明文
+struct ChunkReader<R: Read> {
+    decryptor: StreamDecryptor,
+    reader: R,
+    chunk: Zeroizing<Vec<u8>>,
+    pos: usize,
+}
+
+impl<R: Read> ChunkReader<R> {
+    fn new(decryptor: StreamDecryptor, reader: R) -> Self {
+        Self {
+            decryptor,
+            reader,
+            chunk: Zeroizing::new(Vec::new()),
+            pos: 0,
+        }
+    }
+}
+
+impl<R: Read> Read for ChunkReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.chunk.len() {
+            match self.decryptor.read_chunk(&mut self.reader)? {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+
+        let n = (self.chunk.len() - self.pos).min(buf.len());
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
@@ -0,0 +1,221 @@
+//! SealVault 异步（tokio）加密/解密（需开启 `tokio` 特性）
+//!
+//! 设计要点：
+//! - 输出与 `encrypt_stream` / `decrypt_stream` 完全一致，可与同步接口互相解密。
+//! - CPU 密集的密钥派生（Argon2id / scrypt）在 `spawn_blocking` 中执行，不阻塞异步运行时；
+//!   单个 chunk 的 AEAD 运算在当前任务中完成，耗时与 chunk 大小成正比。
+//! - chunk 的读取与写出均为异步，每次只在内存中保留一个 chunk。
+//! - Header 按字段长度增量读取，多读出的 Stream 数据留给后续 chunk 使用。
+
+use std::io::{self, Cursor, Write};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::task::spawn_blocking;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::algorithm::AeadAlgorithm;
+use crate::crypto::checksum::Blake3Tap;
+use crate::decrypt::{DecryptOptions, decryptor_for_header};
+use crate::encrypt::{EncryptOptions, prepare_encryptor};
+use crate::error::SealVaultError;
+use crate::format::header::{Header, MAX_HEADER_SIZE};
+use crate::format::stream::{INITIAL_CHUNK_CAPACITY, LEN_SIZE, StreamDecryptor, TAG_SIZE};
+use crate::padding::Unpadder;
+
+/// 从异步 reader 读取明文，将完整的 .svlt 数据（Header + Stream）写入异步 writer
+///
+/// 写完 final chunk 后 flush writer，但不关闭它（需要时由调用方 `shutdown`）。
+pub async fn encrypt_async<R, W>(
+    mut reader: R,
+    mut writer: W,
+    password: &[u8],
+    algorithm: AeadAlgorithm,
+) -> io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    // ---------- 在阻塞线程池中派生密钥并生成 Header ----------
+    let password = Zeroizing::new(password.to_vec());
+    let (header, mut encryptor) = spawn_blocking(move || {
+        prepare_encryptor(&password, &EncryptOptions::with_algorithm(algorithm))
+    })
+    .await
+    .map_err(io::Error::other)??;
+
+    let mut frame = Vec::with_capacity(encryptor.chunk_size() + LEN_SIZE + TAG_SIZE);
+    header.write(&mut frame)?;
+    writer.write_all(&frame).await?;
+
+    // ---------- 逐 chunk 加密：先写入内存中的帧，再异步写出 ----------
+    let mut buffer = Zeroizing::new(vec![0u8; encryptor.chunk_size()]);
+    loop {
+        let read_len = read_full(&mut reader, &mut buffer).await?;
+        if read_len == 0 {
+            break;
+        }
+
+        frame.clear();
+        encryptor.write_chunk(&buffer[..read_len], &mut frame)?;
+        writer.write_all(&frame).await?;
+
+        if read_len < buffer.len() {
+            break;
+        }
+    }
+
+    frame.clear();
+    encryptor.finish(&mut frame)?;
+    writer.write_all(&frame).await?;
+    writer.flush().await
+}
+
+/// 从异步 reader 读取 .svlt 数据（先读 Header），将明文写入异步 writer
+///
+/// 压缩、填充与明文摘要按 Header 处理，语义同 `decrypt_stream`：writer 会在每个 chunk
+/// 认证通过后立即收到明文，若后续 chunk 失败，已写出的部分需要调用方自行丢弃。
+/// Header 标记需要 keyfile 时返回携带 `SealVaultError::KeyfileRequired` 的错误。
+pub async fn decrypt_async<R, W>(reader: R, mut writer: W, password: &[u8]) -> io::Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    // ---------- 读取 Header，并在阻塞线程池中派生密钥 ----------
+    let (header, mut reader) = read_header(reader).await?;
+
+    let password = Zeroizing::new(password.to_vec());
+    let key_header = header.clone();
+    let mut decryptor = spawn_blocking(move || {
+        decryptor_for_header(
+            &key_header,
+            &password,
+            None,
+            &DecryptOptions::default(),
+            &mut None,
+        )
+    })
+    .await
+    .map_err(io::Error::other)??;
+
+    // ---------- 逐 chunk 解密，去填充与解压的结果暂存在内存中，写出后即清零 ----------
+    let output = header.codec.decompress(Blake3Tap::new(Vec::new()))?;
+    let mut output = Unpadder::new(output, header.padding);
+    while let Some(chunk) = read_chunk(&mut reader, &mut decryptor).await? {
+        output.write_all(&chunk)?;
+        let ready = output.get_mut().get_mut().get_mut();
+        writer.write_all(ready).await?;
+        ready.zeroize();
+    }
+
+    let tap = output.finish(decryptor.padding_len())?.finish()?;
+    if header.plaintext_digest && decryptor.plaintext_digest() != Some(tap.summary().blake3) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            SealVaultError::CorruptedData,
+        ));
+    }
+    let mut tail = tap.into_inner();
+    writer.write_all(&tail).await?;
+    tail.zeroize();
+
+    writer.flush().await
+}
+
+/// 读取并解析 Header，返回 Header 与位于 Stream 起始处的 reader
+///
+/// 每次最多读到 `MAX_HEADER_SIZE` 字节后尝试解析，数据不足时继续读取；
+/// 多读出的 Stream 数据放在返回的 reader 前面。错误负载同 `Header::read`。
+async fn read_header<R>(mut reader: R) -> io::Result<(Header, impl AsyncRead + Unpin)>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut buf = Vec::with_capacity(MAX_HEADER_SIZE);
+    loop {
+        let mut rest = buf.as_slice();
+        match Header::read(&mut rest) {
+            Ok(header) => {
+                let consumed = buf.len() - rest.len();
+                buf.drain(..consumed);
+                return Ok((header, Cursor::new(buf).chain(reader)));
+            }
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof && buf.len() < MAX_HEADER_SIZE => {
+                let filled = buf.len();
+                buf.resize(MAX_HEADER_SIZE, 0);
+                let read_len = reader.read(&mut buf[filled..]).await?;
+                buf.truncate(filled + read_len);
+                if read_len == 0 {
+                    return Err(e);
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// 从异步 reader 读取并解密单个 chunk，返回值语义同 `StreamDecryptor::read_chunk`
+async fn read_chunk<R>(
+    reader: &mut R,
+    decryptor: &mut StreamDecryptor,
+) -> io::Result<Option<Zeroizing<Vec<u8>>>>
+where
+    R: AsyncRead + Unpin,
+{
+    loop {
+        if decryptor.is_finished() {
+            return Ok(None);
+        }
+
+        // 与同步路径相同：只有恰在 chunk 边界处 EOF 才可能是（v1 旧格式的）正常结束
+        let mut len_buf = [0u8; LEN_SIZE];
+        match read_full(reader, &mut len_buf).await? {
+            0 => {
+                decryptor.end_at_chunk_boundary()?;
+                return Ok(None);
+            }
+            LEN_SIZE => {}
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    SealVaultError::CorruptedData,
+                ));
+            }
+        }
+
+        // 与同步路径相同：按实际读到的数据增长缓冲区，不信任长度前缀一次性分配
+        let frame_len = decryptor.frame_len(len_buf)?;
+        let mut frame = Vec::with_capacity(frame_len.min(INITIAL_CHUNK_CAPACITY));
+        (&mut *reader)
+            .take(frame_len as u64)
+            .read_to_end(&mut frame)
+            .await?;
+
+        if frame.len() != frame_len {
+            return Err(io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                SealVaultError::CorruptedData,
+            ));
+        }
+
+        // 文件名帧不产生明文，继续读取下一帧
+        let is_file_name = decryptor.awaiting_file_name();
+        let chunk = decryptor.open_frame(len_buf, &frame)?;
+        if !is_file_name {
+            return Ok(chunk);
+        }
+    }
+}
+
+/// 尽量读满 buf，返回实际读取的长度（小于 buf 长度即已到 EOF）
+async fn read_full<R>(reader: &mut R, buf: &mut [u8]) -> io::Result<usize>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]).await? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
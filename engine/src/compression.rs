@@ -0,0 +1,167 @@
+//! SealVault 加密前压缩
+//!
+//! 明文在进入 StreamEncryptor 之前可选地经过流式压缩，Header 记录压缩编码，
+//! 解密时在 StreamDecryptor 输出之后透明解压。压缩与解压都是流式的，
+//! 不会把整个文件读入内存。
+//!
+//! 注意（CRIME / BREACH 类问题）：
+//! 压缩后的密文长度取决于明文内容。若攻击者能让自己控制的数据与秘密数据
+//! 一起被压缩加密，并观察密文长度，就可能逐步推断出秘密内容。
+//! 只应对攻击者无法注入内容的数据（如自己的备份、日志）开启压缩。
+//!
+//! zstd 由 `zstd` 特性（默认开启）提供；关闭后 Header 编码照常解析，
+//! 但选择压缩或解密压缩过的文件返回 `Unsupported`。
+
+#[cfg(feature = "fs")]
+use std::io::Read;
+use std::io::{self, Write};
+
+/// 加密前的压缩方式（`EncryptOptions::compression`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Zstandard，参数为压缩级别（1 ~ 22，0 表示 zstd 默认级别）
+    Zstd(i32),
+}
+
+impl Compression {
+    /// 写入 Header 的压缩编码
+    pub fn codec(self) -> Codec {
+        match self {
+            Self::Zstd(_) => Codec::Zstd,
+        }
+    }
+
+    /// 校验压缩参数
+    #[cfg(feature = "zstd")]
+    pub(crate) fn validate(self) -> io::Result<()> {
+        match self {
+            Self::Zstd(level) if !zstd::compression_level_range().contains(&level) => {
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("unsupported zstd compression level: {level}"),
+                ))
+            }
+            Self::Zstd(_) => Ok(()),
+        }
+    }
+
+    /// 校验压缩参数：未开启 `zstd` 特性时不支持任何压缩
+    #[cfg(not(feature = "zstd"))]
+    pub(crate) fn validate(self) -> io::Result<()> {
+        Err(zstd_unsupported())
+    }
+
+    #[cfg(feature = "fs")]
+    /// 包装明文 reader，读出的是压缩后的数据
+    pub(crate) fn compress<'a, R: Read + 'a>(self, reader: R) -> io::Result<Box<dyn Read + 'a>> {
+        match self {
+            #[cfg(feature = "zstd")]
+            Self::Zstd(level) => Ok(Box::new(zstd::stream::read::Encoder::new(reader, level)?)),
+            #[cfg(not(feature = "zstd"))]
+            Self::Zstd(_) => {
+                drop(reader);
+                Err(zstd_unsupported())
+            }
+        }
+    }
+}
+
+/// Header 中记录的压缩编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Codec {
+    /// 未压缩
+    #[default]
+    None,
+    /// Zstandard
+    Zstd,
+}
+
+impl Codec {
+    pub const NONE_ID: u8 = 0;
+    pub const ZSTD_ID: u8 = 1;
+
+    pub fn to_u8(self) -> u8 {
+        match self {
+            Self::None => Self::NONE_ID,
+            Self::Zstd => Self::ZSTD_ID,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            Self::NONE_ID => Some(Self::None),
+            Self::ZSTD_ID => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// 包装明文 writer，写入的压缩数据会被解压后转发
+    ///
+    /// 全部写完后必须调用 `Decompressor::finish` 刷新剩余输出。
+    /// 压缩流的完整性由外层 AEAD 与 final chunk 保证，这里不再单独校验。
+    pub(crate) fn decompress<W: Write>(self, writer: W) -> io::Result<Decompressor<W>> {
+        Ok(match self {
+            Self::None => Decompressor::Plain(writer),
+            #[cfg(feature = "zstd")]
+            Self::Zstd => Decompressor::Zstd(zstd::stream::write::Decoder::new(writer)?),
+            #[cfg(not(feature = "zstd"))]
+            Self::Zstd => return Err(zstd_unsupported()),
+        })
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+fn zstd_unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "zstd compression is not available (built without the `zstd` feature)",
+    )
+}
+
+/// 按 Header 中压缩编码解压的 writer
+pub(crate) enum Decompressor<W: Write> {
+    Plain(W),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Decoder<'static, W>),
+}
+
+impl<W: Write> Decompressor<W> {
+    /// 内层 writer 的可变引用（只含已解压的数据）
+    pub fn get_mut(&mut self) -> &mut W {
+        match self {
+            Self::Plain(writer) => writer,
+            #[cfg(feature = "zstd")]
+            Self::Zstd(decoder) => decoder.get_mut(),
+        }
+    }
+
+    /// 结束解压并返回内层 writer
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Self::Plain(writer) => Ok(writer),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(mut decoder) => {
+                decoder.flush()?;
+                Ok(decoder.into_inner())
+            }
+        }
+    }
+}
+
+impl<W: Write> Write for Decompressor<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(writer) => writer.write(buf),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(decoder) => decoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(writer) => writer.flush(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(decoder) => decoder.flush(),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! SealVault 明文校验和 sidecar
+//!
+//! 加密时在数据流经加密器的同时计算明文 SHA-256，
+//! 并写出与 `sha256sum` 兼容的 `.sha256` sidecar 文件，
+//! 便于接收方在解密后使用标准工具校验。
+//!
+//! 目录 manifest 使用的明文长度与 BLAKE3 摘要（`Blake3Tap`）同样在此计算。
+//!
+//! 注意：
+//! - sidecar 以明文形式保存摘要，会暴露“两份文件内容是否相同”这一信息
+//! - sidecar 不参与 `.svlt` 格式，也不受 AEAD 保护
+
+#[cfg(feature = "fs")]
+use std::ffi::OsStr;
+#[cfg(feature = "fs")]
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "fs")]
+use crate::fs::atomic::write_atomic;
+
+/// 校验和 sidecar 的文件后缀
+pub const CHECKSUM_SIDECAR_EXT: &str = "sha256";
+
+/// 在读取数据的同时计算 SHA-256 的 Reader 包装
+pub struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// 结束读取并返回已读数据的 SHA-256 摘要
+    pub fn finalize(self) -> [u8; 32] {
+        self.hasher.finalize().into()
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read_len = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read_len]);
+        Ok(read_len)
+    }
+}
+
+/// 明文长度与 BLAKE3 摘要
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlaintextSummary {
+    pub len: u64,
+    pub blake3: [u8; 32],
+}
+
+/// 在读取或写入数据的同时统计长度并计算 BLAKE3 的包装
+pub struct Blake3Tap<T> {
+    inner: T,
+    hasher: blake3::Hasher,
+    len: u64,
+}
+
+impl<T> Blake3Tap<T> {
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+            len: 0,
+        }
+    }
+
+    /// 取回内层 reader / writer
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// 内层 reader / writer 的可变引用
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// 已经过的数据的长度与 BLAKE3 摘要
+    pub fn summary(&self) -> PlaintextSummary {
+        PlaintextSummary {
+            len: self.len,
+            blake3: self.hasher.finalize().into(),
+        }
+    }
+
+    fn record(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+        self.len += data.len() as u64;
+    }
+}
+
+impl<R: Read> Read for Blake3Tap<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read_len = self.inner.read(buf)?;
+        self.record(&buf[..read_len]);
+        Ok(read_len)
+    }
+}
+
+impl<W: Write> Write for Blake3Tap<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.record(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// 返回 `output_path` 对应的 sidecar 路径（`<output>.sha256`）
+pub fn sidecar_path(output_path: &Path) -> PathBuf {
+    let mut name = output_path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(CHECKSUM_SIDECAR_EXT);
+    PathBuf::from(name)
+}
+
+#[cfg(feature = "fs")]
+/// 以 `sha256sum` 格式写出 sidecar：`<hex>  <plaintext file name>`
+pub fn write_sidecar(
+    sidecar_path: &Path,
+    digest: &[u8; 32],
+    plaintext_name: &OsStr,
+) -> io::Result<()> {
+    let mut line = String::with_capacity(64 + 2 + plaintext_name.len() + 1);
+    for byte in digest {
+        let _ = write!(line, "{byte:02x}");
+    }
+    line.push_str("  ");
+    line.push_str(&plaintext_name.to_string_lossy());
+    line.push('\n');
+
+    write_atomic(sidecar_path, |output| output.write_all(line.as_bytes()))
+}
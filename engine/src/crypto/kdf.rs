@@ -1,61 +1,530 @@
 //! SealVault 密钥派生函数（KDF）模块
 //!
-//! 本模块负责将用户输入的密码，通过 Argon2id 算法
-//! 派生为高强度的对称加密密钥，用于后续 AES-256-GCM 加解密。
+//! 本模块负责将用户输入的密码，通过 Argon2id（默认）或 scrypt 算法
+//! 派生为高强度的对称加密密钥，用于后续 AEAD 加解密。
+//! scrypt 适合内存受限的设备，可配合较小的参数使用。
 //!
 //! 设计目标：
 //! - 抵抗暴力破解、GPU / ASIC 攻击
-//! - 每个加密文件使用独立的随机 salt
+//! - 每个加密文件默认使用独立的随机 salt（目录加密可选共享 salt，见 `FolderOptions::shared_salt`）
 //! - 敏感密钥材料在离开作用域后自动清零
 //! - 参数可在未来通过版本号升级而不破坏兼容性
 //!
 //! 输出：
 //! - 32 字节密钥（适用于 XChaCha20-Poly1305 / AES-256-GCM）
+//!
+//! Argon2id 的 salt 输入：v2 起直接使用 Header 中的 16 字节原始 salt（`derive_key_raw*`）；
+//! v1 文件使用其 base64（无填充）文本的字节（`derive_key*`，仅为读取旧文件保留）。
+//! scrypt 始终使用原始字节。
+//!
+//! 派生函数均为纯计算、不涉及文件 I/O，可直接计时；`calibrate` 按目标耗时在本机搜索 Argon2id 参数。
 
-use argon2::{Algorithm, Argon2, Params, Version, password_hash::SaltString};
+#[cfg(feature = "test-hooks")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use argon2::{Algorithm, Argon2, Block, Params, Version, password_hash::SaltString};
+#[cfg(feature = "fs")]
+use rand::rngs::OsRng;
+use rand::{CryptoRng, RngCore};
 use zeroize::Zeroizing;
 
 use crate::error::SealVaultError;
+use crate::format::header::SALT_SIZE;
 
 /// 派生密钥长度（256-bit）
 pub const KEY_LEN: usize = 32;
 
-/// Argon2 参数配置（SealVault v1）
+/// 允许的最大内存成本（KiB），即 4 GiB
+///
+/// 解密时参数来自文件 Header，需限制上限，避免恶意文件耗尽内存。
+pub const MAX_M_COST: u32 = 4 * 1024 * 1024;
+
+/// 允许的最大时间成本（迭代次数）
+pub const MAX_T_COST: u32 = 64;
+
+/// 允许的最大并行度
+pub const MAX_P_COST: u32 = 64;
+
+/// `calibrate` 搜索的内存成本下限（KiB），即 8 MiB
+pub const CALIBRATE_MIN_M_COST: u32 = 8 * 1024;
+
+/// `calibrate` 搜索的内存成本上限（KiB），即 1 GiB；更大的内存成本需显式指定
+pub const CALIBRATE_MAX_M_COST: u32 = 1024 * 1024;
+
+/// scrypt 允许的最大内存占用（字节，`128 * r * N`），与 Argon2id 上限一致为 4 GiB
+pub const MAX_SCRYPT_MEMORY: u64 = 4 * 1024 * 1024 * 1024;
+
+/// scrypt 允许的最大并行度
+pub const MAX_SCRYPT_P: u32 = 64;
+
+/// Argon2id 参数
+///
+/// v2 起写入 Header，解密时按文件中保存的参数派生密钥；
+/// v1 文件固定使用 `KdfParams::default()`。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KdfParams {
+    /// 内存成本（KiB）
+    pub m_cost: u32,
+    /// 时间成本（迭代次数）
+    pub t_cost: u32,
+    /// 并行度
+    pub p_cost: u32,
+}
+
+impl Default for KdfParams {
+    /// 新加密文件使用的默认参数
+    ///
+    /// 该参数组合在安全性与性能之间取得平衡。
+    fn default() -> Self {
+        Self {
+            m_cost: 64 * 1024, // 内存成本：64 MB
+            t_cost: 3,         // 时间成本：迭代次数
+            p_cost: 1,         // 并行度
+        }
+    }
+}
+
+impl KdfParams {
+    /// 参数是否在 Argon2 与 SealVault 允许的范围内
+    pub fn is_supported(&self) -> bool {
+        self.m_cost <= MAX_M_COST
+            && self.t_cost <= MAX_T_COST
+            && self.p_cost <= MAX_P_COST
+            && self.argon2_params().is_ok()
+    }
+
+    fn argon2_params(&self) -> Result<Params, argon2::Error> {
+        Params::new(self.m_cost, self.t_cost, self.p_cost, Some(KEY_LEN))
+    }
+}
+
+/// scrypt 参数
 ///
-/// 该参数组合在安全性与性能之间取得平衡，
-/// 后续如需调整，应通过文件格式版本号控制。
-fn argon2_params() -> Params {
-    Params::new(
-        64 * 1024, // 内存成本：64 MB
-        3,         // 时间成本：迭代次数
-        1,         // 并行度
-        Some(KEY_LEN),
-    )
-    .expect("Argon2 参数配置错误")
+/// v2 起可写入 Header，解密时按文件中保存的参数派生密钥。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScryptParams {
+    /// CPU / 内存成本的以 2 为底的对数（N = 2^log_n）
+    pub log_n: u8,
+    /// 块大小
+    pub r: u32,
+    /// 并行度
+    pub p: u32,
 }
 
-/// 根据密码和 salt 派生对称加密密钥
+impl Default for ScryptParams {
+    /// 常用的交互式参数：N = 2^15、r = 8、p = 1，约占用 32 MiB
+    fn default() -> Self {
+        Self {
+            log_n: 15,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+impl ScryptParams {
+    /// 参数是否在 scrypt 与 SealVault 允许的范围内
+    pub fn is_supported(&self) -> bool {
+        // 128 * r * 2^log_n，log_n < 64 时在 u128 中不会溢出
+        self.log_n >= 1
+            && self.log_n < 64
+            && self.p <= MAX_SCRYPT_P
+            && (128u128 * self.r as u128) << self.log_n <= MAX_SCRYPT_MEMORY as u128
+            && self.scrypt_params().is_ok()
+    }
+
+    fn scrypt_params(&self) -> Result<scrypt::Params, scrypt::errors::InvalidParams> {
+        scrypt::Params::new(self.log_n, self.r, self.p, KEY_LEN)
+    }
+}
+
+/// 密钥派生算法及其参数，写入 Header
+///
+/// v1 文件固定为默认参数的 Argon2id；v2 起 Header 记录算法 ID。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KdfAlgorithm {
+    /// Argon2id（默认）
+    Argon2id(KdfParams),
+    /// scrypt，内存占用可调得更低
+    Scrypt(ScryptParams),
+}
+
+impl Default for KdfAlgorithm {
+    fn default() -> Self {
+        Self::Argon2id(KdfParams::default())
+    }
+}
+
+impl KdfAlgorithm {
+    pub const ARGON2ID_ID: u8 = 0;
+    pub const SCRYPT_ID: u8 = 1;
+
+    /// 写入 Header 的算法 ID
+    pub fn id(&self) -> u8 {
+        match self {
+            Self::Argon2id(_) => Self::ARGON2ID_ID,
+            Self::Scrypt(_) => Self::SCRYPT_ID,
+        }
+    }
+
+    /// 参数是否在允许的范围内
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Self::Argon2id(params) => params.is_supported(),
+            Self::Scrypt(params) => params.is_supported(),
+        }
+    }
+
+    /// 派生所需的工作内存（字节）：Argon2id 为 `m_cost` KiB，scrypt 为 `128 * r * N`（溢出时取 `u64::MAX`）
+    pub fn memory_bytes(&self) -> u64 {
+        match self {
+            Self::Argon2id(params) => u64::from(params.m_cost) * 1024,
+            Self::Scrypt(params) if params.log_n < 64 => {
+                let bytes = (128u128 * params.r as u128) << params.log_n;
+                u64::try_from(bytes).unwrap_or(u64::MAX)
+            }
+            Self::Scrypt(_) => u64::MAX,
+        }
+    }
+
+    /// 派生时遍历工作内存的次数，耗时大致与 `memory_bytes() * passes()` 成正比：
+    /// Argon2id 为 `t_cost`，scrypt 为 `p`
+    pub fn passes(&self) -> u32 {
+        match self {
+            Self::Argon2id(params) => params.t_cost,
+            Self::Scrypt(params) => params.p,
+        }
+    }
+}
+
+/// Argon2id 的 salt 输入形式（scrypt 始终使用原始字节）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SaltEncoding {
+    /// 原始 salt 字节（v2 起）
+    #[default]
+    Raw,
+    /// salt 的标准 base64（无填充）文本字节（v1）
+    Base64,
+}
+
+/// 进程内密钥派生（Argon2id 与 scrypt）的调用次数，仅在 `test-hooks` feature 下计数
+#[cfg(feature = "test-hooks")]
+static DERIVE_INVOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+/// 进程启动以来执行密钥派生的次数（用于测试密钥复用）
+#[cfg(feature = "test-hooks")]
+#[doc(hidden)]
+pub fn derive_key_invocations() -> usize {
+    DERIVE_INVOCATIONS.load(Ordering::SeqCst)
+}
+
+/// 记录一次密钥派生；未启用 `test-hooks` 时为空操作
+fn count_derivation() {
+    #[cfg(feature = "test-hooks")]
+    DERIVE_INVOCATIONS.fetch_add(1, Ordering::SeqCst);
+}
+
+/// 与 salt、KDF 算法及参数绑定的派生密钥
+///
+/// 目录操作据此在多个文件间复用同一次派生的结果：
+/// Header 中的 salt、KDF 与 salt 输入形式都相同时，密钥必然相同。
+#[derive(Clone)]
+pub(crate) struct DerivedKey {
+    pub salt: [u8; SALT_SIZE],
+    pub kdf: KdfAlgorithm,
+    pub salt_encoding: SaltEncoding,
+    pub key: Zeroizing<[u8; KEY_LEN]>,
+}
+
+impl DerivedKey {
+    #[cfg(feature = "fs")]
+    /// 生成随机 salt，并使用默认 KDF（Argon2id 默认参数）派生密钥
+    pub fn generate(password: &[u8]) -> std::io::Result<Self> {
+        Self::generate_with(password, KdfAlgorithm::default())
+    }
+
+    #[cfg(feature = "fs")]
+    /// 生成随机 salt，并使用指定 KDF 派生密钥
+    pub fn generate_with(password: &[u8], kdf: KdfAlgorithm) -> std::io::Result<Self> {
+        Self::generate_with_rng(password, kdf, &mut OsRng)
+    }
+
+    /// 同 `generate_with`，但 salt 取自调用方提供的随机数源（测试中注入固定种子以得到确定输出）
+    pub fn generate_with_rng(
+        password: &[u8],
+        kdf: KdfAlgorithm,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> std::io::Result<Self> {
+        let mut salt = [0u8; SALT_SIZE];
+        rng.fill_bytes(&mut salt);
+        Self::derive(password, salt, kdf, SaltEncoding::Raw)
+    }
+
+    /// 使用给定 salt、KDF 与 salt 输入形式（来自 Header 版本）派生密钥
+    pub fn derive(
+        password: &[u8],
+        salt: [u8; SALT_SIZE],
+        kdf: KdfAlgorithm,
+        salt_encoding: SaltEncoding,
+    ) -> std::io::Result<Self> {
+        let key = match (&kdf, salt_encoding) {
+            (KdfAlgorithm::Argon2id(params), SaltEncoding::Raw) => {
+                derive_key_raw_with_params(password, &salt, params)
+            }
+            (KdfAlgorithm::Argon2id(params), SaltEncoding::Base64) => {
+                let salt_string = SaltString::encode_b64(&salt).map_err(|e| {
+                    std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string())
+                })?;
+                derive_key_with_params(password, &salt_string, params)
+            }
+            (KdfAlgorithm::Scrypt(params), _) => derive_key_scrypt(password, &salt, params),
+        }
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        Ok(Self {
+            salt,
+            kdf,
+            salt_encoding,
+            key,
+        })
+    }
+
+    /// 该密钥是否由给定 salt、KDF 与 salt 输入形式派生
+    pub fn matches(
+        &self,
+        salt: &[u8; SALT_SIZE],
+        kdf: &KdfAlgorithm,
+        salt_encoding: SaltEncoding,
+    ) -> bool {
+        self.salt == *salt && self.kdf == *kdf && self.salt_encoding == salt_encoding
+    }
+}
+
+/// 根据密码和原始 salt 字节，使用默认 Argon2id 参数派生对称加密密钥（v2 起的派生方式）
+///
+/// 等价于 `derive_key_raw_with_params(password, salt, &KdfParams::default())`。
+pub fn derive_key_raw(
+    password: &[u8],
+    salt: &[u8],
+) -> Result<Zeroizing<[u8; KEY_LEN]>, SealVaultError> {
+    derive_key_raw_with_params(password, salt, &KdfParams::default())
+}
+
+/// 根据密码、原始 salt 字节与指定的 Argon2id 参数派生对称加密密钥（v2 起的派生方式）
+///
+/// salt 原样作为 Argon2id 的 salt 输入，不经任何编码；长度至少 8 字节
+/// （SealVault 文件固定为 `SALT_SIZE`）。错误与 `derive_key_with_params` 相同。
+pub fn derive_key_raw_with_params(
+    password: &[u8],
+    salt: &[u8],
+    params: &KdfParams,
+) -> Result<Zeroizing<[u8; KEY_LEN]>, SealVaultError> {
+    argon2id(password, salt, params)
+}
+
+/// 根据密码和 salt，使用默认参数派生对称加密密钥（v1 的派生方式）
+///
+/// Argon2id 的 salt 输入是 `salt` 的 base64 文本字节而非原始字节，仅为读取旧文件保留；
+/// 新代码应使用 `derive_key_raw`。
+/// 等价于 `derive_key_with_params(password, salt, &KdfParams::default())`。
+pub fn derive_key(
+    password: &[u8],
+    salt: &SaltString,
+) -> Result<Zeroizing<[u8; KEY_LEN]>, SealVaultError> {
+    derive_key_with_params(password, salt, &KdfParams::default())
+}
+
+/// 根据密码、salt 与指定的 Argon2id 参数派生对称加密密钥（v1 的派生方式）
+///
+/// 与 `derive_key` 相同，Argon2id 的 salt 输入是 `salt.as_str()` 的字节；
+/// 新代码应使用 `derive_key_raw_with_params`。
 ///
 /// #### 参数
-/// - `password`：用户输入的密码（UTF-8）
+/// - `password`：用户输入的密码（任意字节序列，UTF-8 字符串传 `as_bytes()`）
 /// - `salt`：该文件对应的随机 salt
+/// - `params`：Argon2id 参数（解密时来自 Header）
 ///
 /// #### 返回
 /// - 32 字节派生密钥（自动 zeroize）
 ///
 /// #### 错误
-/// - 发生不可预期错误时返回 SealVaultError::Internal
-pub fn derive_key(
-    password: &str,
+/// - `m_cost` 超过 `MAX_M_COST`，或无法分配所需的工作内存时返回
+///   SealVaultError::KdfResourceExhausted（调低 `m_cost` 后可重试）
+/// - 其余参数超出允许范围或发生不可预期错误时返回 SealVaultError::Internal
+pub fn derive_key_with_params(
+    password: &[u8],
     salt: &SaltString,
+    params: &KdfParams,
+) -> Result<Zeroizing<[u8; KEY_LEN]>, SealVaultError> {
+    argon2id(password, salt.as_str().as_bytes(), params)
+}
+
+/// Argon2id 派生，`salt` 为实际送入 Argon2id 的字节
+fn argon2id(
+    password: &[u8],
+    salt: &[u8],
+    params: &KdfParams,
 ) -> Result<Zeroizing<[u8; KEY_LEN]>, SealVaultError> {
-    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, argon2_params());
+    if params.m_cost > MAX_M_COST {
+        return Err(SealVaultError::KdfResourceExhausted);
+    }
+    if !params.is_supported() {
+        return Err(SealVaultError::Internal);
+    }
+    let argon2_params = params
+        .argon2_params()
+        .map_err(|_| SealVaultError::Internal)?;
+
+    // 自行申请工作内存：`hash_password_into` 内部的 `vec!` 在分配失败时会直接中止进程，
+    // 这里改为返回错误，提示调用方调低 m_cost
+    let block_count = argon2_params.block_count();
+    let mut memory = Vec::new();
+    memory
+        .try_reserve_exact(block_count)
+        .map_err(|_| SealVaultError::KdfResourceExhausted)?;
+    memory.resize(block_count, Block::default());
+
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, argon2_params);
+    count_derivation();
 
     // 使用 Zeroizing 包装，确保密钥在作用域结束后被清零
     let mut key = Zeroizing::new([0u8; KEY_LEN]);
 
     argon2
-        .hash_password_into(password.as_bytes(), salt.as_str().as_bytes(), &mut key[..])
+        .hash_password_into_with_memory(password, salt, &mut key[..], &mut memory)
+        .map_err(|_| SealVaultError::Internal)?;
+
+    Ok(key)
+}
+
+/// 在本机上搜索派生耗时不超过且尽量接近 `target` 的 Argon2id 参数
+///
+/// 并行度固定为 1，时间成本先取默认值：
+/// - 以 1 MiB 为粒度倍增再二分搜索 `m_cost`（`CALIBRATE_MIN_M_COST` ~ `CALIBRATE_MAX_M_COST`）；
+/// - 内存成本到达上限仍快于 `target` 时，再增大 `t_cost`（不超过 `MAX_T_COST`）；
+/// - 内存成本取下限仍慢于 `target` 时，减小 `t_cost`，最低为 1（此时可能超过 `target`）。
+///
+/// 每次试探都完整派生一次，总耗时约为 `target` 的十倍左右。结果只反映当前机器与负载，
+/// 写入 `EncryptOptions::kdf` 前应确认解密端同样能承受。
+///
+/// #### 错误
+/// - 连最小参数所需的工作内存都无法分配时返回 SealVaultError::KdfResourceExhausted
+pub fn calibrate(target: Duration) -> Result<KdfParams, SealVaultError> {
+    const MIB: u32 = 1024;
+    let default_t_cost = KdfParams::default().t_cost;
+    let params = |m_cost, t_cost| KdfParams {
+        m_cost,
+        t_cost,
+        p_cost: 1,
+    };
+
+    let m_cost = largest_within(
+        CALIBRATE_MIN_M_COST / MIB,
+        CALIBRATE_MAX_M_COST / MIB,
+        target,
+        |m| params(m * MIB, default_t_cost),
+    )?;
+    match m_cost {
+        Some(m) if m * MIB < CALIBRATE_MAX_M_COST => Ok(params(m * MIB, default_t_cost)),
+        // 内存成本到达上限仍有余量：增加迭代次数
+        Some(_) => {
+            let t_cost = largest_within(default_t_cost, MAX_T_COST, target, |t| {
+                params(CALIBRATE_MAX_M_COST, t)
+            })?;
+            Ok(params(
+                CALIBRATE_MAX_M_COST,
+                t_cost.unwrap_or(default_t_cost),
+            ))
+        }
+        // 内存成本取下限仍太慢：减少迭代次数
+        None => {
+            let t_cost = match largest_within(1, default_t_cost - 1, target, |t| {
+                params(CALIBRATE_MIN_M_COST, t)
+            })? {
+                Some(t) => t,
+                None => {
+                    // 最小参数也超时时仍返回它，但无法派生（如内存不足）时报告错误
+                    calibration_derive(&params(CALIBRATE_MIN_M_COST, 1))?;
+                    1
+                }
+            };
+            Ok(params(CALIBRATE_MIN_M_COST, t_cost))
+        }
+    }
+}
+
+/// 在 `lo..=hi` 中找出 `params_for` 派生耗时不超过 `target` 的最大取值（耗时随取值单调增加）
+///
+/// 先倍增找到第一个超时的取值，再二分；`lo` 本身超时返回 `None`。
+fn largest_within(
+    lo: u32,
+    hi: u32,
+    target: Duration,
+    params_for: impl Fn(u32) -> KdfParams,
+) -> Result<Option<u32>, SealVaultError> {
+    if !derives_within(&params_for(lo), target)? {
+        return Ok(None);
+    }
+    let mut good = lo;
+    let mut bad = loop {
+        let next = good.saturating_mul(2).min(hi);
+        if next == good {
+            return Ok(Some(good));
+        }
+        if !derives_within(&params_for(next), target)? {
+            break next;
+        }
+        good = next;
+    };
+    while bad - good > 1 {
+        let mid = good + (bad - good) / 2;
+        if derives_within(&params_for(mid), target)? {
+            good = mid;
+        } else {
+            bad = mid;
+        }
+    }
+    Ok(Some(good))
+}
+
+/// 按 `params` 试派生一次，返回耗时是否不超过 `target`；内存不足视为超时
+fn derives_within(params: &KdfParams, target: Duration) -> Result<bool, SealVaultError> {
+    let start = Instant::now();
+    match calibration_derive(params) {
+        Ok(()) => Ok(start.elapsed() <= target),
+        Err(SealVaultError::KdfResourceExhausted) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// `calibrate` 的一次试派生（固定密码与全零 salt，结果立即丢弃）
+fn calibration_derive(params: &KdfParams) -> Result<(), SealVaultError> {
+    argon2id(b"sealvault-calibrate", &[0u8; SALT_SIZE], params).map(drop)
+}
+
+/// 根据密码、salt 与 scrypt 参数派生对称加密密钥
+///
+/// 与 Argon2id 不同，salt 直接使用原始字节。
+///
+/// #### 错误
+/// - 参数超出允许范围或发生不可预期错误时返回 SealVaultError::Internal
+pub fn derive_key_scrypt(
+    password: &[u8],
+    salt: &[u8],
+    params: &ScryptParams,
+) -> Result<Zeroizing<[u8; KEY_LEN]>, SealVaultError> {
+    if !params.is_supported() {
+        return Err(SealVaultError::Internal);
+    }
+    let scrypt_params = params
+        .scrypt_params()
+        .map_err(|_| SealVaultError::Internal)?;
+    count_derivation();
+
+    let mut key = Zeroizing::new([0u8; KEY_LEN]);
+
+    scrypt::scrypt(password, salt, &scrypt_params, &mut key[..])
         .map_err(|_| SealVaultError::Internal)?;
 
     Ok(key)
@@ -0,0 +1,31 @@
+//! SealVault keyfile 支持
+//!
+//! 加密时可要求一个 keyfile（任意文件）作为第二因素：KDF 的输入变为
+//! `password || BLAKE3(keyfile)`，密码可以为空。Header 中记录“需要 keyfile”标志，
+//! 解密时缺少 keyfile 会直接报错，而不是当作密码错误。
+//!
+//! 注意：keyfile 的每一个字节都参与派生，丢失或修改 keyfile 等同于丢失数据，
+//! 没有任何恢复手段。
+
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+
+use zeroize::Zeroizing;
+
+/// keyfile 摘要的 BLAKE3 派生上下文（域分离，避免与其他 BLAKE3 用途混淆）
+const KEYFILE_CONTEXT: &str = "SealVault 2026-01 keyfile v1";
+
+/// 读取 keyfile 并与密码拼接为 KDF 输入：`password || BLAKE3(keyfile)`
+///
+/// keyfile 以流式读取，可以是任意大小的文件。
+pub fn combine_with_keyfile(password: &[u8], keyfile: &Path) -> io::Result<Zeroizing<Vec<u8>>> {
+    let mut hasher = blake3::Hasher::new_derive_key(KEYFILE_CONTEXT);
+    hasher.update_reader(BufReader::new(File::open(keyfile)?))?;
+    let digest = hasher.finalize();
+
+    let mut secret = Zeroizing::new(Vec::with_capacity(password.len() + blake3::OUT_LEN));
+    secret.extend_from_slice(password);
+    secret.extend_from_slice(digest.as_bytes());
+    Ok(secret)
+}
@@ -1 +1,4 @@
+pub mod checksum;
 pub mod kdf;
+pub mod keyfile;
+pub mod password;
@@ -0,0 +1,58 @@
+//! SealVault 密码处理辅助
+//!
+//! 密码只以字节形式参与比较与派生，不应出现在日志、错误信息或 `Debug` 输出中。
+
+use std::io;
+
+use subtle::ConstantTimeEq;
+
+use crate::error::SealVaultError;
+
+/// 以常量时间比较两次输入的密码是否相同（用于交互输入时的二次确认）
+///
+/// 耗时只取决于长度，不取决于第一个不同字节的位置；长度不同直接返回 false。
+pub fn passwords_equal(a: &[u8], b: &[u8]) -> bool {
+    a.ct_eq(b).into()
+}
+
+/// 加密时对密码的最低要求
+///
+/// 只在加密与设置新密码时检查，解密从不检查，已有文件始终可以打开。
+/// 同时使用 keyfile 时不检查：此时密码可以为空，由 keyfile 提供秘密。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PasswordPolicy {
+    /// 密码至少包含的字符数（UTF-8 按字符计，其余按字节计），默认 1
+    ///
+    /// 大于 0 时空密码与只含空白的密码一律拒绝；0 表示不做任何检查（`PasswordPolicy::ALLOW_ANY`）。
+    pub min_len: usize,
+}
+
+impl Default for PasswordPolicy {
+    /// 拒绝空密码与只含空白的密码，不另外限制长度
+    fn default() -> Self {
+        Self { min_len: 1 }
+    }
+}
+
+impl PasswordPolicy {
+    /// 不做任何检查、允许空密码：这样的文件任何人都能打开，仅供确有需要的场景（如测试数据）
+    pub const ALLOW_ANY: Self = Self { min_len: 0 };
+
+    /// 检查密码，不满足时返回 `InvalidInput`，负载为 `SealVaultError::WeakPassword`
+    pub fn check(&self, password: &[u8]) -> io::Result<()> {
+        if self.min_len == 0 {
+            return Ok(());
+        }
+        let (len, blank) = match std::str::from_utf8(password) {
+            Ok(text) => (text.chars().count(), text.trim().is_empty()),
+            Err(_) => (password.len(), false),
+        };
+        if blank || len < self.min_len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                SealVaultError::WeakPassword,
+            ));
+        }
+        Ok(())
+    }
+}
@@ -11,43 +11,1016 @@
 //! 注意：
 //! - 若 Header 或任一 chunk 校验失败，必须立即报错
 
-use std::fs::File;
-use std::io::{BufReader, BufWriter, Write};
+use std::io::{Read, Write};
 use std::path::Path;
 
-use argon2::password_hash::SaltString;
+use zeroize::Zeroize;
+
+use crate::algorithm::AeadAlgorithm;
+use crate::compression::Decompressor;
+use crate::crypto::checksum::Blake3Tap;
+use crate::crypto::kdf::{CALIBRATE_MAX_M_COST, DerivedKey, KdfAlgorithm};
+use crate::crypto::keyfile::combine_with_keyfile;
+use crate::error::SealVaultError;
+use crate::format::header::{Header, SALT_SIZE};
+use crate::format::stream::{PLAINTEXT_DIGEST_SIZE, StreamDecryptor};
+use crate::padding::{Padding, Unpadder};
+
+#[cfg(feature = "fs")]
+use std::fs::File;
+#[cfg(feature = "fs")]
+use std::io::{BufReader, BufWriter, Seek, SeekFrom};
+#[cfg(feature = "fs")]
+use std::path::{Component, PathBuf};
+
+#[cfg(feature = "fs")]
+use crate::compression::Codec;
+#[cfg(feature = "fs")]
+use crate::crypto::checksum::PlaintextSummary;
+#[cfg(feature = "fs")]
+use crate::format::checkpoint::Checkpoint;
+#[cfg(feature = "fs")]
+use crate::format::header::MAX_HEADER_SIZE;
+#[cfg(feature = "fs")]
+use crate::format::manifest::os_str_from_bytes;
+#[cfg(feature = "fs")]
+use crate::format::stream::DEFAULT_IO_BUFFER_SIZE;
+#[cfg(feature = "fs")]
+use crate::fs::atomic::{create_fresh, open_existing, replace_file, write_atomic};
+#[cfg(feature = "fs")]
+use crate::progress::Stats;
+
+#[cfg(feature = "fs")]
+/// 断点续做（加密或解密）时，未完成输出的文件后缀（`<output>.svpart`）
+pub const PARTIAL_EXT: &str = "svpart";
+
+#[cfg(feature = "fs")]
+/// 断点续做（加密或解密）时，checkpoint sidecar 的文件后缀（`<output>.svckpt`）
+pub const CHECKPOINT_EXT: &str = "svckpt";
+
+/// 断点续做选项（`decrypt_file_with_checkpoint` 与 `encrypt_file_with_checkpoint` 共用）
+#[derive(Debug, Clone)]
+pub struct CheckpointOptions {
+    /// 每写出多少个 chunk 记录一次 checkpoint（必须大于 0）
+    pub interval_chunks: u64,
+    /// 若存在匹配的 checkpoint，则从其记录的位置继续
+    pub resume: bool,
+}
+
+impl Default for CheckpointOptions {
+    fn default() -> Self {
+        Self {
+            interval_chunks: 64,
+            resume: true,
+        }
+    }
+}
+
+/// 解密选项（文件、内存与流式接口通用）
+#[derive(Debug, Clone)]
+pub struct DecryptOptions {
+    /// 解密（解压、去填充）后明文的字节上限，`None` 为不限制
+    ///
+    /// 超出时立即中止，删除未完成的临时输出并返回携带 `SealVaultError::OutputLimitExceeded`
+    /// 的错误。用于处理不可信的上传文件，防止解压炸弹或超大 Stream 写满磁盘。
+    pub max_output_bytes: Option<u64>,
+    /// 要求文件使用的 AEAD 算法，`None` 为接受 Header 中记录的任意算法
+    ///
+    /// 不一致时在派生密钥之前返回携带 `SealVaultError::UnexpectedAlgorithm` 的错误，
+    /// 不会为不符合预期的文件花费 KDF 的时间与内存。
+    pub expected_algorithm: Option<AeadAlgorithm>,
+    /// final chunk 之后仍有数据时返回携带 `SealVaultError::CorruptedData` 的错误，
+    /// 默认忽略这些数据（v1 旧文件没有 final chunk，不受影响）
+    pub reject_trailing_data: bool,
+    /// 输出文件的父目录不存在时逐级创建（默认），与加密接口一致
+    ///
+    /// 设为 `false` 时父目录必须已存在，否则在派生密钥之前返回 `NotFound`，
+    /// 可避免路径拼写错误时在意外的位置建出目录。只影响文件接口。
+    pub create_parents: bool,
+    /// 允许文件 Header 要求的最大 KDF 工作内存（字节，见 `KdfAlgorithm::memory_bytes`），
+    /// 默认 `DEFAULT_MAX_KDF_MEMORY`
+    ///
+    /// KDF 参数来自文件本身，超出时在派生密钥之前返回携带 `SealVaultError::KdfResourceExhausted`
+    /// 的错误，避免不可信的文件让解密占用数 GiB 内存。解密参数更高的可信文件时调大即可。
+    pub max_kdf_memory: u64,
+    /// 允许文件 Header 要求的最大 KDF 遍历次数（见 `KdfAlgorithm::passes`：Argon2id 的
+    /// `t_cost`、scrypt 的 `p`），默认 `DEFAULT_MAX_KDF_TIME`，超出时的处理同 `max_kdf_memory`
+    pub max_kdf_time: u32,
+}
+
+impl Default for DecryptOptions {
+    fn default() -> Self {
+        Self {
+            max_output_bytes: None,
+            expected_algorithm: None,
+            reject_trailing_data: false,
+            create_parents: true,
+            max_kdf_memory: Self::DEFAULT_MAX_KDF_MEMORY,
+            max_kdf_time: Self::DEFAULT_MAX_KDF_TIME,
+        }
+    }
+}
+
+impl DecryptOptions {
+    /// `max_kdf_memory` 的默认值：1 GiB，即 `kdf::calibrate` 可能选择的最大内存成本
+    /// （默认 Argon2id 参数为 64 MiB）
+    pub const DEFAULT_MAX_KDF_MEMORY: u64 = CALIBRATE_MAX_M_COST as u64 * 1024;
+
+    /// `max_kdf_time` 的默认值：16 次（默认 Argon2id 参数为 3 次）
+    pub const DEFAULT_MAX_KDF_TIME: u32 = 16;
+
+    #[cfg(feature = "fs")]
+    /// 不限制 KDF 参数（仍受 `kdf::MAX_M_COST` 等硬上限约束），用于读取本机刚写出的文件
+    pub(crate) fn unlimited_kdf() -> Self {
+        Self {
+            max_kdf_memory: u64::MAX,
+            max_kdf_time: u32::MAX,
+            ..Self::default()
+        }
+    }
 
-use crate::crypto::kdf;
-use crate::format::header::Header;
-use crate::format::stream::StreamDecryptor;
-use crate::fs::atomic::write_atomic;
+    /// 派生密钥之前检查 Header 中的 KDF 参数不超过 `max_kdf_memory` 与 `max_kdf_time`
+    pub(crate) fn check_kdf(&self, kdf: &KdfAlgorithm) -> std::io::Result<()> {
+        if kdf.memory_bytes() > self.max_kdf_memory || kdf.passes() > self.max_kdf_time {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                SealVaultError::KdfResourceExhausted,
+            ));
+        }
+        Ok(())
+    }
+
+    /// 在派生密钥之前按选项检查 Header
+    pub(crate) fn check_header(&self, header: &Header) -> std::io::Result<()> {
+        match self.expected_algorithm {
+            Some(expected) if expected != header.algorithm => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                SealVaultError::UnexpectedAlgorithm {
+                    expected,
+                    found: header.algorithm,
+                },
+            )),
+            _ => Ok(()),
+        }
+    }
 
+    #[cfg(feature = "fs")]
+    /// `create_parents` 为 `false` 时检查输出文件的父目录已存在
+    fn check_output_parent(&self, output_path: &Path) -> std::io::Result<()> {
+        if self.create_parents {
+            return Ok(());
+        }
+        match output_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() && !parent.is_dir() => {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!("output directory does not exist: {}", parent.display()),
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
 /// 使用密码解密文件
-pub fn decrypt_file(input_path: &Path, output_path: &Path, password: &str) -> std::io::Result<()> {
-    // ---------- 打开输入文件 ----------
-    let input = File::open(input_path)?;
+///
+/// 明文先写入同目录的临时文件，所有 chunk 认证通过后才原子替换 `output_path`；
+/// 任一 chunk 认证失败（密码错误、数据损坏或截断）时不会留下任何部分明文。
+/// `output_path` 的父目录不存在时与加密一样逐级创建（见 `DecryptOptions::create_parents`）。
+/// `output_path` 与 `input_path` 指向同一文件时返回 `InvalidInput`。
+pub fn decrypt_file(input_path: &Path, output_path: &Path, password: &[u8]) -> std::io::Result<()> {
+    decrypt_file_with_options(
+        input_path,
+        output_path,
+        password,
+        &DecryptOptions::default(),
+    )
+}
 
-    let mut reader = BufReader::new(input);
+#[cfg(feature = "fs")]
+/// 按 `DecryptOptions` 解密文件，语义同 `decrypt_file`
+pub fn decrypt_file_with_options(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &DecryptOptions,
+) -> std::io::Result<()> {
+    decrypt_file_reusing_key(input_path, output_path, password, None, options, &mut None)?;
+    Ok(())
+}
 
-    // ---------- 读取并校验 Header ----------
-    let header = Header::read(&mut reader)?;
+#[cfg(feature = "fs")]
+/// 解密文件（语义同 `decrypt_file`），返回明文字节数、密文字节数与数据 chunk 数
+pub fn decrypt_file_with_stats(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+) -> std::io::Result<Stats> {
+    let (_, _, stats) = decrypt_file_reusing_key(
+        input_path,
+        output_path,
+        password,
+        None,
+        &DecryptOptions::default(),
+        &mut None,
+    )?;
+    Ok(stats)
+}
+
+#[cfg(feature = "fs")]
+/// 使用密码与 keyfile 解密文件（密码可为空），语义同 `decrypt_file`
+///
+/// 文件加密时未使用 keyfile 则返回 `InvalidInput`；keyfile 不对时与密码错误一样
+/// 报告 `InvalidPasswordOrFile`。
+pub fn decrypt_file_with_keyfile(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    keyfile: &Path,
+) -> std::io::Result<()> {
+    decrypt_file_reusing_key(
+        input_path,
+        output_path,
+        password,
+        Some(keyfile),
+        &DecryptOptions::default(),
+        &mut None,
+    )?;
+    Ok(())
+}
+
+#[cfg(feature = "fs")]
+/// 解密文件（语义同 `decrypt_file`），并返回 Header 元数据
+///
+/// 文件携带明文摘要时，摘要已在替换输出文件之前与写出的明文比对一致，
+/// 返回值的 `plaintext_digest` 为该摘要，可直接与外部公布的 BLAKE3 校验和比较。
+pub fn decrypt_file_with_info(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+) -> std::io::Result<FileInfo> {
+    let (header, summary, _) = decrypt_file_reusing_key(
+        input_path,
+        output_path,
+        password,
+        None,
+        &DecryptOptions::default(),
+        &mut None,
+    )?;
+
+    Ok(FileInfo {
+        plaintext_digest: header.plaintext_digest.then_some(summary.blake3),
+        ..FileInfo::from_header(&header)
+    })
+}
 
-    // ---------- KDF 派生密钥 ----------
-    let salt_string = SaltString::encode_b64(&header.salt)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+#[cfg(feature = "fs")]
+/// 解密文件到 `output_dir` 中，使用加密时保存的原始文件名，返回写出的路径
+///
+/// 语义同 `decrypt_file`（原子写出、已有同名文件会被替换），`output_dir` 不存在时逐级创建。
+/// 文件未保存文件名时在派生密钥之前返回 `InvalidInput`；保存的文件名不是单个普通路径组件
+/// （含路径分隔符、`.` / `..` 等）时返回 `InvalidData`，不会写出到 `output_dir` 之外。
+pub fn decrypt_file_to_dir(
+    input_path: &Path,
+    output_dir: &Path,
+    password: &[u8],
+) -> std::io::Result<PathBuf> {
+    let input = File::open(input_path)?;
+    let mut reader = BufReader::with_capacity(DEFAULT_IO_BUFFER_SIZE, input);
 
-    let key = kdf::derive_key(password, &salt_string)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+    // ---------- 读取 Header 与文件名帧 ----------
+    let header = Header::read(&mut reader)?;
+    if !header.file_name {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "file does not store its original file name",
+        ));
+    }
+    let mut decryptor = decryptor_for_header(
+        &header,
+        password,
+        None,
+        &DecryptOptions::default(),
+        &mut None,
+    )?;
+    decryptor.read_file_name(&mut reader)?;
+    let name = decryptor
+        .file_name()
+        .ok_or_else(|| std::io::Error::other(SealVaultError::Internal))?;
+    let output_path = output_dir.join(file_name_from_bytes(name)?);
+    ensure_distinct_paths(input_path, &output_path)?;
 
     // ---------- Stream 解密 ----------
-    let mut decryptor = StreamDecryptor::new(&key, header.algorithm, header.base_nonce);
+    write_atomic(&output_path, |output| {
+        let mut writer = decrypt_body_with(
+            &mut reader,
+            &header,
+            &mut decryptor,
+            BufWriter::with_capacity(DEFAULT_IO_BUFFER_SIZE, output),
+        )?;
+        writer.flush()
+    })?;
+
+    Ok(output_path)
+}
+
+#[cfg(feature = "fs")]
+/// 把文件名帧中的字节还原为单个普通路径组件，其余情况视为数据损坏
+fn file_name_from_bytes(bytes: &[u8]) -> std::io::Result<PathBuf> {
+    let name = PathBuf::from(os_str_from_bytes(bytes)?);
+    let mut components = name.components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) if name.as_os_str().len() == bytes.len() => Ok(name),
+        _ => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            SealVaultError::CorruptedData,
+        )),
+    }
+}
+
+#[cfg(feature = "fs")]
+/// 解密文件，Header 中的 salt 与参数和 `cached` 相同时跳过密钥派生
+///
+/// 派生出的新密钥会替换 `cached`，供同一批次的下一个文件使用。
+/// 返回 Header、写出明文的长度与 BLAKE3 摘要（供目录 manifest 校验）以及处理统计。
+pub(crate) fn decrypt_file_reusing_key(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    keyfile: Option<&Path>,
+    options: &DecryptOptions,
+    cached: &mut Option<DerivedKey>,
+) -> std::io::Result<(Header, PlaintextSummary, Stats)> {
+    ensure_distinct_paths(input_path, output_path)?;
+    options.check_output_parent(output_path)?;
 
+    // ---------- 打开输入文件 ----------
+    let input = File::open(input_path)?;
+
+    let mut reader = BufReader::with_capacity(DEFAULT_IO_BUFFER_SIZE, input);
+
+    // ---------- 读取 Header 并准备 StreamDecryptor ----------
+    let header = Header::read(&mut reader)?;
+    options.check_header(&header)?;
+    check_stream_len(&header, &mut reader)?;
+    let mut decryptor = decryptor_for_header(&header, password, keyfile, options, cached)?;
+    if options.reject_trailing_data {
+        decryptor = decryptor.with_trailing_data_rejected();
+    }
+
+    // ---------- Stream 解密 ----------
+    let mut summary = None;
     write_atomic(output_path, |output| {
-        let mut writer = BufWriter::new(output);
-        decryptor.decrypt(&mut reader, &mut writer)?;
+        let mut writer = decrypt_body_with(
+            &mut reader,
+            &header,
+            &mut decryptor,
+            Blake3Tap::new(OutputLimit::new(
+                BufWriter::with_capacity(DEFAULT_IO_BUFFER_SIZE, output),
+                options.max_output_bytes,
+            )),
+        )?;
         writer.flush()?;
+        summary = Some(writer.summary());
         Ok(())
     })?;
 
+    let summary = summary.ok_or_else(|| std::io::Error::other(SealVaultError::Internal))?;
+    let stats = Stats {
+        plaintext_bytes: summary.len,
+        // 已读取到 final chunk 末尾的位置，即 Header 与 Stream 的总长度
+        ciphertext_bytes: reader.stream_position()?,
+        chunks: decryptor.data_chunks(),
+    };
+    Ok((header, summary, stats))
+}
+
+#[cfg(feature = "fs")]
+/// Header 记录了数据 chunk 总数时，在派生密钥之前由文件长度发现截断
+///
+/// Header 之后的剩余长度短于 `Header::min_stream_len` 时返回 `UnexpectedEof`
+/// （负载为 `CorruptedData`）；未记录 chunk 总数的文件由 final chunk 发现截断。
+fn check_stream_len(header: &Header, reader: &mut BufReader<File>) -> std::io::Result<()> {
+    let Some(min_len) = header.min_stream_len() else {
+        return Ok(());
+    };
+    let remaining = reader
+        .get_ref()
+        .metadata()?
+        .len()
+        .saturating_sub(reader.stream_position()?);
+    if remaining < min_len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            SealVaultError::CorruptedData,
+        ));
+    }
+    Ok(())
+}
+
+/// 限制写出总字节数的 writer 包装（`DecryptOptions::max_output_bytes`）
+struct OutputLimit<W> {
+    inner: W,
+    /// 剩余可写字节数，`None` 为不限制
+    remaining: Option<u64>,
+}
+
+impl<W: Write> OutputLimit<W> {
+    fn new(inner: W, limit: Option<u64>) -> Self {
+        Self {
+            inner,
+            remaining: limit,
+        }
+    }
+}
+
+impl<W: Write> Write for OutputLimit<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self
+            .remaining
+            .is_some_and(|remaining| buf.len() as u64 > remaining)
+        {
+            return Err(std::io::Error::other(SealVaultError::OutputLimitExceeded));
+        }
+
+        let written = self.inner.write(buf)?;
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= written as u64;
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// .svlt 文件 Header 中无需密码即可读取的元数据（不含密钥）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileInfo {
+    /// 格式版本
+    pub version: u8,
+    /// AEAD 算法
+    pub algorithm: AeadAlgorithm,
+    /// 明文 chunk 大小（字节）
+    pub chunk_size: u32,
+    /// KDF salt
+    pub salt: [u8; SALT_SIZE],
+    /// 文件是否携带明文 BLAKE3 摘要（Header 标志位）
+    pub has_plaintext_digest: bool,
+    /// 已认证的明文 BLAKE3 摘要
+    ///
+    /// 摘要加密在 Stream 中，只有 `decrypt_file_with_info` 解密成功后才会填入；
+    /// `inspect_file` 始终为 `None`。
+    pub plaintext_digest: Option<[u8; PLAINTEXT_DIGEST_SIZE]>,
+    /// 长度隐藏填充的块大小（未填充时为 `None`）
+    pub padding: Option<Padding>,
+    /// 文件是否加密保存了原始文件名（Header 标志位；文件名本身需解密才能读取）
+    pub has_file_name: bool,
+    /// Header 中的注释（明文保存、经认证，见 `EncryptOptions::header_comment`；没有时为空）
+    ///
+    /// `inspect_file` 读取时尚未认证，只有解密成功才能确认它未被改动。
+    pub header_comment: Vec<u8>,
+    /// Header 记录的数据 chunk 总数（v2 文件输入；流式或压缩加密的文件为 `None`）
+    pub total_chunks: Option<u64>,
+}
+
+impl FileInfo {
+    #[cfg(feature = "fs")]
+    fn from_header(header: &Header) -> Self {
+        Self {
+            version: header.version,
+            algorithm: header.algorithm,
+            chunk_size: header.chunk_size,
+            salt: header.salt,
+            has_plaintext_digest: header.plaintext_digest,
+            plaintext_digest: None,
+            padding: header.padding,
+            has_file_name: header.file_name,
+            header_comment: header.comment.clone(),
+            total_chunks: header.total_chunks,
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
+/// 只读取文件开头的 Header，返回格式元数据，不需要密码、不派生密钥
+///
+/// 错误负载同 `Header::read`：不是 SealVault 文件（magic 不匹配、文件过短）为
+/// `NotSealVaultFile`，未知版本为 `UnsupportedVersion`，Header 字段非法时为 `CorruptedData`。
+pub fn inspect_file(input_path: &Path) -> std::io::Result<FileInfo> {
+    let reader = File::open(input_path)?.take(MAX_HEADER_SIZE as u64);
+
+    let header = Header::read(BufReader::new(reader))?;
+
+    Ok(FileInfo::from_header(&header))
+}
+
+/// 在内存中解密 .svlt 格式的字节数据
+pub fn decrypt_bytes(data: &[u8], password: &[u8]) -> std::io::Result<Vec<u8>> {
+    decrypt_bytes_with_options(data, password, &DecryptOptions::default())
+}
+
+/// 按 `DecryptOptions` 在内存中解密 .svlt 格式的字节数据
+pub fn decrypt_bytes_with_options(
+    data: &[u8],
+    password: &[u8],
+    options: &DecryptOptions,
+) -> std::io::Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(data.len());
+    decrypt_stream_with_options(data, &mut output, password, options)?;
+    Ok(output)
+}
+
+/// 从任意 reader 读取 .svlt 数据（先读 Header），将明文写入 writer
+///
+/// 只做顺序读写，不要求 reader / writer 可 seek，可直接用于管道或网络连接。
+/// 注意：writer 会在每个 chunk 认证通过后立即收到明文，
+/// 若后续 chunk 失败，已写出的部分需要调用方自行丢弃。
+pub fn decrypt_stream<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    password: &[u8],
+) -> std::io::Result<()> {
+    decrypt_stream_with_options(reader, writer, password, &DecryptOptions::default())
+}
+
+/// 按 `DecryptOptions` 流式解密，语义同 `decrypt_stream`
+///
+/// 开启 `reject_trailing_data` 时会在 final chunk 之后再读一次 reader 确认已到 EOF，
+/// 因此不适合 .svlt 数据之后还有其他内容的连接；超出 `max_output_bytes` 时
+/// 已写出的明文同样需要调用方自行丢弃。
+pub fn decrypt_stream_with_options<R: Read, W: Write>(
+    mut reader: R,
+    writer: W,
+    password: &[u8],
+    options: &DecryptOptions,
+) -> std::io::Result<()> {
+    let header = Header::read(&mut reader)?;
+    options.check_header(&header)?;
+    let mut decryptor = decryptor_for_header(&header, password, None, options, &mut None)?;
+    if options.reject_trailing_data {
+        decryptor = decryptor.with_trailing_data_rejected();
+    }
+    let mut writer = decrypt_body_with(
+        reader,
+        &header,
+        &mut decryptor,
+        OutputLimit::new(writer, options.max_output_bytes),
+    )?;
+    writer.flush()
+}
+
+/// 解密紧跟在 Header 之后的 Stream，将明文写入 writer
+///
+/// 供需要在解析 Header 与解密正文之间做决定的调用方使用：先用 `Header::read`
+/// 读取 Header（reader 随即位于 Stream 起始处），检查算法、版本等字段后再调用本函数。
+/// 密钥按 `header.salt` 与 `header.kdf` 重新派生，AEAD 算法与压缩编码取自 Header；
+/// `header` 必须是刚从同一个 reader 读出的 Header，否则第一个 chunk 即认证失败。
+/// Header 标记需要 keyfile 时返回携带 `SealVaultError::KeyfileRequired` 的错误。
+/// 与 `decrypt_stream` 相同，后续 chunk 失败时已写出的明文需要调用方自行丢弃。
+pub fn decrypt_body<R: Read, W: Write>(
+    reader: R,
+    header: &Header,
+    password: &[u8],
+    mut writer: W,
+) -> std::io::Result<()> {
+    let mut decryptor = decryptor_for_header(
+        header,
+        password,
+        None,
+        &DecryptOptions::default(),
+        &mut None,
+    )?;
+    decrypt_body_with(reader, header, &mut decryptor, &mut writer)?;
+    writer.flush()
+}
+
+/// 用已准备好的 StreamDecryptor 解密 Stream，去除填充并按 Header 的压缩编码解压后写入 writer
+///
+/// Header 要求明文摘要时，对解压后的明文计算 BLAKE3 并与 final chunk 中的摘要比对，
+/// 不一致时返回携带 `SealVaultError::CorruptedData` 的错误。
+/// 返回内层 writer，调用方负责 flush。
+fn decrypt_body_with<R: Read, W: Write>(
+    reader: R,
+    header: &Header,
+    decryptor: &mut StreamDecryptor,
+    writer: W,
+) -> std::io::Result<W> {
+    if !header.plaintext_digest {
+        let mut output = Unpadder::new(header.codec.decompress(writer)?, header.padding);
+        decryptor.decrypt(reader, &mut output)?;
+        return output.finish(decryptor.padding_len())?.finish();
+    }
+
+    let output = header.codec.decompress(Blake3Tap::new(writer))?;
+    let mut output = Unpadder::new(output, header.padding);
+    decryptor.decrypt(reader, &mut output)?;
+    let writer = output.finish(decryptor.padding_len())?.finish()?;
+
+    // 每个 chunk 均已认证，摘要不一致只可能来自持有密钥的一方写入了错误的摘要
+    if decryptor.plaintext_digest() != Some(writer.summary().blake3) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            SealVaultError::CorruptedData,
+        ));
+    }
+    Ok(writer.into_inner())
+}
+
+/// 逐 chunk 解密、去除填充并解压 Stream 的 Reader，供 `SealVaultReader` 与 `reencrypt_file` 使用
+///
+/// 每次只解密一个 chunk，解压结果暂存在内存中，读完后即清零。
+/// 读到 final chunk 时，若 Header 要求明文摘要，则与已读出的明文比对。
+pub(crate) struct DecryptedReader<R> {
+    reader: R,
+    decryptor: StreamDecryptor,
+    /// 去填充与解压器；读到 final chunk 后结束解压，剩余数据移入 `tail`
+    output: Option<Unpadder<Decompressor<Blake3Tap<Vec<u8>>>>>,
+    expect_digest: bool,
+    tail: Vec<u8>,
+    pos: usize,
+}
+
+impl<R> DecryptedReader<R> {
+    /// `reader` 须位于 Stream 起始处，`decryptor` 为按同一 `header` 准备的解密器
+    pub(crate) fn new(
+        reader: R,
+        header: &Header,
+        decryptor: StreamDecryptor,
+    ) -> std::io::Result<Self> {
+        let output = header.codec.decompress(Blake3Tap::new(Vec::new()))?;
+        Ok(Self {
+            output: Some(Unpadder::new(output, header.padding)),
+            reader,
+            decryptor,
+            expect_digest: header.plaintext_digest,
+            tail: Vec::new(),
+            pos: 0,
+        })
+    }
+
+    fn buffered(&mut self) -> &mut Vec<u8> {
+        match &mut self.output {
+            Some(output) => output.get_mut().get_mut().get_mut(),
+            None => &mut self.tail,
+        }
+    }
+}
+
+impl<R: Read> Read for DecryptedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            let pos = self.pos;
+            let buffered = self.buffered();
+            if pos < buffered.len() {
+                let n = buf.len().min(buffered.len() - pos);
+                buf[..n].copy_from_slice(&buffered[pos..pos + n]);
+                self.pos += n;
+                return Ok(n);
+            }
+            buffered.zeroize();
+            self.pos = 0;
+
+            let Some(output) = self.output.as_mut() else {
+                return Ok(0);
+            };
+            match self.decryptor.read_chunk(&mut self.reader)? {
+                // 解密出的 chunk 为 Zeroizing，写入解压器后即清零
+                Some(chunk) => output.write_all(&chunk)?,
+                None => {
+                    let output = self.output.take().expect("decompressor present");
+                    let tap = output.finish(self.decryptor.padding_len())?.finish()?;
+                    if self.expect_digest
+                        && self.decryptor.plaintext_digest() != Some(tap.summary().blake3)
+                    {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            SealVaultError::CorruptedData,
+                        ));
+                    }
+                    self.tail = tap.into_inner();
+                }
+            }
+        }
+    }
+}
+
+impl<R> Drop for DecryptedReader<R> {
+    fn drop(&mut self) {
+        self.buffered().zeroize();
+    }
+}
+
+#[cfg(feature = "fs")]
+/// 校验 .svlt 文件能否用该密码完整解密，不输出任何明文
+///
+/// 每个 chunk 的 tag 都会经过认证，并要求以 final chunk 结束（截断视为损坏），
+/// 解密结果直接丢弃。v1 旧格式没有结束标记，无法发现整块截断。
+pub fn verify_file(input_path: &Path, password: &[u8]) -> std::io::Result<()> {
+    verify_file_reusing_key(input_path, password, None, &mut None)
+}
+
+#[cfg(feature = "fs")]
+/// `verify_file` 的密钥复用版本
+///
+/// Header 中的 salt 与参数和 `cached` 相同时跳过密钥派生，语义同 `decrypt_file_reusing_key`。
+pub(crate) fn verify_file_reusing_key(
+    input_path: &Path,
+    password: &[u8],
+    keyfile: Option<&Path>,
+    cached: &mut Option<DerivedKey>,
+) -> std::io::Result<()> {
+    let input = File::open(input_path)?;
+    let mut reader = BufReader::new(input);
+
+    let header = Header::read(&mut reader)?;
+    check_stream_len(&header, &mut reader)?;
+    let mut decryptor = decryptor_for_header(
+        &header,
+        password,
+        keyfile,
+        &DecryptOptions::default(),
+        cached,
+    )?;
+    decrypt_body_with(reader, &header, &mut decryptor, std::io::sink())?;
+    Ok(())
+}
+
+#[cfg(feature = "fs")]
+/// 只解密第一个 chunk，快速判断密码是否正确，不写出明文
+///
+/// - 第一个 chunk（空文件为 final chunk）认证通过时返回 `Ok(true)`；
+/// - 认证失败时返回 `Ok(false)`：密码错误，或第一个 chunk 已损坏，二者无法区分；
+/// - 读取失败、不是 SealVault 文件、需要 keyfile 等情况返回错误。
+///
+/// 其余 chunk 不会被读取，因此不能发现后续数据的损坏或截断，需要时请使用 `verify_file`。
+/// v1 旧格式的空文件没有任何 chunk，无法判断，始终返回 `Ok(true)`。
+pub fn check_password_file(input_path: &Path, password: &[u8]) -> std::io::Result<bool> {
+    let input = File::open(input_path)?;
+    let mut reader = BufReader::new(input);
+
+    let header = Header::read(&mut reader)?;
+    let mut decryptor = decryptor_for_header(
+        &header,
+        password,
+        None,
+        &DecryptOptions::default(),
+        &mut None,
+    )?;
+    match decryptor.read_chunk(&mut reader) {
+        Ok(_) => Ok(true),
+        Err(e)
+            if e.get_ref()
+                .and_then(|inner| inner.downcast_ref::<SealVaultError>())
+                .is_some_and(|inner| matches!(inner, SealVaultError::InvalidPasswordOrFile)) =>
+        {
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(feature = "fs")]
+/// 可断点续做的文件解密
+///
+/// 与 `decrypt_file` 不同，输出先写入 `<output>.svpart`，并按间隔在
+/// `<output>.svckpt` 记录已完整写出的 chunk 数与输出偏移：
+/// - 中途失败时保留两者，下次以 `resume` 调用即可从 checkpoint 继续；
+/// - 恢复时会丢弃 checkpoint 之后写出的内容，并重新校验已跳过的 chunk；
+/// - 全部成功后才把 `.svpart` 替换为最终输出，并删除 checkpoint。
+///
+/// 注意：`.svpart` 中只会包含已通过认证的明文，但失败后它会保留在磁盘上，
+/// 调用方放弃恢复时需要自行删除。压缩或填充过的文件不支持断点续做，返回 `InvalidInput`。
+/// Header 要求明文摘要时，对完整输出计算 BLAKE3 并与 final chunk 中的摘要比对：
+/// 恢复时先重新读取 `.svpart` 中保留的前缀，不一致时返回携带 `SealVaultError::CorruptedData` 的错误。
+/// `.svpart` 不跟随符号链接：从头开始时先删除同名文件再以 `create_new` 创建，恢复时拒绝符号链接。
+pub fn decrypt_file_with_checkpoint(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &CheckpointOptions,
+) -> std::io::Result<()> {
+    if options.interval_chunks == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "checkpoint interval must be greater than zero",
+        ));
+    }
+    ensure_distinct_paths(input_path, output_path)?;
+
+    let partial_path = with_suffix(output_path, PARTIAL_EXT);
+    let checkpoint_path = with_suffix(output_path, CHECKPOINT_EXT);
+
+    let input = File::open(input_path)?;
+    let mut reader = BufReader::new(input);
+    let (header, mut decryptor) = open_for_decrypt(&mut reader, password)?;
+
+    // checkpoint 按 chunk 记录输出偏移，压缩后 chunk 与输出不再一一对应
+    if header.codec != Codec::None {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "checkpointed decryption does not support compressed files",
+        ));
+    }
+    // 填充只有读到 final chunk 才能确定，无法按 chunk 直接写出
+    if header.padding.is_some() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "checkpointed decryption does not support padded files",
+        ));
+    }
+
+    // ---------- 确定恢复位置 ----------
+    let checkpoint = if options.resume {
+        Checkpoint::load(&checkpoint_path)?.filter(|c| c.matches(&header))
+    } else {
+        None
+    };
+    let shorter_than_checkpoint = || {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "partial output is shorter than checkpoint",
+        )
+    };
+    // `.svpart` 不跟随符号链接：恢复时拒绝链接，从头开始时删除同名文件后以 `create_new` 创建
+    let (skip_chunks, output_offset, mut partial) = match checkpoint {
+        Some(checkpoint) => {
+            let partial = match open_existing(&partial_path) {
+                Ok(file) => file,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    return Err(shorter_than_checkpoint());
+                }
+                Err(e) => return Err(e),
+            };
+            (checkpoint.chunk_index, checkpoint.output_offset, partial)
+        }
+        None => (0, 0, create_fresh(&partial_path)?),
+    };
+
+    if partial.metadata()?.len() < output_offset {
+        return Err(shorter_than_checkpoint());
+    }
+    partial.set_len(output_offset)?;
+
+    // 明文摘要覆盖完整输出，恢复时保留的前缀需要重新读出计算
+    let mut digest = header.plaintext_digest.then(blake3::Hasher::new);
+    if let Some(hasher) = digest.as_mut() {
+        partial.seek(SeekFrom::Start(0))?;
+        std::io::copy(&mut (&partial).take(output_offset), hasher)?;
+    }
+    partial.seek(SeekFrom::End(0))?;
+
+    // checkpoint 记录的 chunk 数含文件名帧（若有），跳过时只计数据 chunk
+    decryptor.read_file_name(&mut reader)?;
+    decryptor.skip_output_chunks(
+        &mut reader,
+        skip_chunks.saturating_sub(decryptor.chunk_index()),
+    )?;
+
+    // ---------- 继续解密并定期记录 checkpoint ----------
+    let mut written = output_offset;
+    let mut writer = BufWriter::new(partial);
+    while let Some(plaintext) = decryptor.read_chunk(&mut reader)? {
+        writer.write_all(&plaintext)?;
+        written += plaintext.len() as u64;
+        if let Some(hasher) = digest.as_mut() {
+            hasher.update(&plaintext);
+        }
+
+        if decryptor.chunk_index() % options.interval_chunks == 0 {
+            writer.flush()?;
+            writer.get_ref().sync_data()?;
+            Checkpoint::new(&header, decryptor.chunk_index(), written).store(&checkpoint_path)?;
+        }
+    }
+
+    // 每个 chunk 均已认证，摘要不一致说明保留的前缀被改动，或写入方写入了错误的摘要
+    if let Some(hasher) = digest
+        && decryptor.plaintext_digest() != Some(*hasher.finalize().as_bytes())
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            SealVaultError::CorruptedData,
+        ));
+    }
+
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    drop(writer);
+
+    replace_file(&partial_path, output_path)?;
+
+    match std::fs::remove_file(&checkpoint_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(feature = "fs")]
+/// 拒绝输入与输出指向同一个文件（规范化后比较，可识别符号链接与 `..`）
+///
+/// 同一文件时输出会在读取尚未完成时替换输入，返回 `InvalidInput`；
+/// 输出尚不存在或输入无法规范化时不做判断，交给后续打开文件时报告。
+pub(crate) fn ensure_distinct_paths(input_path: &Path, output_path: &Path) -> std::io::Result<()> {
+    let (Ok(input), Ok(output)) = (input_path.canonicalize(), output_path.canonicalize()) else {
+        return Ok(());
+    };
+    if input == output {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("input and output refer to the same file: {output_path:?}"),
+        ));
+    }
     Ok(())
 }
+
+#[cfg(feature = "fs")]
+pub(crate) fn with_suffix(path: &Path, ext: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(ext);
+    PathBuf::from(name)
+}
+
+/// 为外部 stream 准备解密：读取并校验 Header，派生密钥并返回 StreamDecryptor
+///
+/// 返回后 reader 正好位于 Stream 起始处，调用方可使用
+/// `StreamDecryptor::decrypt` 或 `StreamDecryptor::read_chunk` 继续读取。
+/// `Header.codec` 不为 `Codec::None` 时，StreamDecryptor 输出的是压缩数据，需调用方自行解压。
+pub fn open_for_decrypt<R: Read>(
+    reader: R,
+    password: &[u8],
+) -> std::io::Result<(Header, StreamDecryptor)> {
+    open_for_decrypt_reusing_key(reader, password, None, &mut None)
+}
+
+/// `open_for_decrypt` 的密钥复用版本，语义同 `decrypt_file_reusing_key`
+pub(crate) fn open_for_decrypt_reusing_key<R: Read>(
+    mut reader: R,
+    password: &[u8],
+    keyfile: Option<&Path>,
+    cached: &mut Option<DerivedKey>,
+) -> std::io::Result<(Header, StreamDecryptor)> {
+    // ---------- 读取并校验 Header ----------
+    let header = Header::read(&mut reader)?;
+    let decryptor = decryptor_for_header(
+        &header,
+        password,
+        keyfile,
+        &DecryptOptions::default(),
+        cached,
+    )?;
+
+    Ok((header, decryptor))
+}
+
+/// 按 Header 中的 salt、KDF 与 keyfile 标志派生密钥，构造 StreamDecryptor
+///
+/// Header 标记需要 keyfile 而 `keyfile` 为 `None` 时返回携带
+/// `SealVaultError::KeyfileRequired` 的错误；反之提供了多余的 keyfile 时返回 `InvalidInput`。
+/// 需要派生密钥时先按 `options` 的 KDF 上限检查 Header 参数（复用 `cached` 时无需派生，不检查）。
+pub(crate) fn decryptor_for_header(
+    header: &Header,
+    password: &[u8],
+    keyfile: Option<&Path>,
+    options: &DecryptOptions,
+    cached: &mut Option<DerivedKey>,
+) -> std::io::Result<StreamDecryptor> {
+    // ---------- 按 Header 标志确定 KDF 输入（密码或 密码 || keyfile 摘要） ----------
+    let combined;
+    let secret = match (header.keyfile_required, keyfile) {
+        (true, Some(keyfile)) => {
+            combined = combine_with_keyfile(password, keyfile)?;
+            combined.as_slice()
+        }
+        (false, None) => password,
+        (true, None) => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                SealVaultError::KeyfileRequired,
+            ));
+        }
+        (false, Some(_)) => {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "file was not encrypted with a keyfile",
+            ));
+        }
+    };
+
+    // ---------- KDF 派生密钥（算法与参数来自 Header，salt 与 KDF 相同则复用） ----------
+    let derived = match cached.take() {
+        Some(key) if key.matches(&header.salt, &header.kdf, header.salt_encoding()) => key,
+        _ => {
+            options.check_kdf(&header.kdf)?;
+            DerivedKey::derive(secret, header.salt, header.kdf, header.salt_encoding())?
+        }
+    };
+
+    let decryptor = StreamDecryptor::for_header(&derived.key, header);
+    *cached = Some(derived);
+
+    Ok(decryptor)
+}
@@ -12,22 +12,257 @@
 //! - 不处理文件夹
 //! - 不做 UI / 密码输入
 
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use rand::{CryptoRng, RngCore, rngs::OsRng};
+
+use crate::algorithm::AeadAlgorithm;
+use crate::compression::{Codec, Compression};
+use crate::crypto::kdf::{DerivedKey, KdfAlgorithm, MAX_M_COST};
+use crate::crypto::keyfile::combine_with_keyfile;
+use crate::crypto::password::PasswordPolicy;
+use crate::error::SealVaultError;
+use crate::format::header::{BASE_NONCE_SIZE, Header, MAX_COMMENT_LEN};
+use crate::format::stream::{DEFAULT_CHUNK_SIZE, DEFAULT_IO_BUFFER_SIZE, StreamEncryptor};
+use crate::padding::Padding;
+
+#[cfg(feature = "fs")]
+use std::cell::{Cell, RefCell};
+#[cfg(feature = "fs")]
 use std::fs::File;
-use std::io::{BufReader, BufWriter, Write};
+#[cfg(feature = "fs")]
+use std::io::{BufReader, BufWriter, Seek, SeekFrom};
+#[cfg(feature = "fs")]
 use std::path::Path;
 
-use argon2::password_hash::SaltString;
-use rand::{RngCore, rngs::OsRng};
+#[cfg(feature = "fs")]
+use memmap2::Mmap;
+#[cfg(feature = "fs")]
+use zeroize::Zeroizing;
 
-use crate::algorithm::AeadAlgorithm;
-use crate::crypto::kdf;
-use crate::format::header::{BASE_NONCE_SIZE, Header, SALT_SIZE};
-use crate::format::stream::{DEFAULT_CHUNK_SIZE, StreamEncryptor};
-use crate::fs::atomic::write_atomic;
+#[cfg(feature = "fs")]
+use crate::crypto::checksum::{self, Blake3Tap, HashingReader, PlaintextSummary};
+#[cfg(feature = "fs")]
+use crate::decrypt::{
+    CHECKPOINT_EXT, CheckpointOptions, DecryptOptions, DecryptedReader, PARTIAL_EXT,
+    decryptor_for_header, ensure_distinct_paths, open_for_decrypt, verify_file_reusing_key,
+    with_suffix,
+};
+#[cfg(feature = "fs")]
+use crate::format::checkpoint::{Checkpoint, InputFingerprint, input_prefix_hasher};
+#[cfg(feature = "fs")]
+use crate::format::manifest::os_str_bytes;
+#[cfg(feature = "fs")]
+use crate::format::stream::{MAX_FILE_NAME_LEN, PLAINTEXT_DIGEST_SIZE, read_full};
+#[cfg(feature = "fs")]
+use crate::fs::atomic::{
+    create_fresh, open_existing, replace_file, write_atomic, write_atomic_checked,
+};
+#[cfg(feature = "fs")]
+use crate::fs::wipe::secure_delete;
+#[cfg(feature = "fs")]
+use crate::progress::{ProgressEvent, Stats};
+
+/// `EncryptOptions::auto_for` 的 chunk 大小分档：`(输入长度上限（不含）, chunk 大小)`
+///
+/// 输入不小于最后一档的上限时使用 `AUTO_MAX_CHUNK_SIZE`。
+const AUTO_CHUNK_BANDS: [(u64, usize); 3] = [
+    (1024 * 1024, 16 * 1024),
+    (64 * 1024 * 1024, DEFAULT_CHUNK_SIZE),
+    (1024 * 1024 * 1024, 256 * 1024),
+];
+
+/// `EncryptOptions::auto_for` 为 GiB 级输入选择的 chunk 大小：1 MiB
+const AUTO_MAX_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// 文件加密选项
+#[derive(Debug, Clone)]
+pub struct EncryptOptions {
+    /// AEAD 算法
+    pub algorithm: AeadAlgorithm,
+    /// 明文 chunk 大小（字节），写入 `Header.chunk_size`
+    ///
+    /// 较大的 chunk 减少认证标签开销，较小的 chunk 降低流式传输延迟。
+    pub chunk_size: usize,
+    /// 目标文件已存在时是否覆盖（默认 true）
+    ///
+    /// 为 false 时，在读取输入、派生密钥之前返回携带
+    /// `SealVaultError::AlreadyExists` 的错误，已有文件保持不变。
+    pub overwrite: bool,
+    /// 加密前的流式压缩（默认不压缩），编码写入 Header，解密时自动解压
+    ///
+    /// 压缩会让密文长度随明文内容变化（CRIME 类风险），
+    /// 不要对混有攻击者可控内容的数据开启，详见 `compression` 模块文档。
+    pub compression: Option<Compression>,
+    /// 密钥派生算法及参数（默认 Argon2id 默认参数），写入 Header
+    ///
+    /// 内存受限的设备可改用 `KdfAlgorithm::Scrypt` 并调低参数；
+    /// 参数越低，暴力破解密码越便宜。
+    pub kdf: KdfAlgorithm,
+    /// keyfile 路径（默认无）：设置后 KDF 输入为 `password || BLAKE3(keyfile)`，
+    /// 并在 Header 中标记解密必须提供同一个 keyfile
+    ///
+    /// 丢失或改动 keyfile 即永久丢失数据，没有任何恢复手段。
+    pub keyfile: Option<PathBuf>,
+    /// 读取输入与写出密文时的 I/O 缓冲区容量（字节，默认 `DEFAULT_IO_BUFFER_SIZE`）
+    ///
+    /// 与 `chunk_size` 相互独立，不影响输出格式；机械硬盘等场景调大可减少系统调用。
+    pub io_buffer_size: usize,
+    /// 单个文件内并行加密 chunk 的线程数（默认 1，即串行；0 表示按 CPU 数量）
+    ///
+    /// 大于 1 时由 `StreamEncryptor::encrypt_parallel` 处理，输出格式不变，
+    /// 额外内存约为 `2 * threads` 个 chunk。
+    pub threads: usize,
+    /// 是否在替换目标文件前重新读取刚写出的密文并完整解密一遍（默认 false）
+    ///
+    /// 用于归档场景：在报告成功之前发现磁盘损坏或写出错误。校验复用加密时派生的密钥，
+    /// 代价约为再读一遍密文并解密；校验失败时目标文件保持不变，返回解密时的错误
+    /// （如 `InvalidPasswordOrFile`、`CorruptedData`）。只作用于写出文件的接口，
+    /// `encrypt_bytes` / `encrypt_stream` 忽略它。
+    pub verify_after_write: bool,
+    /// 是否在输出旁写出明文 SHA-256 sidecar（`<output>.sha256`，`sha256sum` 格式，默认 false）
+    ///
+    /// 摘要在读取输入的同时计算，不会二次读取输入；密文写出（及写后校验）成功后才写 sidecar。
+    /// sidecar 以明文保存摘要，注意事项见 `crypto::checksum` 模块文档。只适用于从文件加密，
+    /// `reencrypt_file` 忽略它；断点续做的加密不支持，返回 `InvalidInput`。
+    pub checksum_sidecar: bool,
+    /// 是否在 final chunk 中写入完整明文的 BLAKE3 摘要（默认 false）
+    ///
+    /// 摘要按压缩前的原始输入计算，与 `b3sum` 的结果相同，并随 final chunk 一起认证；
+    /// 解密时自动校验，可通过 `decrypt_file_with_info` 取得。开启后加密时多一次 BLAKE3 计算。
+    pub plaintext_digest: bool,
+    /// 长度隐藏填充（默认不填充）：把送入加密的数据（压缩之后）补零到块大小的整数倍
+    ///
+    /// 块大小写入 Header，真实的填充长度随 final chunk 一起加密认证，解密时自动去除。
+    /// 密文只暴露明文长度所在的块数，代价是最多 `n` 字节的额外密文。
+    pub padding: Option<Padding>,
+    /// 是否把输入文件的原始文件名（含扩展名）加密保存在 Stream 的第一帧（默认 false）
+    ///
+    /// 文件名与数据一样经 AEAD 认证，可用 `decrypt_file_to_dir` 还原为原名；
+    /// 不在意文件名泄露时才需要它，密文长度仍会暴露文件名的字节数。
+    /// 只适用于从文件加密：`rekey_file` 沿用原文件的文件名，`reencrypt_file` 在开启时沿用
+    /// 原文件中保存的文件名（原文件没有时不保存）。
+    pub store_file_name: bool,
+    /// 写入 Header 的注释（默认为空，即不写）：明文保存，但与 Header 一起绑定到每个 chunk 的 AAD
+    ///
+    /// 适合给密文打上不需保密的标签（如备份任务 ID），无需密码即可由 `inspect_file` 读取，
+    /// 改动任一字节都会使解密失败。最长 `MAX_COMMENT_LEN`（255）字节，超出时返回 `InvalidInput`。
+    /// `rekey_file` 沿用原文件的注释，`reencrypt_file` 使用新选项中的注释。
+    pub header_comment: Vec<u8>,
+    /// 对密码的最低要求（默认拒绝空密码与只含空白的密码），设置 `keyfile` 时不检查
+    ///
+    /// 确实需要空密码时设为 `PasswordPolicy::ALLOW_ANY`。
+    pub password_policy: PasswordPolicy,
+}
+
+impl Default for EncryptOptions {
+    fn default() -> Self {
+        Self {
+            algorithm: AeadAlgorithm::XChaCha20Poly1305,
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            overwrite: true,
+            compression: None,
+            kdf: KdfAlgorithm::default(),
+            keyfile: None,
+            io_buffer_size: DEFAULT_IO_BUFFER_SIZE,
+            threads: 1,
+            verify_after_write: false,
+            checksum_sidecar: false,
+            plaintext_digest: false,
+            padding: None,
+            store_file_name: false,
+            header_comment: Vec::new(),
+            password_policy: PasswordPolicy::default(),
+        }
+    }
+}
+
+impl EncryptOptions {
+    /// 允许配置的最大 chunk 大小：1 GiB
+    pub const MAX_CHUNK_SIZE: usize = 1024 * 1024 * 1024;
+
+    /// 允许配置的最大 I/O 缓冲区容量：64 MiB
+    pub const MAX_IO_BUFFER_SIZE: usize = 64 * 1024 * 1024;
 
-#[allow(dead_code)]
+    /// 使用指定算法、其余为默认值的选项
+    pub fn with_algorithm(algorithm: AeadAlgorithm) -> Self {
+        Self {
+            algorithm,
+            ..Self::default()
+        }
+    }
+
+    /// 按输入长度（字节）选择算法与 chunk 大小，其余为默认值
+    ///
+    /// 算法固定为 XChaCha20-Poly1305（随机 nonce 空间大，且不依赖 AES 硬件加速）。
+    /// chunk 大小随输入增大：
+    /// - 小于 1 MiB：16 KiB，小文件不必为一个大 chunk 分配缓冲区；
+    /// - 1 MiB ~ 64 MiB：64 KiB（`DEFAULT_CHUNK_SIZE`）；
+    /// - 64 MiB ~ 1 GiB：256 KiB；
+    /// - 1 GiB 及以上：1 MiB，减少认证标签与长度前缀的开销以及 chunk 数量。
+    ///
+    /// chunk 大小写入 Header，解密时无需知道加密时的选择。
+    pub fn auto_for(input_len: u64) -> Self {
+        let chunk_size = AUTO_CHUNK_BANDS
+            .iter()
+            .find(|(limit, _)| input_len < *limit)
+            .map_or(AUTO_MAX_CHUNK_SIZE, |(_, chunk_size)| *chunk_size);
+
+        Self {
+            chunk_size,
+            ..Self::with_algorithm(AeadAlgorithm::XChaCha20Poly1305)
+        }
+    }
+
+    fn validate(&self) -> std::io::Result<()> {
+        if self.chunk_size == 0 || self.chunk_size > Self::MAX_CHUNK_SIZE {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "chunk size must be between 1 byte and 1 GiB",
+            ));
+        }
+        if self.io_buffer_size == 0 || self.io_buffer_size > Self::MAX_IO_BUFFER_SIZE {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "I/O buffer size must be between 1 byte and 64 MiB",
+            ));
+        }
+        if let Some(compression) = self.compression {
+            compression.validate()?;
+        }
+        if let Some(padding) = self.padding {
+            padding.validate()?;
+        }
+        if self.header_comment.len() > MAX_COMMENT_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("header comment longer than {MAX_COMMENT_LEN} bytes"),
+            ));
+        }
+        if let KdfAlgorithm::Argon2id(params) = &self.kdf
+            && params.m_cost > MAX_M_COST
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                SealVaultError::KdfResourceExhausted,
+            ));
+        }
+        if !self.kdf.is_supported() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "unsupported KDF parameters",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "fs")]
 /// 使用密码加密文件
-pub fn encrypt_file(input_path: &Path, output_path: &Path, password: &str) -> std::io::Result<()> {
+///
+/// 输出经 `write_atomic` 原子写出，`output_path` 的父目录不存在时逐级创建。
+pub fn encrypt_file(input_path: &Path, output_path: &Path, password: &[u8]) -> std::io::Result<()> {
     encrypt_file_with_algorithm(
         input_path,
         output_path,
@@ -36,48 +271,1095 @@ pub fn encrypt_file(input_path: &Path, output_path: &Path, password: &str) -> st
     )
 }
 
+#[cfg(feature = "fs")]
 pub fn encrypt_file_with_algorithm(
     input_path: &Path,
     output_path: &Path,
-    password: &str,
+    password: &[u8],
     algorithm: AeadAlgorithm,
 ) -> std::io::Result<()> {
+    encrypt_file_with_options(
+        input_path,
+        output_path,
+        password,
+        &EncryptOptions::with_algorithm(algorithm),
+    )
+}
+
+#[cfg(feature = "fs")]
+/// 按 `EncryptOptions` 加密文件
+pub fn encrypt_file_with_options(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &EncryptOptions,
+) -> std::io::Result<()> {
+    encrypt_file_with_progress(input_path, output_path, password, options, &mut |_| {})
+}
+
+#[cfg(feature = "fs")]
+/// 按 `EncryptOptions` 加密文件，每写出一个 chunk 报告一次 `BytesProcessed`
+///
+/// `BytesProcessed.total` 为开始加密时输入文件的长度。
+/// `output_path` 与 `input_path` 指向同一文件时返回 `InvalidInput`（原地更换密码请用 `rekey_file`）。
+pub fn encrypt_file_with_progress(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &EncryptOptions,
+    progress: &mut dyn FnMut(ProgressEvent),
+) -> std::io::Result<()> {
+    encrypt_file_inner(
+        input_path,
+        output_path,
+        password,
+        options,
+        &mut OsRng,
+        progress,
+        &mut |_| Ok(()),
+    )?;
+    Ok(())
+}
+
+#[cfg(feature = "fs")]
+/// 按 `EncryptOptions` 加密文件，返回明文字节数、密文字节数与数据 chunk 数
+pub fn encrypt_file_with_stats(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &EncryptOptions,
+) -> std::io::Result<Stats> {
+    encrypt_file_inner(
+        input_path,
+        output_path,
+        password,
+        options,
+        &mut OsRng,
+        &mut |_| {},
+        &mut |_| Ok(()),
+    )
+}
+
+#[cfg(feature = "fs")]
+/// 同 `encrypt_file_with_options`，但 salt 与 base_nonce 取自调用方提供的随机数源
+///
+/// 仅供测试：注入固定种子的 RNG 可得到逐字节确定的输出。生产代码必须使用 `OsRng`，
+/// 同一密码下复用 salt 与 base_nonce 会导致 nonce 重用。
+#[doc(hidden)]
+pub fn encrypt_file_with_rng(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &EncryptOptions,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> std::io::Result<()> {
+    encrypt_file_inner(
+        input_path,
+        output_path,
+        password,
+        options,
+        rng,
+        &mut |_| {},
+        &mut |_| Ok(()),
+    )?;
+    Ok(())
+}
+
+#[cfg(feature = "fs")]
+/// 按选项加密文件；开启 `verify_after_write` 时，`before_verify` 在校验前拿到临时密文路径
+fn encrypt_file_inner(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &EncryptOptions,
+    rng: &mut (impl RngCore + CryptoRng),
+    progress: &mut dyn FnMut(ProgressEvent),
+    before_verify: &mut dyn FnMut(&Path) -> std::io::Result<()>,
+) -> std::io::Result<Stats> {
+    // ---------- 校验选项 ----------
+    options.validate()?;
+    ensure_distinct_paths(input_path, output_path)?;
+    check_overwrite(output_path, options)?;
+
+    let file_name = match options.store_file_name {
+        true => Some(file_name_bytes(input_path)?),
+        false => None,
+    };
+    let sidecar_name = match options.checksum_sidecar {
+        true => Some(input_path.file_name().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "input path has no file name",
+            )
+        })?),
+        false => None,
+    };
+
     // ---------- 打开输入文件 ----------
     let input = File::open(input_path)?;
+    let metadata = input.metadata()?;
+    let total = metadata.len();
 
-    let reader = BufReader::new(input);
+    let mut reader = BufReader::with_capacity(options.io_buffer_size, input);
 
-    // ---------- 生成 salt ----------
-    let mut salt = [0u8; SALT_SIZE];
-    OsRng.fill_bytes(&mut salt);
+    // ---------- 生成 Header 与 StreamEncryptor ----------
+    let total_chunks = file_total_chunks(&metadata, options);
+    let key = derive_encryption_key(password, options, rng)?;
+    let (header, encryptor) = prepare_encryptor_with_key(&key, options, total_chunks, rng)?;
 
-    // ---------- 生成 base nonce ----------
-    let mut base_nonce = [0u8; BASE_NONCE_SIZE];
-    OsRng.fill_bytes(&mut base_nonce);
+    let encrypt = |reader: &mut dyn Read| {
+        write_encrypted(
+            reader,
+            output_path,
+            header,
+            encryptor,
+            options,
+            file_name.as_deref(),
+            total,
+            progress,
+            WriteVerification::for_options(options, password, &key, before_verify),
+        )
+    };
+    match sidecar_name {
+        None => encrypt(&mut reader),
+        // 明文 SHA-256 在输入流经加密器时同步计算，密文写出成功后才写 sidecar
+        Some(name) => {
+            let mut reader = HashingReader::new(reader);
+            let stats = encrypt(&mut reader)?;
+            checksum::write_sidecar(
+                &checksum::sidecar_path(output_path),
+                &reader.finalize(),
+                name,
+            )?;
+            Ok(stats)
+        }
+    }
+}
 
-    // ---------- KDF 派生密钥 ----------
-    let salt_string = SaltString::encode_b64(&salt)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+#[cfg(feature = "fs")]
+/// 取路径的最后一个组件作为文件名帧内容（Unix 上为原始字节，其他平台要求 UTF-8）
+fn file_name_bytes(path: &Path) -> std::io::Result<Vec<u8>> {
+    let name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "input path has no file name",
+        )
+    })?;
+    let bytes = os_str_bytes(name)?;
+    if bytes.len() > MAX_FILE_NAME_LEN {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("file name longer than {MAX_FILE_NAME_LEN} bytes"),
+        ));
+    }
+    Ok(bytes)
+}
+
+#[cfg(feature = "fs")]
+/// 更换文件密码：用旧密码逐 chunk 解密，在内存中直接以新密码重新加密
+///
+/// 新文件使用新的 salt 与 base_nonce，沿用原文件的 AEAD 算法、chunk 大小、KDF 参数、压缩编码
+/// 与填充（压缩数据与填充原样转加密，不解压）。明文只以 chunk 为单位停留在内存中并在用完后清零，
+/// 从不写入磁盘；输出经 `write_atomic` 原子写出，任一 chunk 认证失败（如旧密码错误）
+/// 时不会产生或改动输出。`output_path` 可以与 `input_path` 相同（原地更换密码）。
+/// 需要 keyfile 的文件返回 `KeyfileRequired`。
+pub fn rekey_file(
+    input_path: &Path,
+    output_path: &Path,
+    old_password: &[u8],
+    new_password: &[u8],
+) -> std::io::Result<()> {
+    let input = File::open(input_path)?;
+    let mut reader = BufReader::with_capacity(DEFAULT_IO_BUFFER_SIZE, input);
 
-    let key = kdf::derive_key(password, &salt_string)
-        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))?;
+    // ---------- 旧密钥：读取 Header 并准备解密 ----------
+    let (old_header, mut decryptor) = open_for_decrypt(&mut reader, old_password)?;
+    decryptor.read_file_name(&mut reader)?;
 
-    // ---------- Stream 加密 ----------
-    let mut encryptor = StreamEncryptor::new(&key, algorithm, base_nonce, DEFAULT_CHUNK_SIZE);
+    // ---------- 新密钥：新 salt、沿用原 KDF 参数 ----------
+    PasswordPolicy::default().check(new_password)?;
+    let options = EncryptOptions {
+        chunk_size: old_header.chunk_size as usize,
+        kdf: old_header.kdf,
+        plaintext_digest: old_header.plaintext_digest,
+        padding: old_header.padding,
+        store_file_name: old_header.file_name,
+        header_comment: old_header.comment.clone(),
+        ..EncryptOptions::with_algorithm(old_header.algorithm)
+    };
+    let key = DerivedKey::generate_with(new_password, options.kdf)?;
+    // 数据 chunk 一一对应地转加密，chunk 总数不变
+    let (header, mut encryptor) = prepare_encryptor_with_codec(
+        &key,
+        &options,
+        old_header.codec,
+        old_header.total_chunks,
+        &mut OsRng,
+    )?;
 
     write_atomic(output_path, |output| {
-        let mut writer = BufWriter::new(output);
+        let mut writer = BufWriter::with_capacity(DEFAULT_IO_BUFFER_SIZE, output);
+        header.write(&mut writer)?;
+        if let Some(name) = decryptor.file_name() {
+            encryptor.write_file_name(name, &mut writer)?;
+        }
+
+        // 解密出的 chunk 为 Zeroizing，写入新 stream 后即清零
+        while let Some(plaintext) = decryptor.read_chunk(&mut reader)? {
+            encryptor.write_chunk(&plaintext, &mut writer)?;
+        }
+        // 明文与填充均未变，已认证的摘要与填充长度原样带入新文件
+        encryptor.finish_with_trailer(
+            &mut writer,
+            decryptor.plaintext_digest().as_ref(),
+            decryptor.padding_len(),
+        )?;
+
+        writer.flush()
+    })
+}
+
+#[cfg(feature = "fs")]
+/// 以新的 `EncryptOptions` 重新加密文件，密码不变
+///
+/// 用于调整 chunk 大小、压缩、算法或 KDF 参数：旧文件逐 chunk 解密并按 Header 解压，
+/// 得到的明文直接送入新的加密流程，一次流式完成。新文件使用新的 salt 与 base_nonce，
+/// 除密码外的一切均取自 `new_options`（包括 keyfile）。明文只以 chunk 为单位
+/// 停留在内存中并在用完后清零，从不写入磁盘；输出经 `write_atomic` 原子写出，
+/// 任一 chunk 认证失败（如密码错误）或原文件明文摘要不符时不会产生或改动输出。
+/// `output_path` 可以与 `input_path` 相同（需 `overwrite`）。
+/// 原文件需要 keyfile 时返回 `KeyfileRequired`。
+pub fn reencrypt_file(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    new_options: &EncryptOptions,
+) -> std::io::Result<()> {
+    new_options.validate()?;
+    check_overwrite(output_path, new_options)?;
+
+    let input = File::open(input_path)?;
+    let mut reader = BufReader::with_capacity(new_options.io_buffer_size, input);
+
+    // ---------- 旧文件：读取 Header 并准备解密 ----------
+    let (old_header, mut decryptor) = open_for_decrypt(&mut reader, password)?;
+    decryptor.read_file_name(&mut reader)?;
+    let file_name = decryptor
+        .file_name()
+        .filter(|_| new_options.store_file_name)
+        .map(<[u8]>::to_vec);
+    let plaintext = DecryptedReader::new(reader, &old_header, decryptor)?;
+
+    // ---------- 新文件：新 salt 与 base_nonce ----------
+    let new_options = &EncryptOptions {
+        store_file_name: file_name.is_some(),
+        ..new_options.clone()
+    };
+    let key = derive_encryption_key(password, new_options, &mut OsRng)?;
+    let (header, encryptor) = prepare_encryptor_with_key(&key, new_options, None, &mut OsRng)?;
+
+    // 明文总长度事先未知
+    write_encrypted(
+        plaintext,
+        output_path,
+        header,
+        encryptor,
+        new_options,
+        file_name.as_deref(),
+        0,
+        &mut |_| {},
+        WriteVerification::for_options(new_options, password, &key, &mut |_| Ok(())),
+    )?;
+    Ok(())
+}
+
+#[cfg(feature = "fs")]
+/// 可断点续做的文件加密
+///
+/// 与 `encrypt_file_with_options` 不同，密文直接追加写入 `<output>.svpart`，并按
+/// `checkpoint.interval_chunks` 在 `<output>.svckpt` 记录已完整写出的 chunk 数与密文长度：
+/// - 中途失败时保留两者，下次以 `resume` 调用即可跳过已加密的 chunk 继续；
+/// - 恢复时读取 `.svpart` 中的 Header，要求与 checkpoint 的 salt / base_nonce 一致，
+///   并用当前密码重新认证已写出的 chunk（密码错误返回 `InvalidPasswordOrFile`），
+///   丢弃 checkpoint 之后写出的内容；不一致时从头开始；
+/// - 写入 final chunk 后才把 `.svpart` 替换为最终输出，并删除 checkpoint。
+///
+/// 恢复时沿用 `.svpart` 中 Header 的算法、chunk 大小与 KDF，`options` 中的这些设置被忽略。
+/// 续写的 chunk 沿用原密钥与 base_nonce，两次运行之间输入文件必须保持不变：checkpoint 记录
+/// 输入的长度、修改时间与已加密前缀的带密钥摘要，任一不符时返回 `InvalidData` 并保留
+/// `.svpart` 与 checkpoint，需以 `resume: false` 从头开始。
+/// `.svpart` 不跟随符号链接：从头开始时先删除同名文件再以 `create_new` 创建，恢复时拒绝符号链接。
+/// 开启 `verify_after_write` 时在替换为最终输出之前校验完整的 `.svpart`，失败时保留两者。
+/// 压缩、填充、明文摘要与校验和 sidecar 不支持断点续做，返回 `InvalidInput`；`threads` 被忽略，始终串行加密。
+/// 输出的 Header 不记录数据 chunk 总数（`Header::total_chunks`），截断由 final chunk 发现。
+pub fn encrypt_file_with_checkpoint(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &EncryptOptions,
+    checkpoint: &CheckpointOptions,
+) -> std::io::Result<()> {
+    // ---------- 校验选项 ----------
+    options.validate()?;
+    if checkpoint.interval_chunks == 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "checkpoint interval must be greater than zero",
+        ));
+    }
+    // checkpoint 按 chunk 定位输入，压缩、填充与文件名帧会打破输入偏移与 chunk 的对应关系，
+    // 明文摘要则需要重新读取已跳过的输入
+    if options.compression.is_some()
+        || options.padding.is_some()
+        || options.plaintext_digest
+        || options.store_file_name
+        || options.checksum_sidecar
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "checkpointed encryption does not support compression, padding, plaintext digest, \
+             stored file names or checksum sidecars",
+        ));
+    }
+    ensure_distinct_paths(input_path, output_path)?;
+    check_overwrite(output_path, options)?;
+
+    let partial_path = with_suffix(output_path, PARTIAL_EXT);
+    let checkpoint_path = with_suffix(output_path, CHECKPOINT_EXT);
+
+    let input = File::open(input_path)?;
+    let input_metadata = input.metadata()?;
+    let mut reader = BufReader::with_capacity(options.io_buffer_size, input);
+
+    // ---------- 确定恢复位置 ----------
+    let resumed = if checkpoint.resume {
+        resume_partial(
+            &partial_path,
+            &checkpoint_path,
+            password,
+            options,
+            &mut reader,
+            &input_metadata,
+        )?
+    } else {
+        None
+    };
+
+    let ResumedEncryption {
+        header,
+        key,
+        mut encryptor,
+        mut chunks,
+        partial,
+        mut prefix,
+    } = match resumed {
+        Some(resumed) => resumed,
+        None => {
+            let key = derive_encryption_key(password, options, &mut OsRng)?;
+            let (header, encryptor) = prepare_encryptor_with_key(&key, options, None, &mut OsRng)?;
+            let mut partial = create_fresh(&partial_path)?;
+            header.write(&mut partial)?;
+            let prefix = input_prefix_hasher(&key.key[..]);
+            ResumedEncryption {
+                header,
+                key,
+                encryptor,
+                chunks: 0,
+                partial,
+                prefix,
+            }
+        }
+    };
+
+    // ---------- 继续加密并定期记录 checkpoint ----------
+    let mut writer = BufWriter::with_capacity(options.io_buffer_size, partial);
+    let mut buffer = Zeroizing::new(vec![0u8; header.chunk_size as usize]);
+    loop {
+        let read_len = read_full(&mut reader, &mut buffer)?;
+        if read_len == 0 {
+            break;
+        }
+        prefix.update(&buffer[..read_len]);
+        encryptor.write_chunk(&buffer[..read_len], &mut writer)?;
+        chunks += 1;
+
+        if chunks % checkpoint.interval_chunks == 0 {
+            writer.flush()?;
+            writer.get_ref().sync_data()?;
+            let written = writer.get_mut().stream_position()?;
+            Checkpoint::new(&header, chunks, written)
+                .with_input(InputFingerprint::new(
+                    &input_metadata,
+                    prefix.finalize().into(),
+                ))
+                .store(&checkpoint_path)?;
+        }
+    }
+
+    // ---------- 写入 final chunk 并替换为最终输出 ----------
+    encryptor.finish(&mut writer)?;
+    writer.flush()?;
+    writer.get_ref().sync_all()?;
+    drop(writer);
+
+    if options.verify_after_write {
+        verify_file_reusing_key(
+            &partial_path,
+            password,
+            options.keyfile.as_deref(),
+            &mut Some(key),
+        )?;
+    }
+    replace_file(&partial_path, output_path)?;
+
+    match std::fs::remove_file(&checkpoint_path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
 
-        // ---------- 写入 Header ----------
-        let header = Header::new(algorithm, salt, base_nonce, DEFAULT_CHUNK_SIZE as u32);
+#[cfg(feature = "fs")]
+/// 断点续加密的状态：Header 及其密钥、续写用的 StreamEncryptor、已完成的 chunk 数、
+/// 定位到 checkpoint 末尾的 `.svpart` 与已读取输入前缀的摘要
+struct ResumedEncryption {
+    header: Header,
+    key: DerivedKey,
+    encryptor: StreamEncryptor,
+    chunks: u64,
+    partial: File,
+    prefix: blake3::Hasher,
+}
+
+#[cfg(feature = "fs")]
+/// 按 checkpoint 打开未完成的 `.svpart`，核对输入指纹，并把 `input` 读到 checkpoint 的位置
+///
+/// checkpoint 或 `.svpart` 不存在、二者不匹配时返回 `Ok(None)`（从头开始）；
+/// 输入与 checkpoint 记录的指纹不符（或 checkpoint 未记录指纹）时返回 `InvalidData`。
+fn resume_partial<R: Read>(
+    partial_path: &Path,
+    checkpoint_path: &Path,
+    password: &[u8],
+    options: &EncryptOptions,
+    input: &mut R,
+    input_metadata: &std::fs::Metadata,
+) -> std::io::Result<Option<ResumedEncryption>> {
+    let input_changed = || {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "input changed since the checkpoint was written; encrypt without resume to start over",
+        )
+    };
+
+    let Some(checkpoint) = Checkpoint::load(checkpoint_path)? else {
+        return Ok(None);
+    };
+    let mut partial = match open_existing(partial_path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let mut reader = BufReader::new(&mut partial);
+    let header = match Header::read(&mut reader) {
+        Ok(header) => header,
+        Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if !checkpoint.matches(&header)
+        || header.codec != Codec::None
+        || header.padding.is_some()
+        || header.plaintext_digest
+        || header.file_name
+    {
+        return Ok(None);
+    }
+    // 长度与修改时间在派生密钥之前核对，前缀摘要需要密钥
+    let Some(fingerprint) = checkpoint.input.as_ref() else {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "checkpoint records no input fingerprint (not written by encryption); \
+             encrypt without resume to start over",
+        ));
+    };
+    if !fingerprint.matches_metadata(input_metadata) {
+        return Err(input_changed());
+    }
+
+    // 用当前密码认证已写出的 chunk，确保续写的 chunk 使用同一个密钥；
+    // `.svpart` 的 KDF 参数即此前加密时的选项，不按解密的默认上限限制
+    let mut cached = None;
+    let mut decryptor = decryptor_for_header(
+        &header,
+        password,
+        options.keyfile.as_deref(),
+        &DecryptOptions::unlimited_kdf(),
+        &mut cached,
+    )?;
+    decryptor.skip_output_chunks(&mut reader, checkpoint.chunk_index)?;
+    if reader.stream_position()? != checkpoint.output_offset {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "partial output does not match checkpoint",
+        ));
+    }
+    drop(reader);
+
+    let key = cached.expect("key derived for header");
+
+    // 重新读取已加密的输入前缀并核对摘要，读完后 `input` 恰好位于续写位置
+    let mut prefix = input_prefix_hasher(&key.key[..]);
+    let mut remaining = checkpoint.chunk_index * u64::from(header.chunk_size);
+    let mut buffer = Zeroizing::new(vec![0u8; header.chunk_size as usize]);
+    while remaining > 0 {
+        let want = remaining.min(buffer.len() as u64) as usize;
+        if read_full(&mut *input, &mut buffer[..want])? != want {
+            return Err(input_changed());
+        }
+        prefix.update(&buffer[..want]);
+        remaining -= want as u64;
+    }
+    // `blake3::Hash` 的比较为常量时间
+    if prefix.finalize() != fingerprint.prefix_digest {
+        return Err(input_changed());
+    }
+
+    let encryptor = StreamEncryptor::new(
+        &key.key,
+        header.algorithm,
+        header.base_nonce,
+        header.chunk_size as usize,
+        header.digest(),
+    )?
+    .starting_at_chunk(checkpoint.chunk_index);
+
+    partial.set_len(checkpoint.output_offset)?;
+    partial.seek(SeekFrom::End(0))?;
+    Ok(Some(ResumedEncryption {
+        header,
+        key,
+        encryptor,
+        chunks: checkpoint.chunk_index,
+        partial,
+        prefix,
+    }))
+}
+
+#[cfg(feature = "fs")]
+/// 内存映射输入文件后按 chunk 切片加密，省去读缓冲区的复制（需显式选用）
+///
+/// 以下情况回退到普通的流式路径（`encrypt_file_with_options`）：输入为空、
+/// 映射失败、启用了压缩、填充、`store_file_name`、`verify_after_write` 或 `checksum_sidecar`，
+/// 或 `threads != 1`。输出同样经 `write_atomic` 原子写出。
+///
+/// 注意：映射期间输入文件必须保持不变。若其他进程在加密过程中截断该文件，
+/// 访问映射会触发 SIGBUS（Unix）或访问异常（Windows）导致进程崩溃；
+/// 并发修改则会把新旧内容混合加密。只应对确定只读的文件使用本函数。
+pub fn encrypt_file_mmap(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &EncryptOptions,
+) -> std::io::Result<()> {
+    options.validate()?;
+    if options.compression.is_some()
+        || options.padding.is_some()
+        || options.store_file_name
+        || options.threads != 1
+        || options.verify_after_write
+        || options.checksum_sidecar
+    {
+        return encrypt_file_with_options(input_path, output_path, password, options);
+    }
+    ensure_distinct_paths(input_path, output_path)?;
+    check_overwrite(output_path, options)?;
+
+    let input = File::open(input_path)?;
+    let metadata = input.metadata()?;
+    if metadata.len() == 0 {
+        return encrypt_file_with_options(input_path, output_path, password, options);
+    }
+
+    // SAFETY: 映射只读且仅在本函数内使用；文件在映射期间不被截断是调用方的前提（见文档）
+    let map = match unsafe { Mmap::map(&input) } {
+        Ok(map) => map,
+        Err(_) => return encrypt_file_with_options(input_path, output_path, password, options),
+    };
+
+    let total_chunks = file_total_chunks(&metadata, options);
+    let (header, mut encryptor) =
+        prepare_encryptor_with_rng(password, options, total_chunks, &mut OsRng)?;
+
+    write_atomic(output_path, |output| {
+        let mut writer = BufWriter::with_capacity(options.io_buffer_size, output);
         header.write(&mut writer)?;
+        if header.plaintext_digest {
+            for chunk in map.chunks(encryptor.chunk_size()) {
+                encryptor.write_chunk(chunk, &mut writer)?;
+            }
+            encryptor.finish_with_digest(&mut writer, blake3::hash(&map).as_bytes())?;
+        } else {
+            encryptor.encrypt_slice(&map, &mut writer)?;
+        }
+        writer.flush()
+    })
+}
 
-        encryptor.encrypt(reader, &mut writer)?;
+#[cfg(feature = "fs")]
+/// 元数据为 `metadata` 的输入将写出的数据 chunk 总数，事先无法确定时为 `None`
+///
+/// 写入 Header 后，加密结束时的实际 chunk 数必须与之相等，加密期间被改动的输入会使加密失败。
+/// 以下情况返回 `None`，截断改由 final chunk 发现：
+/// - 压缩：压缩后的长度事先未知；
+/// - 不是普通文件（FIFO、`<(cmd)`、字符设备等）：元数据中的长度不代表可读出的数据量；
+/// - 长度为 0：`/proc` 等伪文件报告长度 0 却能读出内容，与真正的空文件无法区分；
+/// - 填充：空输入不能记录，记录与否会改变 Header 长度，暴露输入是否为空。
+fn file_total_chunks(metadata: &std::fs::Metadata, options: &EncryptOptions) -> Option<u64> {
+    if options.compression.is_some()
+        || options.padding.is_some()
+        || !metadata.is_file()
+        || metadata.len() == 0
+    {
+        return None;
+    }
+    Some(metadata.len().div_ceil(options.chunk_size as u64))
+}
 
-        // 确保所有数据落盘
-        writer.flush()?;
-        Ok(())
-    })?;
+#[cfg(feature = "fs")]
+/// `overwrite` 为 false 且目标已存在时，返回携带 `SealVaultError::AlreadyExists` 的错误
+fn check_overwrite(output_path: &Path, options: &EncryptOptions) -> std::io::Result<()> {
+    if !options.overwrite && output_path.try_exists()? {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::AlreadyExists,
+            SealVaultError::AlreadyExists,
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "fs")]
+/// 统计已读取字节数的 Reader 包装
+struct CountingReader<'a, R> {
+    inner: R,
+    count: &'a Cell<u64>,
+}
+
+#[cfg(feature = "fs")]
+impl<R: Read> Read for CountingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read_len = self.inner.read(buf)?;
+        self.count.set(self.count.get() + read_len as u64);
+        Ok(read_len)
+    }
+}
+
+#[cfg(feature = "fs")]
+/// 在读取数据的同时更新调用方持有的 BLAKE3 hasher 的 Reader 包装
+///
+/// 与 `Blake3Tap` 不同，reader 被压缩器包装并消耗后，调用方仍能从 hasher 取得摘要。
+struct DigestingReader<'a, R> {
+    inner: R,
+    hasher: &'a RefCell<blake3::Hasher>,
+}
+
+#[cfg(feature = "fs")]
+impl<R: Read> Read for DigestingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read_len = self.inner.read(buf)?;
+        self.hasher.borrow_mut().update(&buf[..read_len]);
+        Ok(read_len)
+    }
+}
+
+#[cfg(feature = "fs")]
+/// 使用已派生的密钥加密文件（目录共享 salt 模式）
+///
+/// Header 写入 `key` 的 salt 与 KDF 参数，base_nonce 仍为每个文件独立随机生成；
+/// 开启 `verify_after_write` 时校验同样复用 `key`。
+/// 返回明文长度与 BLAKE3 摘要（供目录 manifest 使用）。
+pub(crate) fn encrypt_file_with_key(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    key: &DerivedKey,
+    options: &EncryptOptions,
+    progress: &mut dyn FnMut(ProgressEvent),
+) -> std::io::Result<PlaintextSummary> {
+    let input = File::open(input_path)?;
+    let metadata = input.metadata()?;
+    let total = metadata.len();
+    let mut reader = Blake3Tap::new(BufReader::with_capacity(options.io_buffer_size, input));
+
+    let total_chunks = file_total_chunks(&metadata, options);
+    let (header, encryptor) = prepare_encryptor_with_key(key, options, total_chunks, &mut OsRng)?;
+
+    write_encrypted(
+        &mut reader,
+        output_path,
+        header,
+        encryptor,
+        options,
+        None,
+        total,
+        progress,
+        WriteVerification::for_options(options, password, key, &mut |_| Ok(())),
+    )?;
+    Ok(reader.summary())
+}
+
+#[cfg(feature = "fs")]
+/// `write_encrypted` 的写后校验：替换目标文件之前用加密时的密钥完整解密临时密文
+struct WriteVerification<'a> {
+    password: &'a [u8],
+    /// 加密所用的派生密钥，刚写出的密文与其 salt、参数一致，校验时无需再次派生
+    key: DerivedKey,
+    /// 测试接缝：校验前拿到临时密文路径
+    before_verify: &'a mut dyn FnMut(&Path) -> std::io::Result<()>,
+}
+
+#[cfg(feature = "fs")]
+impl<'a> WriteVerification<'a> {
+    /// `options.verify_after_write` 开启时返回校验状态，否则为 `None`
+    fn for_options(
+        options: &EncryptOptions,
+        password: &'a [u8],
+        key: &DerivedKey,
+        before_verify: &'a mut dyn FnMut(&Path) -> std::io::Result<()>,
+    ) -> Option<Self> {
+        options.verify_after_write.then(|| Self {
+            password,
+            key: key.clone(),
+            before_verify,
+        })
+    }
+
+    /// 校验临时密文；错误原样返回（如 `InvalidPasswordOrFile`、`CorruptedData`）
+    fn verify(self, written: &Path, keyfile: Option<&Path>) -> std::io::Result<()> {
+        (self.before_verify)(written)?;
+        verify_file_reusing_key(written, self.password, keyfile, &mut Some(self.key))
+    }
+}
+
+#[cfg(feature = "fs")]
+/// 原子写出 Header 与完整 Stream（按 `options.threads` 选择串行或并行加密）
+///
+/// 按 `options.compression` 压缩输入；Header 要求明文摘要时，
+/// 摘要在压缩之前对原始输入计算，并写入 final chunk。
+/// Header 要求文件名时，`file_name` 作为 Stream 的第一帧写出。
+/// `verify` 不为空时，在替换目标文件之前校验写出的密文。返回本次处理的统计。
+#[allow(clippy::too_many_arguments)]
+fn write_encrypted<'r, R: Read + 'r>(
+    reader: R,
+    output_path: &Path,
+    header: Header,
+    mut encryptor: StreamEncryptor,
+    options: &EncryptOptions,
+    file_name: Option<&[u8]>,
+    total: u64,
+    progress: &mut dyn FnMut(ProgressEvent),
+    verify: Option<WriteVerification<'_>>,
+) -> std::io::Result<Stats> {
+    let hasher = RefCell::new(blake3::Hasher::new());
+    let consumed = Cell::new(0);
+    let padding_len = Cell::new(0);
+    let mut ciphertext_bytes = 0;
 
+    let mut reader: Box<dyn Read + '_> = Box::new(CountingReader {
+        inner: reader,
+        count: &consumed,
+    });
+    if header.plaintext_digest {
+        reader = Box::new(DigestingReader {
+            inner: reader,
+            hasher: &hasher,
+        });
+    }
+    if let Some(compression) = options.compression {
+        reader = compression.compress(reader)?;
+    }
+    // 填充加在压缩之后，使实际加密的数据长度按块对齐
+    if let Some(padding) = header.padding {
+        reader = Box::new(padding.pad(reader, &padding_len));
+    }
+
+    // 压缩时进度按已读取的明文字节数报告，而不是压缩后的字节数
+    let mut progress = |event| match event {
+        ProgressEvent::BytesProcessed { total, .. } if options.compression.is_some() => {
+            progress(ProgressEvent::BytesProcessed {
+                done: consumed.get(),
+                total,
+            })
+        }
+        other => progress(other),
+    };
+
+    write_atomic_checked(
+        output_path,
+        |output| {
+            let mut writer = BufWriter::with_capacity(options.io_buffer_size, &mut *output);
+
+            // ---------- 写入 Header 与文件名帧 ----------
+            header.write(&mut writer)?;
+            if let Some(name) = file_name {
+                encryptor.write_file_name(name, &mut writer)?;
+            }
+
+            match options.threads {
+                1 => encryptor.encrypt_chunks(&mut reader, &mut writer, total, &mut progress)?,
+                threads => encryptor.encrypt_chunks_parallel(
+                    &mut reader,
+                    &mut writer,
+                    threads,
+                    total,
+                    &mut progress,
+                )?,
+            }
+
+            // ---------- 写入 final chunk（可携带明文摘要与填充长度） ----------
+            let digest: Option<[u8; PLAINTEXT_DIGEST_SIZE]> = header
+                .plaintext_digest
+                .then(|| hasher.borrow().finalize().into());
+            encryptor.finish_with_trailer(
+                &mut writer,
+                digest.as_ref(),
+                header.padding.map(|_| padding_len.get()),
+            )?;
+
+            // 确保所有数据落盘
+            writer.flush()?;
+            drop(writer);
+            ciphertext_bytes = output.stream_position()?;
+            Ok(())
+        },
+        |written| match verify {
+            Some(verify) => verify.verify(written, options.keyfile.as_deref()),
+            None => Ok(()),
+        },
+    )?;
+
+    Ok(Stats {
+        plaintext_bytes: consumed.get(),
+        ciphertext_bytes,
+        chunks: encryptor.data_chunks(),
+    })
+}
+
+#[cfg(feature = "fs")]
+/// 按 `EncryptOptions` 加密文件，成功后用 `secure_delete` 覆写并删除明文输入
+///
+/// 无论 `options.verify_after_write` 如何设置都会做写后校验：只有密文已完整校验、落盘并
+/// 原子替换到 `output_path`（含父目录 fsync）之后才开始覆写；加密或校验失败时输入保持不变。
+/// 覆写的局限（SSD、写时复制文件系统等）见 `fs::wipe` 模块文档。
+pub fn encrypt_file_and_wipe(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &EncryptOptions,
+) -> std::io::Result<()> {
+    let options = EncryptOptions {
+        verify_after_write: true,
+        ..options.clone()
+    };
+    encrypt_file_with_options(input_path, output_path, password, &options)?;
+    secure_delete(input_path)
+}
+
+#[cfg(feature = "fs")]
+/// 写后校验的测试接缝：按 `options` 加密并强制开启 `verify_after_write`，
+/// `before_verify` 在校验前拿到临时密文路径
+#[doc(hidden)]
+pub fn encrypt_file_with_verify_hook<H>(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &EncryptOptions,
+    mut before_verify: H,
+) -> std::io::Result<()>
+where
+    H: FnMut(&Path) -> std::io::Result<()>,
+{
+    let options = EncryptOptions {
+        verify_after_write: true,
+        ..options.clone()
+    };
+    encrypt_file_inner(
+        input_path,
+        output_path,
+        password,
+        &options,
+        &mut OsRng,
+        &mut |_| {},
+        &mut before_verify,
+    )?;
     Ok(())
 }
+
+/// 在内存中加密字节数据，输出与 `encrypt_file` 写出的 .svlt 内容格式完全一致
+pub fn encrypt_bytes(
+    data: &[u8],
+    password: &[u8],
+    algorithm: AeadAlgorithm,
+) -> std::io::Result<Vec<u8>> {
+    let mut output = Vec::with_capacity(data.len() + data.len() / 1024 + 128);
+    encrypt_stream(data, &mut output, password, algorithm)?;
+    Ok(output)
+}
+
+#[cfg(feature = "fs")]
+/// 使用已派生的密钥在内存中加密字节数据（目录 manifest 使用）
+pub(crate) fn encrypt_bytes_with_key(
+    data: &[u8],
+    key: &DerivedKey,
+    algorithm: AeadAlgorithm,
+) -> std::io::Result<Vec<u8>> {
+    let (header, mut encryptor) = prepare_encryptor_with_key(
+        key,
+        &EncryptOptions::with_algorithm(algorithm),
+        None,
+        &mut OsRng,
+    )?;
+
+    let mut output = Vec::with_capacity(data.len() + 128);
+    header.write(&mut output)?;
+    encryptor.encrypt(data, &mut output)?;
+    Ok(output)
+}
+
+/// 从任意 reader 读取明文，将完整的 .svlt 数据（Header + Stream）写入 writer
+///
+/// 只做顺序读写，不要求 reader / writer 可 seek，可直接用于管道或网络连接。
+pub fn encrypt_stream<R: Read, W: Write>(
+    reader: R,
+    mut writer: W,
+    password: &[u8],
+    algorithm: AeadAlgorithm,
+) -> std::io::Result<()> {
+    let (_header, mut encryptor) = open_for_encrypt(&mut writer, password, algorithm)?;
+    encryptor.encrypt(reader, &mut writer)?;
+    writer.flush()
+}
+
+/// 为外部 stream 准备加密：写入 Header 并返回可直接驱动 chunk 循环的 StreamEncryptor
+///
+/// 调用方随后可使用 `StreamEncryptor::encrypt` 或 `StreamEncryptor::write_chunk`
+/// 将 stream 写入同一个 writer；手动驱动 chunk 循环时，最后必须调用
+/// `StreamEncryptor::finish` 写入结束标记。
+pub fn open_for_encrypt<W: Write>(
+    mut writer: W,
+    password: &[u8],
+    algorithm: AeadAlgorithm,
+) -> std::io::Result<(Header, StreamEncryptor)> {
+    let (header, encryptor) =
+        prepare_encryptor(password, &EncryptOptions::with_algorithm(algorithm))?;
+    header.write(&mut writer)?;
+    Ok((header, encryptor))
+}
+
+/// 生成 salt 与 base_nonce，派生密钥，并构造 Header 与 StreamEncryptor
+pub(crate) fn prepare_encryptor(
+    password: &[u8],
+    options: &EncryptOptions,
+) -> std::io::Result<(Header, StreamEncryptor)> {
+    prepare_encryptor_with_rng(password, options, None, &mut OsRng)
+}
+
+/// 同 `prepare_encryptor`，但 salt 与 base_nonce 取自给定的随机数源，
+/// 已知数据 chunk 总数时（文件输入）写入 Header
+fn prepare_encryptor_with_rng(
+    password: &[u8],
+    options: &EncryptOptions,
+    total_chunks: Option<u64>,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> std::io::Result<(Header, StreamEncryptor)> {
+    options.validate()?;
+    let key = derive_encryption_key(password, options, rng)?;
+    prepare_encryptor_with_key(&key, options, total_chunks, rng)
+}
+
+/// 生成 salt 并按选项中的 KDF 派生密钥（可混入 keyfile；不使用 keyfile 时先检查密码强度）
+fn derive_encryption_key(
+    password: &[u8],
+    options: &EncryptOptions,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> std::io::Result<DerivedKey> {
+    match &options.keyfile {
+        Some(keyfile) => DerivedKey::generate_with_rng(
+            &combine_with_keyfile(password, keyfile)?,
+            options.kdf,
+            rng,
+        ),
+        None => {
+            options.password_policy.check(password)?;
+            DerivedKey::generate_with_rng(password, options.kdf, rng)
+        }
+    }
+}
+
+/// 使用已派生的密钥生成 base_nonce，并构造 Header 与 StreamEncryptor
+fn prepare_encryptor_with_key(
+    key: &DerivedKey,
+    options: &EncryptOptions,
+    total_chunks: Option<u64>,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> std::io::Result<(Header, StreamEncryptor)> {
+    let codec = options
+        .compression
+        .map(Compression::codec)
+        .unwrap_or_default();
+    prepare_encryptor_with_codec(key, options, codec, total_chunks, rng)
+}
+
+/// 同 `prepare_encryptor_with_key`，但 Header 中的压缩编码由调用方直接指定
+///
+/// 用于 `rekey_file`：Stream 中已是压缩数据，只需原样记录编码。
+fn prepare_encryptor_with_codec(
+    key: &DerivedKey,
+    options: &EncryptOptions,
+    codec: Codec,
+    total_chunks: Option<u64>,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> std::io::Result<(Header, StreamEncryptor)> {
+    options.validate()?;
+
+    // ---------- 生成 base nonce ----------
+    let mut base_nonce = [0u8; BASE_NONCE_SIZE];
+    rng.fill_bytes(&mut base_nonce);
+
+    // ---------- 构造 Header（salt 与 KDF 来自派生密钥） ----------
+    let mut header = Header::new(
+        options.algorithm,
+        key.salt,
+        base_nonce,
+        options.chunk_size as u32,
+    );
+    header.kdf = key.kdf;
+    header.codec = codec;
+    header.keyfile_required = options.keyfile.is_some();
+    header.plaintext_digest = options.plaintext_digest;
+    header.padding = options.padding;
+    header.file_name = options.store_file_name;
+    header.comment = options.header_comment.clone();
+    header.total_chunks = total_chunks;
+
+    // ---------- 构造 StreamEncryptor ----------
+    let mut encryptor = StreamEncryptor::new(
+        &key.key,
+        options.algorithm,
+        base_nonce,
+        options.chunk_size,
+        header.digest(),
+    )?;
+    if options.plaintext_digest {
+        encryptor = encryptor.with_plaintext_digest();
+    }
+    if options.padding.is_some() {
+        encryptor = encryptor.with_padding();
+    }
+    if options.store_file_name {
+        encryptor = encryptor.with_file_name();
+    }
+    if let Some(total_chunks) = total_chunks {
+        encryptor = encryptor.with_total_chunks(total_chunks);
+    }
+
+    Ok((header, encryptor))
+}
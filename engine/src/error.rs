@@ -1,22 +1,93 @@
+//! SealVault 公开错误类型
+//!
+//! 内部各层仍以 `std::io::Result` 传递错误，需要区分的情况把 `SealVaultError`
+//! 作为 `io::Error` 的负载携带；公开接口在边界处通过 `From<io::Error>` 还原为具体变体。
+
 use thiserror::Error;
 
+use crate::algorithm::AeadAlgorithm;
+
+/// 公开接口返回的错误
+///
+/// 标记为 `#[non_exhaustive]`：后续版本可能新增变体，crate 外的 `match` 必须带通配分支。
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum SealVaultError {
     #[error("I/O error")]
-    Io(#[from] std::io::Error),
+    Io(#[source] std::io::Error),
+
+    #[error("invalid password or keyfile")]
+    InvalidPasswordOrFile,
+
+    #[error("not a SealVault file")]
+    NotSealVaultFile,
 
-    #[error("invalid password or not a SealVault file")]
-    _InvalidPasswordOrFile,
+    #[error("this file requires a keyfile to decrypt")]
+    KeyfileRequired,
 
     #[error("unsupported SealVault version")]
-    _UnsupportedVersion,
+    UnsupportedVersion,
+
+    /// 文件使用了本版本不认识的 AEAD 算法 ID（可能由更新的版本写出）
+    #[error("unsupported AEAD algorithm (id {0})")]
+    UnsupportedAlgorithm(u8),
 
     #[error("corrupted data")]
-    _CorruptedData,
+    CorruptedData,
+
+    #[error("not enough memory for key derivation; lower the KDF memory cost (m_cost)")]
+    KdfResourceExhausted,
+
+    /// 加密时密码不满足 `PasswordPolicy`（为空、只含空白或短于要求的长度）
+    #[error("password is empty, blank or shorter than required")]
+    WeakPassword,
+
+    /// 文件的 AEAD 算法与 `DecryptOptions::expected_algorithm` 不一致
+    #[error("file is encrypted with {found}, expected {expected}")]
+    UnexpectedAlgorithm {
+        expected: AeadAlgorithm,
+        found: AeadAlgorithm,
+    },
 
     #[error("output already exists")]
-    _AlreadyExists,
+    AlreadyExists,
+
+    #[error("decrypted output exceeds the configured size limit")]
+    OutputLimitExceeded,
+
+    /// 目录操作被取消；`completed` 为取消前已完成（输出已就位）的文件数
+    #[error("operation cancelled after {completed} file(s) completed")]
+    Cancelled { completed: usize },
+
+    #[error("folder does not match manifest: {0}")]
+    ManifestMismatch(String),
 
     #[error("internal error")]
     Internal,
 }
+
+/// 内部 `io::Error` 到公开错误的转换
+///
+/// 约定：内部需要报告具体错误时，构造 `io::Error::new(kind, SealVaultError::…)`，
+/// 以 `SealVaultError` 本身（而不是字符串或其他包装）作为负载；这里据此 downcast 还原。
+/// 负载经过其他错误类型再包装、或仅以 `Display` 文本保留时无法识别，会被归为 `Io`。
+/// 没有负载或负载为其他类型的 `io::Error` 一律归为 `Io`，原错误作为 `source` 保留。
+impl From<std::io::Error> for SealVaultError {
+    /// 若 `io::Error` 携带 `SealVaultError` 负载则取出，否则归为 `Io`
+    fn from(err: std::io::Error) -> Self {
+        if !err
+            .get_ref()
+            .is_some_and(|inner| inner.is::<SealVaultError>())
+        {
+            return Self::Io(err);
+        }
+
+        match err
+            .into_inner()
+            .map(|inner| inner.downcast::<SealVaultError>())
+        {
+            Some(Ok(inner)) => *inner,
+            _ => Self::Internal,
+        }
+    }
+}
@@ -5,29 +5,329 @@
 //! - 文件逐个流式加/解密，避免整文件读入内存。
 //! - 严格校验相对路径组件，防止路径穿越写出到目标目录之外。
 
+use std::collections::{BTreeMap, HashMap};
 use std::ffi::OsStr;
-use std::io;
+use std::io::{self, BufReader};
 use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
 
 use walkdir::WalkDir;
 
 use crate::algorithm::AeadAlgorithm;
-use crate::decrypt::decrypt_file;
-use crate::encrypt::encrypt_file_with_algorithm;
+use crate::crypto::kdf::DerivedKey;
+use crate::crypto::password::PasswordPolicy;
+use crate::decrypt::{
+    DecryptOptions, decrypt_file_reusing_key, open_for_decrypt_reusing_key, verify_file_reusing_key,
+};
+use crate::encrypt::{EncryptOptions, encrypt_bytes_with_key, encrypt_file_with_key};
+use crate::error::SealVaultError;
+use crate::format::header::Header;
+use crate::format::manifest::{
+    FileMetadata, MANIFEST_FILE_NAME, Manifest, ManifestEntry, ManifestSymlink,
+};
+use crate::fs::atomic::{cleanup_orphaned_temp, write_atomic};
+use crate::progress::ProgressEvent;
 
 const ENCRYPTED_EXT: &str = "svlt";
 
+/// 解密目录时，对非 `.svlt` 文件的处理策略。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonEncryptedFilePolicy {
+    /// 跳过，不写入输出目录（默认行为）。
+    #[default]
+    Skip,
+    /// 原样复制到输出目录的对应相对路径。
+    Copy,
+}
+
+/// 目录加解密时对符号链接的处理策略。
+///
+/// 遍历始终不跟随指向目录的链接，避免循环与越出输入目录。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymlinkPolicy {
+    /// 忽略符号链接：加密时不记录，解密时也不还原 manifest 中记录的链接。
+    Skip,
+    /// 不跟随，把链接内容（目标路径字符串）记录到加密的 manifest 中，
+    /// 解密时原样重建链接（默认行为）。
+    #[default]
+    PreserveAsLink,
+    /// 加密时跟随指向普通文件的链接，以链接自身的路径加密目标文件的内容；
+    /// 指向目录或已失效的链接按 `PreserveAsLink` 记录。
+    FollowFiles,
+}
+
+/// 目录加解密的可选行为。
+#[derive(Debug, Clone, Default)]
+pub struct FolderOptions {
+    /// 解密时如何处理非 `.svlt` 文件。
+    pub non_encrypted_files: NonEncryptedFilePolicy,
+    /// 加密时每个文件写出后重新完整校验一遍。
+    pub verify_after_write: bool,
+    /// 取消标志：其他线程（或信号处理函数）置为 `true` 后，在处理下一个文件前停止，
+    /// 返回携带 `SealVaultError::Cancelled` 的错误，其中记录已完成的文件数。
+    /// 正在处理的文件照常完成原子写入，已完成的文件保留，未开始的文件不会产生任何输出
+    /// （加密时也不会写出 manifest）。
+    pub cancel: Option<Arc<AtomicBool>>,
+    /// 加密时整个目录只派生一次密钥：所有文件 Header 使用同一个随机 salt，
+    /// 每个文件仍有独立的随机 base_nonce。
+    ///
+    /// 安全取舍：
+    /// - 省去每个文件一次 Argon2id（默认 64 MiB、3 轮），大量小文件时显著加快；
+    /// - 相同 salt 会暴露“这些文件属于同一次加密”，且一次猜中密码即可解开整批文件，
+    ///   攻击者针对整批只需做一次派生（与逐文件 salt 相比无额外代价）；
+    /// - 同一密钥下的 nonce 唯一性依赖随机 base_nonce：使用 96 位 nonce 的算法
+    ///   （`AeadAlgorithm::max_chunks` 小于 `u64::MAX` 的算法）整批 chunk 总数应远小于其 `max_chunks`。
+    ///
+    /// 格式不变，解密无需任何选项：目录解密会自动复用 salt 与参数相同的密钥。
+    pub shared_salt: bool,
+    /// 解密时对照输入目录根部的 `manifest.svmf` 校验：
+    /// 每个 `.svlt` 文件的明文长度、算法与 BLAKE3 摘要必须与记录一致，
+    /// 且不能多出或缺少文件，否则返回携带 `SealVaultError::ManifestMismatch` 的错误。
+    ///
+    /// 校验随解密逐个进行，失败前已写出的文件会保留。非 `.svlt` 文件不参与校验。
+    pub verify_manifest: bool,
+    /// 符号链接的处理策略（默认 `SymlinkPolicy::PreserveAsLink`）。
+    pub symlinks: SymlinkPolicy,
+    /// 加密时对密码的最低要求（默认拒绝空密码与只含空白的密码），在遍历目录、写出任何文件之前检查。
+    pub password_policy: PasswordPolicy,
+    /// `verify_folder_with_options` 遇到第一个校验失败的文件即返回其错误；
+    /// 默认校验全部文件，失败的记入 `FolderResult::failed`。
+    pub stop_on_first_failure: bool,
+}
+
+/// 目录加解密的结果汇总
+///
+/// 用于发现“指错了目录”之类的问题：例如解密时 `processed` 为 0 而 `skipped` 不为空。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FolderResult {
+    /// 加密、解密或校验通过的文件数量（不含 manifest）
+    pub processed: usize,
+    /// 解密或校验时因不是 `.svlt` 文件而跳过的文件（相对输入目录）；
+    /// 按 `NonEncryptedFilePolicy::Copy` 复制的文件不计入，加密时始终为空
+    pub skipped: Vec<PathBuf>,
+    /// 校验时未通过的 `.svlt` 文件（相对输入目录），仅 `verify_folder` 使用
+    pub failed: Vec<PathBuf>,
+}
+
+/// 待加密的单个文件
+struct WorkItem {
+    source: PathBuf,
+    /// 相对输入目录的路径（已校验）
+    rel: PathBuf,
+    target: PathBuf,
+}
+
 /// 将目录中的文件逐个加密到目标目录。
 ///
 /// - 会递归创建目录结构。
 /// - 普通文件会输出为同名 + `.svlt`。
+/// - 输出根目录写出加密的 `manifest.svmf`，记录每个文件的明文长度、算法、BLAKE3 摘要、
+///   权限与修改时间；符号链接默认不跟随，只在 manifest 中记录链接内容（见 `SymlinkPolicy`）。
 /// - 使用现有文件流式加密逻辑，不重新实现算法。
+/// - 清理本次写入的各输出目录（不含其他子目录）中先前中断的运行留下的临时文件
+///   （见 `fs::cleanup_orphaned_temp`）。
 pub fn encrypt_folder(
     input_path: &Path,
     output_path: &Path,
-    password: &str,
+    password: &[u8],
     algorithm: AeadAlgorithm,
-) -> io::Result<()> {
+) -> io::Result<FolderResult> {
+    encrypt_folder_with_options(
+        input_path,
+        output_path,
+        password,
+        algorithm,
+        &FolderOptions::default(),
+    )
+}
+
+/// 按 `options` 将目录中的文件逐个加密到目标目录。
+pub fn encrypt_folder_with_options(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    algorithm: AeadAlgorithm,
+    options: &FolderOptions,
+) -> io::Result<FolderResult> {
+    encrypt_folder_with_progress(
+        input_path,
+        output_path,
+        password,
+        algorithm,
+        options,
+        &mut |_| {},
+    )
+}
+
+/// 按 `options` 逐个加密目录中的文件，并通过 `progress` 报告进度。
+///
+/// - 先完整遍历目录以确定文件总数，再依次加密。
+/// - 每个文件依次报告 `FileStarted`、若干 `BytesProcessed`、`FileFinished`；
+///   失败或取消的文件不会报告 `FileFinished`。
+/// - 返回加密的文件数量。
+pub fn encrypt_folder_with_progress(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    algorithm: AeadAlgorithm,
+    options: &FolderOptions,
+    progress: &mut dyn FnMut(ProgressEvent),
+) -> io::Result<FolderResult> {
+    options.password_policy.check(password)?;
+    let (work, symlinks) = collect_encrypt_work(input_path, output_path, options)?;
+    let shared_key = shared_key_for(password, options, &work)?;
+
+    let total = work.len();
+    let mut entries = Vec::with_capacity(total);
+    for (index, item) in work.iter().enumerate() {
+        check_cancelled(options, index)?;
+
+        progress(ProgressEvent::FileStarted {
+            path: item.source.clone(),
+            index,
+            total,
+        });
+        entries.push(encrypt_one(
+            item,
+            password,
+            algorithm,
+            options,
+            shared_key.as_ref(),
+            progress,
+        )?);
+        progress(ProgressEvent::FileFinished {
+            path: item.source.clone(),
+        });
+    }
+
+    let processed = entries.len();
+    write_manifest(
+        output_path,
+        password,
+        algorithm,
+        shared_key.as_ref(),
+        Manifest::new(entries, symlinks),
+    )?;
+
+    Ok(FolderResult {
+        processed,
+        ..FolderResult::default()
+    })
+}
+
+/// 使用 `threads` 个工作线程并行加密目录。
+///
+/// - 先完整遍历目录：按遍历顺序创建全部输出目录（父目录先于子目录），
+///   并收集 (源文件, 目标文件) 工作项；之后才开始加密。
+/// - 工作线程从共享队列领取文件，任一文件失败后其余线程不再领取新文件，
+///   返回第一个错误；已完成的文件保留。
+/// - `threads == 0` 时使用 `std::thread::available_parallelism()`。
+/// - 每个线程同时进行一次 Argon2id 派生（默认 64 MiB），内存占用随线程数线性增长。
+pub fn encrypt_folder_parallel(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    algorithm: AeadAlgorithm,
+    options: &FolderOptions,
+    threads: usize,
+) -> io::Result<FolderResult> {
+    options.password_policy.check(password)?;
+    let (work, symlinks) = collect_encrypt_work(input_path, output_path, options)?;
+
+    let threads = match threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+    .min(work.len())
+    .max(1);
+
+    let shared_key = shared_key_for(password, options, &work)?;
+
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let first_error: Mutex<Option<io::Error>> = Mutex::new(None);
+    let entries = Mutex::new(Vec::with_capacity(work.len()));
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                while !failed.load(Ordering::SeqCst) {
+                    let index = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(item) = work.get(index) else {
+                        break;
+                    };
+
+                    let result = check_cancelled(options, 0).and_then(|()| {
+                        encrypt_one(
+                            item,
+                            password,
+                            algorithm,
+                            options,
+                            shared_key.as_ref(),
+                            &mut |_| {},
+                        )
+                    });
+
+                    match result {
+                        Ok(entry) => entries
+                            .lock()
+                            .unwrap_or_else(|p| p.into_inner())
+                            .push(entry),
+                        Err(e) => {
+                            failed.store(true, Ordering::SeqCst);
+                            let mut slot = first_error.lock().unwrap_or_else(|p| p.into_inner());
+                            slot.get_or_insert(e);
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    let entries = entries.into_inner().unwrap_or_else(|p| p.into_inner());
+    if let Some(e) = first_error.into_inner().unwrap_or_else(|p| p.into_inner()) {
+        // 取消时其他线程可能仍在完成各自的文件，全部结束后才能得出完成数
+        return Err(match is_cancelled(&e) {
+            true => cancelled(entries.len()),
+            false => e,
+        });
+    }
+
+    let processed = entries.len();
+    write_manifest(
+        output_path,
+        password,
+        algorithm,
+        shared_key.as_ref(),
+        Manifest::new(entries, symlinks),
+    )?;
+
+    Ok(FolderResult {
+        processed,
+        ..FolderResult::default()
+    })
+}
+
+/// 创建与输入目录对应的输出目录，并清理其中先前中断的运行留下的临时文件
+///
+/// 遍历从输入根目录开始，因此输出根目录也经由这里清理；只清理本次运行写入的目录，
+/// 输出目录中其他已有的子树不会被遍历。
+fn create_target_dir(output_path: &Path, safe_rel: &Path) -> io::Result<()> {
+    let target_dir = safe_join(output_path, safe_rel)?;
+    std::fs::create_dir_all(&target_dir)?;
+    cleanup_orphaned_temp(&target_dir);
+    Ok(())
+}
+
+/// 遍历输入目录：按遍历顺序创建全部输出目录（父目录先于子目录），
+/// 返回待加密的文件列表与按 `options.symlinks` 需要记录的符号链接。
+fn collect_encrypt_work(
+    input_path: &Path,
+    output_path: &Path,
+    options: &FolderOptions,
+) -> io::Result<(Vec<WorkItem>, Vec<ManifestSymlink>)> {
     if !input_path.is_dir() {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
@@ -35,9 +335,15 @@ pub fn encrypt_folder(
         ));
     }
 
+    ensure_output_outside_input(input_path, output_path)?;
+
     std::fs::create_dir_all(output_path)?;
 
+    let mut work = Vec::new();
+    let mut symlinks = Vec::new();
     for entry in WalkDir::new(input_path).follow_links(false) {
+        check_cancelled(options, 0)?;
+
         let entry = entry.map_err(walkdir_to_io)?;
         let source_path = entry.path();
 
@@ -48,43 +354,277 @@ pub fn encrypt_folder(
         let safe_rel = validate_relative_path(rel)?;
 
         if entry.file_type().is_dir() {
-            let target_dir = safe_join(output_path, &safe_rel)?;
-            std::fs::create_dir_all(target_dir)?;
+            create_target_dir(output_path, &safe_rel)?;
             continue;
         }
 
-        if entry.file_type().is_file() {
-            let mut target_file_rel = safe_rel.clone();
-            let name = source_path.file_name().ok_or_else(|| {
-                io::Error::new(io::ErrorKind::InvalidData, "文件名为空，无法加密")
-            })?;
+        let file_type = entry.file_type();
+        let follow = file_type.is_symlink()
+            && options.symlinks == SymlinkPolicy::FollowFiles
+            && std::fs::metadata(source_path).is_ok_and(|m| m.is_file());
+        if file_type.is_file() || follow {
+            let target_file_path = encrypted_target_path(output_path, &safe_rel, source_path)?;
+            work.push(WorkItem {
+                source: source_path.to_path_buf(),
+                rel: safe_rel,
+                target: target_file_path,
+            });
+        } else if file_type.is_symlink() && options.symlinks != SymlinkPolicy::Skip {
+            symlinks.push(ManifestSymlink {
+                path: safe_rel,
+                target: std::fs::read_link(source_path)?,
+            });
+        }
+    }
 
-            let encrypted_name = append_svlt_suffix(name);
-            target_file_rel.set_file_name(encrypted_name);
+    Ok((work, symlinks))
+}
 
-            let target_file_path = safe_join(output_path, &target_file_rel)?;
-            if let Some(parent) = target_file_path.parent() {
-                std::fs::create_dir_all(parent)?;
+/// 计算源文件对应的加密输出路径（同名 + `.svlt`），并确保其父目录存在。
+fn encrypted_target_path(
+    output_path: &Path,
+    safe_rel: &Path,
+    source_path: &Path,
+) -> io::Result<PathBuf> {
+    let mut target_file_rel = safe_rel.to_path_buf();
+    let name = source_path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "文件名为空，无法加密"))?;
+
+    let encrypted_name = append_svlt_suffix(name);
+    target_file_rel.set_file_name(encrypted_name);
+
+    let target_file_path = safe_join(output_path, &target_file_rel)?;
+    if let Some(parent) = target_file_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    Ok(target_file_path)
+}
+
+/// `options.shared_salt` 开启且有文件待加密时，为整次目录加密派生唯一的密钥。
+fn shared_key_for(
+    password: &[u8],
+    options: &FolderOptions,
+    work: &[WorkItem],
+) -> io::Result<Option<DerivedKey>> {
+    if options.shared_salt && !work.is_empty() {
+        DerivedKey::generate(password).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// 按 `options` 加密单个文件并返回其 manifest 记录；
+/// `shared_key` 为空时为该文件生成独立 salt 并派生密钥。
+fn encrypt_one(
+    item: &WorkItem,
+    password: &[u8],
+    algorithm: AeadAlgorithm,
+    options: &FolderOptions,
+    shared_key: Option<&DerivedKey>,
+    progress: &mut dyn FnMut(ProgressEvent),
+) -> io::Result<ManifestEntry> {
+    let own_key;
+    let key = match shared_key {
+        Some(key) => key,
+        None => {
+            own_key = DerivedKey::generate(password)?;
+            &own_key
+        }
+    };
+
+    let metadata = FileMetadata::from_fs(&std::fs::metadata(&item.source)?)?;
+
+    let file_options = EncryptOptions {
+        verify_after_write: options.verify_after_write,
+        ..EncryptOptions::with_algorithm(algorithm)
+    };
+    let summary = encrypt_file_with_key(
+        &item.source,
+        &item.target,
+        password,
+        key,
+        &file_options,
+        progress,
+    )?;
+
+    Ok(ManifestEntry {
+        path: item.rel.clone(),
+        plaintext_len: summary.len,
+        algorithm,
+        blake3: summary.blake3,
+        metadata,
+    })
+}
+
+/// 将 manifest 加密写入输出根目录；有共享密钥时复用，否则单独派生。
+fn write_manifest(
+    output_path: &Path,
+    password: &[u8],
+    algorithm: AeadAlgorithm,
+    shared_key: Option<&DerivedKey>,
+    manifest: Manifest,
+) -> io::Result<()> {
+    let plaintext = manifest.to_bytes()?;
+
+    let own_key;
+    let key = match shared_key {
+        Some(key) => key,
+        None => {
+            own_key = DerivedKey::generate(password)?;
+            &own_key
+        }
+    };
+    let ciphertext = encrypt_bytes_with_key(&plaintext, key, algorithm)?;
+
+    write_atomic(&output_path.join(MANIFEST_FILE_NAME), |output| {
+        io::Write::write_all(output, &ciphertext)
+    })
+}
+
+/// 读取并解密输入根目录中的 manifest；不存在时返回 `None`。
+fn read_manifest(
+    input_path: &Path,
+    password: &[u8],
+    cached_key: &mut Option<DerivedKey>,
+) -> io::Result<Option<Manifest>> {
+    let file = match std::fs::File::open(input_path.join(MANIFEST_FILE_NAME)) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+
+    let mut reader = BufReader::new(file);
+    let (_header, mut decryptor) =
+        open_for_decrypt_reusing_key(&mut reader, password, None, cached_key)?;
+    let mut plaintext = Vec::new();
+    decryptor.decrypt(&mut reader, &mut plaintext)?;
+
+    Manifest::from_bytes(&plaintext).map(Some)
+}
+
+/// 在输出目录中按 manifest 记录重建符号链接（链接内容原样还原）。
+///
+/// 在所有文件写出之后调用，避免后续写入经由新建的链接落到输出目录之外。
+/// 目标位置已有符号链接时替换；已有其他文件时报错。非 Unix 平台跳过。
+/// 链接路径的上级目录中含有符号链接（如先还原的 `a -> /etc` 与 `a/passwd`）时报错，
+/// 防止经由链接写到输出目录之外。
+fn restore_symlinks(output_path: &Path, symlinks: &[ManifestSymlink]) -> io::Result<()> {
+    for symlink in symlinks {
+        let safe_rel = validate_relative_path(&symlink.path)?;
+        ensure_no_symlink_ancestors(output_path, &safe_rel)?;
+        let link_path = safe_join(output_path, &safe_rel)?;
+        if let Some(parent) = link_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        match std::fs::symlink_metadata(&link_path) {
+            Ok(existing) if existing.file_type().is_symlink() => {
+                std::fs::remove_file(&link_path)?;
+            }
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("无法创建符号链接，目标已存在: {link_path:?}"),
+                ));
             }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
 
-            encrypt_file_with_algorithm(source_path, &target_file_path, password, algorithm)?;
+        create_symlink(&symlink.target, &link_path)?;
+    }
+    Ok(())
+}
+
+/// 确认 `rel` 在 `root` 之下的各级上级目录都不是符号链接。
+fn ensure_no_symlink_ancestors(root: &Path, rel: &Path) -> io::Result<()> {
+    let Some(parent) = rel.parent() else {
+        return Ok(());
+    };
+
+    let mut current = root.to_path_buf();
+    for comp in parent.components() {
+        current.push(comp);
+        match std::fs::symlink_metadata(&current) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("路径经过符号链接，拒绝写入: {rel:?}"),
+                ));
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => break,
+            Err(e) => return Err(e),
         }
     }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &Path, link_path: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link_path)
+}
 
+#[cfg(not(unix))]
+fn create_symlink(_target: &Path, _link_path: &Path) -> io::Result<()> {
     Ok(())
 }
 
+fn manifest_mismatch(reason: String) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        SealVaultError::ManifestMismatch(reason),
+    )
+}
+
 /// 将目录中的 `.svlt` 文件逐个解密到目标目录。
 ///
 /// - 只处理 `.svlt` 文件。
 /// - 解密后去掉 `.svlt` 后缀并恢复相对路径。
+/// - 每个文件的算法由其 Header 决定，目录中可混合不同算法。
+/// - 相邻文件的 salt 与 KDF 参数相同（共享 salt 加密）时复用上一次派生的密钥。
 /// - 严格限制输出路径，防止路径穿越。
+/// - 开始前清理输出目录中先前中断的运行留下的临时文件。
+/// - 返回解密的文件数量与跳过的非 `.svlt` 文件。
 pub fn decrypt_folder(
     input_path: &Path,
     output_path: &Path,
-    password: &str,
-    _algorithm: AeadAlgorithm,
-) -> io::Result<()> {
+    password: &[u8],
+) -> io::Result<FolderResult> {
+    decrypt_folder_with_options(input_path, output_path, password, &FolderOptions::default())
+}
+
+/// 按 `options` 将目录中的 `.svlt` 文件逐个解密到目标目录。
+///
+/// - 文件是否被视为密文只看最后一个后缀是否为 `.svlt`（如 `data.svlt.bak` 不是）。
+/// - 非 `.svlt` 文件按 `options.non_encrypted_files` 跳过（记入返回值的 `skipped`）或原样复制。
+/// - 输入根目录的 `manifest.svmf` 不会被解密或复制；存在时据此还原文件权限、修改时间
+///   与符号链接，`options.verify_manifest` 时还用于校验内容。
+/// - 未要求 `verify_manifest` 时，manifest 无法读取或解密视同不存在：文件照常解密，
+///   只是不还原权限、修改时间与符号链接。
+pub fn decrypt_folder_with_options(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &FolderOptions,
+) -> io::Result<FolderResult> {
+    decrypt_folder_with_progress(input_path, output_path, password, options, &mut |_| {})
+}
+
+/// 按 `options` 解密目录，并通过 `progress` 报告进度。
+///
+/// - 先遍历一遍目录统计 `.svlt` 文件数，作为 `FileStarted` 的 `total`。
+/// - 每个 `.svlt` 文件依次报告 `FileStarted`、`FileFinished`（不报告 `BytesProcessed`）；
+///   失败或取消的文件不会报告 `FileFinished`。跳过或复制的非 `.svlt` 文件不报告。
+pub fn decrypt_folder_with_progress(
+    input_path: &Path,
+    output_path: &Path,
+    password: &[u8],
+    options: &FolderOptions,
+    progress: &mut dyn FnMut(ProgressEvent),
+) -> io::Result<FolderResult> {
     if !input_path.is_dir() {
         return Err(io::Error::new(
             io::ErrorKind::InvalidInput,
@@ -92,9 +632,47 @@ pub fn decrypt_folder(
         ));
     }
 
+    ensure_output_outside_input(input_path, output_path)?;
+
     std::fs::create_dir_all(output_path)?;
 
+    let mut cached_key = None;
+    let manifest = match read_manifest(input_path, password, &mut cached_key) {
+        Ok(manifest) => manifest,
+        Err(e) if options.verify_manifest => return Err(e),
+        // 未要求校验时 manifest 只用于还原元数据，损坏不应阻止解密文件
+        Err(_) => None,
+    };
+    if options.verify_manifest && manifest.is_none() {
+        return Err(manifest_mismatch(format!("{MANIFEST_FILE_NAME} not found")));
+    }
+    let (mut records, symlinks): (BTreeMap<PathBuf, ManifestEntry>, _) = match manifest {
+        Some(manifest) => (
+            manifest
+                .entries
+                .into_iter()
+                .map(|entry| (entry.path.clone(), entry))
+                .collect(),
+            manifest.symlinks,
+        ),
+        None => Default::default(),
+    };
+
+    // 遍历错误在下面的正式遍历中报告
+    let total = WalkDir::new(input_path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry.file_type().is_file()
+                && entry.path().extension().and_then(OsStr::to_str) == Some(ENCRYPTED_EXT)
+        })
+        .count();
+
+    let mut result = FolderResult::default();
     for entry in WalkDir::new(input_path).follow_links(false) {
+        check_cancelled(options, result.processed)?;
+
         let entry = entry.map_err(walkdir_to_io)?;
         let source_path = entry.path();
 
@@ -105,14 +683,24 @@ pub fn decrypt_folder(
         let safe_rel = validate_relative_path(rel)?;
 
         if entry.file_type().is_dir() {
-            let target_dir = safe_join(output_path, &safe_rel)?;
-            std::fs::create_dir_all(target_dir)?;
+            create_target_dir(output_path, &safe_rel)?;
             continue;
         }
 
         if entry.file_type().is_file() {
-            // 仅处理 .svlt 文件，其余文件跳过（避免误解密）。
+            if safe_rel == Path::new(MANIFEST_FILE_NAME) {
+                continue;
+            }
+
+            // 仅解密 .svlt 文件，其余文件按策略处理（避免误解密）。
             if source_path.extension().and_then(OsStr::to_str) != Some(ENCRYPTED_EXT) {
+                match options.non_encrypted_files {
+                    NonEncryptedFilePolicy::Copy => {
+                        let target_file_path = safe_join(output_path, &safe_rel)?;
+                        copy_file_atomic(source_path, &target_file_path)?;
+                    }
+                    NonEncryptedFilePolicy::Skip => result.skipped.push(safe_rel),
+                }
                 continue;
             }
 
@@ -123,11 +711,213 @@ pub fn decrypt_folder(
                 std::fs::create_dir_all(parent)?;
             }
 
-            decrypt_file(source_path, &target_file_path, password)?;
+            progress(ProgressEvent::FileStarted {
+                path: source_path.to_path_buf(),
+                index: result.processed,
+                total,
+            });
+            let (header, summary, _) = decrypt_file_reusing_key(
+                source_path,
+                &target_file_path,
+                password,
+                None,
+                &DecryptOptions::default(),
+                &mut cached_key,
+            )?;
+            result.processed += 1;
+
+            let record = records.remove(&target_file_rel);
+            if options.verify_manifest {
+                let Some(record) = &record else {
+                    return Err(manifest_mismatch(format!(
+                        "unexpected file {}",
+                        target_file_rel.display()
+                    )));
+                };
+                if record.plaintext_len != summary.len
+                    || record.blake3 != summary.blake3
+                    || record.algorithm != header.algorithm
+                {
+                    return Err(manifest_mismatch(format!(
+                        "content of {} differs",
+                        target_file_rel.display()
+                    )));
+                }
+            }
+
+            if let Some(record) = record {
+                record.metadata.apply_to(&target_file_path)?;
+            }
+            progress(ProgressEvent::FileFinished {
+                path: source_path.to_path_buf(),
+            });
         }
     }
 
-    Ok(())
+    if options.verify_manifest
+        && let Some(missing) = records.keys().next()
+    {
+        return Err(manifest_mismatch(format!(
+            "missing file {}",
+            missing.display()
+        )));
+    }
+
+    if options.symlinks != SymlinkPolicy::Skip {
+        restore_symlinks(output_path, &symlinks)?;
+    }
+    Ok(result)
+}
+
+/// 不写出任何明文，逐个完整校验目录中的 `.svlt` 文件能否用该密码解密。
+///
+/// 见 `verify_folder_with_options`，默认不因单个文件失败而停止。
+pub fn verify_folder(input_path: &Path, password: &[u8]) -> io::Result<FolderResult> {
+    verify_folder_with_options(input_path, password, &FolderOptions::default())
+}
+
+/// 按 `options` 校验目录中的 `.svlt` 文件，不写出任何明文。
+///
+/// - 每个文件认证全部 chunk 与结束标记（同 `verify_file`），通过的计入 `processed`；
+/// - 失败（密码错误、损坏、截断、无法读取等）的记入 `failed` 并继续，
+///   `options.stop_on_first_failure` 时直接返回该文件的错误；
+/// - 非 `.svlt` 文件记入 `skipped`，输入根目录的 `manifest.svmf` 不参与校验；
+/// - 相邻文件的 salt 与 KDF 参数相同时复用上一次派生的密钥；
+/// - 遍历目录出错或 `options.cancel` 被置位时返回错误。
+pub fn verify_folder_with_options(
+    input_path: &Path,
+    password: &[u8],
+    options: &FolderOptions,
+) -> io::Result<FolderResult> {
+    if !input_path.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "input_path 不是目录",
+        ));
+    }
+
+    let mut cached_key = None;
+    let mut result = FolderResult::default();
+    for entry in WalkDir::new(input_path).follow_links(false) {
+        check_cancelled(options, result.processed)?;
+
+        let entry = entry.map_err(walkdir_to_io)?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let source_path = entry.path();
+        let rel = source_path.strip_prefix(input_path).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("无法计算相对路径: {e}"))
+        })?;
+        if rel == Path::new(MANIFEST_FILE_NAME) {
+            continue;
+        }
+        if source_path.extension().and_then(OsStr::to_str) != Some(ENCRYPTED_EXT) {
+            result.skipped.push(rel.to_path_buf());
+            continue;
+        }
+
+        match verify_file_reusing_key(source_path, password, None, &mut cached_key) {
+            Ok(()) => result.processed += 1,
+            Err(e) if options.stop_on_first_failure => return Err(e),
+            Err(_) => result.failed.push(rel.to_path_buf()),
+        }
+    }
+
+    Ok(result)
+}
+
+/// 已请求取消时返回 `cancelled(completed)`
+fn check_cancelled(options: &FolderOptions, completed: usize) -> io::Result<()> {
+    match &options.cancel {
+        Some(flag) if flag.load(Ordering::SeqCst) => Err(cancelled(completed)),
+        _ => Ok(()),
+    }
+}
+
+fn cancelled(completed: usize) -> io::Error {
+    io::Error::other(SealVaultError::Cancelled { completed })
+}
+
+fn is_cancelled(err: &io::Error) -> bool {
+    err.get_ref()
+        .and_then(|inner| inner.downcast_ref::<SealVaultError>())
+        .is_some_and(|inner| matches!(inner, SealVaultError::Cancelled { .. }))
+}
+
+/// 原样复制文件，输出同样走原子写。
+fn copy_file_atomic(source_path: &Path, target_path: &Path) -> io::Result<()> {
+    let mut source = std::fs::File::open(source_path)?;
+    write_atomic(target_path, |output| {
+        io::copy(&mut source, output)?;
+        Ok(())
+    })
+}
+
+/// 目录中 `.svlt` 文件的格式分布统计（仅读取 Header，不需要密码）。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FolderInfo {
+    /// 成功解析 Header 的 `.svlt` 文件数量
+    pub files: usize,
+    /// 按 (version, algorithm) 统计的文件数量
+    pub formats: HashMap<(u8, AeadAlgorithm), usize>,
+    /// 按 chunk_size 统计的文件数量
+    pub chunk_sizes: HashMap<u32, usize>,
+    /// Header 无法解析的 `.svlt` 文件（相对输入目录）
+    pub invalid: Vec<PathBuf>,
+}
+
+impl FolderInfo {
+    /// 目录中是否混用了多种版本/算法/chunk 大小
+    pub fn is_mixed(&self) -> bool {
+        self.formats.len() > 1 || self.chunk_sizes.len() > 1
+    }
+}
+
+/// 统计目录中所有 `.svlt` 文件的版本、算法与 chunk 大小分布。
+///
+/// - 只读取每个文件的 Header，不派生密钥、不解密。
+/// - Header 解析失败的文件记入 `invalid`，不会中断统计。
+pub fn folder_info(input_path: &Path) -> io::Result<FolderInfo> {
+    if !input_path.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "input_path 不是目录",
+        ));
+    }
+
+    let mut info = FolderInfo::default();
+
+    for entry in WalkDir::new(input_path).follow_links(false) {
+        let entry = entry.map_err(walkdir_to_io)?;
+        let source_path = entry.path();
+
+        if !entry.file_type().is_file()
+            || source_path.extension().and_then(OsStr::to_str) != Some(ENCRYPTED_EXT)
+        {
+            continue;
+        }
+
+        let rel = source_path.strip_prefix(input_path).map_err(|e| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("无法计算相对路径: {e}"))
+        })?;
+
+        let reader = BufReader::new(std::fs::File::open(source_path)?);
+        match Header::read(reader) {
+            Ok(header) => {
+                info.files += 1;
+                *info
+                    .formats
+                    .entry((header.version, header.algorithm))
+                    .or_default() += 1;
+                *info.chunk_sizes.entry(header.chunk_size).or_default() += 1;
+            }
+            Err(_) => info.invalid.push(rel.to_path_buf()),
+        }
+    }
+
+    Ok(info)
 }
 
 fn append_svlt_suffix(name: &OsStr) -> std::ffi::OsString {
@@ -164,8 +954,54 @@ fn remove_svlt_extension(rel_path: &Path) -> io::Result<PathBuf> {
     Ok(out)
 }
 
+/// 拒绝输出目录位于输入目录内部（或与之相同）的情况。
+///
+/// 否则遍历输入目录时会遇到刚写出的结果，导致重复加密甚至无限增长。
+/// 两个路径都会先规范化（解析符号链接与 `..`），输出目录允许尚不存在。
+pub(crate) fn ensure_output_outside_input(input_path: &Path, output_path: &Path) -> io::Result<()> {
+    let input = input_path.canonicalize()?;
+    let output = canonicalize_lenient(output_path)?;
+
+    if output.starts_with(&input) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("输出目录不能位于输入目录内部: {output_path:?}"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// 规范化可能尚不存在的路径：规范化最近的已存在祖先，再按词法拼接剩余部分。
+fn canonicalize_lenient(path: &Path) -> io::Result<PathBuf> {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(path)
+    };
+
+    let mut existing = absolute.as_path();
+    let mut rest = Vec::new();
+    while !existing.exists() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                rest.push(name.to_os_string());
+                existing = parent;
+            }
+            // 以 `..` 结尾或已到根：交给 canonicalize 处理
+            _ => break,
+        }
+    }
+
+    let mut resolved = existing.canonicalize()?;
+    for name in rest.iter().rev() {
+        resolved.push(name);
+    }
+    Ok(resolved)
+}
+
 /// 校验相对路径仅包含安全组件，防止 `..`、绝对路径、盘符路径等穿越问题。
-fn validate_relative_path(rel: &Path) -> io::Result<PathBuf> {
+pub(crate) fn validate_relative_path(rel: &Path) -> io::Result<PathBuf> {
     let mut safe = PathBuf::new();
     for comp in rel.components() {
         match comp {
@@ -183,7 +1019,7 @@ fn validate_relative_path(rel: &Path) -> io::Result<PathBuf> {
 }
 
 /// 在目标根目录下拼接已校验的相对路径，并再次校验结果不越界。
-fn safe_join(root: &Path, rel: &Path) -> io::Result<PathBuf> {
+pub(crate) fn safe_join(root: &Path, rel: &Path) -> io::Result<PathBuf> {
     let joined = root.join(rel);
     if !joined.starts_with(root) {
         return Err(io::Error::new(
@@ -194,6 +1030,11 @@ fn safe_join(root: &Path, rel: &Path) -> io::Result<PathBuf> {
     Ok(joined)
 }
 
-fn walkdir_to_io(err: walkdir::Error) -> io::Error {
-    io::Error::other(err.to_string())
+/// 遍历错误转为 `io::Error`：有底层 I/O 错误时保留其 `ErrorKind`（如 `PermissionDenied`），
+/// 消息中带出错路径；符号链接成环等没有底层错误的情况归为 `Other`
+pub(crate) fn walkdir_to_io(err: walkdir::Error) -> io::Error {
+    match err.io_error() {
+        Some(inner) => io::Error::new(inner.kind(), err.to_string()),
+        None => io::Error::other(err.to_string()),
+    }
 }
@@ -0,0 +1,145 @@
+//! SealVault 单文件目录归档
+//!
+//! 把整个目录树序列化为一条逻辑明文流，再整体按 .svlt 格式（Header + Stream）加密，
+//! 输出只有一个文件，不暴露目录结构与文件数量。
+//!
+//! 本模块只负责归档明文的编码与解码，加解密与落盘见 `crate::archive`。
+//!
+//! 明文布局（整数均为大端）：
+//!
+//! ```text
+//! magic "SVAR" (4) | version (1)
+//! entry: kind u8 | path_len u32 | path | [kind = file: len u64 | data (len)]
+//! end:   kind u8 = 0
+//! ```
+//!
+//! `kind`：`1` 目录、`2` 普通文件、`0` 结束标记。结束标记之后不应再有任何数据，
+//! 以此区分完整归档与在条目边界处被截断的归档。
+//!
+//! `path` 与目录 manifest 使用相同编码：相对归档根目录，组件以 `/` 连接，
+//! Unix 上按原始字节保存，其他平台要求 UTF-8。目录条目总是先于其中的内容出现。
+
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+
+use crate::error::SealVaultError;
+use crate::format::manifest::{MAX_PATH_LEN, path_from_bytes, path_to_bytes};
+
+/// 归档明文魔数
+const ARCHIVE_MAGIC: &[u8; 4] = b"SVAR";
+
+/// 归档明文格式版本
+const ARCHIVE_VERSION: u8 = 1;
+
+const KIND_END: u8 = 0;
+const KIND_DIR: u8 = 1;
+const KIND_FILE: u8 = 2;
+
+/// 归档中的单个条目头（文件内容紧随其后，不在此结构中）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArchiveEntry {
+    /// 目录（可以为空）
+    Dir { path: PathBuf },
+    /// 普通文件，之后紧跟 `len` 字节内容
+    File { path: PathBuf, len: u64 },
+}
+
+impl ArchiveEntry {
+    /// 写出条目头
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let (kind, path) = match self {
+            Self::Dir { path } => (KIND_DIR, path),
+            Self::File { path, .. } => (KIND_FILE, path),
+        };
+
+        let path = path_to_bytes(path)?;
+        if path.is_empty() || path.len() > MAX_PATH_LEN {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "归档路径为空或过长",
+            ));
+        }
+
+        writer.write_all(&[kind])?;
+        writer.write_all(&(path.len() as u32).to_be_bytes())?;
+        writer.write_all(&path)?;
+        if let Self::File { len, .. } = self {
+            writer.write_all(&len.to_be_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// 读取下一个条目头；读到结束标记时返回 `None`
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Option<Self>> {
+        let mut kind = [0u8; 1];
+        read_field(&mut reader, &mut kind)?;
+        if kind[0] == KIND_END {
+            return Ok(None);
+        }
+        if kind[0] != KIND_DIR && kind[0] != KIND_FILE {
+            return Err(corrupted());
+        }
+
+        let mut len_buf = [0u8; 4];
+        read_field(&mut reader, &mut len_buf)?;
+        let path_len = u32::from_be_bytes(len_buf) as usize;
+        if path_len == 0 || path_len > MAX_PATH_LEN {
+            return Err(corrupted());
+        }
+        let mut path = vec![0u8; path_len];
+        read_field(&mut reader, &mut path)?;
+        let path = path_from_bytes(&path)?;
+
+        if kind[0] == KIND_DIR {
+            return Ok(Some(Self::Dir { path }));
+        }
+
+        let mut len = [0u8; 8];
+        read_field(&mut reader, &mut len)?;
+        Ok(Some(Self::File {
+            path,
+            len: u64::from_be_bytes(len),
+        }))
+    }
+}
+
+/// 写出归档开头（魔数与版本）
+pub fn write_start<W: Write>(mut writer: W) -> io::Result<()> {
+    writer.write_all(ARCHIVE_MAGIC)?;
+    writer.write_all(&[ARCHIVE_VERSION])
+}
+
+/// 写出结束标记
+pub fn write_end<W: Write>(mut writer: W) -> io::Result<()> {
+    writer.write_all(&[KIND_END])
+}
+
+/// 读取并校验归档开头
+pub fn read_start<R: Read>(mut reader: R) -> io::Result<()> {
+    let mut magic = [0u8; 4];
+    read_field(&mut reader, &mut magic)?;
+    if &magic != ARCHIVE_MAGIC {
+        return Err(corrupted());
+    }
+
+    let mut version = [0u8; 1];
+    read_field(&mut reader, &mut version)?;
+    if version[0] != ARCHIVE_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            SealVaultError::UnsupportedVersion,
+        ));
+    }
+    Ok(())
+}
+
+fn corrupted() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, SealVaultError::CorruptedData)
+}
+
+fn read_field<R: Read>(mut reader: R, buf: &mut [u8]) -> io::Result<()> {
+    reader.read_exact(buf).map_err(|e| match e.kind() {
+        io::ErrorKind::UnexpectedEof => corrupted(),
+        _ => e,
+    })
+}
@@ -0,0 +1,202 @@
+//! SealVault 断点（checkpoint）sidecar 格式
+//!
+//! 用于超大文件的可恢复加密与解密：记录已完整写出的 chunk 数量与输出偏移，
+//! 重启后可从该位置继续，而不必从头开始。
+//!
+//! 布局（固定 113 字节，整数均为大端）：
+//!
+//! 8  (magic)
+//! 16 (salt)        —— 与 Header 中的 salt 一致，用于确认是同一个 .svlt
+//! 24 (base_nonce)  —— 与 Header 中的 base_nonce 一致
+//! 8  (chunk_index) —— 已完整写出的 chunk 数量
+//! 8  (output_offset) —— 已写出的输出字节数（解密为明文长度，加密为含 Header 的密文长度）
+//! 1  (has_input)   —— 是否记录了输入指纹（加密为 1，解密为 0）
+//! 8  (input_len)   —— 输入文件长度
+//! 8  (input_modified) —— 输入文件修改时间（UNIX 纪元起的纳秒数，无法获取时为 0）
+//! 32 (input_prefix_digest) —— 已读取的输入前缀的带密钥 BLAKE3（见 `input_prefix_digest`）
+//!
+//! 不记录输入指纹时最后 48 字节为 0。
+//!
+//! 加密恢复时沿用原 Header 的密钥与 base_nonce，续写的 chunk 与中断前已写出（随后被截断）的
+//! chunk 使用相同的 nonce；输入在两次运行之间改变会以同一 nonce 加密不同明文。因此加密必须
+//! 记录输入指纹，恢复前核对长度、修改时间与已读取前缀的摘要，不一致时拒绝恢复。
+//! 解密恢复时已跳过的 chunk 会逐个重新认证，不需要输入指纹。
+
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use crate::format::header::{BASE_NONCE_SIZE, Header, SALT_SIZE};
+use crate::fs::atomic::write_atomic;
+
+/// checkpoint 文件魔数
+pub const CHECKPOINT_MAGIC: &[u8; 8] = b"SVCKPT1\0";
+
+/// 派生输入前缀摘要密钥的 BLAKE3 上下文字符串
+const INPUT_PREFIX_CONTEXT: &str = "SealVault 2026-10 checkpoint input prefix";
+
+/// checkpoint 内容
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    pub salt: [u8; SALT_SIZE],
+    pub base_nonce: [u8; BASE_NONCE_SIZE],
+    pub chunk_index: u64,
+    pub output_offset: u64,
+    /// 输入文件指纹（加密时记录），`None` 表示未记录
+    pub input: Option<InputFingerprint>,
+}
+
+/// 加密 checkpoint 记录的输入文件指纹
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputFingerprint {
+    /// 输入文件长度
+    pub len: u64,
+    /// 输入文件修改时间（UNIX 纪元起的纳秒数，无法获取时为 0）
+    pub modified: u64,
+    /// 已读取的输入前缀（`chunk_index * chunk_size` 字节）的带密钥 BLAKE3
+    pub prefix_digest: [u8; 32],
+}
+
+impl InputFingerprint {
+    /// 由输入文件的元数据与已读取前缀的摘要构造
+    pub fn new(metadata: &std::fs::Metadata, prefix_digest: [u8; 32]) -> Self {
+        Self {
+            len: metadata.len(),
+            modified: modified_nanos(metadata),
+            prefix_digest,
+        }
+    }
+
+    /// 元数据中的长度与修改时间是否与记录一致（不比较前缀摘要）
+    pub fn matches_metadata(&self, metadata: &std::fs::Metadata) -> bool {
+        self.len == metadata.len() && self.modified == modified_nanos(metadata)
+    }
+}
+
+fn modified_nanos(metadata: &std::fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| u64::try_from(d.as_nanos()).unwrap_or(u64::MAX))
+}
+
+/// 计算输入前缀摘要的 hasher，密钥由文件的 AEAD 密钥派生
+///
+/// 使用带密钥的 BLAKE3，checkpoint 不会泄露可用于离线猜测明文的摘要。
+pub(crate) fn input_prefix_hasher(key: &[u8]) -> blake3::Hasher {
+    blake3::Hasher::new_keyed(&blake3::derive_key(INPUT_PREFIX_CONTEXT, key))
+}
+
+/// 计算输入前缀的带密钥摘要（`key` 为文件的 AEAD 密钥），与加密时记录的 `prefix_digest` 一致
+pub fn input_prefix_digest(key: &[u8], prefix: &[u8]) -> [u8; 32] {
+    input_prefix_hasher(key).update(prefix).finalize().into()
+}
+
+impl Checkpoint {
+    /// 为指定 Header 创建 checkpoint（不含输入指纹）
+    pub fn new(header: &Header, chunk_index: u64, output_offset: u64) -> Self {
+        Self {
+            salt: header.salt,
+            base_nonce: header.base_nonce,
+            chunk_index,
+            output_offset,
+            input: None,
+        }
+    }
+
+    /// 附加输入指纹
+    pub fn with_input(self, input: InputFingerprint) -> Self {
+        Self {
+            input: Some(input),
+            ..self
+        }
+    }
+
+    /// checkpoint 是否属于该 Header 对应的文件
+    pub fn matches(&self, header: &Header) -> bool {
+        self.salt == header.salt && self.base_nonce == header.base_nonce
+    }
+
+    /// 从文件读取 checkpoint；文件不存在时返回 `Ok(None)`
+    pub fn load(path: &Path) -> io::Result<Option<Self>> {
+        let mut file = match std::fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+
+        let mut magic = [0u8; 8];
+        file.read_exact(&mut magic)?;
+        if &magic != CHECKPOINT_MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "invalid SealVault checkpoint",
+            ));
+        }
+
+        let mut salt = [0u8; SALT_SIZE];
+        file.read_exact(&mut salt)?;
+
+        let mut base_nonce = [0u8; BASE_NONCE_SIZE];
+        file.read_exact(&mut base_nonce)?;
+
+        let mut chunk_index = [0u8; 8];
+        file.read_exact(&mut chunk_index)?;
+
+        let mut output_offset = [0u8; 8];
+        file.read_exact(&mut output_offset)?;
+
+        let mut has_input = [0u8; 1];
+        file.read_exact(&mut has_input)?;
+        let mut len = [0u8; 8];
+        file.read_exact(&mut len)?;
+        let mut modified = [0u8; 8];
+        file.read_exact(&mut modified)?;
+        let mut prefix_digest = [0u8; 32];
+        file.read_exact(&mut prefix_digest)?;
+        let input = match has_input[0] {
+            0 => None,
+            1 => Some(InputFingerprint {
+                len: u64::from_be_bytes(len),
+                modified: u64::from_be_bytes(modified),
+                prefix_digest,
+            }),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "invalid SealVault checkpoint",
+                ));
+            }
+        };
+
+        Ok(Some(Self {
+            salt,
+            base_nonce,
+            chunk_index: u64::from_be_bytes(chunk_index),
+            output_offset: u64::from_be_bytes(output_offset),
+            input,
+        }))
+    }
+
+    /// 原子写出 checkpoint
+    pub fn store(&self, path: &Path) -> io::Result<()> {
+        write_atomic(path, |file| {
+            file.write_all(CHECKPOINT_MAGIC)?;
+            file.write_all(&self.salt)?;
+            file.write_all(&self.base_nonce)?;
+            file.write_all(&self.chunk_index.to_be_bytes())?;
+            file.write_all(&self.output_offset.to_be_bytes())?;
+            match &self.input {
+                Some(input) => {
+                    file.write_all(&[1])?;
+                    file.write_all(&input.len.to_be_bytes())?;
+                    file.write_all(&input.modified.to_be_bytes())?;
+                    file.write_all(&input.prefix_digest)?;
+                }
+                None => file.write_all(&[0u8; 1 + 8 + 8 + 32])?,
+            }
+            Ok(())
+        })
+    }
+}
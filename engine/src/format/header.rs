@@ -1,6 +1,6 @@
-//! SealVault v1 Header 实现
+//! SealVault Header 实现
 //!
-//! 本模块定义并实现 SealVault v1 文件格式中的 Header 部分。
+//! 本模块定义并实现 SealVault 文件格式中的 Header 部分。
 //!
 //! Header 的职责：
 //! - 标识文件类型（magic）
@@ -9,26 +9,64 @@
 //! - 提供密钥派生所需的 salt
 //! - 提供 Stream 所需的 base_nonce
 //! - 指明 stream 的 chunk_size
+//! - 指明 KDF 算法（Argon2id / scrypt）及其参数（v2 起）
+//! - 指明明文压缩编码（v2 起）
+//! - 标志位，及由标志位决定是否出现的扩展字段（v2 起）：keyfile、明文摘要、原始文件名、
+//!   长度隐藏填充的块大小、经认证但不加密的注释、数据 chunk 总数
+//! - 末尾的 Header 校验值（v2 起），解析时即发现损坏的 Header
 //!
 //! Header 是整个 .svlt 文件的“格式锚点”：
 //! - 解密前必须完整读取并校验 Header
 //! - Header 一旦解析失败，必须拒绝继续处理
 //!
-//! SealVault v1 Header 为固定结构，后续版本只能：
-//! - bump version
-//! - 或在 Header 后追加扩展区
+//! v1 Header 为固定结构（最早的 v1 文件没有 algorithm 字节，见 `read_v1_fields`）。
+//! v2 在其后依次追加 KDF 算法 ID 与参数区、1 字节压缩编码、1 字节标志位，以及扩展区：
+//! 扩展区只包含标志位选中的字段，按标志位从低到高的顺序排列
+//! （`FLAG_PADDED` 的 4 字节填充块大小、`FLAG_COMMENT` 的 1 字节长度与注释、
+//! `FLAG_TOTAL_CHUNKS` 的 8 字节 chunk 总数），最后是校验值。
+//!
+//! 后续新增 Header 字段时定义新的标志位，并把字段按上述顺序加入扩展区，不再提升版本号；
+//! 不认识的标志位一律视为损坏，旧实现不会误读新字段。只有改变既有字段或 Stream 语义时才 bump version。
+//!
+//! 校验值是不带密钥的截断 SHA-256，只是让损坏在派生密钥之前就被发现的完整性校验和，
+//! 不是认证：任何人都能在改动 Header 后重算它。Header 的真实性只由 `header_digest`
+//! 绑定到每个 chunk 的 AAD 来保证，改动过的 Header 最迟在第一个 chunk 认证失败。
 
 use std::io::{Read, Write};
 
-use crate::algorithm::AeadAlgorithm;
+use sha2::{Digest, Sha256};
+
+// Header 只使用 `algorithm` 模块中唯一的算法枚举及其 ID 映射，不另行定义
+pub use crate::algorithm::AeadAlgorithm;
+use crate::algorithm::DEFAULT_AEAD_ALGORITHM;
+use crate::compression::Codec;
+use crate::crypto::kdf::{KdfAlgorithm, KdfParams, SaltEncoding, ScryptParams};
+use crate::error::SealVaultError;
+use crate::format::stream::{LEN_SIZE, PADDING_LEN_SIZE, PLAINTEXT_DIGEST_SIZE, TAG_SIZE};
+use crate::padding::Padding;
 
 /// SealVault 文件魔数（ASCII）
 ///
 /// 用于快速判断文件类型，避免误读。
 pub const MAGIC: &[u8; 8] = b"SVLTv1\0\0";
 
-/// 当前支持的版本号
-pub const VERSION: u8 = 1;
+/// 当前写入的版本号
+///
+/// v2：Header 记录 KDF、压缩编码与标志位扩展区，末尾带校验值；Stream 以 final chunk 结束，
+/// AAD 绑定 Header 摘要；Argon2id 以原始 salt 字节为输入。
+pub const VERSION: u8 = 2;
+
+/// 旧版本号（仅用于解密兼容）
+///
+/// v1 Stream 没有结束标记，AAD 不绑定 Header；KDF 固定为默认 Argon2id 参数，
+/// 以 salt 的 base64 文本为输入；最早的 v1 文件没有 algorithm 字节（见 `read_v1_fields`）。
+pub const VERSION_V1: u8 = 1;
+
+/// v1 写入端固定使用的 chunk 大小，用于区分 v1 的两种 Header 布局
+pub const LEGACY_V1_CHUNK_SIZE: u32 = 64 * 1024;
+
+/// Header 摘要长度（SHA-256，字节）
+pub const HEADER_DIGEST_SIZE: usize = 32;
 
 /// KDF 使用的 salt 长度（字节）
 pub const SALT_SIZE: usize = 16;
@@ -37,7 +75,10 @@ pub const SALT_SIZE: usize = 16;
 /// AES-256-GCM 实际使用前 12 字节
 pub const BASE_NONCE_SIZE: usize = 24;
 
-/// SealVault v1 Header 固定大小
+/// 各版本共有的 Header 固定部分大小（即 v1 Header 的完整大小）
+///
+/// v2 在其后追加 KDF 参数、压缩编码、标志位等，完整 Header 最长为 `MAX_HEADER_SIZE`；
+/// 最早没有 algorithm 字节的 v1 Header 比它少 1 字节。
 ///
 /// 8  (magic)
 /// 1  (version)
@@ -45,7 +86,91 @@ pub const BASE_NONCE_SIZE: usize = 24;
 /// 16 (salt)
 /// 24 (base_nonce)
 /// 4  (chunk_size)
-pub const _HEADER_SIZE: usize = 8 + 1 + 1 + SALT_SIZE + BASE_NONCE_SIZE + 4;
+pub const HEADER_SIZE: usize = 8 + 1 + 1 + SALT_SIZE + BASE_NONCE_SIZE + 4;
+
+/// v2 追加的 Argon2id 参数区大小
+///
+/// 4 (m_cost)
+/// 4 (t_cost)
+/// 4 (p_cost)
+pub const KDF_PARAMS_SIZE: usize = 4 + 4 + 4;
+
+/// v2 追加的压缩编码大小
+pub const CODEC_SIZE: usize = 1;
+
+/// v2 KDF 参数区之前的 KDF 算法 ID 大小
+pub const KDF_ID_SIZE: usize = 1;
+
+/// v2 scrypt 参数区大小
+///
+/// 1 (log_n)
+/// 4 (r)
+/// 4 (p)
+pub const SCRYPT_PARAMS_SIZE: usize = 1 + 4 + 4;
+
+/// v2 追加的标志位大小
+pub const FLAGS_SIZE: usize = 1;
+
+/// 标志位：KDF 输入包含 keyfile，解密时必须提供同一个 keyfile
+pub const FLAG_KEYFILE: u8 = 0x01;
+
+/// 标志位：FinalChunk 携带完整明文（压缩前）的 BLAKE3 摘要
+pub const FLAG_PLAINTEXT_DIGEST: u8 = 0x02;
+
+/// 标志位：Stream 数据经长度隐藏填充，标志位之后跟 4 字节填充块大小，
+/// FinalChunk 携带真实的填充长度
+pub const FLAG_PADDED: u8 = 0x04;
+
+/// 标志位：Stream 以携带原始文件名的文件名帧开始
+pub const FLAG_FILE_NAME: u8 = 0x08;
+
+/// 标志位：Header 末尾带有经认证（不加密）的注释
+pub const FLAG_COMMENT: u8 = 0x10;
+
+/// 标志位：Header 记录数据 chunk 总数，注释之后跟 8 字节大端的 `total_chunks`
+pub const FLAG_TOTAL_CHUNKS: u8 = 0x20;
+
+/// algorithm 字节在 Header 中的偏移（magic 与 version 之后，v1 无 algorithm 字节的布局除外）
+const ALGORITHM_OFFSET: usize = MAGIC.len() + 1;
+
+/// 已定义的全部标志位，其余位必须为 0
+const KNOWN_FLAGS: u8 = FLAG_KEYFILE
+    | FLAG_PLAINTEXT_DIGEST
+    | FLAG_PADDED
+    | FLAG_FILE_NAME
+    | FLAG_COMMENT
+    | FLAG_TOTAL_CHUNKS;
+
+/// 设置 `FLAG_PADDED` 时追加的填充块大小字段大小
+pub const PADDING_BLOCK_SIZE: usize = 4;
+
+/// 设置 `FLAG_COMMENT` 时注释前的长度字段大小
+pub const COMMENT_LEN_SIZE: usize = 1;
+
+/// 注释的最大长度（字节）
+pub const MAX_COMMENT_LEN: usize = u8::MAX as usize;
+
+/// 设置 `FLAG_TOTAL_CHUNKS` 时追加的 chunk 总数字段大小
+pub const TOTAL_CHUNKS_SIZE: usize = 8;
+
+/// v2 Header 末尾校验值的大小：此前全部 Header 字节的 SHA-256 的前 16 字节
+///
+/// 校验值不含密钥，是快速失败的完整性校验和而非认证：只用于在派生密钥、读取 Stream 之前
+/// 发现损坏或随手改动的 Header；连同校验值一起重算的伪造仍会因 `header_digest` 不匹配
+/// 而使第一个 chunk 认证失败。
+pub const HEADER_CHECK_SIZE: usize = 16;
+
+/// 各版本中最长的 Header 大小
+pub const MAX_HEADER_SIZE: usize = HEADER_SIZE
+    + KDF_ID_SIZE
+    + KDF_PARAMS_SIZE
+    + CODEC_SIZE
+    + FLAGS_SIZE
+    + PADDING_BLOCK_SIZE
+    + COMMENT_LEN_SIZE
+    + MAX_COMMENT_LEN
+    + TOTAL_CHUNKS_SIZE
+    + HEADER_CHECK_SIZE;
 
 /// SealVault v1 Header 结构
 ///
@@ -58,10 +183,32 @@ pub struct Header {
     pub salt: [u8; SALT_SIZE],
     pub base_nonce: [u8; BASE_NONCE_SIZE],
     pub chunk_size: u32,
+    /// KDF 算法及参数（v1 文件固定为默认 Argon2id）
+    pub kdf: KdfAlgorithm,
+    /// 明文压缩编码（v1 文件固定为 `Codec::None`）
+    pub codec: Codec,
+    /// 是否需要 keyfile 才能派生密钥（v1 文件固定为 false）
+    pub keyfile_required: bool,
+    /// FinalChunk 是否携带明文 BLAKE3 摘要（v1 文件固定为 false）
+    pub plaintext_digest: bool,
+    /// Stream 数据的长度隐藏填充（v1 文件固定为 `None`）
+    pub padding: Option<Padding>,
+    /// Stream 是否以原始文件名帧开始（v1 文件固定为 false）
+    pub file_name: bool,
+    /// 明文保存、随 Header 摘要绑定到每个 chunk AAD 的注释，空表示没有（v1 文件固定为空）
+    ///
+    /// 长度不能超过 `MAX_COMMENT_LEN`；改动任一字节都会使解密失败。
+    pub comment: Vec<u8>,
+    /// Stream 中数据 chunk 的总数（不含文件名帧与 final chunk），`None` 表示未记录
+    /// （v1 文件与流式输入固定为 `None`）
+    ///
+    /// 设置时除最后一个外的数据 chunk 均为满 chunk，解密方可在读取 Stream 之前
+    /// 由文件长度发现截断（见 `min_stream_len`），并拒绝数量不符的 Stream。
+    pub total_chunks: Option<u64>,
 }
 
 impl Header {
-    /// 创建新的 v1 Header
+    /// 创建新的 Header（当前版本，默认 Argon2id 参数，不压缩）
     ///
     /// 该函数通常在加密时调用。
     pub fn new(
@@ -76,95 +223,447 @@ impl Header {
             salt,
             base_nonce,
             chunk_size,
+            kdf: KdfAlgorithm::default(),
+            codec: Codec::None,
+            keyfile_required: false,
+            plaintext_digest: false,
+            padding: None,
+            file_name: false,
+            comment: Vec::new(),
+            total_chunks: None,
         }
     }
 
+    /// 该版本 Argon2id 的 salt 输入形式（v2 起为原始字节）
+    pub fn salt_encoding(&self) -> SaltEncoding {
+        if self.version >= VERSION {
+            SaltEncoding::Raw
+        } else {
+            SaltEncoding::Base64
+        }
+    }
+
+    /// 由 `total_chunks` 推出的 Stream 最小长度（Header 之后的字节数），未记录时为 `None`
+    ///
+    /// 除最后一个外的数据 chunk 均为满 chunk，最后一个至少 1 字节；再加上文件名帧（若有，
+    /// 至少 1 字节）与 final chunk。剩余数据短于该值的文件必然被截断，无需派生密钥即可拒绝。
+    pub fn min_stream_len(&self) -> Option<u64> {
+        const FRAME_OVERHEAD: u64 = (LEN_SIZE + TAG_SIZE) as u64;
+
+        let total_chunks = self.total_chunks?;
+        let data = match total_chunks {
+            0 => 0,
+            n => (n - 1)
+                .saturating_mul(u64::from(self.chunk_size) + FRAME_OVERHEAD)
+                .saturating_add(1 + FRAME_OVERHEAD),
+        };
+        let file_name = if self.file_name {
+            1 + FRAME_OVERHEAD
+        } else {
+            0
+        };
+        let mut final_chunk = FRAME_OVERHEAD;
+        if self.plaintext_digest {
+            final_chunk += PLAINTEXT_DIGEST_SIZE as u64;
+        }
+        if self.padding.is_some() {
+            final_chunk += PADDING_LEN_SIZE as u64;
+        }
+        Some(data.saturating_add(file_name + final_chunk))
+    }
+
     /// 将 Header 写入输出流
     ///
-    /// 写入顺序和字节布局必须严格遵循 v1 规范。
+    /// 写入顺序和字节布局必须严格遵循规范。
+    /// 注释超过 `MAX_COMMENT_LEN`，或 v1 Header 带有 v2 才能表示的字段时返回 `InvalidInput`。
     pub fn write<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        if self.comment.len() > MAX_COMMENT_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "header comment is too long",
+            ));
+        }
+        if self.version < VERSION && self.has_v2_fields() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "KDF parameters, compression and flags require format v2",
+            ));
+        }
+        writer.write_all(&self.to_bytes())
+    }
+
+    /// 是否带有 v1 布局无法表示的字段（非默认 KDF、压缩或任一标志位）
+    fn has_v2_fields(&self) -> bool {
+        self.kdf != KdfAlgorithm::default() || self.codec != Codec::None || self.flags() != 0
+    }
+
+    /// Header 序列化字节的 SHA-256 摘要
+    ///
+    /// v2 起作为每个 chunk AAD 的一部分，使 chunk 只能在其所属文件中通过认证。
+    pub fn digest(&self) -> [u8; HEADER_DIGEST_SIZE] {
+        Sha256::digest(self.to_bytes()).into()
+    }
+
+    /// 按规范字节布局序列化 Header（v2 起末尾带校验值）
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.field_bytes();
+        if self.version >= VERSION {
+            let check = header_check(&bytes);
+            bytes.extend_from_slice(&check);
+        }
+        bytes
+    }
+
+    /// 序列化校验值之前的全部 Header 字段
+    fn field_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(MAX_HEADER_SIZE);
+
         // magic
-        writer.write_all(MAGIC)?;
+        bytes.extend_from_slice(MAGIC);
 
         // version
-        writer.write_all(&[self.version])?;
+        bytes.push(self.version);
 
         // algorithm
-        writer.write_all(&[self.algorithm.to_u8()])?;
+        bytes.push(self.algorithm.to_u8());
 
         // salt
-        writer.write_all(&self.salt)?;
+        bytes.extend_from_slice(&self.salt);
 
         // base nonce
-        writer.write_all(&self.base_nonce)?;
+        bytes.extend_from_slice(&self.base_nonce);
 
         // chunk size（大端）
-        writer.write_all(&self.chunk_size.to_be_bytes())?;
+        bytes.extend_from_slice(&self.chunk_size.to_be_bytes());
+
+        if self.version < VERSION {
+            return bytes;
+        }
+
+        // KDF 算法 ID 与参数（大端）
+        bytes.push(self.kdf.id());
+        match &self.kdf {
+            KdfAlgorithm::Argon2id(params) => {
+                bytes.extend_from_slice(&params.m_cost.to_be_bytes());
+                bytes.extend_from_slice(&params.t_cost.to_be_bytes());
+                bytes.extend_from_slice(&params.p_cost.to_be_bytes());
+            }
+            KdfAlgorithm::Scrypt(params) => {
+                bytes.push(params.log_n);
+                bytes.extend_from_slice(&params.r.to_be_bytes());
+                bytes.extend_from_slice(&params.p.to_be_bytes());
+            }
+        }
+
+        // 压缩编码
+        bytes.push(self.codec.to_u8());
+
+        // 标志位
+        bytes.push(self.flags());
 
-        Ok(())
+        // 扩展区：按标志位从低到高排列，只包含已设置标志位的字段
+
+        // 填充块大小（大端，仅设置 FLAG_PADDED 时）
+        if let Some(padding) = self.padding {
+            bytes.extend_from_slice(&padding.block_size().to_be_bytes());
+        }
+
+        // 注释长度与内容（仅设置 FLAG_COMMENT 时；长度已由 `write` 校验）
+        if !self.comment.is_empty() {
+            bytes.push(self.comment.len() as u8);
+            bytes.extend_from_slice(&self.comment);
+        }
+
+        // 数据 chunk 总数（大端，仅设置 FLAG_TOTAL_CHUNKS 时）
+        if let Some(total_chunks) = self.total_chunks {
+            bytes.extend_from_slice(&total_chunks.to_be_bytes());
+        }
+
+        bytes
+    }
+
+    /// 由各字段得到的标志位
+    fn flags(&self) -> u8 {
+        let mut flags = 0u8;
+        if self.keyfile_required {
+            flags |= FLAG_KEYFILE;
+        }
+        if self.plaintext_digest {
+            flags |= FLAG_PLAINTEXT_DIGEST;
+        }
+        if self.padding.is_some() {
+            flags |= FLAG_PADDED;
+        }
+        if self.file_name {
+            flags |= FLAG_FILE_NAME;
+        }
+        if !self.comment.is_empty() {
+            flags |= FLAG_COMMENT;
+        }
+        if self.total_chunks.is_some() {
+            flags |= FLAG_TOTAL_CHUNKS;
+        }
+        flags
     }
 
     /// 从输入流读取并解析 Header
     ///
     /// 该函数通常在解密时调用。
-    /// 若 Header 不合法，必须返回错误。
+    /// 若 Header 不合法，必须返回错误，错误负载为 `SealVaultError`：
+    /// - magic 不匹配或文件过短：`NotSealVaultFile`
+    /// - 未知版本：`UnsupportedVersion`
+    /// - 其余字段非法、被截断或与校验值（v2 起）不符：`CorruptedData`
     pub fn read<R: Read>(mut reader: R) -> std::io::Result<Self> {
         let mut magic = [0u8; 8];
-        reader.read_exact(&mut magic)?;
+        read_field(&mut reader, &mut magic, SealVaultError::NotSealVaultFile)?;
 
         if &magic != MAGIC {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "invalid SealVault magic",
-            ));
+            return Err(invalid_header(SealVaultError::NotSealVaultFile));
         }
 
         let mut version_buf = [0u8; 1];
-        reader.read_exact(&mut version_buf)?;
+        read_field(&mut reader, &mut version_buf, SealVaultError::CorruptedData)?;
         let version = version_buf[0];
 
-        let algorithm = match version {
+        let (algorithm_id, salt, base_nonce, chunk_size) = match version {
+            VERSION_V1 => {
+                let (algorithm, salt, base_nonce, chunk_size) = read_v1_fields(&mut reader)?;
+                (algorithm.to_u8(), salt, base_nonce, chunk_size)
+            }
             VERSION => {
                 let mut algorithm_buf = [0u8; 1];
-                reader.read_exact(&mut algorithm_buf)?;
-                AeadAlgorithm::from_u8(algorithm_buf[0]).ok_or_else(|| {
-                    std::io::Error::new(
-                        std::io::ErrorKind::InvalidData,
-                        "unsupported SealVault algorithm",
-                    )
-                })?
+                read_field(
+                    &mut reader,
+                    &mut algorithm_buf,
+                    SealVaultError::CorruptedData,
+                )?;
+                let mut salt = [0u8; SALT_SIZE];
+                read_field(&mut reader, &mut salt, SealVaultError::CorruptedData)?;
+
+                let mut base_nonce = [0u8; BASE_NONCE_SIZE];
+                read_field(&mut reader, &mut base_nonce, SealVaultError::CorruptedData)?;
+
+                (
+                    algorithm_buf[0],
+                    salt,
+                    base_nonce,
+                    read_u32_be(&mut reader)?,
+                )
             }
             _ => {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    "unsupported SealVault version",
-                ));
+                return Err(invalid_header(SealVaultError::UnsupportedVersion));
             }
         };
 
-        let mut salt = [0u8; SALT_SIZE];
-        reader.read_exact(&mut salt)?;
+        if chunk_size == 0 {
+            return Err(invalid_header(SealVaultError::CorruptedData));
+        }
+        // 未知算法 ID 在其余字段（及 v2 起的校验值）通过检查后才报告，
+        // 以区分更新版本写出的文件与损坏的 Header
+        let algorithm = AeadAlgorithm::from_u8(algorithm_id);
 
-        let mut base_nonce = [0u8; BASE_NONCE_SIZE];
-        reader.read_exact(&mut base_nonce)?;
+        let (kdf, codec, flags) = if version >= VERSION {
+            read_v2_fields(&mut reader)?
+        } else {
+            (KdfAlgorithm::default(), Codec::None, 0)
+        };
 
-        let mut chunk_size_buf = [0u8; 4];
-        reader.read_exact(&mut chunk_size_buf)?;
-        let chunk_size = u32::from_be_bytes(chunk_size_buf);
+        let padding = if flags & FLAG_PADDED != 0 {
+            // 块大小决定解密时暂存的数据量，来自文件，必须限制上限
+            let padding = Padding::ToBlock(read_u32_be(&mut reader)?);
+            if !padding.is_supported() {
+                return Err(invalid_header(SealVaultError::CorruptedData));
+            }
+            Some(padding)
+        } else {
+            None
+        };
 
-        if chunk_size == 0 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                "invalid chunk size",
-            ));
-        }
+        let comment = if flags & FLAG_COMMENT != 0 {
+            let mut len_buf = [0u8; COMMENT_LEN_SIZE];
+            read_field(&mut reader, &mut len_buf, SealVaultError::CorruptedData)?;
+            // 空注释不设置标志位，出现即为损坏
+            if len_buf[0] == 0 {
+                return Err(invalid_header(SealVaultError::CorruptedData));
+            }
+            let mut comment = vec![0u8; len_buf[0] as usize];
+            read_field(&mut reader, &mut comment, SealVaultError::CorruptedData)?;
+            comment
+        } else {
+            Vec::new()
+        };
 
-        Ok(Self {
+        let total_chunks = if flags & FLAG_TOTAL_CHUNKS != 0 {
+            let mut total_buf = [0u8; TOTAL_CHUNKS_SIZE];
+            read_field(&mut reader, &mut total_buf, SealVaultError::CorruptedData)?;
+            Some(u64::from_be_bytes(total_buf))
+        } else {
+            None
+        };
+
+        let header = Self {
             version,
-            algorithm,
+            algorithm: algorithm.unwrap_or(DEFAULT_AEAD_ALGORITHM),
             salt,
             base_nonce,
             chunk_size,
-        })
+            kdf,
+            codec,
+            keyfile_required: flags & FLAG_KEYFILE != 0,
+            plaintext_digest: flags & FLAG_PLAINTEXT_DIGEST != 0,
+            padding,
+            file_name: flags & FLAG_FILE_NAME != 0,
+            comment,
+            total_chunks,
+        };
+
+        // 各字段均按唯一的规范形式解析，重新序列化即得到文件中的原始字节
+        if version >= VERSION {
+            let mut check = [0u8; HEADER_CHECK_SIZE];
+            read_field(&mut reader, &mut check, SealVaultError::CorruptedData)?;
+            let mut fields = header.field_bytes();
+            // 未知算法无法由 Header 表示，按文件中的原始 ID 计算
+            fields[ALGORITHM_OFFSET] = algorithm_id;
+            if check != header_check(&fields) {
+                return Err(invalid_header(SealVaultError::CorruptedData));
+            }
+        }
+
+        if algorithm.is_none() {
+            return Err(invalid_header(SealVaultError::UnsupportedAlgorithm(
+                algorithm_id,
+            )));
+        }
+        Ok(header)
+    }
+}
+
+/// 读取 v2 Header 中 chunk_size 之后的 KDF 算法与参数、压缩编码与标志位
+///
+/// 扩展区字段由调用方按标志位继续读取。
+fn read_v2_fields<R: Read>(mut reader: R) -> std::io::Result<(KdfAlgorithm, Codec, u8)> {
+    let mut kdf_id = [0u8; KDF_ID_SIZE];
+    read_field(&mut reader, &mut kdf_id, SealVaultError::CorruptedData)?;
+
+    let kdf = match kdf_id[0] {
+        KdfAlgorithm::ARGON2ID_ID => {
+            let m_cost = read_u32_be(&mut reader)?;
+            let t_cost = read_u32_be(&mut reader)?;
+            let p_cost = read_u32_be(&mut reader)?;
+            KdfAlgorithm::Argon2id(KdfParams {
+                m_cost,
+                t_cost,
+                p_cost,
+            })
+        }
+        KdfAlgorithm::SCRYPT_ID => {
+            let mut log_n = [0u8; 1];
+            read_field(&mut reader, &mut log_n, SealVaultError::CorruptedData)?;
+            let r = read_u32_be(&mut reader)?;
+            let p = read_u32_be(&mut reader)?;
+            KdfAlgorithm::Scrypt(ScryptParams {
+                log_n: log_n[0],
+                r,
+                p,
+            })
+        }
+        _ => return Err(invalid_header(SealVaultError::CorruptedData)),
+    };
+    // 参数来自文件，派生密钥前先限制上限
+    if !kdf.is_supported() {
+        return Err(invalid_header(SealVaultError::CorruptedData));
+    }
+
+    let mut codec_buf = [0u8; CODEC_SIZE];
+    read_field(&mut reader, &mut codec_buf, SealVaultError::CorruptedData)?;
+    let codec = Codec::from_u8(codec_buf[0])
+        .ok_or_else(|| invalid_header(SealVaultError::CorruptedData))?;
+
+    let mut flags = [0u8; FLAGS_SIZE];
+    read_field(&mut reader, &mut flags, SealVaultError::CorruptedData)?;
+    // 未知标志位可能改变解密语义（或带有不认识的扩展字段），不能忽略
+    if flags[0] & !KNOWN_FLAGS != 0 {
+        return Err(invalid_header(SealVaultError::CorruptedData));
+    }
+
+    Ok((kdf, codec, flags[0]))
+}
+
+/// 读取 v1 Header 中 version 之后的 algorithm、salt、base_nonce 与 chunk_size
+///
+/// v1 有两种布局，版本号相同：最早的写入端没有 algorithm 字节（固定为默认算法），
+/// 之后才在 version 后加入。两种写入端的 chunk 大小都固定为 `LEGACY_V1_CHUNK_SIZE`，据此区分：
+/// 先按无 algorithm 字节的布局读取，若首字节不是合法算法 ID，或 chunk_size 位置恰为
+/// `LEGACY_V1_CHUNK_SIZE`，即为无 algorithm 字节的布局；否则再补读 chunk_size 的末字节。
+/// 带 algorithm 字节的布局中该位置为 `nonce 末字节 || 00 01 00`，不会与之混淆。
+fn read_v1_fields<R: Read>(
+    mut reader: R,
+) -> std::io::Result<(AeadAlgorithm, [u8; SALT_SIZE], [u8; BASE_NONCE_SIZE], u32)> {
+    const FIELDS_LEN: usize = SALT_SIZE + BASE_NONCE_SIZE + 4;
+
+    let mut fields = [0u8; FIELDS_LEN];
+    read_field(&mut reader, &mut fields, SealVaultError::CorruptedData)?;
+
+    let tail: [u8; 4] = fields[FIELDS_LEN - 4..].try_into().expect("4 bytes");
+    if let Some(algorithm) = AeadAlgorithm::from_u8(fields[0])
+        && u32::from_be_bytes(tail) != LEGACY_V1_CHUNK_SIZE
+    {
+        let mut last = [0u8; 1];
+        read_field(&mut reader, &mut last, SealVaultError::CorruptedData)?;
+        let mut chunk_size = [0u8; 4];
+        chunk_size[..3].copy_from_slice(&fields[FIELDS_LEN - 3..]);
+        chunk_size[3] = last[0];
+        return Ok((
+            algorithm,
+            fields[1..1 + SALT_SIZE].try_into().expect("salt"),
+            fields[1 + SALT_SIZE..1 + SALT_SIZE + BASE_NONCE_SIZE]
+                .try_into()
+                .expect("base nonce"),
+            u32::from_be_bytes(chunk_size),
+        ));
     }
+
+    Ok((
+        DEFAULT_AEAD_ALGORITHM,
+        fields[..SALT_SIZE].try_into().expect("salt"),
+        fields[SALT_SIZE..SALT_SIZE + BASE_NONCE_SIZE]
+            .try_into()
+            .expect("base nonce"),
+        u32::from_be_bytes(tail),
+    ))
+}
+
+/// 计算 v2 Header 校验值：`field_bytes` 的 SHA-256 的前 `HEADER_CHECK_SIZE` 字节
+///
+/// 不带密钥，通过校验不代表 Header 未被伪造。
+fn header_check(field_bytes: &[u8]) -> [u8; HEADER_CHECK_SIZE] {
+    Sha256::digest(field_bytes)[..HEADER_CHECK_SIZE]
+        .try_into()
+        .expect("check size")
+}
+
+/// 构造携带 `SealVaultError` 的 Header 解析错误
+fn invalid_header(error: SealVaultError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+}
+
+/// 读取定长字段；数据不足时返回携带 `on_eof` 的 `UnexpectedEof`
+fn read_field<R: Read>(
+    mut reader: R,
+    buf: &mut [u8],
+    on_eof: SealVaultError,
+) -> std::io::Result<()> {
+    reader.read_exact(buf).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::UnexpectedEof {
+            std::io::Error::new(std::io::ErrorKind::UnexpectedEof, on_eof)
+        } else {
+            e
+        }
+    })
+}
+
+fn read_u32_be<R: Read>(reader: R) -> std::io::Result<u32> {
+    let mut buf = [0u8; 4];
+    read_field(reader, &mut buf, SealVaultError::CorruptedData)?;
+    Ok(u32::from_be_bytes(buf))
 }
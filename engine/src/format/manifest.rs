@@ -0,0 +1,373 @@
+//! SealVault 目录 manifest
+//!
+//! 目录加密时在输出根目录写出 `manifest.svmf`，记录每个被加密文件的
+//! 相对路径、明文长度、算法与明文 BLAKE3 摘要，供解密时确认没有文件被
+//! 增加、删除或替换；同时记录文件权限、修改时间与符号链接，解密时据此还原。
+//!
+//! 本模块只负责 manifest 明文的序列化；写入磁盘时整体按 .svlt 格式
+//! （Header + Stream）使用同一密码加密，因此 manifest 本身也受 AEAD 保护。
+//!
+//! 明文布局（整数均为大端）：
+//!
+//! ```text
+//! magic "SVMF" (4) | version (1) | entry_count u32
+//! entry: path_len u32 | path | plaintext_len u64 | algorithm u8 | blake3 (32)
+//!        | mode u32 | mtime_secs i64 | mtime_nanos u32
+//! symlink_count u32
+//! symlink: path_len u32 | path | target_len u32 | target
+//! ```
+//!
+//! `path` 为相对输出根目录的路径，组件以 `/` 连接；Unix 上按原始字节保存，
+//! 其他平台要求 UTF-8。`target` 为符号链接内容，原样保存（可为绝对路径）。
+//! `mode` 在 Unix 上为 rwx 权限位（不含 setuid / setgid / sticky），其他平台只区分只读（`0o444`）
+//! 与可写（`0o644`）。
+
+use std::io::{self, Read};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::algorithm::AeadAlgorithm;
+use crate::error::SealVaultError;
+
+/// manifest 在输出根目录中的文件名
+pub const MANIFEST_FILE_NAME: &str = "manifest.svmf";
+
+/// manifest 明文魔数
+const MANIFEST_MAGIC: &[u8; 4] = b"SVMF";
+
+/// manifest 明文格式版本
+const MANIFEST_VERSION: u8 = 1;
+
+/// 单个路径允许的最大长度（字节）
+pub(crate) const MAX_PATH_LEN: usize = 64 * 1024;
+
+/// manifest 中的单个文件记录
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    /// 明文文件相对目录根的路径（不含 `.svlt` 后缀）
+    pub path: PathBuf,
+    /// 明文长度（字节）
+    pub plaintext_len: u64,
+    /// 加密该文件使用的算法
+    pub algorithm: AeadAlgorithm,
+    /// 明文 BLAKE3 摘要
+    pub blake3: [u8; 32],
+    /// 源文件权限与修改时间
+    pub metadata: FileMetadata,
+}
+
+/// 需要在解密后还原的文件元数据
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileMetadata {
+    /// 权限位（见模块文档）
+    pub mode: u32,
+    /// 修改时间
+    pub modified: SystemTime,
+}
+
+impl FileMetadata {
+    /// 从源文件元数据中提取权限与修改时间
+    pub fn from_fs(metadata: &std::fs::Metadata) -> io::Result<Self> {
+        Ok(Self {
+            mode: mode_of(&metadata.permissions()),
+            modified: metadata.modified()?,
+        })
+    }
+
+    /// 将修改时间与权限应用到 `path`
+    ///
+    /// 先设置时间再设置权限，避免只读权限导致无法打开文件修改时间。
+    pub fn apply_to(&self, path: &Path) -> io::Result<()> {
+        std::fs::File::options()
+            .write(true)
+            .open(path)?
+            .set_modified(self.modified)?;
+        std::fs::set_permissions(path, permissions_for(path, self.mode)?)
+    }
+}
+
+/// manifest 中的符号链接记录（不跟随，原样保存链接内容）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestSymlink {
+    /// 链接自身相对目录根的路径
+    pub path: PathBuf,
+    /// 链接内容
+    pub target: PathBuf,
+}
+
+/// 目录 manifest：按路径排序的文件与符号链接记录
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Manifest {
+    pub entries: Vec<ManifestEntry>,
+    pub symlinks: Vec<ManifestSymlink>,
+}
+
+impl Manifest {
+    /// 由文件与符号链接记录构造 manifest，记录按路径排序以保证输出稳定
+    pub fn new(mut entries: Vec<ManifestEntry>, mut symlinks: Vec<ManifestSymlink>) -> Self {
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        symlinks.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { entries, symlinks }
+    }
+
+    /// 序列化为 manifest 明文
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(13 + self.entries.len() * 80);
+        bytes.extend_from_slice(MANIFEST_MAGIC);
+        bytes.push(MANIFEST_VERSION);
+        bytes.extend_from_slice(&count_to_u32(self.entries.len())?.to_be_bytes());
+
+        for entry in &self.entries {
+            write_bytes_field(&mut bytes, &path_to_bytes(&entry.path)?)?;
+            bytes.extend_from_slice(&entry.plaintext_len.to_be_bytes());
+            bytes.push(entry.algorithm.to_u8());
+            bytes.extend_from_slice(&entry.blake3);
+
+            let (secs, nanos) = time_to_parts(entry.metadata.modified);
+            bytes.extend_from_slice(&entry.metadata.mode.to_be_bytes());
+            bytes.extend_from_slice(&secs.to_be_bytes());
+            bytes.extend_from_slice(&nanos.to_be_bytes());
+        }
+
+        bytes.extend_from_slice(&count_to_u32(self.symlinks.len())?.to_be_bytes());
+        for symlink in &self.symlinks {
+            write_bytes_field(&mut bytes, &path_to_bytes(&symlink.path)?)?;
+            write_bytes_field(&mut bytes, &os_str_bytes(symlink.target.as_os_str())?)?;
+        }
+
+        Ok(bytes)
+    }
+
+    /// 解析 manifest 明文，任何格式错误均返回携带 `CorruptedData` 的错误
+    pub fn from_bytes(mut bytes: &[u8]) -> io::Result<Self> {
+        let reader = &mut bytes;
+
+        let mut magic = [0u8; 4];
+        read_field(reader, &mut magic)?;
+        if &magic != MANIFEST_MAGIC {
+            return Err(corrupted());
+        }
+
+        let mut version = [0u8; 1];
+        read_field(reader, &mut version)?;
+        if version[0] != MANIFEST_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                SealVaultError::UnsupportedVersion,
+            ));
+        }
+
+        let count = read_u32_be(reader)?;
+        let mut entries = Vec::new();
+        for _ in 0..count {
+            let path = path_from_bytes(&read_bytes_field(reader)?)?;
+
+            let mut len = [0u8; 8];
+            read_field(reader, &mut len)?;
+
+            let mut algorithm = [0u8; 1];
+            read_field(reader, &mut algorithm)?;
+
+            let mut blake3 = [0u8; 32];
+            read_field(reader, &mut blake3)?;
+
+            let mode = read_u32_be(reader)?;
+            let mut secs = [0u8; 8];
+            read_field(reader, &mut secs)?;
+            let nanos = read_u32_be(reader)?;
+            let metadata = FileMetadata {
+                mode,
+                modified: time_from_parts(i64::from_be_bytes(secs), nanos)?,
+            };
+
+            entries.push(ManifestEntry {
+                path,
+                plaintext_len: u64::from_be_bytes(len),
+                algorithm: AeadAlgorithm::from_u8(algorithm[0]).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        SealVaultError::UnsupportedAlgorithm(algorithm[0]),
+                    )
+                })?,
+                blake3,
+                metadata,
+            });
+        }
+
+        let mut symlinks = Vec::new();
+        for _ in 0..read_u32_be(reader)? {
+            let path = path_from_bytes(&read_bytes_field(reader)?)?;
+            let target = read_bytes_field(reader)?;
+            if target.is_empty() {
+                return Err(corrupted());
+            }
+            symlinks.push(ManifestSymlink {
+                path,
+                target: PathBuf::from(os_str_from_bytes(&target)?),
+            });
+        }
+
+        if !reader.is_empty() {
+            return Err(corrupted());
+        }
+
+        Ok(Self { entries, symlinks })
+    }
+}
+
+fn count_to_u32(count: usize) -> io::Result<u32> {
+    u32::try_from(count)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "manifest 记录过多"))
+}
+
+/// 写出 `len u32 | bytes` 字段
+fn write_bytes_field(bytes: &mut Vec<u8>, field: &[u8]) -> io::Result<()> {
+    if field.len() > MAX_PATH_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "manifest 路径过长",
+        ));
+    }
+    bytes.extend_from_slice(&(field.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(field);
+    Ok(())
+}
+
+/// 读取 `len u32 | bytes` 字段
+fn read_bytes_field(reader: &mut &[u8]) -> io::Result<Vec<u8>> {
+    let len = read_u32_be(reader)? as usize;
+    if len > MAX_PATH_LEN || len > reader.len() {
+        return Err(corrupted());
+    }
+    let mut field = vec![0u8; len];
+    read_field(reader, &mut field)?;
+    Ok(field)
+}
+
+/// 将时间拆为相对 UNIX 纪元的秒（可为负）与纳秒（`0..1e9`）
+fn time_to_parts(time: SystemTime) -> (i64, u32) {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => (after.as_secs() as i64, after.subsec_nanos()),
+        Err(e) => {
+            let before = e.duration();
+            let secs = -(before.as_secs() as i64);
+            match before.subsec_nanos() {
+                0 => (secs, 0),
+                nanos => (secs - 1, 1_000_000_000 - nanos),
+            }
+        }
+    }
+}
+
+fn time_from_parts(secs: i64, nanos: u32) -> io::Result<SystemTime> {
+    if nanos >= 1_000_000_000 {
+        return Err(corrupted());
+    }
+    let time = if secs >= 0 {
+        UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos))
+    } else {
+        UNIX_EPOCH
+            .checked_sub(Duration::from_secs(secs.unsigned_abs()))
+            .and_then(|t| t.checked_add(Duration::from_nanos(nanos as u64)))
+    };
+    time.ok_or_else(corrupted)
+}
+
+#[cfg(unix)]
+fn mode_of(permissions: &std::fs::Permissions) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    permissions.mode() & 0o777
+}
+
+#[cfg(not(unix))]
+fn mode_of(permissions: &std::fs::Permissions) -> u32 {
+    if permissions.readonly() { 0o444 } else { 0o644 }
+}
+
+#[cfg(unix)]
+fn permissions_for(_path: &Path, mode: u32) -> io::Result<std::fs::Permissions> {
+    use std::os::unix::fs::PermissionsExt;
+    // manifest 可能由他人提供，不还原 setuid / setgid / sticky，避免以 root 解密时得到 setuid 程序
+    Ok(std::fs::Permissions::from_mode(mode & 0o777))
+}
+
+#[cfg(not(unix))]
+fn permissions_for(path: &Path, mode: u32) -> io::Result<std::fs::Permissions> {
+    // 非 Unix 平台只能表达只读标志
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    permissions.set_readonly(mode & 0o222 == 0);
+    Ok(permissions)
+}
+
+fn corrupted() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, SealVaultError::CorruptedData)
+}
+
+fn read_field(reader: &mut &[u8], buf: &mut [u8]) -> io::Result<()> {
+    reader.read_exact(buf).map_err(|_| corrupted())
+}
+
+fn read_u32_be(reader: &mut &[u8]) -> io::Result<u32> {
+    let mut buf = [0u8; 4];
+    read_field(reader, &mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+/// 将相对路径编码为以 `/` 连接的组件字节
+pub(crate) fn path_to_bytes(path: &Path) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    for component in path.components() {
+        let Component::Normal(name) = component else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("manifest 路径必须是规范的相对路径: {path:?}"),
+            ));
+        };
+        if !bytes.is_empty() {
+            bytes.push(b'/');
+        }
+        bytes.extend_from_slice(&os_str_bytes(name)?);
+    }
+    Ok(bytes)
+}
+
+/// 解析以 `/` 连接的组件字节，拒绝空组件与 `.` / `..`
+pub(crate) fn path_from_bytes(bytes: &[u8]) -> io::Result<PathBuf> {
+    let mut path = PathBuf::new();
+    for name in bytes.split(|b| *b == b'/') {
+        if name.is_empty() || name == b"." || name == b".." {
+            return Err(corrupted());
+        }
+        path.push(os_str_from_bytes(name)?);
+    }
+    Ok(path)
+}
+
+#[cfg(unix)]
+pub(crate) fn os_str_bytes(name: &std::ffi::OsStr) -> io::Result<Vec<u8>> {
+    use std::os::unix::ffi::OsStrExt;
+    Ok(name.as_bytes().to_vec())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn os_str_bytes(name: &std::ffi::OsStr) -> io::Result<Vec<u8>> {
+    name.to_str().map(|s| s.as_bytes().to_vec()).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("非 Unix 平台上的文件名必须是 UTF-8: {name:?}"),
+        )
+    })
+}
+
+#[cfg(unix)]
+pub(crate) fn os_str_from_bytes(bytes: &[u8]) -> io::Result<std::ffi::OsString> {
+    use std::os::unix::ffi::OsStrExt;
+    Ok(std::ffi::OsStr::from_bytes(bytes).to_os_string())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn os_str_from_bytes(bytes: &[u8]) -> io::Result<std::ffi::OsString> {
+    std::str::from_utf8(bytes)
+        .map(Into::into)
+        .map_err(|_| corrupted())
+}
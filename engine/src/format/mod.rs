@@ -1,2 +1,6 @@
+pub mod archive;
+#[cfg(feature = "fs")]
+pub mod checkpoint;
 pub mod header;
+pub mod manifest;
 pub mod stream;
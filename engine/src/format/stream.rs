@@ -1,42 +1,127 @@
-//! SealVault v1 Stream 实现
+//! SealVault Stream 实现
 //!
-//! 本模块负责 SealVault v1 中“数据流（Stream）”部分的加密与解密。
+//! 本模块负责 SealVault 中“数据流（Stream）”部分的加密与解密。
 //!
 //! 职责范围：
 //! - 将任意大小的输入数据按固定大小分割为多个 chunk
-//! - 对每个 chunk 使用 XChaCha20-Poly1305 进行独立加密与认证
-//! - 按 v1 Stream 格式将加密结果顺序写入输出流
-//! - 在解密时严格校验每个 chunk 的完整性与顺序
+//! - 对每个 chunk 使用 Header 指定的 AEAD 算法进行独立加密与认证
+//! - 按 Stream 格式将加密结果顺序写入输出流
+//! - 在解密时严格校验每个 chunk 的完整性、顺序与 stream 是否完整结束
 //!
 //! 设计前提与约束：
 //! - 仅支持单文件流式处理（不涉及文件夹、元数据）
 //! - Header 已负责提供：AEAD key、base_nonce、chunk_size
 //! - 每个 chunk 使用唯一 nonce，并绑定 chunk_index 作为 AAD
 //! - 本模块不负责原子写入、路径处理、错误恢复策略
+//!
+//! 结束标记：
+//! - 数据 chunk 的 AAD 为 `chunk_index || 0x00`，长度必须大于 0
+//! - stream 以一个零长度的 final chunk 结束，其 AAD 为 `chunk_index || 0x01`
+//! - 缺少 final chunk（如在 chunk 边界处被截断）时解密失败
+//! - 空文件即只包含 final chunk 的 stream
+//!
+//! 明文摘要（标志位 `FLAG_PLAINTEXT_DIGEST`）：
+//! - final chunk 的长度前缀仍为 0，但其密文为 32 字节的明文 BLAKE3 摘要（加 tag）
+//! - 摘要与 final chunk 一起认证，无法被单独伪造；摘要由调用方计算（压缩前的原始明文）
+//!
+//! 长度隐藏填充（标志位 `FLAG_PADDED`）：
+//! - 填充字节位于数据 chunk 中，与数据一样加密认证，本模块不区分二者
+//! - final chunk 在摘要（若有）之后携带 8 字节大端的填充长度，去除填充由调用方负责
+//!
+//! 原始文件名（标志位 `FLAG_FILE_NAME`）：
+//! - Stream 的第一帧（chunk_index 0）为文件名帧，AAD 类型标记为 `0x02`，
+//!   密文为原始文件名的字节（Unix 上为原始字节，不要求 UTF-8），长度 `1..=MAX_FILE_NAME_LEN`
+//! - 数据 chunk 从 chunk_index 1 开始；文件名帧不经压缩与填充，其长度前缀会暴露文件名长度
+//!
+//! 数据 chunk 总数（标志位 `FLAG_TOTAL_CHUNKS`）：
+//! - Header 记录数据 chunk 总数，随 Header 摘要绑定到每个 chunk 的 AAD
+//! - 加密方结束 stream 时写出的数据 chunk 数必须与之相等；解密方拒绝多出的数据 chunk，
+//!   未满该数量即遇到 final chunk 或 EOF 均视为截断。final chunk 仍照常写出与校验
+//!
+//! Header 绑定：
+//! - 每个 chunk 的 AAD 前缀为 Header 摘要：`header_digest || chunk_index || flag`
+//! - 即使多个文件使用相同的 key，chunk 也无法被拼接到其他文件中
+//!
+//! 密钥生命周期：
+//! - StreamEncryptor / StreamDecryptor 持有的 key 副本为 `Zeroizing`，随其 drop 清零
+//! - 每个 chunk 临时构造 AEAD 实例，用完即 drop；ChaCha20-Poly1305 系列自带 drop 清零，
+//!   AES 轮密钥与 GHASH 子密钥依赖 `aes` / `ghash` / `polyval` 的 `zeroize` 特性（见 Cargo.toml）
+//!
+//! v1 stream 没有结束标记，AAD 仅为 `chunk_index`（不含 Header 摘要），仅为兼容旧文件保留解密能力。
 
+use std::collections::BTreeMap;
 use std::io::{Read, Write};
+use std::sync::{Mutex, mpsc};
+use std::thread;
 
-use crate::algorithm::{AeadAlgorithm, aes_256_gcm, xchacha20_poly1305};
-use crate::format::header::BASE_NONCE_SIZE;
+use zeroize::Zeroizing;
+
+use crate::algorithm::{
+    AeadAlgorithm, aes_128_gcm, aes_256_gcm, chacha20_poly1305, xchacha20_poly1305,
+};
+use crate::error::SealVaultError;
+use crate::format::header::{BASE_NONCE_SIZE, HEADER_DIGEST_SIZE, Header, VERSION_V1};
+use crate::progress::ProgressEvent;
 
 // AEAD 认证标签长度，固定为 16 字节
-const TAG_SIZE: usize = 16;
+pub const TAG_SIZE: usize = 16;
 
 // 每个 chunk 前的长度字段大小（u32，大端）
-const LEN_SIZE: usize = 4;
+pub(crate) const LEN_SIZE: usize = 4;
 
 // 推荐的默认明文 chunk 大小：64 KiB
 pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
 
+/// 文件加解密时 BufReader / BufWriter 的默认容量：两个默认 chunk（128 KiB）
+///
+/// 只影响系统调用次数，与密码学上的 chunk 大小无关，不写入文件。
+pub const DEFAULT_IO_BUFFER_SIZE: usize = 2 * DEFAULT_CHUNK_SIZE;
+
+// 解密时单个 chunk 缓冲区的初始容量上限，超出部分随实际读取增长
+pub(crate) const INITIAL_CHUNK_CAPACITY: usize = DEFAULT_CHUNK_SIZE + TAG_SIZE;
+
+// AAD 中的 chunk 类型标记
+const DATA_CHUNK_FLAG: u8 = 0x00;
+const FINAL_CHUNK_FLAG: u8 = 0x01;
+const FILE_NAME_CHUNK_FLAG: u8 = 0x02;
+
+/// final chunk 携带的明文摘要长度（BLAKE3，字节）
+pub const PLAINTEXT_DIGEST_SIZE: usize = 32;
+
+/// final chunk 携带的填充长度字段大小（u64，大端）
+pub const PADDING_LEN_SIZE: usize = 8;
+
+/// 文件名帧允许的最大文件名长度（字节）
+///
+/// 常见文件系统的单个文件名不超过 255 字节（NTFS 为 255 个 UTF-16 单元，UTF-8 下至多 765 字节）。
+pub const MAX_FILE_NAME_LEN: usize = 1024;
+
+/// 允许的最大明文 chunk 大小
+///
+/// 保证 `chunk_size + TAG_SIZE` 能放入 u32，长度前缀不会被截断。
+pub const MAX_CHUNK_SIZE: usize = u32::MAX as usize - TAG_SIZE;
+
 /// 流式加密器
 ///
 /// 负责将明文数据流按 chunk 加密并写入输出流。
 pub struct StreamEncryptor {
-    key: [u8; 32],
+    key: Zeroizing<[u8; 32]>,
     algorithm: AeadAlgorithm,
     base_nonce: [u8; BASE_NONCE_SIZE],
+    header_digest: [u8; HEADER_DIGEST_SIZE],
     chunk_index: u64,
     chunk_size: usize,
+    /// 已写出的数据 chunk 数
+    data_chunks: u64,
+    /// final chunk 是否必须携带明文摘要（对应 Header 的 `plaintext_digest`）
+    plaintext_digest: bool,
+    /// final chunk 是否必须携带填充长度（对应 Header 的 `padding`）
+    padding: bool,
+    /// 是否仍需先写出文件名帧（对应 Header 的 `file_name`）
+    pending_file_name: bool,
+    /// 结束 stream 时必须已写出的数据 chunk 数（对应 Header 的 `total_chunks`）
+    total_chunks: Option<u64>,
+    finished: bool,
 }
 
 impl StreamEncryptor {
@@ -44,97 +129,669 @@ impl StreamEncryptor {
     ///
     /// - key: 32 字节 AEAD 密钥（来自 KDF）
     /// - base_nonce: Header 中生成并保存的 base nonce
-    /// - chunk_size: 每个明文 chunk 的大小
+    /// - chunk_size: 每个明文 chunk 的大小，必须在 `1..=MAX_CHUNK_SIZE` 内
+    /// - header_digest: 所属文件 Header 的摘要（见 `Header::digest`），绑定到每个 chunk 的 AAD
     pub fn new(
         key: &[u8; 32],
         algorithm: AeadAlgorithm,
         base_nonce: [u8; BASE_NONCE_SIZE],
         chunk_size: usize,
-    ) -> Self {
-        Self {
-            key: *key,
+        header_digest: [u8; HEADER_DIGEST_SIZE],
+    ) -> std::io::Result<Self> {
+        if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "chunk size out of range",
+            ));
+        }
+
+        Ok(Self {
+            key: Zeroizing::new(*key),
             algorithm,
             base_nonce,
+            header_digest,
             chunk_index: 0,
             chunk_size,
-        }
+            data_chunks: 0,
+            plaintext_digest: false,
+            padding: false,
+            pending_file_name: false,
+            total_chunks: None,
+            finished: false,
+        })
+    }
+
+    /// 要求以 `finish_with_digest` 结束 stream（Header 设置了 `plaintext_digest` 时使用）
+    ///
+    /// 之后 `finish` 以及内部调用 `finish` 的 `encrypt*` 方法会返回 `InvalidInput`。
+    pub fn with_plaintext_digest(mut self) -> Self {
+        self.plaintext_digest = true;
+        self
+    }
+
+    /// 要求以携带填充长度的 `finish_with_trailer` 结束 stream（Header 设置了 `padding` 时使用）
+    ///
+    /// 填充字节由调用方作为普通数据写入；之后 `finish` 与 `finish_with_digest` 返回 `InvalidInput`。
+    pub fn with_padding(mut self) -> Self {
+        self.padding = true;
+        self
+    }
+
+    /// 要求在任何数据 chunk 之前以 `write_file_name` 写出文件名帧（Header 设置了 `file_name` 时使用）
+    ///
+    /// 写出文件名帧之前，`write_chunk`、`finish` 等写入方法返回 `InvalidInput`。
+    pub fn with_file_name(mut self) -> Self {
+        self.pending_file_name = true;
+        self
+    }
+
+    /// 要求结束 stream 时恰好已写出 `total_chunks` 个数据 chunk（Header 设置了 `total_chunks` 时使用）
+    ///
+    /// 数量不符（如输入文件在加密期间被改动）时 `finish*` 返回 `InvalidData`，不写出 final chunk。
+    pub fn with_total_chunks(mut self, total_chunks: u64) -> Self {
+        self.total_chunks = Some(total_chunks);
+        self
+    }
+
+    /// 从指定的 chunk_index 开始加密（断点续加密，以及测试 chunk 数上限）
+    #[doc(hidden)]
+    pub fn starting_at_chunk(mut self, chunk_index: u64) -> Self {
+        self.chunk_index = chunk_index;
+        self
     }
 
-    /// 从 reader 读取明文数据，加密后写入 writer
-    pub fn encrypt<R: Read, W: Write>(
+    /// 每个明文 chunk 的最大长度
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// 本加密器已写出的数据 chunk 数（不含文件名帧与 final chunk）
+    pub fn data_chunks(&self) -> u64 {
+        self.data_chunks
+    }
+
+    /// 从 reader 读取明文数据，加密后写入 writer，并在末尾写入 final chunk
+    pub fn encrypt<R: Read, W: Write>(&mut self, reader: R, writer: W) -> std::io::Result<()> {
+        self.encrypt_with_progress(reader, writer, 0, &mut |_| {})
+    }
+
+    /// 同 `encrypt`，每写出一个数据 chunk 后以 `ProgressEvent::BytesProcessed` 报告进度
+    ///
+    /// `total` 为调用方已知的明文总长度（未知时传 0），原样写入事件。
+    pub fn encrypt_with_progress<R: Read, W: Write>(
+        &mut self,
+        reader: R,
+        mut writer: W,
+        total: u64,
+        progress: &mut dyn FnMut(ProgressEvent),
+    ) -> std::io::Result<()> {
+        self.encrypt_chunks(reader, &mut writer, total, progress)?;
+        self.finish(writer)
+    }
+
+    /// 同 `encrypt_with_progress`，但只写数据 chunk，由调用方负责结束 stream
+    pub(crate) fn encrypt_chunks<R: Read, W: Write>(
         &mut self,
         mut reader: R,
         mut writer: W,
+        total: u64,
+        progress: &mut dyn FnMut(ProgressEvent),
     ) -> std::io::Result<()> {
-        let mut buffer = vec![0u8; self.chunk_size];
+        // 复用的明文缓冲区在返回（包括出错返回）时清零，不在已释放的堆内存中留下明文
+        let mut buffer = Zeroizing::new(vec![0u8; self.chunk_size]);
+        let mut done = 0u64;
 
         loop {
-            let read_len = reader.read(&mut buffer)?;
+            // 读满整个 chunk 再加密：除最后一个外每个数据 chunk 都是完整的，
+            // chunk 长度不随 reader 每次返回的字节数变化（填充依赖这一点隐藏真实长度）
+            let read_len = read_full(&mut reader, &mut buffer)?;
             if read_len == 0 {
                 break;
             }
 
-            let plaintext = &buffer[..read_len];
+            self.write_chunk(&buffer[..read_len], &mut writer)?;
+            done += read_len as u64;
+            progress(ProgressEvent::BytesProcessed { done, total });
+        }
 
-            // 使用 chunk_index 作为 AAD，防止块重排
-            let aad = self.chunk_index.to_be_bytes();
+        Ok(())
+    }
 
-            let ciphertext = match self.algorithm {
-                AeadAlgorithm::XChaCha20Poly1305 => xchacha20_poly1305::encrypt_chunk(
-                    &self.key,
-                    &self.base_nonce,
-                    self.chunk_index,
-                    plaintext,
-                    &aad,
-                )?,
-                AeadAlgorithm::Aes256Gcm => aes_256_gcm::encrypt_chunk(
-                    &self.key,
-                    &self.base_nonce,
-                    self.chunk_index,
-                    plaintext,
-                    &aad,
-                )?,
-            };
+    /// 同 `encrypt`，但使用 `threads` 个工作线程并行加密各 chunk（0 表示按 CPU 数量）
+    ///
+    /// 每个 chunk 的 nonce 与 AAD 只依赖 chunk_index，各 chunk 可独立加密；
+    /// 调用线程负责读取明文与按 chunk_index 顺序写出，输出与串行路径格式相同，
+    /// 可由普通的 StreamDecryptor 解密。
+    ///
+    /// 同时在途的 chunk 不超过 `2 * threads` 个，内存占用与文件大小无关。
+    /// 与 `encrypt` 相同，每个数据 chunk 都读满 `chunk_size`（最后一个除外），两条路径的 chunk 划分一致。
+    pub fn encrypt_parallel<R: Read, W: Write>(
+        &mut self,
+        reader: R,
+        writer: W,
+        threads: usize,
+    ) -> std::io::Result<()> {
+        self.encrypt_parallel_with_progress(reader, writer, threads, 0, &mut |_| {})
+    }
+
+    /// 同 `encrypt_parallel`，每按顺序写出一个数据 chunk 后报告 `BytesProcessed`
+    pub fn encrypt_parallel_with_progress<R: Read, W: Write>(
+        &mut self,
+        reader: R,
+        mut writer: W,
+        threads: usize,
+        total: u64,
+        progress: &mut dyn FnMut(ProgressEvent),
+    ) -> std::io::Result<()> {
+        self.encrypt_chunks_parallel(reader, &mut writer, threads, total, progress)?;
+        self.finish(writer)
+    }
+
+    /// 同 `encrypt_parallel_with_progress`，但只写数据 chunk，由调用方负责结束 stream
+    pub(crate) fn encrypt_chunks_parallel<R: Read, W: Write>(
+        &mut self,
+        mut reader: R,
+        mut writer: W,
+        threads: usize,
+        total: u64,
+        progress: &mut dyn FnMut(ProgressEvent),
+    ) -> std::io::Result<()> {
+        self.check_writable()?;
+
+        let threads = match threads {
+            0 => thread::available_parallelism().map_or(1, |n| n.get()),
+            n => n,
+        };
+        let window = 2 * threads;
+        let first_index = self.chunk_index;
+
+        let next_index = {
+            let this = &*self;
+            let (job_tx, job_rx) = mpsc::sync_channel::<(u64, Zeroizing<Vec<u8>>)>(window);
+            let (result_tx, result_rx) = mpsc::channel::<(u64, std::io::Result<Vec<u8>>)>();
+            let job_rx = Mutex::new(job_rx);
+
+            thread::scope(|scope| -> std::io::Result<u64> {
+                // 移入闭包：出错返回时 job_tx 随之 drop，工作线程的 recv 结束，scope 才能回收
+                let job_tx = job_tx;
+
+                for _ in 0..threads {
+                    let job_rx = &job_rx;
+                    let result_tx = result_tx.clone();
+                    scope.spawn(move || {
+                        loop {
+                            // 锁只在取任务时持有，加密在锁外进行
+                            let job = job_rx.lock().map(|rx| rx.recv());
+                            let Ok(Ok((index, plaintext))) = job else {
+                                break;
+                            };
+                            let sealed = this.seal(index, &plaintext, DATA_CHUNK_FLAG);
+                            if result_tx.send((index, sealed)).is_err() {
+                                break;
+                            }
+                        }
+                    });
+                }
+                drop(result_tx);
+
+                // ---------- 调用线程：读取、分发，并按顺序写出 ----------
+                let mut pending: BTreeMap<u64, Vec<u8>> = BTreeMap::new();
+                let mut read_index = first_index;
+                let mut write_index = first_index;
+                let mut eof = false;
+                let mut done = 0u64;
+
+                loop {
+                    while !eof && read_index - write_index < window as u64 {
+                        let mut buffer = Zeroizing::new(vec![0u8; this.chunk_size]);
+                        let read_len = read_full(&mut reader, &mut buffer)?;
+                        if read_len == 0 {
+                            eof = true;
+                            break;
+                        }
+                        buffer.truncate(read_len);
+                        job_tx.send((read_index, buffer)).map_err(|_| {
+                            std::io::Error::other("parallel encryption worker exited")
+                        })?;
+                        read_index += 1;
+                        if read_len < this.chunk_size {
+                            eof = true;
+                        }
+                    }
+
+                    if write_index == read_index {
+                        break;
+                    }
+
+                    let (index, sealed) = result_rx
+                        .recv()
+                        .map_err(|_| std::io::Error::other("parallel encryption worker exited"))?;
+                    pending.insert(index, sealed?);
+
+                    while let Some(ciphertext) = pending.remove(&write_index) {
+                        write_frame(&mut writer, &ciphertext)?;
+                        write_index += 1;
+                        // 流密码 AEAD：密文长度 = 明文长度 + tag
+                        done += (ciphertext.len() - TAG_SIZE) as u64;
+                        progress(ProgressEvent::BytesProcessed { done, total });
+                    }
+                }
+
+                Ok(write_index)
+            })?
+        };
+
+        self.data_chunks += next_index - first_index;
+        self.chunk_index = next_index;
+        Ok(())
+    }
+
+    /// 将内存中的完整明文按 `chunk_size` 切片加密写入 writer，并在末尾写入 final chunk
+    ///
+    /// 用于内存映射的输入：直接加密切片，不经过额外的读缓冲区复制。
+    pub fn encrypt_slice<W: Write>(&mut self, data: &[u8], mut writer: W) -> std::io::Result<()> {
+        for chunk in data.chunks(self.chunk_size) {
+            self.write_chunk(chunk, &mut writer)?;
+        }
+        self.finish(writer)
+    }
 
-            // ciphertext = [cipher_body | tag]
-            let cipher_len = ciphertext.len() - TAG_SIZE;
-            let (cipher_body, tag) = ciphertext.split_at(cipher_len);
+    /// 加密单个明文数据 chunk，并按 Stream 格式写入 writer
+    ///
+    /// 供需要自行驱动 chunk 循环的调用方使用；
+    /// `plaintext` 长度必须在 `1..=chunk_size` 内，全部写完后必须调用 `finish`。
+    pub fn write_chunk<W: Write>(&mut self, plaintext: &[u8], writer: W) -> std::io::Result<()> {
+        if plaintext.is_empty() || plaintext.len() > self.chunk_size {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "data chunk length out of range",
+            ));
+        }
 
-            // 写入 chunk 长度（仅包含 cipher_body）
-            writer.write_all(&(cipher_body.len() as u32).to_be_bytes())?;
-            writer.write_all(cipher_body)?;
-            writer.write_all(tag)?;
+        self.seal_and_write(plaintext, DATA_CHUNK_FLAG, writer)
+    }
 
-            self.chunk_index += 1;
+    /// 写出文件名帧（Stream 的第一帧）
+    ///
+    /// 仅用于 `with_file_name` 创建的加密器，且只能在写出任何数据 chunk 之前调用一次；
+    /// `name` 长度须在 `1..=MAX_FILE_NAME_LEN` 内，否则返回 `InvalidInput`。
+    pub fn write_file_name<W: Write>(&mut self, name: &[u8], writer: W) -> std::io::Result<()> {
+        if !self.pending_file_name {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "stream does not expect a file name",
+            ));
+        }
+        if name.is_empty() || name.len() > MAX_FILE_NAME_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "file name length out of range",
+            ));
+        }
+
+        self.pending_file_name = false;
+        self.seal_and_write(name, FILE_NAME_CHUNK_FLAG, writer)
+    }
+
+    /// 写入零长度的 final chunk，标记 stream 结束
+    ///
+    /// 之后不能再写入任何 chunk。
+    pub fn finish<W: Write>(&mut self, writer: W) -> std::io::Result<()> {
+        self.finish_with_trailer(writer, None, None)
+    }
+
+    /// 写入携带明文摘要的 final chunk，标记 stream 结束
+    ///
+    /// 仅用于 `with_plaintext_digest` 创建的加密器；`digest` 为调用方计算的完整明文 BLAKE3。
+    pub fn finish_with_digest<W: Write>(
+        &mut self,
+        writer: W,
+        digest: &[u8; PLAINTEXT_DIGEST_SIZE],
+    ) -> std::io::Result<()> {
+        self.finish_with_trailer(writer, Some(digest), None)
+    }
+
+    /// 写入 final chunk，按需携带明文摘要与填充长度，标记 stream 结束
+    ///
+    /// `digest` 与 `padding_len` 是否为 `Some` 必须分别与 `with_plaintext_digest`、
+    /// `with_padding` 一致，否则返回 `InvalidInput`。
+    pub fn finish_with_trailer<W: Write>(
+        &mut self,
+        writer: W,
+        digest: Option<&[u8; PLAINTEXT_DIGEST_SIZE]>,
+        padding_len: Option<u64>,
+    ) -> std::io::Result<()> {
+        match (self.plaintext_digest, digest.is_some()) {
+            (true, false) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "stream requires a plaintext digest",
+                ));
+            }
+            (false, true) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "stream does not carry a plaintext digest",
+                ));
+            }
+            _ => {}
+        }
+        match (self.padding, padding_len.is_some()) {
+            (true, false) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "stream requires a padding length",
+                ));
+            }
+            (false, true) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "stream is not padded",
+                ));
+            }
+            _ => {}
+        }
+        if self
+            .total_chunks
+            .is_some_and(|total_chunks| total_chunks != self.data_chunks)
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "data chunk count does not match the header (input changed during encryption?)",
+            ));
+        }
+
+        // final chunk 内容：摘要（可选）|| 填充长度（可选，大端）
+        let mut trailer = Vec::with_capacity(PLAINTEXT_DIGEST_SIZE + PADDING_LEN_SIZE);
+        if let Some(digest) = digest {
+            trailer.extend_from_slice(digest);
+        }
+        if let Some(padding_len) = padding_len {
+            trailer.extend_from_slice(&padding_len.to_be_bytes());
+        }
+
+        self.seal_and_write(&trailer, FINAL_CHUNK_FLAG, writer)?;
+        self.finished = true;
+        Ok(())
+    }
+
+    fn seal_and_write<W: Write>(
+        &mut self,
+        plaintext: &[u8],
+        flag: u8,
+        mut writer: W,
+    ) -> std::io::Result<()> {
+        self.check_writable()?;
+
+        let ciphertext = self.seal(self.chunk_index, plaintext, flag)?;
+        if flag == FINAL_CHUNK_FLAG {
+            // final chunk 的长度前缀恒为 0，携带的摘要与填充长度由 Header 标志位确定
+            writer.write_all(&0u32.to_be_bytes())?;
+            writer.write_all(&ciphertext)?;
+        } else {
+            write_frame(&mut writer, &ciphertext)?;
+        }
+
+        self.chunk_index += 1;
+        if flag == DATA_CHUNK_FLAG {
+            self.data_chunks += 1;
         }
 
         Ok(())
     }
+
+    /// stream 已结束，或仍需先写出文件名帧时返回 `InvalidInput`
+    fn check_writable(&self) -> std::io::Result<()> {
+        if self.finished {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "stream already finished",
+            ));
+        }
+        if self.pending_file_name {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "stream requires a file name first",
+            ));
+        }
+        Ok(())
+    }
+
+    /// 加密指定 chunk_index 的单个 chunk，返回 `cipher_body | tag`（不修改状态）
+    ///
+    /// chunk_index 达到算法的 chunk 数上限时返回 `InvalidInput`；数据 chunk 须为 final chunk
+    /// 留出最后一个 index，保证 stream 总能正常结束。
+    fn seal(&self, chunk_index: u64, plaintext: &[u8], flag: u8) -> std::io::Result<Vec<u8>> {
+        let limit = match flag {
+            FINAL_CHUNK_FLAG => self.algorithm.max_chunks(),
+            _ => self.algorithm.max_chunks() - 1,
+        };
+        if chunk_index >= limit {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "stream exceeds the maximum number of chunks for this algorithm",
+            ));
+        }
+
+        // 使用 Header 摘要、chunk_index 与 chunk 类型作为 AAD，防止跨文件拼接、块重排与截断
+        let aad = chunk_aad(Some(&self.header_digest), chunk_index, Some(flag));
+
+        match self.algorithm {
+            AeadAlgorithm::XChaCha20Poly1305 => xchacha20_poly1305::encrypt_chunk(
+                &self.key,
+                &self.base_nonce,
+                chunk_index,
+                plaintext,
+                &aad,
+            ),
+            AeadAlgorithm::Aes256Gcm => aes_256_gcm::encrypt_chunk(
+                &self.key,
+                &self.base_nonce,
+                chunk_index,
+                plaintext,
+                &aad,
+            ),
+            AeadAlgorithm::ChaCha20Poly1305 => chacha20_poly1305::encrypt_chunk(
+                &self.key,
+                &self.base_nonce,
+                chunk_index,
+                plaintext,
+                &aad,
+            ),
+            AeadAlgorithm::Aes128Gcm => aes_128_gcm::encrypt_chunk(
+                &self.key[..self.algorithm.key_len()],
+                &self.base_nonce,
+                chunk_index,
+                plaintext,
+                &aad,
+            ),
+        }
+    }
 }
 
 /// 流式解密器
 ///
 /// 负责从加密 stream 中读取数据并还原明文。
 pub struct StreamDecryptor {
-    key: [u8; 32],
+    key: Zeroizing<[u8; 32]>,
     algorithm: AeadAlgorithm,
     base_nonce: [u8; BASE_NONCE_SIZE],
     chunk_index: u64,
+    /// 已通过认证的数据 chunk 数
+    data_chunks: u64,
+    /// 允许的最大密文 chunk 长度（不含 tag），通常来自 `Header.chunk_size`
+    max_chunk_size: usize,
+    /// 绑定到 AAD 的 Header 摘要（v1 旧文件为 None）
+    header_digest: Option<[u8; HEADER_DIGEST_SIZE]>,
+    /// 是否要求 final chunk（v1 旧文件为 false）
+    final_marker: bool,
+    /// final chunk 是否携带明文摘要
+    expect_plaintext_digest: bool,
+    /// 已认证的 final chunk 中的明文摘要
+    plaintext_digest: Option<[u8; PLAINTEXT_DIGEST_SIZE]>,
+    /// final chunk 是否携带填充长度
+    expect_padding: bool,
+    /// 已认证的 final chunk 中的填充长度
+    padding_len: Option<u64>,
+    /// Stream 是否以文件名帧开始
+    expect_file_name: bool,
+    /// 已认证的文件名帧中的原始文件名
+    file_name: Option<Zeroizing<Vec<u8>>>,
+    /// Header 记录的数据 chunk 总数
+    expect_total_chunks: Option<u64>,
+    /// final chunk 之后是否要求 reader 立即 EOF
+    reject_trailing_data: bool,
+    finished: bool,
 }
 
 impl StreamDecryptor {
-    /// 创建新的 StreamDecryptor
+    /// 创建新的 StreamDecryptor（当前 Stream 格式，要求 final chunk 并校验 Header 绑定）
+    ///
+    /// - header_digest: 所属文件 Header 的摘要（见 `Header::digest`）
     pub fn new(
         key: &[u8; 32],
         algorithm: AeadAlgorithm,
         base_nonce: [u8; BASE_NONCE_SIZE],
+        header_digest: [u8; HEADER_DIGEST_SIZE],
     ) -> Self {
         Self {
-            key: *key,
+            key: Zeroizing::new(*key),
             algorithm,
             base_nonce,
             chunk_index: 0,
+            data_chunks: 0,
+            max_chunk_size: MAX_CHUNK_SIZE,
+            header_digest: Some(header_digest),
+            final_marker: true,
+            expect_plaintext_digest: false,
+            plaintext_digest: None,
+            expect_padding: false,
+            padding_len: None,
+            expect_file_name: false,
+            file_name: None,
+            expect_total_chunks: None,
+            reject_trailing_data: false,
+            finished: false,
+        }
+    }
+
+    /// 创建解密 v1 旧格式 stream 的 StreamDecryptor
+    ///
+    /// v1 没有结束标记，无法发现在 chunk 边界处的截断，仅用于兼容旧文件。
+    pub fn new_legacy_v1(
+        key: &[u8; 32],
+        algorithm: AeadAlgorithm,
+        base_nonce: [u8; BASE_NONCE_SIZE],
+    ) -> Self {
+        Self {
+            header_digest: None,
+            final_marker: false,
+            ..Self::new(key, algorithm, base_nonce, [0u8; HEADER_DIGEST_SIZE])
+        }
+    }
+
+    /// 按 Header 中的版本与参数创建 StreamDecryptor
+    ///
+    /// 声明长度超过 `header.chunk_size` 的 chunk 会被拒绝。
+    pub fn for_header(key: &[u8; 32], header: &Header) -> Self {
+        let decryptor = match header.version {
+            VERSION_V1 => Self::new_legacy_v1(key, header.algorithm, header.base_nonce),
+            _ => Self::new(key, header.algorithm, header.base_nonce, header.digest()),
+        };
+        let mut decryptor = decryptor.with_max_chunk_size(header.chunk_size as usize);
+        if header.plaintext_digest {
+            decryptor = decryptor.with_plaintext_digest();
+        }
+        if header.padding.is_some() {
+            decryptor = decryptor.with_padding();
+        }
+        if header.file_name {
+            decryptor = decryptor.with_file_name();
         }
+        if let Some(total_chunks) = header.total_chunks {
+            decryptor = decryptor.with_total_chunks(total_chunks);
+        }
+        decryptor
+    }
+
+    /// 要求 final chunk 携带明文摘要（Header 设置了 `plaintext_digest` 时使用）
+    pub fn with_plaintext_digest(mut self) -> Self {
+        self.expect_plaintext_digest = true;
+        self
+    }
+
+    /// final chunk 中已通过认证的明文摘要
+    ///
+    /// 读到 final chunk 之前、或 stream 不携带摘要时为 `None`。
+    pub fn plaintext_digest(&self) -> Option<[u8; PLAINTEXT_DIGEST_SIZE]> {
+        self.plaintext_digest
+    }
+
+    /// 要求 final chunk 携带填充长度（Header 设置了 `padding` 时使用）
+    ///
+    /// 数据 chunk 中的填充字节照常输出，由调用方按 `padding_len` 去除。
+    pub fn with_padding(mut self) -> Self {
+        self.expect_padding = true;
+        self
+    }
+
+    /// final chunk 中已通过认证的填充长度
+    ///
+    /// 读到 final chunk 之前、或 stream 未填充时为 `None`。
+    pub fn padding_len(&self) -> Option<u64> {
+        self.padding_len
+    }
+
+    /// 要求 Stream 以文件名帧开始（Header 设置了 `file_name` 时使用）
+    pub fn with_file_name(mut self) -> Self {
+        self.expect_file_name = true;
+        self
+    }
+
+    /// 文件名帧中已通过认证的原始文件名字节
+    ///
+    /// 读到文件名帧之前、或 stream 不携带文件名时为 `None`。
+    pub fn file_name(&self) -> Option<&[u8]> {
+        self.file_name.as_deref().map(Vec::as_slice)
+    }
+
+    /// 若 Stream 以文件名帧开始且尚未读取，读取并认证它；否则什么也不做
+    ///
+    /// `read_chunk` 会自动先读取文件名帧，只有需要在输出任何明文之前得知文件名时才需调用。
+    pub fn read_file_name<R: Read>(&mut self, reader: R) -> std::io::Result<()> {
+        if self.awaiting_file_name() {
+            self.read_frame(reader)?;
+        }
+        Ok(())
+    }
+
+    /// 下一帧是否为尚未读取的文件名帧
+    pub(crate) fn awaiting_file_name(&self) -> bool {
+        self.expect_file_name && self.file_name.is_none() && !self.finished
+    }
+
+    /// 要求 Stream 恰好包含 `total_chunks` 个数据 chunk（Header 设置了 `total_chunks` 时使用）
+    ///
+    /// 多出的数据 chunk 在读取其内容之前即被拒绝，未满该数量就遇到 final chunk 视为截断，
+    /// 错误负载均为 `CorruptedData`。
+    pub fn with_total_chunks(mut self, total_chunks: u64) -> Self {
+        self.expect_total_chunks = Some(total_chunks);
+        self
+    }
+
+    /// 要求 final chunk 之后 reader 立即 EOF，否则返回 `InvalidData`（负载为 `CorruptedData`）
+    ///
+    /// 默认读到 final chunk 即停止，不检查其后的数据。检查只发生在 `read_chunk` / `decrypt` 中，
+    /// 自行读取帧的调用方需自己确认。
+    pub fn with_trailing_data_rejected(mut self) -> Self {
+        self.reject_trailing_data = true;
+        self
+    }
+
+    /// 限制单个 chunk 的最大明文长度，声明长度超出时返回 `InvalidData`
+    pub fn with_max_chunk_size(mut self, max_chunk_size: usize) -> Self {
+        self.max_chunk_size = max_chunk_size;
+        self
     }
 
     /// 从 reader 读取加密数据流，解密后写入 writer
@@ -143,56 +800,318 @@ impl StreamDecryptor {
         mut reader: R,
         mut writer: W,
     ) -> std::io::Result<()> {
-        loop {
-            let mut len_buf = [0u8; LEN_SIZE];
+        while let Some(plaintext) = self.read_chunk(&mut reader)? {
+            writer.write_all(&plaintext)?;
+        }
 
-            // 读取 chunk 长度；若在边界处 EOF，视为正常结束
-            if let Err(e) = reader.read_exact(&mut len_buf) {
-                if e.kind() == std::io::ErrorKind::UnexpectedEof {
-                    break;
-                }
-                return Err(e);
+        Ok(())
+    }
+
+    /// 解密并丢弃前 `count` 个 chunk 的输出
+    ///
+    /// 用于断点恢复：已写出的 chunk 仍会逐个校验认证标签，只是不再输出明文。
+    /// 若 stream 在此之前结束，返回 `UnexpectedEof`。
+    pub fn skip_output_chunks<R: Read>(
+        &mut self,
+        mut reader: R,
+        count: u64,
+    ) -> std::io::Result<()> {
+        for _ in 0..count {
+            if self.read_chunk(&mut reader)?.is_none() {
+                return Err(corrupted(std::io::ErrorKind::UnexpectedEof));
             }
+        }
 
-            let cipher_len = u32::from_be_bytes(len_buf) as usize;
-            if cipher_len == 0 {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    "invalid chunk length",
-                ));
+        Ok(())
+    }
+
+    /// 当前已处理的 chunk 数量（含文件名帧）
+    pub fn chunk_index(&self) -> u64 {
+        self.chunk_index
+    }
+
+    /// 已通过认证的数据 chunk 数（不含文件名帧与 final chunk）
+    pub fn data_chunks(&self) -> u64 {
+        self.data_chunks
+    }
+
+    /// 是否已读到并通过认证的 final chunk
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// 从 reader 读取并解密单个数据 chunk（Stream 以文件名帧开始时先读取文件名帧）
+    ///
+    /// - 返回 `Ok(Some(plaintext))`：该数据 chunk 已通过认证；明文在 drop 时清零
+    /// - 返回 `Ok(None)`：final chunk 已通过认证，stream 结束
+    ///   （v1 旧格式：在 chunk 边界处遇到 EOF）
+    /// - 在 final chunk 之前遇到 EOF：返回 `InvalidData`（stream 被截断）
+    /// - 长度前缀超过 chunk 上限：在读取 chunk 内容之前返回 `InvalidData`
+    /// - 第一个 chunk 认证失败：错误负载为 `SealVaultError::InvalidPasswordOrFile`
+    ///   （通常是密码错误）；其余格式与认证错误的负载为 `SealVaultError::CorruptedData`
+    pub fn read_chunk<R: Read>(
+        &mut self,
+        mut reader: R,
+    ) -> std::io::Result<Option<Zeroizing<Vec<u8>>>> {
+        self.read_file_name(&mut reader)?;
+        self.read_frame(reader)
+    }
+
+    /// 读取并解密下一帧，返回值语义同 `open_frame`
+    fn read_frame<R: Read>(
+        &mut self,
+        mut reader: R,
+    ) -> std::io::Result<Option<Zeroizing<Vec<u8>>>> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        let mut len_buf = [0u8; LEN_SIZE];
+
+        // 读取 chunk 长度；在边界处 EOF 时，只有 v1 旧格式视为正常结束，
+        // 只剩不足一个长度前缀的字节则是截断或多余数据
+        match read_full(&mut reader, &mut len_buf)? {
+            0 => {
+                self.end_at_chunk_boundary()?;
+                return Ok(None);
             }
+            LEN_SIZE => {}
+            _ => return Err(corrupted(std::io::ErrorKind::UnexpectedEof)),
+        }
+
+        let frame_len = self.frame_len(len_buf)?;
+
+        // 按实际读到的数据增长缓冲区，而不是按长度前缀（或 Header）声明的大小一次性分配，
+        // 避免极小文件通过夸大的长度声明放大内存占用。
+        let mut frame = Vec::with_capacity(frame_len.min(INITIAL_CHUNK_CAPACITY));
+        (&mut reader)
+            .take(frame_len as u64)
+            .read_to_end(&mut frame)?;
 
-            let mut cipher_body = vec![0u8; cipher_len];
-            let mut tag = vec![0u8; TAG_SIZE];
+        if frame.len() != frame_len {
+            return Err(corrupted(std::io::ErrorKind::UnexpectedEof));
+        }
+
+        let plaintext = self.open_frame(len_buf, &frame)?;
+        if self.finished && self.reject_trailing_data {
+            ensure_eof(reader)?;
+        }
+        Ok(plaintext)
+    }
+
+    /// 在 chunk 边界处遇到 EOF：v1 旧格式视为正常结束，其余格式视为截断（缺少 final chunk）
+    pub(crate) fn end_at_chunk_boundary(&mut self) -> std::io::Result<()> {
+        if self.final_marker {
+            return Err(corrupted(std::io::ErrorKind::InvalidData));
+        }
+        self.finished = true;
+        Ok(())
+    }
+
+    /// 校验 chunk 的长度前缀，返回其后需要读取的 `cipher_body | tag` 长度
+    ///
+    /// 供需要自行读取帧的调用方（如异步 reader）使用，随后把读到的帧交给 `open_frame`。
+    pub(crate) fn frame_len(&self, len_buf: [u8; LEN_SIZE]) -> std::io::Result<usize> {
+        // 合法的加密方不会写出超过算法上限的 chunk，nonce 也就不会回绕复用
+        if self.chunk_index >= self.algorithm.max_chunks() {
+            return Err(corrupted(std::io::ErrorKind::InvalidData));
+        }
+
+        let cipher_len = u32::from_be_bytes(len_buf) as usize;
+        if self.awaiting_file_name() {
+            if cipher_len == 0 || cipher_len > MAX_FILE_NAME_LEN {
+                return Err(corrupted(std::io::ErrorKind::InvalidData));
+            }
+            return Ok(cipher_len + TAG_SIZE);
+        }
+        if cipher_len == 0 && !self.final_marker {
+            return Err(corrupted(std::io::ErrorKind::InvalidData));
+        }
+        // 在任何读取与分配之前拒绝超出 Header 声明的长度
+        if cipher_len > self.max_chunk_size {
+            return Err(corrupted(std::io::ErrorKind::InvalidData));
+        }
+        // Header 记录了 chunk 总数时，数据 chunk 不能多于、final chunk 不能早于该数量
+        if let Some(total_chunks) = self.expect_total_chunks {
+            if cipher_len == 0 && self.data_chunks < total_chunks {
+                return Err(corrupted(std::io::ErrorKind::UnexpectedEof));
+            }
+            if cipher_len > 0 && self.data_chunks >= total_chunks {
+                return Err(corrupted(std::io::ErrorKind::InvalidData));
+            }
+        }
+
+        // 零长度 chunk 即 final chunk（v1 除外），携带摘要或填充长度时其密文长度由 Header 确定
+        let body_len = if cipher_len == 0 {
+            let digest_len = if self.expect_plaintext_digest {
+                PLAINTEXT_DIGEST_SIZE
+            } else {
+                0
+            };
+            let padding_len = if self.expect_padding {
+                PADDING_LEN_SIZE
+            } else {
+                0
+            };
+            digest_len + padding_len
+        } else {
+            cipher_len
+        };
 
-            reader.read_exact(&mut cipher_body)?;
-            reader.read_exact(&mut tag)?;
+        Ok(body_len + TAG_SIZE)
+    }
 
-            cipher_body.extend_from_slice(&tag);
+    /// 认证并解密一个完整的帧（长度前缀 `len_buf` 之后的 `cipher_body | tag`）
+    ///
+    /// 返回值语义同 `read_chunk`；`cipher_body` 的长度必须等于 `frame_len(len_buf)`。
+    /// 文件名帧认证通过后记录文件名，返回空的明文。
+    pub(crate) fn open_frame(
+        &mut self,
+        len_buf: [u8; LEN_SIZE],
+        cipher_body: &[u8],
+    ) -> std::io::Result<Option<Zeroizing<Vec<u8>>>> {
+        let is_file_name = self.awaiting_file_name();
+        let is_final = !is_file_name && u32::from_be_bytes(len_buf) == 0;
 
-            let aad = self.chunk_index.to_be_bytes();
+        let flag = if !self.final_marker {
+            None
+        } else if is_file_name {
+            Some(FILE_NAME_CHUNK_FLAG)
+        } else if is_final {
+            Some(FINAL_CHUNK_FLAG)
+        } else {
+            Some(DATA_CHUNK_FLAG)
+        };
+        let aad = chunk_aad(self.header_digest.as_ref(), self.chunk_index, flag);
 
-            let plaintext = match self.algorithm {
+        let plaintext = Zeroizing::new(
+            match self.algorithm {
                 AeadAlgorithm::XChaCha20Poly1305 => xchacha20_poly1305::decrypt_chunk(
                     &self.key,
                     &self.base_nonce,
                     self.chunk_index,
-                    &cipher_body,
+                    cipher_body,
                     &aad,
-                )?,
+                ),
                 AeadAlgorithm::Aes256Gcm => aes_256_gcm::decrypt_chunk(
                     &self.key,
                     &self.base_nonce,
                     self.chunk_index,
-                    &cipher_body,
+                    cipher_body,
                     &aad,
-                )?,
-            };
+                ),
+                AeadAlgorithm::ChaCha20Poly1305 => chacha20_poly1305::decrypt_chunk(
+                    &self.key,
+                    &self.base_nonce,
+                    self.chunk_index,
+                    cipher_body,
+                    &aad,
+                ),
+                AeadAlgorithm::Aes128Gcm => aes_128_gcm::decrypt_chunk(
+                    &self.key[..self.algorithm.key_len()],
+                    &self.base_nonce,
+                    self.chunk_index,
+                    cipher_body,
+                    &aad,
+                ),
+            }
+            .map_err(|_| {
+                // 第一个 chunk 就认证失败，几乎总是密码错误（或 Header 被改动）
+                let error = if self.chunk_index == 0 {
+                    SealVaultError::InvalidPasswordOrFile
+                } else {
+                    SealVaultError::CorruptedData
+                };
+                std::io::Error::new(std::io::ErrorKind::InvalidData, error)
+            })?,
+        );
 
-            writer.write_all(&plaintext)?;
-            self.chunk_index += 1;
+        self.chunk_index += 1;
+
+        if is_file_name {
+            self.file_name = Some(plaintext);
+            return Ok(Some(Zeroizing::new(Vec::new())));
         }
 
-        Ok(())
+        if is_final {
+            let mut trailer = plaintext.as_slice();
+            if self.expect_plaintext_digest {
+                let (digest, rest) = trailer
+                    .split_first_chunk::<PLAINTEXT_DIGEST_SIZE>()
+                    .ok_or_else(|| corrupted(std::io::ErrorKind::InvalidData))?;
+                self.plaintext_digest = Some(*digest);
+                trailer = rest;
+            }
+            if self.expect_padding {
+                let (padding_len, rest) = trailer
+                    .split_first_chunk::<PADDING_LEN_SIZE>()
+                    .ok_or_else(|| corrupted(std::io::ErrorKind::InvalidData))?;
+                self.padding_len = Some(u64::from_be_bytes(*padding_len));
+                trailer = rest;
+            }
+            if !trailer.is_empty() {
+                return Err(corrupted(std::io::ErrorKind::InvalidData));
+            }
+            self.finished = true;
+            return Ok(None);
+        }
+
+        self.data_chunks += 1;
+        Ok(Some(plaintext))
+    }
+}
+
+/// 按 Stream 格式写出一个已加密的 chunk：`len (u32, 仅 cipher_body) | cipher_body | tag`
+fn write_frame<W: Write>(mut writer: W, ciphertext: &[u8]) -> std::io::Result<()> {
+    let (cipher_body, tag) = ciphertext.split_at(ciphertext.len() - TAG_SIZE);
+    writer.write_all(&(cipher_body.len() as u32).to_be_bytes())?;
+    writer.write_all(cipher_body)?;
+    writer.write_all(tag)
+}
+
+/// 尽量读满 buf，返回实际读取的长度（小于 buf 长度即已到 EOF）
+pub(crate) fn read_full<R: Read>(mut reader: R, buf: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// 确认 reader 已到 EOF，还有剩余数据时返回 `InvalidData`（负载为 `CorruptedData`）
+fn ensure_eof<R: Read>(reader: R) -> std::io::Result<()> {
+    match read_full(reader, &mut [0u8; 1])? {
+        0 => Ok(()),
+        _ => Err(corrupted(std::io::ErrorKind::InvalidData)),
+    }
+}
+
+/// 构造携带 `SealVaultError::CorruptedData` 的 stream 格式错误
+fn corrupted(kind: std::io::ErrorKind) -> std::io::Error {
+    std::io::Error::new(kind, SealVaultError::CorruptedData)
+}
+
+/// 构造 chunk 的 AAD：[Header 摘要 ||] `chunk_index`（大端）[|| chunk 类型标记]
+///
+/// - `header_digest` 为 `None` 时对应 v1 旧格式
+/// - `flag` 为 `None` 时对应 v1 旧格式
+fn chunk_aad(
+    header_digest: Option<&[u8; HEADER_DIGEST_SIZE]>,
+    chunk_index: u64,
+    flag: Option<u8>,
+) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(HEADER_DIGEST_SIZE + 9);
+    if let Some(digest) = header_digest {
+        aad.extend_from_slice(digest);
+    }
+    aad.extend_from_slice(&chunk_index.to_be_bytes());
+    if let Some(flag) = flag {
+        aad.push(flag);
     }
+    aad
 }
@@ -3,23 +3,53 @@
 //! 提供「先写临时文件，成功后再替换目标文件」的写出语义，
 //! 避免在写入失败时污染或截断目标文件。
 
-use std::fs::{self, File};
+use std::fs::{self, File, OpenOptions};
 use std::io;
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::Duration;
 
-static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+use rand::{RngCore, rngs::OsRng};
+
+/// 临时文件名冲突时的最大尝试次数
+const TMP_ATTEMPTS: u32 = 16;
+
+/// 调试用环境变量：设为 `1` 时，写入失败后保留临时文件供排查。
+pub const KEEP_TMP_ENV: &str = "SEALVAULT_KEEP_TMP";
+
+/// 无法判断所属进程是否存活时，临时文件超过该时长未修改即视为残留
+pub const ORPHAN_TMP_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// 所属进程已不存在时，临时文件至少这么久未修改才视为残留
+///
+/// pid 只在本机、本 pid 命名空间内有意义：共享目录（NFS、SMB）中其他主机或其他容器写的
+/// 临时文件，其 pid 在本机上可能恰好不存在。仍在写入的文件会持续更新修改时间，不会被误删。
+pub const ORPHAN_TMP_DEAD_OWNER_MIN_AGE: Duration = Duration::from_secs(10 * 60);
 
 /// 原子写文件。
 ///
 /// 流程：
-/// 1. 在目标目录创建临时文件；
+/// 1. 在目标目录以 `create_new` 创建随机命名的临时文件（已存在的路径，包括预先放置的
+///    符号链接，都不会被跟随或覆盖，而是换一个名字重试）；
 /// 2. 调用 `write_fn` 写入完整内容；
-/// 3. 写入成功后，使用 rename 原子替换目标文件。
+/// 3. 写入成功后，原子替换目标文件（见 `replace_file`）。
+///
+/// 写入失败时默认删除临时文件；若设置了 `SEALVAULT_KEEP_TMP=1`，
+/// 则保留临时文件并在 stderr 打印其路径。
 pub fn write_atomic<F>(target: &Path, write_fn: F) -> io::Result<()>
 where
     F: FnOnce(&mut File) -> io::Result<()>,
+{
+    write_atomic_checked(target, write_fn, |_| Ok(()))
+}
+
+/// 原子写文件，并在替换目标文件前校验临时文件。
+///
+/// `check_fn` 在临时文件写完并落盘后、rename 之前调用，参数为临时文件路径；
+/// 校验失败与写入失败同样处理，目标文件保持不变。
+pub fn write_atomic_checked<F, C>(target: &Path, write_fn: F, check_fn: C) -> io::Result<()>
+where
+    F: FnOnce(&mut File) -> io::Result<()>,
+    C: FnOnce(&Path) -> io::Result<()>,
 {
     let parent = target.parent().ok_or_else(|| {
         io::Error::new(
@@ -30,40 +60,309 @@ where
 
     fs::create_dir_all(parent)?;
 
-    let tmp_path = build_tmp_path(parent, target.file_name());
-    let mut tmp_file = File::create(&tmp_path)?;
+    let (mut tmp_file, tmp_path) =
+        create_tmp_file_with(|| build_tmp_path(parent, target.file_name()))?;
+
+    let result = write_fn(&mut tmp_file)
+        .and_then(|()| tmp_file.sync_all())
+        .and_then(|()| check_fn(&tmp_path));
 
-    if let Err(err) = write_fn(&mut tmp_file) {
-        let _ = fs::remove_file(&tmp_path);
+    if let Err(err) = result {
+        if keep_tmp_on_failure() {
+            eprintln!(
+                "SealVault: 写入失败，已保留临时文件: {}",
+                tmp_path.display()
+            );
+        } else {
+            let _ = fs::remove_file(&tmp_path);
+        }
         return Err(err);
     }
 
-    tmp_file.sync_all()?;
+    replace_file(&tmp_path, target)
+}
+
+/// 用已写完并落盘的 `source` 替换 `target`。
+///
+/// 供 `write_atomic` 以及需要自行管理中间文件的流程（如断点续写）共用。
+///
+/// 替换是单个原子操作，任何时刻 `target` 要么是旧内容、要么是新内容，不会先被删除：
+/// - Unix：`rename` 原子覆盖，之后 fsync 父目录，保证掉电后 rename 本身也已落盘；
+/// - Windows：目标已存在时使用 `ReplaceFileW`，否则使用 `MoveFileExW`，之后对目标执行
+///   `FlushFileBuffers`；目标被其他进程（如杀毒软件、索引服务）短暂占用时按退避重试。
+pub fn replace_file(source: &Path, target: &Path) -> io::Result<()> {
+    rename_replacing(source, target)?;
+    sync_replaced(target)
+}
+
+#[cfg(not(windows))]
+fn rename_replacing(source: &Path, target: &Path) -> io::Result<()> {
+    fs::rename(source, target)
+}
+
+#[cfg(windows)]
+fn rename_replacing(source: &Path, target: &Path) -> io::Result<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use std::time::Duration;
 
-    if target.exists() {
-        fs::remove_file(target)?;
+    use windows_sys::Win32::Storage::FileSystem::{
+        MOVEFILE_REPLACE_EXISTING, MOVEFILE_WRITE_THROUGH, MoveFileExW,
+        REPLACEFILE_IGNORE_MERGE_ERRORS, ReplaceFileW,
+    };
+
+    /// 共享冲突等临时错误的最大尝试次数（退避总计约 1.5 秒）
+    const ATTEMPTS: u32 = 6;
+
+    fn wide(path: &Path) -> Vec<u16> {
+        path.as_os_str().encode_wide().chain(Some(0)).collect()
+    }
+
+    let source_w = wide(source);
+    let target_w = wide(target);
+
+    let mut attempt = 0;
+    loop {
+        // SAFETY: 两个路径均为以 0 结尾的 UTF-16 缓冲区，在调用期间保持有效
+        let ok = unsafe {
+            if target.exists() {
+                ReplaceFileW(
+                    target_w.as_ptr(),
+                    source_w.as_ptr(),
+                    std::ptr::null(),
+                    REPLACEFILE_IGNORE_MERGE_ERRORS,
+                    std::ptr::null(),
+                    std::ptr::null(),
+                )
+            } else {
+                MoveFileExW(
+                    source_w.as_ptr(),
+                    target_w.as_ptr(),
+                    MOVEFILE_REPLACE_EXISTING | MOVEFILE_WRITE_THROUGH,
+                )
+            }
+        };
+        if ok != 0 {
+            return Ok(());
+        }
+
+        let err = io::Error::last_os_error();
+        attempt += 1;
+        // ERROR_ACCESS_DENIED / ERROR_SHARING_VIOLATION / ERROR_LOCK_VIOLATION
+        let transient = matches!(err.raw_os_error(), Some(5 | 32 | 33));
+        if !transient || attempt >= ATTEMPTS {
+            return Err(err);
+        }
+        std::thread::sleep(Duration::from_millis(50 << attempt));
     }
+}
+
+/// fsync 目标所在目录，使刚完成的 rename 在掉电后依然可见。
+#[cfg(unix)]
+fn sync_replaced(target: &Path) -> io::Result<()> {
+    match target.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => File::open(parent)?.sync_all(),
+        _ => File::open(".")?.sync_all(),
+    }
+}
 
-    fs::rename(&tmp_path, target)?;
+/// Windows 无法以普通方式打开目录并 fsync，且 `ReplaceFileW` 不支持 `MOVEFILE_WRITE_THROUGH`；
+/// 改为以写方式打开替换后的目标并 `FlushFileBuffers`（`sync_all`），把元数据一并刷到磁盘。
+#[cfg(not(unix))]
+fn sync_replaced(target: &Path) -> io::Result<()> {
+    OpenOptions::new().write(true).open(target)?.sync_all()
+}
+
+fn keep_tmp_on_failure() -> bool {
+    std::env::var_os(KEEP_TMP_ENV).is_some_and(|v| v == "1")
+}
+
+/// 依次尝试 `next_path` 给出的路径，以 `create_new`（`O_CREAT | O_EXCL`）创建临时文件
+///
+/// 路径已存在（包括指向任意位置的符号链接）时不会打开它，而是换下一个路径；
+/// 连续 `TMP_ATTEMPTS` 次冲突后返回 `AlreadyExists`。`write_atomic` 内部使用，
+/// 公开仅为测试冲突处理。
+#[doc(hidden)]
+pub fn create_tmp_file_with<N>(mut next_path: N) -> io::Result<(File, PathBuf)>
+where
+    N: FnMut() -> PathBuf,
+{
+    for _ in 0..TMP_ATTEMPTS {
+        let tmp_path = next_path();
+        match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp_path)
+        {
+            Ok(file) => return Ok((file, tmp_path)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+            Err(e) => return Err(e),
+        }
+    }
 
-    Ok(())
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        "无法创建临时文件：候选路径均已存在",
+    ))
 }
 
+/// 创建新的中间文件（如断点续做的 `.svpart`），可读写
+///
+/// 先删除同名的旧文件或符号链接（不影响链接目标），再以 `create_new` 创建，不会跟随预先放置
+/// 的符号链接写到其他位置；删除后又被抢先创建时返回 `AlreadyExists`。父目录不存在时逐级创建。
+pub fn create_fresh(path: &Path) -> io::Result<File> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(path)
+}
+
+/// 以读写方式打开已有的中间文件，不跟随符号链接
+///
+/// `path` 是符号链接或其他非普通文件时返回 `InvalidInput`；打开时同样不跟随链接
+/// （Unix 为 `O_NOFOLLOW`，Windows 为 `FILE_FLAG_OPEN_REPARSE_POINT`），检查之后被换成链接也不会生效。
+pub fn open_existing(path: &Path) -> io::Result<File> {
+    let not_regular = || {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("not a regular file: {}", path.display()),
+        )
+    };
+    if !fs::symlink_metadata(path)?.is_file() {
+        return Err(not_regular());
+    }
+
+    let mut options = OpenOptions::new();
+    options.read(true).write(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.custom_flags(libc::O_NOFOLLOW);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+        options.custom_flags(windows_sys::Win32::Storage::FileSystem::FILE_FLAG_OPEN_REPARSE_POINT);
+    }
+    let file = options.open(path)?;
+    if !file.metadata()?.is_file() {
+        return Err(not_regular());
+    }
+    Ok(file)
+}
+
+/// 生成 `.{base}.tmp-{pid}-{随机 16 位十六进制}` 形式的临时文件路径
+///
+/// pid 供 `cleanup_orphaned_temp` 判断所属进程是否仍在运行；随机部分来自 OsRng，不可预测。
 fn build_tmp_path(parent: &Path, file_name: Option<&std::ffi::OsStr>) -> PathBuf {
     let base_name = file_name
         .and_then(|n| n.to_str())
         .filter(|s| !s.is_empty())
         .unwrap_or("sealvault-output");
 
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_nanos())
-        .unwrap_or_default();
-
-    let counter = TMP_COUNTER.fetch_add(1, Ordering::Relaxed);
-
     parent.join(format!(
-        ".{base_name}.tmp-{}-{timestamp}-{counter}",
-        std::process::id()
+        ".{base_name}.tmp-{}-{:016x}",
+        std::process::id(),
+        OsRng.next_u64()
     ))
 }
+
+/// 删除 `dir` 中（不递归，不跟随符号链接）先前崩溃的运行留下的临时文件，返回删除的个数
+///
+/// 只处理 `write_atomic` 命名格式的普通文件，满足以下任一条件时删除：
+/// - 文件名中的 pid 对应的进程已不存在（仅 Unix 可判断），且超过
+///   `ORPHAN_TMP_DEAD_OWNER_MIN_AGE` 未被修改；
+/// - 超过 `ORPHAN_TMP_MAX_AGE` 未被修改（包括早期版本 `{pid}-{时间戳}-{计数}` 命名的文件）。
+///
+/// 属于当前进程的临时文件永远不会被删除；其他仍在运行的进程正在写的文件会持续更新
+/// 修改时间，因此也会被保留。
+///
+/// 只检查 `dir` 的直接子项，不递归进入子目录：临时文件总是与目标文件位于同一目录，
+/// 调用方对自己会写入的每个目录分别调用即可，不会波及无关的子树。
+///
+/// 清理只是顺带的整理工作，尽力而为：`dir` 无法读取、无法读取元数据或删除失败的文件
+/// 直接跳过，不报告错误。`dir` 不存在时返回 0。
+pub fn cleanup_orphaned_temp(dir: &Path) -> usize {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    for entry in entries.filter_map(Result::ok) {
+        if !entry.file_type().is_ok_and(|t| t.is_file()) {
+            continue;
+        }
+        let Some(owner) = entry.file_name().to_str().and_then(parse_tmp_owner) else {
+            continue;
+        };
+        let path = entry.path();
+        if !is_orphaned(owner, &path).unwrap_or(false) {
+            continue;
+        }
+        // 与其他清理者或刚完成 rename 的写入者竞争时文件可能已不存在
+        if fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    removed
+}
+
+/// 从临时文件名中解析所属进程的 pid，不是 SealVault 临时文件时返回 `None`
+///
+/// 识别当前的 `.{base}.tmp-{pid}-{随机 16 位十六进制}` 与早期版本的
+/// `.{base}.tmp-{pid}-{时间戳}-{计数}` 两种命名。
+fn parse_tmp_owner(name: &str) -> Option<u32> {
+    let (base, suffix) = name.strip_prefix('.')?.rsplit_once(".tmp-")?;
+    if base.is_empty() {
+        return None;
+    }
+    let (pid, rest) = suffix.split_once('-')?;
+    let is_decimal = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let is_random = rest.len() == 16 && rest.bytes().all(|b| b.is_ascii_hexdigit());
+    let is_legacy = rest
+        .split_once('-')
+        .is_some_and(|(timestamp, counter)| is_decimal(timestamp) && is_decimal(counter));
+    if !is_random && !is_legacy {
+        return None;
+    }
+    pid.parse().ok()
+}
+
+fn is_orphaned(owner: u32, path: &Path) -> io::Result<bool> {
+    if owner == std::process::id() {
+        return Ok(false);
+    }
+    let max_age = match process_alive(owner) {
+        Some(false) => ORPHAN_TMP_DEAD_OWNER_MIN_AGE,
+        _ => ORPHAN_TMP_MAX_AGE,
+    };
+    let modified = fs::symlink_metadata(path)?.modified()?;
+    Ok(modified.elapsed().is_ok_and(|age| age > max_age))
+}
+
+/// 判断进程是否存在；无法判断时返回 `None`
+#[cfg(unix)]
+fn process_alive(pid: u32) -> Option<bool> {
+    let pid = libc::pid_t::try_from(pid).ok().filter(|&pid| pid > 0)?;
+    // SAFETY: 信号 0 只做存在与权限检查，不会向目标进程发送任何信号
+    if unsafe { libc::kill(pid, 0) } == 0 {
+        return Some(true);
+    }
+    match io::Error::last_os_error().raw_os_error() {
+        Some(libc::ESRCH) => Some(false),
+        // EPERM：进程存在，但属于其他用户
+        _ => Some(true),
+    }
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> Option<bool> {
+    None
+}
@@ -1 +1,5 @@
 pub mod atomic;
+pub mod wipe;
+
+pub use atomic::cleanup_orphaned_temp;
+pub use wipe::secure_delete;
@@ -0,0 +1,46 @@
+//! SealVault 安全删除工具。
+//!
+//! 删除文件前先用随机数据覆写其全部内容并落盘，避免直接 `remove_file` 后原内容
+//! 仍可从磁盘上恢复。
+//!
+//! 覆写只能作用于文件系统交给我们的那些块，以下情况不保证旧数据被清除：
+//! - SSD / 闪存：磨损均衡与 TRIM 会把写入重定向到新的物理页，旧页可能仍保留原内容；
+//! - 写时复制（CoW）文件系统（Btrfs、ZFS、APFS 等）与快照：覆写写入新块，旧块仍被快照引用
+//!   或等待回收；
+//! - 日志、备份、交换分区以及其他进程或编辑器留下的副本。
+//!
+//! 这些场景下应依赖全盘加密，或一开始就不让明文落盘（如经 stdin 流式加密）。
+
+use std::fs;
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use rand::{RngCore, rngs::OsRng};
+
+use crate::format::stream::DEFAULT_IO_BUFFER_SIZE;
+use crate::fs::atomic::open_existing;
+
+/// 用一遍随机数据覆写文件内容并落盘，然后删除文件。
+///
+/// 只处理普通文件：`path` 为符号链接或目录时返回 `InvalidInput`，不会跟随链接覆写其目标。
+/// 文件经 `open_existing` 以不跟随链接的方式打开，并以打开后的句柄确认是普通文件、取得长度，
+/// 检查之后 `path` 被换成链接也不会覆写链接目标。
+/// 覆写或落盘失败时不删除文件，调用方可据此重试；覆写的保证范围见模块文档。
+pub fn secure_delete(path: &Path) -> io::Result<()> {
+    let mut file = open_existing(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(0))?;
+
+    let mut buf = vec![0u8; DEFAULT_IO_BUFFER_SIZE];
+    let mut remaining = len;
+    while remaining > 0 {
+        let n = remaining.min(buf.len() as u64) as usize;
+        OsRng.fill_bytes(&mut buf[..n]);
+        file.write_all(&buf[..n])?;
+        remaining -= n as u64;
+    }
+    file.sync_all()?;
+    drop(file);
+
+    fs::remove_file(path)
+}
@@ -1,49 +1,762 @@
+#[cfg(feature = "fs")]
+mod archive;
+#[cfg(feature = "tokio")]
+mod async_io;
 mod decrypt;
 mod encrypt;
+#[cfg(feature = "fs")]
 mod folder;
+mod reader;
+mod writer;
 
 pub mod algorithm;
+pub mod compression;
 pub mod crypto;
 pub mod error;
 pub mod format;
+#[cfg(feature = "fs")]
 pub mod fs;
+pub mod padding;
+pub mod progress;
 
-pub use algorithm::AeadAlgorithm;
+pub use algorithm::{AeadAlgorithm, ParseAlgorithmError};
+pub use compression::Compression;
+pub use crypto::password::PasswordPolicy;
+pub use decrypt::{CheckpointOptions, DecryptOptions, FileInfo, open_for_decrypt};
+pub use encrypt::{EncryptOptions, open_for_encrypt};
 pub use error::SealVaultError;
+#[cfg(feature = "fs")]
+pub use folder::{FolderInfo, FolderOptions, FolderResult, NonEncryptedFilePolicy, SymlinkPolicy};
+pub use format::header::{HEADER_SIZE, MAGIC, VERSION};
+pub use padding::Padding;
+pub use progress::{ProgressEvent, Stats};
+pub use reader::SealVaultReader;
+pub use writer::SealVaultWriter;
 
-use std::path::Path;
+use std::io::{Read, Write};
+#[cfg(feature = "fs")]
+use std::path::{Path, PathBuf};
 
-pub fn encrypt(input: &Path, output: &Path, password: &str) -> std::io::Result<()> {
-    encrypt::encrypt_file(input, output, password)
+use crate::format::header::Header;
+
+#[cfg(feature = "fs")]
+pub fn encrypt(input: &Path, output: &Path, password: &str) -> Result<(), SealVaultError> {
+    Ok(encrypt::encrypt_file(input, output, password.as_bytes())?)
 }
 
+#[cfg(feature = "fs")]
 pub fn encrypt_with_algorithm(
     input: &Path,
     output: &Path,
     password: &str,
     algorithm: AeadAlgorithm,
-) -> std::io::Result<()> {
-    encrypt::encrypt_file_with_algorithm(input, output, password, algorithm)
+) -> Result<(), SealVaultError> {
+    Ok(encrypt::encrypt_file_with_algorithm(
+        input,
+        output,
+        password.as_bytes(),
+        algorithm,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 按 `EncryptOptions` 加密文件（算法、chunk 大小）
+pub fn encrypt_with_options(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &EncryptOptions,
+) -> Result<(), SealVaultError> {
+    Ok(encrypt::encrypt_file_with_options(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 按 `EncryptOptions` 加密文件，返回明文字节数、密文字节数与数据 chunk 数
+pub fn encrypt_with_stats(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &EncryptOptions,
+) -> Result<Stats, SealVaultError> {
+    Ok(encrypt::encrypt_file_with_stats(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// `encrypt_with_options` 的测试接缝，salt 与 base_nonce 取自给定的 RNG（固定种子可得到确定输出）
+#[doc(hidden)]
+pub fn encrypt_with_rng<R>(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &EncryptOptions,
+    rng: &mut R,
+) -> Result<(), SealVaultError>
+where
+    R: rand::RngCore + rand::CryptoRng,
+{
+    Ok(encrypt::encrypt_file_with_rng(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+        rng,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 内存映射输入文件后加密（只适用于加密期间不会被修改或截断的文件）
+///
+/// 输入为空、映射失败、启用压缩或多线程时回退到 `encrypt_with_options` 的流式路径。
+pub fn encrypt_mmap(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &EncryptOptions,
+) -> Result<(), SealVaultError> {
+    Ok(encrypt::encrypt_file_mmap(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 按 `EncryptOptions` 加密文件，每写出一个 chunk 通过 `progress` 报告 `BytesProcessed`
+pub fn encrypt_with_progress(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &EncryptOptions,
+    progress: &mut dyn FnMut(ProgressEvent),
+) -> Result<(), SealVaultError> {
+    Ok(encrypt::encrypt_file_with_progress(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+        progress,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 按 `EncryptOptions` 加密文件（总是开启写后校验），密文落盘后覆写并删除明文输入
+///
+/// 覆写在 SSD、写时复制文件系统与快照上不能保证清除旧数据，见 `fs::wipe`。
+pub fn encrypt_and_wipe(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &EncryptOptions,
+) -> Result<(), SealVaultError> {
+    Ok(encrypt::encrypt_file_and_wipe(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// `EncryptOptions::verify_after_write` 的测试接缝：按 `options` 加密并强制开启写后校验，
+/// `before_verify` 可在校验前改写临时密文
+#[doc(hidden)]
+pub fn encrypt_with_verify_hook<H>(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &EncryptOptions,
+    before_verify: H,
+) -> Result<(), SealVaultError>
+where
+    H: FnMut(&Path) -> std::io::Result<()>,
+{
+    Ok(encrypt::encrypt_file_with_verify_hook(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+        before_verify,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+pub fn decrypt(input: &Path, output: &Path, password: &str) -> Result<(), SealVaultError> {
+    Ok(decrypt::decrypt_file(input, output, password.as_bytes())?)
+}
+
+#[cfg(feature = "fs")]
+/// 解密文件，返回明文字节数、密文字节数与数据 chunk 数
+pub fn decrypt_with_stats(
+    input: &Path,
+    output: &Path,
+    password: &str,
+) -> Result<Stats, SealVaultError> {
+    Ok(decrypt::decrypt_file_with_stats(
+        input,
+        output,
+        password.as_bytes(),
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 按 `DecryptOptions` 解密文件（限制明文输出大小、限定算法、拒绝 final chunk 之后的多余数据、
+/// 要求输出目录已存在）
+pub fn decrypt_with_options(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &DecryptOptions,
+) -> Result<(), SealVaultError> {
+    Ok(decrypt::decrypt_file_with_options(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 使用 keyfile 加密文件，可同时要求密码
+///
+/// keyfile 的全部内容参与密钥派生，Header 中标记解密必须提供同一个 keyfile。
+/// 丢失或改动 keyfile 即永久丢失数据，请像保管密码一样备份它。
+///
+/// - `Some(password)`：密码与 keyfile 同时参与派生，密码须满足默认的 `PasswordPolicy`，
+///   否则返回 `WeakPassword`；
+/// - `None`：只凭 keyfile 加密，不涉及密码，因此不做密码检查；解密时同样传入 `None`。
+pub fn encrypt_with_keyfile(
+    input: &Path,
+    output: &Path,
+    password: Option<&str>,
+    keyfile: &Path,
+    algorithm: AeadAlgorithm,
+) -> Result<(), SealVaultError> {
+    let options = EncryptOptions {
+        keyfile: Some(keyfile.to_path_buf()),
+        ..EncryptOptions::with_algorithm(algorithm)
+    };
+    let password = match password {
+        Some(password) => {
+            options.password_policy.check(password.as_bytes())?;
+            password.as_bytes()
+        }
+        // 仅 keyfile：KDF 输入中的密码部分为空
+        None => &[],
+    };
+    Ok(encrypt::encrypt_file_with_options(
+        input, output, password, &options,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 解密需要 keyfile 的文件；未提供 keyfile 解密此类文件时 `decrypt` 返回 `KeyfileRequired`
+///
+/// `password` 须与加密时一致：只凭 keyfile 加密的文件传入 `None`。
+pub fn decrypt_with_keyfile(
+    input: &Path,
+    output: &Path,
+    password: Option<&str>,
+    keyfile: &Path,
+) -> Result<(), SealVaultError> {
+    let password = match password {
+        Some(password) => password.as_bytes(),
+        None => &[],
+    };
+    Ok(decrypt::decrypt_file_with_keyfile(
+        input, output, password, keyfile,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 使用密码解密文件，并返回文件元数据（含已校验的明文 BLAKE3 摘要，若有）
+pub fn decrypt_with_info(
+    input: &Path,
+    output: &Path,
+    password: &str,
+) -> Result<FileInfo, SealVaultError> {
+    Ok(decrypt::decrypt_file_with_info(
+        input,
+        output,
+        password.as_bytes(),
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 解密到 `output_dir` 中，文件名取加密时保存的原始文件名（见 `EncryptOptions::store_file_name`），
+/// 返回写出的路径
+///
+/// 文件未保存文件名时返回 `Io`（`InvalidInput`）。
+pub fn decrypt_to_dir(
+    input: &Path,
+    output_dir: &Path,
+    password: &str,
+) -> Result<PathBuf, SealVaultError> {
+    Ok(decrypt::decrypt_file_to_dir(
+        input,
+        output_dir,
+        password.as_bytes(),
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 更换 .svlt 文件的密码：流式解密并在内存中以新密码（新 salt 与 nonce）重新加密，不落盘明文
+///
+/// `output` 可与 `input` 相同；旧密码错误或文件损坏时不会产生或改动输出。
+/// 新密码须满足默认的 `PasswordPolicy`，否则返回 `WeakPassword`。
+pub fn rekey(
+    input: &Path,
+    output: &Path,
+    old_password: &str,
+    new_password: &str,
+) -> Result<(), SealVaultError> {
+    Ok(encrypt::rekey_file(
+        input,
+        output,
+        old_password.as_bytes(),
+        new_password.as_bytes(),
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 以新的 `EncryptOptions` 重新加密 .svlt 文件（如调整 chunk 大小或压缩），密码不变
+///
+/// 一次流式完成，明文不落盘，新文件使用新的 salt 与 nonce；
+/// `output` 可与 `input` 相同，密码错误或文件损坏时不会产生或改动输出。
+pub fn reencrypt(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    new_options: &EncryptOptions,
+) -> Result<(), SealVaultError> {
+    Ok(encrypt::reencrypt_file(
+        input,
+        output,
+        password.as_bytes(),
+        new_options,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 校验 .svlt 文件能否用该密码完整解密（认证全部 chunk 与结束标记），不写出明文
+pub fn verify(input: &Path, password: &str) -> Result<(), SealVaultError> {
+    Ok(decrypt::verify_file(input, password.as_bytes())?)
+}
+
+#[cfg(feature = "fs")]
+/// 只解密第一个 chunk，快速判断密码是否正确；不校验其余 chunk，完整校验请用 `verify`
+///
+/// 密码错误时返回 `Ok(false)`，I/O 或格式错误仍返回 `Err`。
+pub fn check_password(input: &Path, password: &str) -> Result<bool, SealVaultError> {
+    Ok(decrypt::check_password_file(input, password.as_bytes())?)
+}
+
+/// 本 crate 加密时写入的格式版本（即 `VERSION`）
+///
+/// 更早版本的文件仍可解密；已有文件的版本通过 `inspect` 读取：
+///
+/// ```
+/// use engine::{AeadAlgorithm, EncryptOptions};
+///
+/// let dir = tempfile::tempdir()?;
+/// let input = dir.path().join("note.txt");
+/// let encrypted = dir.path().join("note.txt.svlt");
+/// std::fs::write(&input, b"hello")?;
+/// let options = EncryptOptions::with_algorithm(AeadAlgorithm::Aes256Gcm);
+/// engine::encrypt_with_options(&input, &encrypted, "password", &options)?;
+///
+/// let info = engine::inspect(&encrypted)?;
+/// assert_eq!(info.version, engine::format_version());
+/// assert_eq!(AeadAlgorithm::from_u8(info.algorithm.to_u8()), Some(info.algorithm));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn format_version() -> u8 {
+    VERSION
+}
+
+#[cfg(feature = "fs")]
+/// 读取 .svlt 文件的版本、算法、chunk 大小与 salt（只读 Header，不需要密码）
+pub fn inspect(input: &Path) -> Result<FileInfo, SealVaultError> {
+    Ok(decrypt::inspect_file(input)?)
+}
+
+#[cfg(feature = "fs")]
+/// 使用任意字节序列作为密码加密文件（如来自 FFI、文件描述符的非 UTF-8 密码）
+pub fn encrypt_with_password_bytes(
+    input: &Path,
+    output: &Path,
+    password: &[u8],
+    algorithm: AeadAlgorithm,
+) -> Result<(), SealVaultError> {
+    Ok(encrypt::encrypt_file_with_algorithm(
+        input, output, password, algorithm,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 使用任意字节序列作为密码解密文件
+pub fn decrypt_with_password_bytes(
+    input: &Path,
+    output: &Path,
+    password: &[u8],
+) -> Result<(), SealVaultError> {
+    Ok(decrypt::decrypt_file(input, output, password)?)
+}
+
+#[cfg(feature = "fs")]
+/// 可断点续做的文件加密（见 `CheckpointOptions`）
+///
+/// 中断后以相同参数再次调用即可跳过已加密的 chunk；不支持压缩、填充与明文摘要。
+pub fn encrypt_with_checkpoint(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &EncryptOptions,
+    checkpoint: &CheckpointOptions,
+) -> Result<(), SealVaultError> {
+    Ok(encrypt::encrypt_file_with_checkpoint(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+        checkpoint,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 可断点续做的文件解密（见 `CheckpointOptions`）
+pub fn decrypt_with_checkpoint(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &CheckpointOptions,
+) -> Result<(), SealVaultError> {
+    Ok(decrypt::decrypt_file_with_checkpoint(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+    )?)
+}
+
+/// 在内存中加密数据，结果与 `encrypt` 写出的 .svlt 文件格式一致
+pub fn encrypt_bytes(
+    data: &[u8],
+    password: &str,
+    algorithm: AeadAlgorithm,
+) -> Result<Vec<u8>, SealVaultError> {
+    Ok(encrypt::encrypt_bytes(
+        data,
+        password.as_bytes(),
+        algorithm,
+    )?)
+}
+
+/// 在内存中解密 .svlt 格式的数据
+pub fn decrypt_bytes(data: &[u8], password: &str) -> Result<Vec<u8>, SealVaultError> {
+    Ok(decrypt::decrypt_bytes(data, password.as_bytes())?)
 }
 
-pub fn decrypt(input: &Path, output: &Path, password: &str) -> std::io::Result<()> {
-    decrypt::decrypt_file(input, output, password)
+/// 按 `DecryptOptions` 在内存中解密 .svlt 格式的数据（如拒绝 final chunk 之后的多余数据）
+pub fn decrypt_bytes_with_options(
+    data: &[u8],
+    password: &str,
+    options: &DecryptOptions,
+) -> Result<Vec<u8>, SealVaultError> {
+    Ok(decrypt::decrypt_bytes_with_options(
+        data,
+        password.as_bytes(),
+        options,
+    )?)
+}
+
+/// 流式加密：从 reader 读取明文，向 writer 写出完整 .svlt 数据（不要求可 seek）
+pub fn encrypt_stream<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    password: &str,
+    algorithm: AeadAlgorithm,
+) -> Result<(), SealVaultError> {
+    Ok(encrypt::encrypt_stream(
+        reader,
+        writer,
+        password.as_bytes(),
+        algorithm,
+    )?)
 }
 
+/// 流式解密：从 reader 读取 .svlt 数据，向 writer 写出明文（不要求可 seek）
+pub fn decrypt_stream<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    password: &str,
+) -> Result<(), SealVaultError> {
+    Ok(decrypt::decrypt_stream(
+        reader,
+        writer,
+        password.as_bytes(),
+    )?)
+}
+
+/// 按 `DecryptOptions` 流式解密，语义同 `decrypt_stream`
+///
+/// 开启 `reject_trailing_data` 时要求 reader 在 final chunk 之后立即结束。
+pub fn decrypt_stream_with_options<R: Read, W: Write>(
+    reader: R,
+    writer: W,
+    password: &str,
+    options: &DecryptOptions,
+) -> Result<(), SealVaultError> {
+    Ok(decrypt::decrypt_stream_with_options(
+        reader,
+        writer,
+        password.as_bytes(),
+        options,
+    )?)
+}
+
+/// 异步流式加密（`tokio` 特性）：输出与 `encrypt_stream` 一致，密钥派生在 `spawn_blocking` 中执行
+#[cfg(feature = "tokio")]
+pub async fn encrypt_async<R, W>(
+    reader: R,
+    writer: W,
+    password: &str,
+    algorithm: AeadAlgorithm,
+) -> Result<(), SealVaultError>
+where
+    R: tokio::io::AsyncRead + Unpin,
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    Ok(async_io::encrypt_async(reader, writer, password.as_bytes(), algorithm).await?)
+}
+
+/// 异步流式解密（`tokio` 特性）：可解密同步接口写出的任意 .svlt 数据，语义同 `decrypt_stream`
+#[cfg(feature = "tokio")]
+pub async fn decrypt_async<R, W>(reader: R, writer: W, password: &str) -> Result<(), SealVaultError>
+where
+    R: tokio::io::AsyncRead + Unpin,
+    W: tokio::io::AsyncWrite + Unpin,
+{
+    Ok(async_io::decrypt_async(reader, writer, password.as_bytes()).await?)
+}
+
+/// 解密 Header 之后的 Stream：先用 `Header::read` 读取并检查 Header，再调用本函数写出明文
+///
+/// 密钥按 Header 中的 salt 与 KDF 重新派生，AEAD 算法取自 Header。
+pub fn decrypt_body<R: Read, W: Write>(
+    reader: R,
+    header: &Header,
+    password: &str,
+    writer: W,
+) -> Result<(), SealVaultError> {
+    Ok(decrypt::decrypt_body(
+        reader,
+        header,
+        password.as_bytes(),
+        writer,
+    )?)
+}
+
+#[cfg(feature = "fs")]
 pub fn encrypt_folder(
     input: &Path,
     output: &Path,
     password: &str,
     algorithm: AeadAlgorithm,
-) -> std::io::Result<()> {
-    folder::encrypt_folder(input, output, password, algorithm)
+) -> Result<FolderResult, SealVaultError> {
+    Ok(folder::encrypt_folder(
+        input,
+        output,
+        password.as_bytes(),
+        algorithm,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+pub fn encrypt_folder_with_options(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    algorithm: AeadAlgorithm,
+    options: &FolderOptions,
+) -> Result<FolderResult, SealVaultError> {
+    Ok(folder::encrypt_folder_with_options(
+        input,
+        output,
+        password.as_bytes(),
+        algorithm,
+        options,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 按 `options` 加密目录，并通过 `progress` 报告每个文件的开始、chunk 进度与完成
+pub fn encrypt_folder_with_progress(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    algorithm: AeadAlgorithm,
+    options: &FolderOptions,
+    progress: &mut dyn FnMut(ProgressEvent),
+) -> Result<FolderResult, SealVaultError> {
+    Ok(folder::encrypt_folder_with_progress(
+        input,
+        output,
+        password.as_bytes(),
+        algorithm,
+        options,
+        progress,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 使用 `threads` 个工作线程并行加密目录（`threads == 0` 表示按 CPU 核数）
+///
+/// 输出与 `encrypt_folder` 一致；每个线程同时占用一次 Argon2id 派生的内存（默认 64 MiB）。
+pub fn encrypt_folder_parallel(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    algorithm: AeadAlgorithm,
+    threads: usize,
+) -> Result<FolderResult, SealVaultError> {
+    Ok(folder::encrypt_folder_parallel(
+        input,
+        output,
+        password.as_bytes(),
+        algorithm,
+        &FolderOptions::default(),
+        threads,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 同 `encrypt_folder_parallel`，但按 `options` 控制写后校验、共享 salt、符号链接等行为
+pub fn encrypt_folder_parallel_with_options(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    algorithm: AeadAlgorithm,
+    options: &FolderOptions,
+    threads: usize,
+) -> Result<FolderResult, SealVaultError> {
+    Ok(folder::encrypt_folder_parallel(
+        input,
+        output,
+        password.as_bytes(),
+        algorithm,
+        options,
+        threads,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 不写出明文，逐个完整校验目录中的 `.svlt` 文件能否用该密码解密
+///
+/// 单个文件失败不会中止，通过的文件计入 `processed`，失败的记入 `failed`。
+pub fn verify_folder(input: &Path, password: &str) -> Result<FolderResult, SealVaultError> {
+    Ok(folder::verify_folder(input, password.as_bytes())?)
+}
+
+#[cfg(feature = "fs")]
+/// 按 `options` 校验目录，`FolderOptions::stop_on_first_failure` 时在第一个失败的文件处返回其错误
+pub fn verify_folder_with_options(
+    input: &Path,
+    password: &str,
+    options: &FolderOptions,
+) -> Result<FolderResult, SealVaultError> {
+    Ok(folder::verify_folder_with_options(
+        input,
+        password.as_bytes(),
+        options,
+    )?)
 }
 
+#[cfg(feature = "fs")]
+/// 解密目录中的 `.svlt` 文件，算法由每个文件的 Header 决定
 pub fn decrypt_folder(
     input: &Path,
     output: &Path,
     password: &str,
+) -> Result<FolderResult, SealVaultError> {
+    Ok(folder::decrypt_folder(input, output, password.as_bytes())?)
+}
+
+#[cfg(feature = "fs")]
+pub fn decrypt_folder_with_options(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &FolderOptions,
+) -> Result<FolderResult, SealVaultError> {
+    Ok(folder::decrypt_folder_with_options(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 按 `options` 解密目录，并通过 `progress` 报告每个 `.svlt` 文件的开始与完成
+pub fn decrypt_folder_with_progress(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    options: &FolderOptions,
+    progress: &mut dyn FnMut(ProgressEvent),
+) -> Result<FolderResult, SealVaultError> {
+    Ok(folder::decrypt_folder_with_progress(
+        input,
+        output,
+        password.as_bytes(),
+        options,
+        progress,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 将目录打包并加密为单个归档文件（不暴露目录结构与文件数量）
+pub fn encrypt_folder_to_archive(
+    input: &Path,
+    output: &Path,
+    password: &str,
     algorithm: AeadAlgorithm,
-) -> std::io::Result<()> {
-    folder::decrypt_folder(input, output, password, algorithm)
+) -> Result<(), SealVaultError> {
+    Ok(archive::encrypt_folder_to_archive(
+        input,
+        output,
+        password.as_bytes(),
+        algorithm,
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 解密 `encrypt_folder_to_archive` 生成的归档文件，还原到目标目录
+pub fn decrypt_archive_to_folder(
+    input: &Path,
+    output: &Path,
+    password: &str,
+) -> Result<(), SealVaultError> {
+    Ok(archive::decrypt_archive_to_folder(
+        input,
+        output,
+        password.as_bytes(),
+    )?)
+}
+
+#[cfg(feature = "fs")]
+/// 统计目录中 `.svlt` 文件的版本/算法/chunk 大小分布（不需要密码）
+pub fn folder_info(input: &Path) -> Result<FolderInfo, SealVaultError> {
+    Ok(folder::folder_info(input)?)
 }
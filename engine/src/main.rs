@@ -3,47 +3,307 @@
 //! 提供最小可用的 CLI：
 //!
 //! 用法：
-//!   cargo run -- encrypt|e <input> [output] <password> [algorithm]
-//!   cargo run -- decrypt|d <input> [output] <password>
-//!   cargo run -- encrypt-folder|ef <input_dir> [output_dir] <password> [algorithm]
-//!   cargo run -- decrypt-folder|df <input_dir> [output_dir] <password> [algorithm]
+//!   cargo run -- encrypt|e <input> [output] [password|-] [algorithm] [--algorithm <name>] [--verify-after-write] [--threads <n>]
+//!   cargo run -- decrypt|d <input> [output] [password|-]
+//!   cargo run -- encrypt-folder|ef <input_dir> [output_dir] [password|-] [algorithm] [--algorithm <name>] [--verify-after-write] [--threads <n>]
+//!   cargo run -- decrypt-folder|df <input_dir> [output_dir] [password|-] [--verify-manifest] [--passthrough-plaintext]
+//!   cargo run -- reencrypt|re <input> [output] [password|-] [algorithm] [--algorithm <name>] [--chunk-size <bytes>] [--zstd <level>]
+//!   cargo run -- list-algorithms
+//!
+//! 解密时算法一律从文件 Header 读取，无需指定。`encrypt` 未指定算法时按输入文件大小
+//! 自动选择 chunk 大小（见 `EncryptOptions::auto_for`）。
+//!
+//! 加密命令的算法推荐用 `--algorithm <name>` 指定；第四个位置参数的旧写法仍然可用，
+//! 二者不能同时给出。`list-algorithms` 在 stdout 逐行打印支持的算法名称（可直接用作
+//! `--algorithm` 的值）与写入 Header 的算法 ID，并标出默认算法。
+//!
+//! `encrypt` / `decrypt` 的 input 或 output 为 `-` 时分别表示 stdin / stdout（按原始字节读写，
+//! 不做换行转换），可用于管道：`sealvault encrypt - - env: < in > out.svlt`。input 为 `-` 且
+//! 省略 output 时输出到 stdout。stdin 用于传数据时不能再从中读取密码，需通过参数、`env:`
+//! 或 `SEALVAULT_PASSWORD` 提供。解密到 stdout 时明文逐 chunk 写出，后续 chunk 认证失败
+//! 时已写出的部分无法撤回（退出码非 0），写入文件时仍为原子替换。
+//! `reencrypt` 用同一密码按新参数重新加密（省略 output 时原地替换），新文件的算法、
+//! chunk 大小与压缩只取自命令行，不沿用原文件。
+//!
+//! `decrypt-folder` 默认跳过输入目录中的非 `.svlt` 文件；加 `--passthrough-plaintext` 时
+//! 将它们原样复制到输出目录的对应位置（同样经过路径安全检查）。
+//!
+//! `--threads <n>` 让 `encrypt` 用 n 个线程并行加密各 chunk、`encrypt-folder` 用 n 个线程
+//! 并行加密各文件；默认单线程，输出与执行顺序均保持确定。n 必须至少为 1。
+//!
+//! 所有命令都接受 `--quiet` 或 `--verbose`（二者不能同时使用）：`--quiet` 成功时不输出任何内容，
+//! 出错时只打印错误本身（不附用法提示与警告）；`--verbose` 额外在 stderr 打印所用算法、
+//! chunk 大小，以及目录命令中逐个开始处理的文件（并行加密时不逐个打印）。
+//! 任何输出都不包含密码或密钥。
+//!
+//! 目录命令运行中按 Ctrl-C 不会立即终止：当前文件照常完成原子写入后停止，不留下临时文件，
+//! 打印已完成的文件数并以退出码 130 退出；再按一次 Ctrl-C 则立即退出。
+//!
+//! 密码为 `-` 或省略时从终端读取（不回显），加密命令会要求输入两次确认；
+//! stdin 不是终端时（管道、脚本）按行读取。位置参数形式的密码会留在 shell 历史与
+//! 进程列表中，仅为脚本兼容保留。
+//!
+//! CI 等无法交互的场景可设置环境变量 `SEALVAULT_PASSWORD`：密码参数为 `env:` 时必须从中读取
+//! （未设置则报错），省略密码参数时若已设置也优先使用它，否则才交互读取。
+//! 优先级：位置参数密码 > `env:` > 省略时的环境变量 > 交互输入。
 //!
 //! 设计原则：
 //! - 不依赖 clap / structopt
 //! - 参数解析保持“一眼能懂”
-//! - 所有实际逻辑都委托给 command 模块
-
-mod algorithm;
-mod crypto;
-mod error;
-mod format;
-mod fs;
+//! - 所有实际逻辑都委托给 engine 库接口
 
 use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufWriter, IsTerminal, Read, Write};
 use std::path::{Path, PathBuf};
 use std::process::exit;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-mod decrypt;
-mod encrypt;
-mod folder;
+use engine::crypto::password::passwords_equal;
+use engine::fs::atomic::write_atomic;
+use engine::{AeadAlgorithm, ParseAlgorithmError, ProgressEvent, SealVaultError};
+use zeroize::Zeroizing;
 
-use crate::algorithm::AeadAlgorithm;
+/// 表示“交互读取密码”的密码参数
+const PROMPT_PASSWORD_ARG: &str = "-";
 
-fn print_usage() {
+/// 表示“从环境变量读取密码”的密码参数
+const ENV_PASSWORD_ARG: &str = "env:";
+
+/// 提供密码的环境变量名
+const PASSWORD_ENV_VAR: &str = "SEALVAULT_PASSWORD";
+
+/// 表示 stdin / stdout 的 input / output 参数
+const STDIO_PATH_ARG: &str = "-";
+
+/// 被 Ctrl-C 中断时的退出码（128 + SIGINT）
+const EXIT_INTERRUPTED: i32 = 130;
+
+/// CLI 输出的详细程度（`--quiet` / `--verbose`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Verbosity {
+    /// 成功时不输出，出错时只打印错误
+    Quiet,
+    Normal,
+    /// 额外打印算法、chunk 大小与逐个文件的进度
+    Verbose,
+}
+
+fn print_usage(verbosity: Verbosity) {
+    if verbosity == Verbosity::Quiet {
+        return;
+    }
     eprintln!(
         "Usage:\n  \
-         sealvault encrypt|e <input> [output] <password> [algorithm]\n  \
-         sealvault decrypt|d <input> [output] <password>\n  \
-         sealvault encrypt-folder|ef <input_dir> [output_dir] <password> [algorithm]\n  \
-         sealvault decrypt-folder|df <input_dir> [output_dir] <password> [algorithm]"
+         sealvault encrypt|e <input> [output] [password|-] [algorithm] [--algorithm <name>] [--verify-after-write] [--threads <n>]\n  \
+         sealvault decrypt|d <input> [output] [password|-]\n  \
+         sealvault encrypt-folder|ef <input_dir> [output_dir] [password|-] [algorithm] [--algorithm <name>] [--verify-after-write] [--threads <n>]\n  \
+         sealvault decrypt-folder|df <input_dir> [output_dir] [password|-] [--verify-manifest] [--passthrough-plaintext]\n  \
+         sealvault reencrypt|re <input> [output] [password|-] [algorithm] [--algorithm <name>] [--chunk-size <bytes>] [--zstd <level>]\n  \
+         sealvault list-algorithms\n\n\
+         Use '-' (or omit the password) to be prompted instead of passing it as an argument.\n\
+         Use 'env:' to read the password from SEALVAULT_PASSWORD; an omitted password also uses it when set.\n\
+         Run 'sealvault list-algorithms' for the names accepted by --algorithm.\n\
+         Add --quiet to print nothing but errors, or --verbose for algorithm, chunk size and per-file progress."
     );
 }
 
+/// 为目录命令安装 Ctrl-C 处理，返回供 `FolderOptions::cancel` 使用的取消标志
+///
+/// 第一次 Ctrl-C 只置位标志，目录循环在当前文件写完后停止；第二次立即退出。
+/// 应在读取密码之后调用，交互输入密码时 Ctrl-C 仍可直接终止进程。
+fn install_cancel_handler(verbosity: Verbosity) -> Arc<AtomicBool> {
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&cancel);
+    let installed = ctrlc::set_handler(move || {
+        if flag.swap(true, Ordering::SeqCst) {
+            exit(EXIT_INTERRUPTED);
+        }
+        if verbosity > Verbosity::Quiet {
+            eprintln!("Interrupted: finishing the current file (press Ctrl-C again to abort now)");
+        }
+    });
+    if let Err(e) = installed
+        && verbosity > Verbosity::Quiet
+    {
+        eprintln!("Warning: cannot install Ctrl-C handler: {e}");
+    }
+    cancel
+}
+
+/// `--verbose` 时打印所用算法与 chunk 大小（目录加密按文件各自选择，不打印 chunk 大小）
+fn report_settings(verbosity: Verbosity, algorithm: AeadAlgorithm, chunk_size: Option<usize>) {
+    if verbosity < Verbosity::Verbose {
+        return;
+    }
+    eprintln!("Algorithm: {algorithm}");
+    if let Some(chunk_size) = chunk_size {
+        eprintln!("Chunk size: {chunk_size} bytes");
+    }
+}
+
+/// 目录命令的进度回调：`--verbose` 时逐个打印开始处理的文件（`action` 如 "Encrypting"）
+fn report_file_progress(verbosity: Verbosity, action: &str) -> impl FnMut(ProgressEvent) + '_ {
+    move |event| {
+        if verbosity == Verbosity::Verbose
+            && let ProgressEvent::FileStarted { path, index, total } = event
+        {
+            eprintln!("{action} [{}/{total}] {}", index + 1, path.display());
+        }
+    }
+}
+
+/// 从参数中取出开关型 flag（如 `--verify-after-write`），返回是否出现过
+fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let before = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != before
+}
+
+/// 从参数中取出带值的选项（如 `--chunk-size 1048576`），缺少值时打印用法并退出
+fn take_option(args: &mut Vec<String>, flag: &str, verbosity: Verbosity) -> Option<String> {
+    let index = args.iter().position(|arg| arg == flag)?;
+    if index + 1 >= args.len() {
+        eprintln!("Error: {flag} requires a value");
+        print_usage(verbosity);
+        exit(1);
+    }
+    let value = args.remove(index + 1);
+    args.remove(index);
+    Some(value)
+}
+
+/// 解析数值型选项，无法解析时打印用法并退出
+fn parse_number_or_exit<T: std::str::FromStr>(flag: &str, value: &str, verbosity: Verbosity) -> T {
+    value.parse().unwrap_or_else(|_| {
+        eprintln!("Error: invalid value for {flag}: {value}");
+        print_usage(verbosity);
+        exit(1);
+    })
+}
+
+/// 解析密码参数：`env:` 读取环境变量，省略时优先使用环境变量，`-` 或省略时交互读取，
+/// 否则使用位置参数并提示其不安全
+///
+/// `confirm` 为 true 时要求交互输入两次并校验一致（用于加密）；环境变量不需要确认。
+fn resolve_password(
+    arg: Option<&String>,
+    confirm: bool,
+    verbosity: Verbosity,
+) -> io::Result<Zeroizing<String>> {
+    if arg.map(String::as_str) == Some(ENV_PASSWORD_ARG) {
+        return password_from_env()?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("'{ENV_PASSWORD_ARG}' was given but {PASSWORD_ENV_VAR} is not set"),
+            )
+        });
+    }
+    if arg.is_none()
+        && let Some(password) = password_from_env()?
+    {
+        return Ok(password);
+    }
+
+    match arg.map(String::as_str) {
+        None | Some(PROMPT_PASSWORD_ARG) => {
+            let password = prompt_password("Password: ")?;
+            if password.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "password must not be empty",
+                ));
+            }
+            if confirm
+                && !passwords_equal(
+                    prompt_password("Confirm password: ")?.as_bytes(),
+                    password.as_bytes(),
+                )
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "passwords do not match",
+                ));
+            }
+            Ok(password)
+        }
+        Some(password) => {
+            if verbosity > Verbosity::Quiet {
+                eprintln!(
+                    "Warning: a password passed as an argument is visible in shell history and process listings; use '-' to be prompted instead"
+                );
+            }
+            Ok(Zeroizing::new(password.to_owned()))
+        }
+    }
+}
+
+/// 读取 `SEALVAULT_PASSWORD`；未设置时返回 `None`，为空或不是合法 UTF-8 时报错
+fn password_from_env() -> io::Result<Option<Zeroizing<String>>> {
+    let Some(value) = env::var_os(PASSWORD_ENV_VAR) else {
+        return Ok(None);
+    };
+    let password = Zeroizing::new(value.into_string().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{PASSWORD_ENV_VAR} is not valid UTF-8"),
+        )
+    })?);
+    if password.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{PASSWORD_ENV_VAR} must not be empty"),
+        ));
+    }
+    Ok(Some(password))
+}
+
+/// `resolve_password` 失败时打印错误并退出
+fn password_or_exit(
+    arg: Option<&String>,
+    confirm: bool,
+    verbosity: Verbosity,
+) -> Zeroizing<String> {
+    match resolve_password(arg, confirm, verbosity) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            exit(1);
+        }
+    }
+}
+
+/// 读取一次密码：终端上不回显，否则（管道、脚本）从 stdin 按行读取
+fn prompt_password(prompt: &str) -> io::Result<Zeroizing<String>> {
+    if io::stdin().is_terminal() {
+        return rpassword::prompt_password(prompt).map(Zeroizing::new);
+    }
+
+    eprint!("{prompt}");
+    let mut line = Zeroizing::new(String::new());
+    if io::stdin().lock().read_line(&mut line)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "no password provided on stdin",
+        ));
+    }
+    let len = line.trim_end_matches(['\r', '\n']).len();
+    line.truncate(len);
+    Ok(line)
+}
+
 fn default_encrypted_output(input: &Path) -> PathBuf {
+    if is_stdio(input) {
+        return PathBuf::from(STDIO_PATH_ARG);
+    }
     PathBuf::from(format!("{}.svlt", input.display()))
 }
 
 fn default_decrypted_output(input: &Path) -> Result<PathBuf, &'static str> {
+    if is_stdio(input) {
+        return Ok(PathBuf::from(STDIO_PATH_ARG));
+    }
     if input.extension().and_then(|v| v.to_str()) != Some("svlt") {
         return Err("decrypt input must end with .svlt when output is omitted");
     }
@@ -53,136 +313,430 @@ fn default_decrypted_output(input: &Path) -> Result<PathBuf, &'static str> {
     Ok(output)
 }
 
-fn parse_algorithm(arg: Option<&String>) -> Result<AeadAlgorithm, &'static str> {
-    match arg.map(String::as_str) {
-        None => Ok(AeadAlgorithm::XChaCha20Poly1305),
-        Some("xchacha20") | Some("xchacha20poly1305") => Ok(AeadAlgorithm::XChaCha20Poly1305),
-        Some("aes256gcm") | Some("aes-256-gcm") => Ok(AeadAlgorithm::Aes256Gcm),
-        Some(_) => Err("unsupported algorithm"),
+fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO_PATH_ARG
+}
+
+/// input 为 stdin 且 stdin 不是终端时，密码不能再从 stdin 读取
+fn reject_password_from_stdin_data(input: &Path, password_arg: Option<&String>) {
+    let would_prompt = match password_arg.map(String::as_str) {
+        Some(PROMPT_PASSWORD_ARG) => true,
+        None => env::var_os(PASSWORD_ENV_VAR).is_none(),
+        Some(_) => false,
+    };
+    if is_stdio(input) && would_prompt && !io::stdin().is_terminal() {
+        eprintln!(
+            "Error: stdin carries the input data; pass the password as an argument, '{ENV_PASSWORD_ARG}' or {PASSWORD_ENV_VAR}"
+        );
+        exit(1);
+    }
+}
+
+/// 打开输入：`-` 为 stdin，否则为文件
+fn open_input(input: &Path) -> io::Result<Box<dyn Read>> {
+    if is_stdio(input) {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    Ok(Box::new(File::open(input)?))
+}
+
+/// 写出输出：`-` 直接写入 stdout，否则原子写入文件
+fn write_output<F>(output: &Path, write_fn: F) -> Result<(), SealVaultError>
+where
+    F: FnOnce(&mut dyn Write) -> Result<(), SealVaultError>,
+{
+    if is_stdio(output) {
+        return write_fn(&mut BufWriter::new(io::stdout().lock()));
+    }
+    Ok(write_atomic(output, |file| {
+        write_fn(&mut BufWriter::new(file)).map_err(io::Error::other)
+    })?)
+}
+
+/// 经 stdin / stdout 流式加密
+fn encrypt_stdio(
+    input: &Path,
+    output: &Path,
+    password: &str,
+    algorithm: AeadAlgorithm,
+) -> Result<(), SealVaultError> {
+    let reader = open_input(input)?;
+    write_output(output, |writer| {
+        engine::encrypt_stream(reader, writer, password, algorithm)
+    })
+}
+
+/// 经 stdin / stdout 流式解密
+fn decrypt_stdio(input: &Path, output: &Path, password: &str) -> Result<(), SealVaultError> {
+    let reader = open_input(input)?;
+    write_output(output, |writer| {
+        engine::decrypt_stream(reader, writer, password)
+    })
+}
+
+fn parse_algorithm(arg: Option<&String>) -> Result<AeadAlgorithm, ParseAlgorithmError> {
+    arg.map_or(Ok(AeadAlgorithm::XChaCha20Poly1305), |name| name.parse())
+}
+
+/// 解析算法参数，无法识别时打印用法并退出
+///
+/// 错误信息不回显参数内容：参数顺序写错时，这个位置上可能是密码。
+fn algorithm_or_exit(arg: Option<&String>, verbosity: Verbosity) -> AeadAlgorithm {
+    parse_algorithm(arg).unwrap_or_else(|_| {
+        eprintln!(
+            "Error: unsupported algorithm (the value is not shown in case it is a password); run 'sealvault list-algorithms'"
+        );
+        print_usage(verbosity);
+        exit(1);
+    })
+}
+
+/// 合并 `--algorithm` 与位置参数形式的算法，二者同时给出时打印用法并退出
+fn algorithm_arg_or_exit<'a>(
+    flag: Option<&'a String>,
+    positional: Option<&'a String>,
+    verbosity: Verbosity,
+) -> Option<&'a String> {
+    if flag.is_some() && positional.is_some() {
+        eprintln!(
+            "Error: the algorithm was given both with --algorithm and as a positional argument"
+        );
+        print_usage(verbosity);
+        exit(1);
+    }
+    flag.or(positional)
+}
+
+/// `list-algorithms`：逐行打印算法名称与 Header 中的算法 ID
+fn list_algorithms() {
+    for algorithm in AeadAlgorithm::ALL {
+        let default = if algorithm == AeadAlgorithm::XChaCha20Poly1305 {
+            " (default)"
+        } else {
+            ""
+        };
+        println!("{algorithm:<20} id {}{default}", algorithm.to_u8());
     }
 }
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let verbosity = match (
+        take_flag(&mut args, "--quiet"),
+        take_flag(&mut args, "--verbose"),
+    ) {
+        (false, false) => Verbosity::Normal,
+        (true, false) => Verbosity::Quiet,
+        (false, true) => Verbosity::Verbose,
+        (true, true) => {
+            eprintln!("Error: --quiet and --verbose cannot be used together");
+            exit(1);
+        }
+    };
+    let verify_after_write = take_flag(&mut args, "--verify-after-write");
+    let verify_manifest = take_flag(&mut args, "--verify-manifest");
+    let passthrough_plaintext = take_flag(&mut args, "--passthrough-plaintext");
+    let chunk_size = take_option(&mut args, "--chunk-size", verbosity)
+        .map(|value| parse_number_or_exit::<usize>("--chunk-size", &value, verbosity));
+    let zstd_level = take_option(&mut args, "--zstd", verbosity)
+        .map(|value| parse_number_or_exit::<i32>("--zstd", &value, verbosity));
+    let threads = take_option(&mut args, "--threads", verbosity)
+        .map(|value| parse_number_or_exit::<usize>("--threads", &value, verbosity));
+    let algorithm_flag = take_option(&mut args, "--algorithm", verbosity);
 
     if args.len() < 2 {
-        print_usage();
+        print_usage(verbosity);
         exit(1);
     }
 
     let command = args[1].as_str();
 
+    if verify_after_write && !matches!(command, "encrypt" | "e" | "encrypt-folder" | "ef") {
+        eprintln!("Error: --verify-after-write only applies to encrypt commands");
+        print_usage(verbosity);
+        exit(1);
+    }
+
+    if verify_manifest && !matches!(command, "decrypt-folder" | "df") {
+        eprintln!("Error: --verify-manifest only applies to decrypt-folder");
+        print_usage(verbosity);
+        exit(1);
+    }
+
+    if passthrough_plaintext && !matches!(command, "decrypt-folder" | "df") {
+        eprintln!("Error: --passthrough-plaintext only applies to decrypt-folder");
+        print_usage(verbosity);
+        exit(1);
+    }
+
+    if (chunk_size.is_some() || zstd_level.is_some()) && !matches!(command, "reencrypt" | "re") {
+        eprintln!("Error: --chunk-size and --zstd only apply to reencrypt");
+        print_usage(verbosity);
+        exit(1);
+    }
+
+    if threads.is_some() && !matches!(command, "encrypt" | "e" | "encrypt-folder" | "ef") {
+        eprintln!("Error: --threads only applies to encrypt commands");
+        print_usage(verbosity);
+        exit(1);
+    }
+
+    if algorithm_flag.is_some()
+        && !matches!(
+            command,
+            "encrypt" | "e" | "encrypt-folder" | "ef" | "reencrypt" | "re"
+        )
+    {
+        eprintln!("Error: --algorithm only applies to encrypt commands and reencrypt");
+        print_usage(verbosity);
+        exit(1);
+    }
+
+    // 库接口中 0 表示“按 CPU 数量”，CLI 要求显式给出线程数
+    if threads == Some(0) {
+        eprintln!("Error: --threads must be at least 1");
+        print_usage(verbosity);
+        exit(1);
+    }
+
     let result = match command {
         "encrypt" | "e" => {
-            if args.len() != 4 && args.len() != 5 && args.len() != 6 {
-                print_usage();
+            if !(3..=6).contains(&args.len()) {
+                print_usage(verbosity);
                 exit(1);
             }
 
             let input = Path::new(&args[2]);
-            let (output, password, algorithm_arg) = if args.len() == 4 {
-                (default_encrypted_output(input), &args[3], None)
-            } else {
-                let algorithm_arg = if args.len() == 6 { args.get(5) } else { None };
-                (PathBuf::from(&args[3]), &args[4], algorithm_arg)
+            let (output, password_arg, algorithm_arg) = match args.len() {
+                3 => (default_encrypted_output(input), None, None),
+                4 => (default_encrypted_output(input), args.get(3), None),
+                _ => (PathBuf::from(&args[3]), args.get(4), args.get(5)),
             };
+            let algorithm_arg =
+                algorithm_arg_or_exit(algorithm_flag.as_ref(), algorithm_arg, verbosity);
 
-            let algorithm = match parse_algorithm(algorithm_arg) {
-                Ok(v) => v,
-                Err(e) => {
-                    eprintln!("Error: {e}");
-                    print_usage();
+            let algorithm = algorithm_or_exit(algorithm_arg, verbosity);
+            if is_stdio(input) || is_stdio(&output) {
+                if verify_after_write || threads.is_some() {
+                    eprintln!(
+                        "Error: --verify-after-write and --threads cannot be used with stdin / stdout"
+                    );
                     exit(1);
                 }
-            };
-            encrypt::encrypt_file_with_algorithm(input, &output, password, algorithm)
+                reject_password_from_stdin_data(input, password_arg);
+                let password = password_or_exit(password_arg, true, verbosity);
+                let chunk_size = engine::EncryptOptions::with_algorithm(algorithm).chunk_size;
+                report_settings(verbosity, algorithm, Some(chunk_size));
+                encrypt_stdio(input, &output, &password, algorithm)
+            } else {
+                let password = password_or_exit(password_arg, true, verbosity);
+                let options = match algorithm_arg {
+                    Some(_) => engine::EncryptOptions::with_algorithm(algorithm),
+                    // 未指定算法时按输入大小选择 chunk 大小（读不到长度时交给加密报告错误）
+                    None => {
+                        engine::EncryptOptions::auto_for(input.metadata().map_or(0, |m| m.len()))
+                    }
+                };
+                let options = engine::EncryptOptions {
+                    threads: threads.unwrap_or(options.threads),
+                    verify_after_write,
+                    ..options
+                };
+                report_settings(verbosity, options.algorithm, Some(options.chunk_size));
+                engine::encrypt_with_options(input, &output, &password, &options)
+            }
         }
         "decrypt" | "d" => {
-            if args.len() != 4 && args.len() != 5 {
-                print_usage();
+            if !(3..=5).contains(&args.len()) {
+                print_usage(verbosity);
                 exit(1);
             }
 
             let input = Path::new(&args[2]);
-            let (output, password) = if args.len() == 4 {
+            let (output, password_arg) = if args.len() <= 4 {
                 let output = match default_decrypted_output(input) {
                     Ok(v) => v,
                     Err(e) => {
                         eprintln!("Error: {e}");
-                        print_usage();
+                        print_usage(verbosity);
                         exit(1);
                     }
                 };
-                (output, &args[3])
+                (output, args.get(3))
             } else {
-                (PathBuf::from(&args[3]), &args[4])
+                (PathBuf::from(&args[3]), args.get(4))
             };
-            decrypt::decrypt_file(input, &output, password)
+            reject_password_from_stdin_data(input, password_arg);
+            let password = password_or_exit(password_arg, false, verbosity);
+            // 算法与 chunk 大小取自 Header；stdin 不预读，读取失败时交给解密报告错误
+            if !is_stdio(input)
+                && let Ok(info) = engine::inspect(input)
+            {
+                report_settings(verbosity, info.algorithm, Some(info.chunk_size as usize));
+            }
+            if is_stdio(input) || is_stdio(&output) {
+                decrypt_stdio(input, &output, &password)
+            } else {
+                engine::decrypt(input, &output, &password)
+            }
         }
         "encrypt-folder" | "ef" => {
-            if args.len() != 4 && args.len() != 5 && args.len() != 6 {
-                print_usage();
+            if !(3..=6).contains(&args.len()) {
+                print_usage(verbosity);
                 exit(1);
             }
 
             let input = Path::new(&args[2]);
-            let (output, password, algorithm_arg) = if args.len() == 4 {
-                (default_encrypted_output(input), &args[3], None)
-            } else {
-                let algorithm_arg = if args.len() == 6 { args.get(5) } else { None };
-                (PathBuf::from(&args[3]), &args[4], algorithm_arg)
+            let (output, password_arg, algorithm_arg) = match args.len() {
+                3 => (default_encrypted_output(input), None, None),
+                4 => (default_encrypted_output(input), args.get(3), None),
+                _ => (PathBuf::from(&args[3]), args.get(4), args.get(5)),
             };
+            let algorithm_arg =
+                algorithm_arg_or_exit(algorithm_flag.as_ref(), algorithm_arg, verbosity);
 
-            let algorithm = match parse_algorithm(algorithm_arg) {
-                Ok(v) => v,
-                Err(e) => {
-                    eprintln!("Error: {e}");
-                    print_usage();
-                    exit(1);
-                }
+            let algorithm = algorithm_or_exit(algorithm_arg, verbosity);
+            let password = password_or_exit(password_arg, true, verbosity);
+            let options = engine::FolderOptions {
+                verify_after_write,
+                cancel: Some(install_cancel_handler(verbosity)),
+                ..Default::default()
             };
-            folder::encrypt_folder(input, &output, password, algorithm)
+            report_settings(verbosity, algorithm, None);
+            match threads {
+                Some(threads) => engine::encrypt_folder_parallel_with_options(
+                    input, &output, &password, algorithm, &options, threads,
+                ),
+                None => engine::encrypt_folder_with_progress(
+                    input,
+                    &output,
+                    &password,
+                    algorithm,
+                    &options,
+                    &mut report_file_progress(verbosity, "Encrypting"),
+                ),
+            }
+            .map(|result| {
+                if verbosity > Verbosity::Quiet {
+                    eprintln!("Encrypted {} file(s)", result.processed);
+                }
+            })
         }
         "decrypt-folder" | "df" => {
-            if args.len() != 4 && args.len() != 5 && args.len() != 6 {
-                print_usage();
+            if !(3..=5).contains(&args.len()) {
+                print_usage(verbosity);
                 exit(1);
             }
 
             let input = Path::new(&args[2]);
-            let (output, password, algorithm_arg) = if args.len() == 4 {
+            let (output, password_arg) = if args.len() <= 4 {
                 let output = match default_decrypted_output(input) {
                     Ok(v) => v,
                     Err(e) => {
                         eprintln!("Error: {e}");
-                        print_usage();
+                        print_usage(verbosity);
                         exit(1);
                     }
                 };
-                (output, &args[3], None)
+                (output, args.get(3))
             } else {
-                let algorithm_arg = if args.len() == 6 { args.get(5) } else { None };
-                (PathBuf::from(&args[3]), &args[4], algorithm_arg)
+                (PathBuf::from(&args[3]), args.get(4))
             };
-
-            let algorithm = match parse_algorithm(algorithm_arg) {
-                Ok(v) => v,
-                Err(e) => {
-                    eprintln!("Error: {e}");
-                    print_usage();
-                    exit(1);
+            let password = password_or_exit(password_arg, false, verbosity);
+            let options = engine::FolderOptions {
+                verify_manifest,
+                non_encrypted_files: if passthrough_plaintext {
+                    engine::NonEncryptedFilePolicy::Copy
+                } else {
+                    engine::NonEncryptedFilePolicy::Skip
+                },
+                cancel: Some(install_cancel_handler(verbosity)),
+                ..Default::default()
+            };
+            engine::decrypt_folder_with_progress(
+                input,
+                &output,
+                &password,
+                &options,
+                &mut report_file_progress(verbosity, "Decrypting"),
+            )
+            .map(|result| {
+                if verbosity == Verbosity::Quiet {
+                    return;
                 }
+                eprintln!("Decrypted {} file(s)", result.processed);
+                if !result.skipped.is_empty() {
+                    eprintln!(
+                        "Skipped {} non-.svlt file(s) (use --passthrough-plaintext to copy them)",
+                        result.skipped.len()
+                    );
+                }
+                if result.processed == 0 {
+                    eprintln!("Warning: no .svlt files found in {}", input.display());
+                }
+            })
+        }
+        "reencrypt" | "re" => {
+            if !(3..=6).contains(&args.len()) {
+                print_usage(verbosity);
+                exit(1);
+            }
+
+            let input = Path::new(&args[2]);
+            let (output, password_arg, algorithm_arg) = match args.len() {
+                3 => (input.to_path_buf(), None, None),
+                4 => (input.to_path_buf(), args.get(3), None),
+                _ => (PathBuf::from(&args[3]), args.get(4), args.get(5)),
             };
-            folder::decrypt_folder(input, &output, password, algorithm)
+            let algorithm_arg =
+                algorithm_arg_or_exit(algorithm_flag.as_ref(), algorithm_arg, verbosity);
+
+            let algorithm = algorithm_or_exit(algorithm_arg, verbosity);
+            let defaults = engine::EncryptOptions::with_algorithm(algorithm);
+            let options = engine::EncryptOptions {
+                chunk_size: chunk_size.unwrap_or(defaults.chunk_size),
+                compression: zstd_level.map(engine::Compression::Zstd),
+                ..defaults
+            };
+            let password = password_or_exit(password_arg, false, verbosity);
+            report_settings(verbosity, options.algorithm, Some(options.chunk_size));
+            engine::reencrypt(input, &output, &password, &options)
+        }
+        "list-algorithms" => {
+            if args.len() != 2 {
+                print_usage(verbosity);
+                exit(1);
+            }
+            list_algorithms();
+            Ok(())
         }
         _ => {
-            print_usage();
+            print_usage(verbosity);
             exit(1);
         }
     };
 
-    if let Err(e) = result {
-        eprintln!("Error: {e}");
-        exit(1);
+    match result {
+        Ok(()) => {}
+        Err(SealVaultError::Cancelled { completed }) => {
+            eprintln!("Cancelled: {completed} file(s) completed before the interruption");
+            exit(EXIT_INTERRUPTED);
+        }
+        Err(e) => {
+            eprintln!("Error: {}", error_chain(&e));
+            exit(1);
+        }
+    }
+}
+
+/// 错误及其 `source()` 链，以 `: ` 连接（如 `I/O error: No such file or directory`）
+fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
     }
+    message
 }
@@ -0,0 +1,167 @@
+//! SealVault 长度隐藏填充
+//!
+//! 小文件的密文长度几乎等于明文长度加固定开销，单字节的 "y" / "n" 一眼即可区分。
+//! 启用 `Padding::ToBlock(n)` 后，送入 StreamEncryptor 的数据（压缩之后）在末尾补零，
+//! 使总长度为 `n` 的整数倍（至少一个块）。填充字节与数据一样按 chunk 加密认证，
+//! 真实的填充长度写在经认证的 final chunk 中；Header 只记录块大小 `n`，不泄露真实长度。
+//!
+//! 解密时最多暂存 `n` 字节，读到 final chunk 后按其中的填充长度精确去除。
+
+#[cfg(feature = "fs")]
+use std::cell::Cell;
+#[cfg(feature = "fs")]
+use std::io::Read;
+use std::io::{self, Write};
+
+use zeroize::Zeroizing;
+
+use crate::error::SealVaultError;
+
+/// 允许的最大填充块大小（64 MiB），也是解密时暂存数据的上限
+pub const MAX_PADDING_BLOCK: u32 = 64 * 1024 * 1024;
+
+/// 填充方式（`EncryptOptions::padding`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Padding {
+    /// 补齐到 `n` 字节的整数倍（空输入补齐为一个块），`n` 取 1 ~ `MAX_PADDING_BLOCK`
+    ToBlock(u32),
+}
+
+impl Padding {
+    /// 填充块大小（字节）
+    pub fn block_size(self) -> u32 {
+        match self {
+            Self::ToBlock(block) => block,
+        }
+    }
+
+    /// 块大小是否在允许范围内
+    pub fn is_supported(self) -> bool {
+        (1..=MAX_PADDING_BLOCK).contains(&self.block_size())
+    }
+
+    /// 校验填充参数
+    pub(crate) fn validate(self) -> io::Result<()> {
+        if !self.is_supported() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "padding block size must be between 1 byte and 64 MiB",
+            ));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "fs")]
+    /// 包装待加密的 reader，在其 EOF 之后补零；补齐的字节数写入 `padding_len`
+    pub(crate) fn pad<R: Read>(self, reader: R, padding_len: &Cell<u64>) -> PaddingReader<'_, R> {
+        PaddingReader {
+            inner: reader,
+            block: u64::from(self.block_size()),
+            len: 0,
+            remaining: None,
+            padding_len,
+        }
+    }
+}
+
+#[cfg(feature = "fs")]
+/// 在内层 reader 结束后补零到块大小整数倍的 Reader 包装
+pub(crate) struct PaddingReader<'a, R> {
+    inner: R,
+    block: u64,
+    /// 已读出的内层数据长度
+    len: u64,
+    /// 内层结束后尚未输出的填充字节数
+    remaining: Option<u64>,
+    padding_len: &'a Cell<u64>,
+}
+
+#[cfg(feature = "fs")]
+impl<R: Read> Read for PaddingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.remaining.is_none() {
+            let read_len = self.inner.read(buf)?;
+            if read_len > 0 || buf.is_empty() {
+                self.len += read_len as u64;
+                return Ok(read_len);
+            }
+            let padded = self.len.div_ceil(self.block).max(1) * self.block;
+            self.padding_len.set(padded - self.len);
+            self.remaining = Some(padded - self.len);
+        }
+
+        let remaining = self.remaining.as_mut().expect("padding computed");
+        let n = buf
+            .len()
+            .min(usize::try_from(*remaining).unwrap_or(usize::MAX));
+        buf[..n].fill(0);
+        *remaining -= n as u64;
+        Ok(n)
+    }
+}
+
+/// 去除 Stream 末尾填充的 writer 包装
+///
+/// 始终暂存最后至多一个填充块的数据，读到 final chunk 后调用 `finish` 按填充长度截断。
+/// 未启用填充时直接转发。
+pub(crate) struct Unpadder<W: Write> {
+    inner: W,
+    /// 可能的最大填充长度，即需要暂存的字节数；0 表示未启用填充
+    hold: usize,
+    held: Zeroizing<Vec<u8>>,
+}
+
+impl<W: Write> Unpadder<W> {
+    pub fn new(inner: W, padding: Option<Padding>) -> Self {
+        Self {
+            inner,
+            hold: padding.map_or(0, |padding| padding.block_size() as usize),
+            held: Zeroizing::new(Vec::new()),
+        }
+    }
+
+    /// 内层 writer 的可变引用（只含已确定不是填充的数据）
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// 按 final chunk 中的填充长度去除填充，写出剩余数据并返回内层 writer
+    ///
+    /// 填充长度与 Header 不符（缺失、多余或超过暂存的数据）时返回携带
+    /// `SealVaultError::CorruptedData` 的错误。
+    pub fn finish(mut self, padding_len: Option<u64>) -> io::Result<W> {
+        let padding_len = match (self.hold, padding_len) {
+            (0, None) => 0,
+            (hold, Some(len)) if hold > 0 && len <= self.held.len() as u64 => len as usize,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    SealVaultError::CorruptedData,
+                ));
+            }
+        };
+        let data_len = self.held.len() - padding_len;
+        self.inner.write_all(&self.held[..data_len])?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for Unpadder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.hold == 0 {
+            return self.inner.write(buf);
+        }
+
+        self.held.extend_from_slice(buf);
+        if self.held.len() > self.hold {
+            let ready = self.held.len() - self.hold;
+            self.inner.write_all(&self.held[..ready])?;
+            self.held.drain(..ready);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,44 @@
+//! SealVault 进度通知
+//!
+//! 长时间运行的加密操作通过回调报告进度，供 GUI / CLI 显示进度条。
+//! 回调在执行操作的线程上同步调用，不要求 `Send`。
+//! 操作完成后的汇总数字见 `Stats`。
+
+use std::path::PathBuf;
+
+/// 进度事件
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// 开始处理目录中的一个文件
+    FileStarted {
+        /// 源文件路径
+        path: PathBuf,
+        /// 文件序号（从 0 开始）
+        index: usize,
+        /// 本次操作的文件总数
+        total: usize,
+    },
+    /// 当前文件又处理完一个 chunk
+    BytesProcessed {
+        /// 当前文件已处理的明文字节数
+        done: u64,
+        /// 当前文件的明文总字节数（调用方未知时为 0）
+        total: u64,
+    },
+    /// 当前文件处理完成（输出已就位）
+    FileFinished {
+        /// 源文件路径
+        path: PathBuf,
+    },
+}
+
+/// 单个文件加密或解密完成后的统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// 明文字节数（压缩之前、不含填充）
+    pub plaintext_bytes: u64,
+    /// .svlt 数据的字节数（含 Header）
+    pub ciphertext_bytes: u64,
+    /// 数据 chunk 数（不含文件名帧与 final chunk）
+    pub chunks: u64,
+}
@@ -0,0 +1,59 @@
+//! SealVault 流式解密 reader
+//!
+//! `SealVaultReader` 把 .svlt 数据当作普通的明文 `Read` 使用，可直接交给
+//! `serde_json::from_reader`、CSV 解析器等，无需先解密到临时文件。
+
+use std::io::{self, Read};
+
+use crate::decrypt::DecryptedReader;
+use crate::decrypt::open_for_decrypt;
+use crate::error::SealVaultError;
+use crate::format::header::Header;
+
+/// 实现 `std::io::Read` 的流式解密器
+///
+/// - 构造时读取 Header 并派生密钥（Argon2id 默认参数下约耗时数百毫秒）；
+/// - 每次只解密一个 chunk，认证通过后才交出其中的明文，已读出的明文缓冲区随即清零；
+/// - 压缩、填充与明文摘要按 Header 自动处理；
+/// - 认证失败、截断等错误在读到对应 chunk 时以 `InvalidData` 等 `io::Error` 返回，
+///   错误负载为 `SealVaultError`；此前已读出的明文均来自通过认证的 chunk，
+///   但整体是否完整只有读到 EOF 才能确定。出错后的读取始终返回错误。
+///
+/// 需要 keyfile 的文件返回 `KeyfileRequired`。
+pub struct SealVaultReader<R: Read> {
+    header: Header,
+    inner: DecryptedReader<R>,
+    failed: bool,
+}
+
+impl<R: Read> SealVaultReader<R> {
+    /// 读取 Header 并准备解密；返回后 `reader` 由本结构持有，按需读取 Stream
+    pub fn new(mut reader: R, password: &str) -> Result<Self, SealVaultError> {
+        let (header, decryptor) = open_for_decrypt(&mut reader, password.as_bytes())?;
+        let inner = DecryptedReader::new(reader, &header, decryptor)?;
+
+        Ok(Self {
+            header,
+            inner,
+            failed: false,
+        })
+    }
+
+    /// 已解析的 Header
+    pub fn header(&self) -> &Header {
+        &self.header
+    }
+}
+
+impl<R: Read> Read for SealVaultReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.failed {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                SealVaultError::CorruptedData,
+            ));
+        }
+
+        self.inner.read(buf).inspect_err(|_| self.failed = true)
+    }
+}
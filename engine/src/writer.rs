@@ -0,0 +1,125 @@
+//! SealVault 增量加密 writer
+//!
+//! `SealVaultWriter` 包装任意输出 `Write`，把逐次写入的明文按 chunk 加密为完整的 .svlt 数据，
+//! 适合明文分批产生（如日志行）、不便一次性交给 `encrypt_stream` 的场景。
+//! 输出与 `encrypt_stream` 使用相同选项时格式完全一致。
+
+use std::io::{self, Write};
+
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::algorithm::AeadAlgorithm;
+use crate::encrypt::{EncryptOptions, prepare_encryptor};
+use crate::error::SealVaultError;
+use crate::format::header::Header;
+use crate::format::stream::StreamEncryptor;
+
+/// 实现 `std::io::Write` 的增量加密器
+///
+/// - 构造时派生密钥（Argon2id 默认参数下约耗时数百毫秒），Header 在第一次写入、
+///   `flush` 或 `finish` 时写出；
+/// - 写入的明文在内存中攒满 `chunk_size` 字节后才加密写出一个 chunk，
+///   `flush` 只刷新已写出的密文，不会提前切出不满的 chunk；
+/// - 必须调用 `finish` 写出最后一个（可能不满的）chunk 与结束标记，否则输出会被视为截断，
+///   无法解密。drop 不会代为 `finish`（`Drop` 无法报告写出错误），也不会给出任何提示，
+///   调用方需自行保证每条成功路径都调用了 `finish`。
+///
+/// 内部 writer 报错后输出已不完整，应丢弃整个输出。
+pub struct SealVaultWriter<W: Write> {
+    /// `finish` 后为 `None`
+    inner: Option<W>,
+    /// 尚未写出的 Header，写出后为 `None`
+    header: Option<Header>,
+    encryptor: StreamEncryptor,
+    buffer: Zeroizing<Vec<u8>>,
+}
+
+impl<W: Write> SealVaultWriter<W> {
+    /// 使用指定算法与默认选项创建 writer
+    pub fn new(inner: W, password: &str, algorithm: AeadAlgorithm) -> Result<Self, SealVaultError> {
+        Self::with_options(inner, password, &EncryptOptions::with_algorithm(algorithm))
+    }
+
+    /// 按 `EncryptOptions` 创建 writer（算法、chunk 大小、KDF 与 keyfile）
+    ///
+    /// 压缩、填充与明文摘要需要在整个输入上处理，文件名需要输入文件，写后校验与校验和 sidecar
+    /// 需要输出文件，本 writer 均不支持，返回 `InvalidInput`；
+    /// `threads`、`io_buffer_size` 与 `overwrite` 不适用，被忽略。
+    pub fn with_options(
+        inner: W,
+        password: &str,
+        options: &EncryptOptions,
+    ) -> Result<Self, SealVaultError> {
+        if options.compression.is_some()
+            || options.padding.is_some()
+            || options.plaintext_digest
+            || options.store_file_name
+            || options.verify_after_write
+            || options.checksum_sidecar
+        {
+            return Err(SealVaultError::Io(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "SealVaultWriter does not support compression, padding, plaintext digest, \
+                 stored file names, verify after write or checksum sidecars",
+            )));
+        }
+
+        let (header, encryptor) = prepare_encryptor(password.as_bytes(), options)?;
+        let buffer = Zeroizing::new(Vec::with_capacity(encryptor.chunk_size()));
+
+        Ok(Self {
+            inner: Some(inner),
+            header: Some(header),
+            encryptor,
+            buffer,
+        })
+    }
+
+    /// 写出剩余明文与结束标记，flush 并返回内部 writer
+    pub fn finish(mut self) -> Result<W, SealVaultError> {
+        self.write_pending_header()?;
+        let mut inner = self.inner.take().ok_or(SealVaultError::Internal)?;
+
+        if !self.buffer.is_empty() {
+            self.encryptor.write_chunk(&self.buffer, &mut inner)?;
+            self.buffer.zeroize();
+        }
+        self.encryptor.finish(&mut inner)?;
+        inner.flush()?;
+        Ok(inner)
+    }
+
+    fn write_pending_header(&mut self) -> io::Result<()> {
+        if let Some(header) = self.header.take() {
+            header.write(self.inner.as_mut().ok_or_else(finished)?)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for SealVaultWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_pending_header()?;
+
+        let chunk_size = self.encryptor.chunk_size();
+        let take = buf.len().min(chunk_size - self.buffer.len());
+        self.buffer.extend_from_slice(&buf[..take]);
+
+        if self.buffer.len() == chunk_size {
+            let inner = self.inner.as_mut().ok_or_else(finished)?;
+            self.encryptor.write_chunk(&self.buffer, inner)?;
+            self.buffer.zeroize();
+        }
+        Ok(take)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_pending_header()?;
+        self.inner.as_mut().ok_or_else(finished)?.flush()
+    }
+}
+
+/// `finish` 之后继续使用 writer 的错误（正常情况下所有权已被 `finish` 取走，不会发生）
+fn finished() -> io::Error {
+    io::Error::other("SealVaultWriter already finished")
+}
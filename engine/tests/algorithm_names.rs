@@ -0,0 +1,79 @@
+//! 算法名称与 ID 测试：`FromStr` 接受 CLI 的全部别名，`Display` 与 Header ID 均可往返
+
+use engine::{AeadAlgorithm, ParseAlgorithmError};
+
+#[test]
+fn every_alias_parses_to_its_algorithm() {
+    let cases = [
+        ("xchacha20", AeadAlgorithm::XChaCha20Poly1305),
+        ("xchacha20poly1305", AeadAlgorithm::XChaCha20Poly1305),
+        ("xchacha20-poly1305", AeadAlgorithm::XChaCha20Poly1305),
+        ("aes256gcm", AeadAlgorithm::Aes256Gcm),
+        ("aes-256-gcm", AeadAlgorithm::Aes256Gcm),
+        ("chacha20", AeadAlgorithm::ChaCha20Poly1305),
+        ("chacha20poly1305", AeadAlgorithm::ChaCha20Poly1305),
+        ("chacha20-poly1305", AeadAlgorithm::ChaCha20Poly1305),
+        ("aes128gcm", AeadAlgorithm::Aes128Gcm),
+        ("aes-128-gcm", AeadAlgorithm::Aes128Gcm),
+        ("AES-256-GCM", AeadAlgorithm::Aes256Gcm),
+    ];
+
+    for (name, expected) in cases {
+        assert_eq!(name.parse::<AeadAlgorithm>(), Ok(expected), "{name}");
+    }
+}
+
+#[test]
+fn display_roundtrips_through_from_str() {
+    for algorithm in [
+        AeadAlgorithm::XChaCha20Poly1305,
+        AeadAlgorithm::Aes256Gcm,
+        AeadAlgorithm::ChaCha20Poly1305,
+        AeadAlgorithm::Aes128Gcm,
+    ] {
+        let name = algorithm.to_string();
+        assert_eq!(name, algorithm.name());
+        assert_eq!(name.parse::<AeadAlgorithm>(), Ok(algorithm));
+    }
+    assert_eq!(AeadAlgorithm::Aes256Gcm.to_string(), "AES-256-GCM");
+}
+
+#[test]
+fn unknown_algorithm_is_rejected() {
+    let err = "aes192gcm"
+        .parse::<AeadAlgorithm>()
+        .expect_err("unknown name");
+    assert_eq!(err, ParseAlgorithmError("aes192gcm".to_owned()));
+    assert_eq!(err.to_string(), "unsupported algorithm: aes192gcm");
+}
+
+/// 全部变体；`AeadAlgorithm` 为 `#[non_exhaustive]`，crate 外无法穷尽匹配，
+/// 由 `algorithm_ids_roundtrip_and_are_distinct` 确认 `ALL` 与 `from_u8` 认识的 ID 一致
+fn all_algorithms() -> Vec<AeadAlgorithm> {
+    AeadAlgorithm::ALL.to_vec()
+}
+
+#[test]
+fn header_uses_the_canonical_algorithm_enum() {
+    // 同一类型：Header 中的路径只是 `algorithm` 模块的再导出
+    let from_header: engine::format::header::AeadAlgorithm = AeadAlgorithm::Aes256Gcm;
+    assert_eq!(from_header, engine::algorithm::AeadAlgorithm::Aes256Gcm);
+}
+
+#[test]
+fn algorithm_ids_roundtrip_and_are_distinct() {
+    let algorithms = all_algorithms();
+    let mut ids: Vec<u8> = algorithms.iter().map(|a| a.to_u8()).collect();
+
+    for algorithm in &algorithms {
+        assert_eq!(AeadAlgorithm::from_u8(algorithm.to_u8()), Some(*algorithm));
+    }
+
+    ids.sort_unstable();
+    ids.dedup();
+    assert_eq!(ids.len(), algorithms.len(), "algorithm IDs must be unique");
+    assert_eq!(AeadAlgorithm::from_u8(0), None);
+
+    let known: Vec<AeadAlgorithm> = (0..=u8::MAX).filter_map(AeadAlgorithm::from_u8).collect();
+    assert_eq!(known, algorithms, "ALL must list every known ID in order");
+}
@@ -0,0 +1,175 @@
+//! 目录打包为单个加密归档的测试
+#![cfg(feature = "fs")]
+
+use std::fs;
+
+use tempfile::tempdir;
+
+#[test]
+fn archive_roundtrip_nested_tree_with_empty_dir() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let archive_path = temp.path().join("backup.svlt");
+    let decrypted_dir = temp.path().join("decrypted");
+
+    fs::create_dir_all(input_dir.join("a/b/c")).expect("create nested dirs");
+    fs::create_dir_all(input_dir.join("empty")).expect("create empty dir");
+    fs::create_dir_all(input_dir.join("a/also-empty")).expect("create nested empty dir");
+    fs::write(input_dir.join("root.txt"), b"hello root").expect("write root file");
+    fs::write(input_dir.join("a/b/c/deep.log"), b"hello deep").expect("write deep file");
+    fs::write(input_dir.join("a/zero.bin"), b"").expect("write empty file");
+    // 跨越多个 chunk 的文件
+    let big: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+    fs::write(input_dir.join("a/b/big.bin"), &big).expect("write big file");
+
+    engine::encrypt_folder_to_archive(
+        &input_dir,
+        &archive_path,
+        "archive-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt folder to archive");
+
+    // 输出只有一个文件
+    assert!(archive_path.is_file());
+    assert_eq!(fs::read_dir(temp.path()).expect("list temp dir").count(), 2);
+
+    engine::decrypt_archive_to_folder(&archive_path, &decrypted_dir, "archive-password")
+        .expect("decrypt archive");
+
+    assert_eq!(
+        fs::read(decrypted_dir.join("root.txt")).expect("read root"),
+        b"hello root"
+    );
+    assert_eq!(
+        fs::read(decrypted_dir.join("a/b/c/deep.log")).expect("read deep"),
+        b"hello deep"
+    );
+    assert_eq!(
+        fs::read(decrypted_dir.join("a/zero.bin")).expect("read empty file"),
+        b""
+    );
+    assert_eq!(
+        fs::read(decrypted_dir.join("a/b/big.bin")).expect("read big"),
+        big
+    );
+    for empty in ["empty", "a/also-empty"] {
+        let dir = decrypted_dir.join(empty);
+        assert!(dir.is_dir(), "{empty} should be restored");
+        assert_eq!(fs::read_dir(dir).expect("list empty dir").count(), 0);
+    }
+}
+
+#[test]
+fn decrypt_archive_with_wrong_password_fails() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let archive_path = temp.path().join("backup.svlt");
+    let decrypted_dir = temp.path().join("decrypted");
+
+    fs::create_dir_all(&input_dir).expect("create input dir");
+    fs::write(input_dir.join("secret.txt"), b"secret").expect("write file");
+
+    engine::encrypt_folder_to_archive(
+        &input_dir,
+        &archive_path,
+        "right-password",
+        engine::AeadAlgorithm::Aes256Gcm,
+    )
+    .expect("encrypt folder to archive");
+
+    let err = engine::decrypt_archive_to_folder(&archive_path, &decrypted_dir, "wrong-password")
+        .expect_err("wrong password must fail");
+    assert!(
+        matches!(err, engine::SealVaultError::InvalidPasswordOrFile),
+        "unexpected error: {err:?}"
+    );
+    assert!(!decrypted_dir.join("secret.txt").exists());
+}
+
+#[test]
+fn decrypt_archive_rejects_truncated_archive() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let archive_path = temp.path().join("backup.svlt");
+    let truncated_path = temp.path().join("truncated.svlt");
+
+    fs::create_dir_all(&input_dir).expect("create input dir");
+    fs::write(input_dir.join("data.bin"), vec![7u8; 100_000]).expect("write file");
+
+    engine::encrypt_folder_to_archive(
+        &input_dir,
+        &archive_path,
+        "pw",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt folder to archive");
+
+    // 去掉最后的 final chunk（4 字节长度 + 16 字节 tag）
+    let archive = fs::read(&archive_path).expect("read archive");
+    fs::write(&truncated_path, &archive[..archive.len() - 20]).expect("write truncated");
+
+    let err = engine::decrypt_archive_to_folder(&truncated_path, &temp.path().join("out"), "pw")
+        .expect_err("truncated archive must fail");
+    assert!(
+        matches!(err, engine::SealVaultError::CorruptedData),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn encrypt_folder_to_archive_rejects_output_inside_input() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    fs::create_dir_all(&input_dir).expect("create input dir");
+    fs::write(input_dir.join("a.txt"), b"a").expect("write file");
+
+    let err = engine::encrypt_folder_to_archive(
+        &input_dir,
+        &input_dir.join("backup.svlt"),
+        "pw",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect_err("output inside input must fail");
+    assert!(
+        matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+        "unexpected error: {err:?}"
+    );
+    assert!(!input_dir.join("backup.svlt").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn archive_roundtrip_supports_non_utf8_file_names() {
+    use std::ffi::OsString;
+    use std::os::unix::ffi::OsStringExt;
+
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let archive_path = temp.path().join("backup.svlt");
+    let decrypted_dir = temp.path().join("decrypted");
+
+    let dir_name = OsString::from_vec(vec![0x64, 0xff, 0x69, 0x72]);
+    let file_name = OsString::from_vec(vec![0x66, 0x6f, 0x80, 0x2e, 0x74, 0x78, 0x74]);
+    fs::create_dir_all(input_dir.join(&dir_name)).expect("create non-utf8 dir");
+    fs::write(
+        input_dir.join(&dir_name).join(&file_name),
+        b"non-utf8 payload",
+    )
+    .expect("write non-utf8 file");
+
+    engine::encrypt_folder_to_archive(
+        &input_dir,
+        &archive_path,
+        "pw",
+        engine::AeadAlgorithm::ChaCha20Poly1305,
+    )
+    .expect("encrypt folder to archive");
+    engine::decrypt_archive_to_folder(&archive_path, &decrypted_dir, "pw")
+        .expect("decrypt archive");
+
+    assert_eq!(
+        fs::read(decrypted_dir.join(&dir_name).join(&file_name)).expect("read non-utf8 file"),
+        b"non-utf8 payload"
+    );
+}
@@ -0,0 +1,85 @@
+//! SealVault 异步（tokio）API 测试
+#![cfg(feature = "tokio")]
+
+use std::fs;
+
+use engine::{AeadAlgorithm, Compression, EncryptOptions, Padding};
+use tempfile::tempdir;
+
+/// 测试通过 `tokio::io::duplex` 异步加密并解密，跨越多个 chunk
+#[tokio::test]
+async fn async_roundtrip_through_duplex() {
+    let plaintext: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+
+    let (client, mut server) = tokio::io::duplex(4096);
+    let input = plaintext.clone();
+    // 加密在单独的任务中运行（同时验证返回的 future 为 Send）
+    let encrypt = tokio::spawn(async move {
+        engine::encrypt_async(
+            input.as_slice(),
+            client,
+            "async-password",
+            AeadAlgorithm::XChaCha20Poly1305,
+        )
+        .await
+    });
+
+    let mut ciphertext = Vec::new();
+    tokio::io::copy(&mut server, &mut ciphertext)
+        .await
+        .expect("read ciphertext");
+    encrypt.await.expect("join").expect("encrypt async");
+
+    let mut decrypted = Vec::new();
+    engine::decrypt_async(ciphertext.as_slice(), &mut decrypted, "async-password")
+        .await
+        .expect("decrypt async");
+    assert_eq!(decrypted, plaintext);
+
+    // 与同步接口格式一致
+    assert_eq!(
+        engine::decrypt_bytes(&ciphertext, "async-password").expect("sync decrypt"),
+        plaintext
+    );
+
+    // 密码错误
+    let err = engine::decrypt_async(ciphertext.as_slice(), Vec::new(), "wrong-password")
+        .await
+        .expect_err("wrong password");
+    assert!(matches!(err, engine::SealVaultError::InvalidPasswordOrFile));
+}
+
+/// 测试异步解密同步接口写出的压缩、填充并携带明文摘要与文件名的文件
+#[tokio::test]
+async fn async_decrypt_reads_sync_files_with_options() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    let plaintext = b"sync written, async read ".repeat(4096);
+    fs::write(&input, &plaintext).expect("write input");
+
+    let options = EncryptOptions {
+        chunk_size: 1024,
+        compression: Some(Compression::Zstd(3)),
+        plaintext_digest: true,
+        padding: Some(Padding::ToBlock(4096)),
+        store_file_name: true,
+        ..EncryptOptions::with_algorithm(AeadAlgorithm::Aes256Gcm)
+    };
+    engine::encrypt_with_options(&input, &encrypted, "sync-password", &options)
+        .expect("encrypt sync");
+
+    let ciphertext = fs::read(&encrypted).expect("read encrypted");
+    let (mut client, server) = tokio::io::duplex(512);
+    let feed =
+        tokio::spawn(
+            async move { tokio::io::AsyncWriteExt::write_all(&mut client, &ciphertext).await },
+        );
+
+    let mut decrypted = Vec::new();
+    engine::decrypt_async(server, &mut decrypted, "sync-password")
+        .await
+        .expect("decrypt async");
+    feed.await.expect("join").expect("feed ciphertext");
+    assert_eq!(decrypted, plaintext);
+}
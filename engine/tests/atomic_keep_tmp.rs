@@ -0,0 +1,54 @@
+//! `SEALVAULT_KEEP_TMP` 环境变量测试
+//!
+//! 修改环境变量会与同一进程中并行运行、读取环境的测试（如 `tempdir()`）竞争，
+//! 因此单独放在本文件中，且只有一个测试。
+#![cfg(feature = "fs")]
+
+mod common;
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use common::tmp_files;
+use engine::fs::atomic::{KEEP_TMP_ENV, write_atomic};
+use tempfile::tempdir;
+
+fn failing_write(target: &Path) -> io::Result<()> {
+    write_atomic(target, |file| {
+        file.write_all(b"partial")?;
+        Err(io::Error::other("simulated failure"))
+    })
+}
+
+/// 测试 SEALVAULT_KEEP_TMP 控制失败时是否保留临时文件
+///
+/// 环境变量是进程级状态，两种情况放在同一个测试中顺序执行。
+#[test]
+fn keep_tmp_env_controls_temp_cleanup_on_failure() {
+    let temp = tempdir().expect("create temp dir");
+
+    // 默认：失败后删除临时文件
+    let removed_dir = temp.path().join("removed");
+    fs::create_dir_all(&removed_dir).expect("create dir");
+    unsafe { std::env::remove_var(KEEP_TMP_ENV) };
+    assert!(failing_write(&removed_dir.join("target.bin")).is_err());
+    assert!(tmp_files(&removed_dir).is_empty());
+    assert!(!removed_dir.join("target.bin").exists());
+
+    // 设置后：失败后保留临时文件
+    let kept_dir = temp.path().join("kept");
+    fs::create_dir_all(&kept_dir).expect("create dir");
+    unsafe { std::env::set_var(KEEP_TMP_ENV, "1") };
+    let result = failing_write(&kept_dir.join("target.bin"));
+    unsafe { std::env::remove_var(KEEP_TMP_ENV) };
+
+    assert!(result.is_err());
+    let kept = tmp_files(&kept_dir);
+    assert_eq!(kept.len(), 1, "expected one kept temp file: {kept:?}");
+    assert_eq!(
+        fs::read(kept_dir.join(&kept[0])).expect("read kept temp"),
+        b"partial"
+    );
+    assert!(!kept_dir.join("target.bin").exists());
+}
@@ -0,0 +1,244 @@
+//! SealVault 原子写入测试
+#![cfg(feature = "fs")]
+
+mod common;
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use common::tmp_files;
+use engine::fs::atomic::{replace_file, write_atomic};
+use tempfile::tempdir;
+
+/// 测试已存在的目标文件被原子替换：写入期间旧内容一直可读，完成后变为新内容
+#[test]
+fn write_atomic_replaces_existing_target() {
+    let temp = tempdir().expect("create temp dir");
+    let target = temp.path().join("target.bin");
+    fs::write(&target, b"old content").expect("write existing target");
+
+    write_atomic(&target, |file| {
+        // 替换发生前，目标文件不能被提前删除或截断
+        assert_eq!(fs::read(&target).expect("read target"), b"old content");
+        file.write_all(b"new content")
+    })
+    .expect("replace existing target");
+
+    assert_eq!(fs::read(&target).expect("read target"), b"new content");
+    assert!(tmp_files(temp.path()).is_empty());
+}
+
+/// 测试替换后 fsync 父目录不影响正常写入：新建的多级目录、连续覆盖与 `replace_file`
+#[test]
+fn write_atomic_with_parent_dir_sync_keeps_normal_writes_working() {
+    let temp = tempdir().expect("create temp dir");
+
+    let nested = temp.path().join("a/b/c/new.bin");
+    write_atomic(&nested, |file| file.write_all(b"first")).expect("write into new dirs");
+    assert_eq!(fs::read(&nested).expect("read nested"), b"first");
+
+    for round in 0..3u8 {
+        write_atomic(&nested, |file| file.write_all(&[round; 16])).expect("overwrite");
+        assert_eq!(fs::read(&nested).expect("read nested"), [round; 16]);
+    }
+    assert!(tmp_files(nested.parent().expect("parent")).is_empty());
+
+    let source = temp.path().join("staged.bin");
+    let target = temp.path().join("final.bin");
+    fs::write(&source, b"staged").expect("write staged");
+    replace_file(&source, &target).expect("replace into new target");
+    assert!(!source.exists());
+    assert_eq!(fs::read(&target).expect("read final"), b"staged");
+}
+
+/// 测试临时文件以 `create_new` 创建：已存在的候选路径（包括预先放置的符号链接）
+/// 不会被打开或覆盖，而是换下一个名字；全部冲突时返回错误
+#[test]
+fn tmp_file_creation_never_reuses_existing_paths() {
+    use engine::fs::atomic::create_tmp_file_with;
+
+    let temp = tempdir().expect("create temp dir");
+    let victim = temp.path().join("victim.txt");
+    fs::write(&victim, b"do not overwrite").expect("write victim");
+
+    let planted = temp.path().join(".target.bin.tmp-planted");
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&victim, &planted).expect("plant symlink");
+    #[cfg(not(unix))]
+    fs::write(&planted, b"planted").expect("plant file");
+    let fresh = temp.path().join(".target.bin.tmp-fresh");
+
+    let mut candidates = vec![fresh.clone(), planted.clone()];
+    let (mut file, path) =
+        create_tmp_file_with(|| candidates.pop().expect("candidate")).expect("retry with fresh");
+    assert_eq!(path, fresh);
+    file.write_all(b"new data").expect("write tmp");
+    assert_eq!(fs::read(&victim).expect("read victim"), b"do not overwrite");
+
+    // 所有候选都已存在：报 AlreadyExists，不跟随链接写入
+    let err = create_tmp_file_with(|| planted.clone()).expect_err("all candidates taken");
+    assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    assert_eq!(fs::read(&victim).expect("read victim"), b"do not overwrite");
+
+    // 正常写入完成后不留自己的临时文件，只剩上面的两个
+    let target = temp.path().join("target.bin");
+    write_atomic(&target, |file| file.write_all(b"final")).expect("write atomic");
+    assert_eq!(fs::read(&target).expect("read target"), b"final");
+    assert_eq!(tmp_files(temp.path()).len(), 2);
+}
+
+fn plant(path: &Path, age: std::time::Duration) {
+    fs::write(path, b"stale").expect("plant temp file");
+    let modified = std::time::SystemTime::now() - age;
+    fs::File::options()
+        .write(true)
+        .open(path)
+        .expect("open planted")
+        .set_modified(modified)
+        .expect("set mtime");
+}
+
+/// 测试清理残留临时文件：删除所属进程已退出且已有一段时间未修改的、或长期未修改的，
+/// 保留当前进程的、刚修改过的与无关文件；子目录不被递归清理
+#[test]
+fn cleanup_orphaned_temp_removes_only_stale_files() {
+    use engine::fs::atomic::{ORPHAN_TMP_DEAD_OWNER_MIN_AGE, ORPHAN_TMP_MAX_AGE};
+    use engine::fs::cleanup_orphaned_temp;
+    use std::time::Duration;
+
+    let temp = tempdir().expect("create temp dir");
+    let dir = temp.path().join("out");
+    fs::create_dir_all(dir.join("nested")).expect("create dirs");
+    let old = ORPHAN_TMP_MAX_AGE + Duration::from_secs(3600);
+    let fresh = Duration::ZERO;
+    let own_pid = std::process::id();
+    // 无法判断是否存活的 pid，只按修改时间判断
+    let unknown_pid = u32::MAX;
+
+    // 早期版本的 `{pid}-{时间戳}-{计数}` 命名
+    plant(
+        &dir.join(format!(".a.bin.tmp-{unknown_pid}-1700000000000000000-0")),
+        old,
+    );
+    plant(
+        &dir.join(format!(".b.bin.tmp-{unknown_pid}-1700000000000000000-1")),
+        fresh,
+    );
+    // 当前进程的临时文件即使很旧也保留
+    plant(
+        &dir.join(format!(".c.bin.tmp-{own_pid}-0123456789abcdef")),
+        old,
+    );
+    // 不符合命名格式的文件不动
+    plant(&dir.join(".notes.tmp-draft"), old);
+    plant(&dir.join(".g.bin.tmp-0123456789abcdef"), old);
+    plant(&dir.join("d.bin"), old);
+    // 子目录中的临时文件即使已过期也不处理
+    let nested = format!(".h.bin.tmp-{unknown_pid}-0123456789abcdef");
+    plant(&dir.join("nested").join(&nested), old);
+    let mut expected_left = vec![
+        format!(".b.bin.tmp-{unknown_pid}-1700000000000000000-1"),
+        format!(".c.bin.tmp-{own_pid}-0123456789abcdef"),
+        ".notes.tmp-draft".to_string(),
+        ".g.bin.tmp-0123456789abcdef".to_string(),
+    ];
+
+    #[cfg(unix)]
+    let dead_owner_kept = {
+        // 已退出的子进程的 pid：刚修改过的可能属于共享目录中其他主机的进程，保留
+        let mut child = std::process::Command::new("true")
+            .spawn()
+            .expect("spawn child");
+        let dead_pid = child.id();
+        child.wait().expect("wait child");
+        plant(
+            &dir.join(format!(".e.bin.tmp-{dead_pid}-0123456789abcdef")),
+            ORPHAN_TMP_DEAD_OWNER_MIN_AGE + Duration::from_secs(60),
+        );
+        let kept = format!(".f.bin.tmp-{dead_pid}-0123456789abcdef");
+        plant(&dir.join(&kept), fresh);
+        Some(kept)
+    };
+    #[cfg(not(unix))]
+    let dead_owner_kept = None;
+    expected_left.extend(dead_owner_kept);
+
+    let expected_removed = if cfg!(unix) { 2 } else { 1 };
+    assert_eq!(cleanup_orphaned_temp(&dir), expected_removed);
+    let mut left = tmp_files(&dir);
+    left.sort();
+    expected_left.sort();
+    assert_eq!(left, expected_left);
+    assert_eq!(tmp_files(&dir.join("nested")), vec![nested]);
+    assert!(dir.join("d.bin").exists());
+
+    assert_eq!(cleanup_orphaned_temp(&temp.path().join("missing")), 0);
+}
+
+/// 测试目录加密清理其写入的输出目录（根目录与对应输入子目录的目录）中的残留临时文件，
+/// 输出目录中与本次运行无关的子树保持不变
+#[test]
+fn encrypt_folder_cleans_orphaned_temp_in_output() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let output_dir = temp.path().join("encrypted");
+    fs::create_dir_all(input_dir.join("sub")).expect("create input dir");
+    fs::create_dir_all(output_dir.join("sub")).expect("create output dir");
+    fs::create_dir_all(output_dir.join("unrelated")).expect("create unrelated dir");
+    fs::write(input_dir.join("file.txt"), b"payload").expect("write input");
+    fs::write(input_dir.join("sub").join("inner.txt"), b"payload").expect("write input");
+
+    let age = engine::fs::atomic::ORPHAN_TMP_MAX_AGE + std::time::Duration::from_secs(60);
+    let stale_name = format!(".file.txt.svlt.tmp-{}-1700000000000000000-0", u32::MAX);
+    let stale = output_dir.join(&stale_name);
+    let stale_sub = output_dir.join("sub").join(&stale_name);
+    let unrelated = output_dir.join("unrelated").join(&stale_name);
+    for path in [&stale, &stale_sub, &unrelated] {
+        plant(path, age);
+    }
+
+    engine::encrypt_folder(
+        &input_dir,
+        &output_dir,
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt folder");
+    assert!(!stale.exists());
+    assert!(!stale_sub.exists());
+    assert!(tmp_files(&output_dir).is_empty());
+    assert!(unrelated.exists());
+}
+
+/// 测试输出目录中存在无法读取的子目录时，残留临时文件的清理跳过该目录，不影响目录加密
+#[cfg(unix)]
+#[test]
+fn unreadable_output_subdirectory_does_not_block_folder_encryption() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let output_dir = temp.path().join("encrypted");
+    let locked = output_dir.join("locked");
+    fs::create_dir_all(&input_dir).expect("create input dir");
+    fs::create_dir_all(&locked).expect("create locked dir");
+    fs::write(input_dir.join("file.txt"), b"payload").expect("write input");
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).expect("chmod locked");
+
+    // root 等不受目录权限限制时无法构造该情形
+    if fs::read_dir(&locked).is_ok() {
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).expect("restore locked");
+        return;
+    }
+
+    let result = engine::encrypt_folder(
+        &input_dir,
+        &output_dir,
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    );
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).expect("restore locked");
+    result.expect("encrypt folder");
+    assert!(output_dir.join("file.txt.svlt").exists());
+}
@@ -0,0 +1,91 @@
+//! SealVault 内存字节 API 测试
+#![cfg(feature = "fs")]
+
+use std::fs;
+
+use tempfile::tempdir;
+
+/// 测试内存加解密 round-trip
+#[test]
+fn encrypt_decrypt_bytes_roundtrip() {
+    let plaintext = b"in-memory sealvault payload";
+
+    for algorithm in [
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        engine::AeadAlgorithm::Aes256Gcm,
+    ] {
+        let encrypted =
+            engine::encrypt_bytes(plaintext, "bytes-password", algorithm).expect("encrypt bytes");
+        assert_ne!(&encrypted[..], &plaintext[..]);
+
+        let decrypted = engine::decrypt_bytes(&encrypted, "bytes-password").expect("decrypt bytes");
+        assert_eq!(decrypted, plaintext);
+    }
+}
+
+/// 测试内存解密时密码错误
+#[test]
+fn decrypt_bytes_with_wrong_password_fails() {
+    let encrypted = engine::encrypt_bytes(
+        b"in-memory secret",
+        "right-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt bytes");
+
+    let result = engine::decrypt_bytes(&encrypted, "wrong-password");
+    assert!(result.is_err(), "expected wrong password to fail");
+}
+
+/// 测试内存格式与文件格式互通
+#[test]
+fn bytes_and_file_formats_interoperate() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let encrypted_path = temp_dir.path().join("input.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.txt");
+
+    fs::write(&input_path, b"file side payload").expect("write input");
+    engine::encrypt(&input_path, &encrypted_path, "shared-password").expect("encrypt file");
+
+    let from_file = fs::read(&encrypted_path).expect("read encrypted");
+    assert_eq!(
+        engine::decrypt_bytes(&from_file, "shared-password").expect("decrypt bytes"),
+        b"file side payload"
+    );
+
+    let from_bytes = engine::encrypt_bytes(
+        b"bytes side payload",
+        "shared-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt bytes");
+    fs::write(&encrypted_path, from_bytes).expect("write encrypted");
+    engine::decrypt(&encrypted_path, &decrypted_path, "shared-password").expect("decrypt file");
+    assert_eq!(
+        fs::read(&decrypted_path).expect("read decrypted"),
+        b"bytes side payload"
+    );
+}
+
+/// 测试内存数据被篡改或截断时解密失败，且不返回任何明文
+#[test]
+fn decrypt_bytes_rejects_tampered_or_truncated_data() {
+    let encrypted = engine::encrypt_bytes(
+        b"api-token-0123456789",
+        "bytes-password",
+        engine::AeadAlgorithm::Aes256Gcm,
+    )
+    .expect("encrypt bytes");
+
+    // 翻转最后一个字节（final chunk 的 tag）
+    let mut tampered = encrypted.clone();
+    *tampered.last_mut().expect("non-empty") ^= 0x01;
+    let err = engine::decrypt_bytes(&tampered, "bytes-password").expect_err("tampered tag");
+    assert!(matches!(err, engine::SealVaultError::CorruptedData));
+
+    // 去掉 final chunk：视为截断
+    let truncated = &encrypted[..encrypted.len() - 20];
+    let err = engine::decrypt_bytes(truncated, "bytes-password").expect_err("truncated");
+    assert!(matches!(err, engine::SealVaultError::CorruptedData));
+}
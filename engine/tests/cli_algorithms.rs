@@ -0,0 +1,127 @@
+//! SealVault CLI `--algorithm` 与 `list-algorithms` 测试
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::fs;
+
+use engine::AeadAlgorithm;
+use tempfile::tempdir;
+
+const PASSWORD: &str = "algorithm-flag-secret-2026";
+
+/// 测试 `list-algorithms` 逐行列出全部算法及其 ID，打印的名称可解析回同一算法
+#[test]
+fn list_algorithms_prints_names_and_ids() {
+    let output = common::run_cli(["list-algorithms"], b"", Some(PASSWORD));
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stderr.is_empty(), "{output:?}");
+    let stdout = String::from_utf8(output.stdout).expect("utf-8 output");
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines.len(), AeadAlgorithm::ALL.len(), "{stdout}");
+
+    for (line, algorithm) in lines.iter().zip(AeadAlgorithm::ALL) {
+        let mut fields = line.split_whitespace();
+        let name = fields.next().expect("name");
+        assert_eq!(name, algorithm.to_string());
+        assert_eq!(name.parse::<AeadAlgorithm>(), Ok(algorithm));
+        assert_eq!(fields.next(), Some("id"));
+        assert_eq!(fields.next(), Some(algorithm.to_u8().to_string().as_str()));
+    }
+    assert!(stdout.contains("XChaCha20-Poly1305"), "{stdout}");
+    assert!(stdout.contains("AES-256-GCM"), "{stdout}");
+
+    let output = common::run_cli(["list-algorithms", "extra"], b"", Some(PASSWORD));
+    assert!(!output.status.success(), "{output:?}");
+}
+
+/// 测试 `--algorithm` 决定加密所用算法，可放在任意位置；与位置参数同时给出、
+/// 用于解密命令或名称无法识别时报错
+#[test]
+fn algorithm_flag_selects_the_algorithm() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.txt");
+    let encrypted = temp.path().join("input.txt.svlt");
+    let decrypted = temp.path().join("decrypted.txt");
+    fs::write(&input, b"algorithm flag payload").expect("write input");
+
+    let output = common::run_cli(
+        [
+            "--algorithm".as_ref(),
+            "aes-256-gcm".as_ref(),
+            "encrypt".as_ref(),
+            input.as_os_str(),
+            encrypted.as_os_str(),
+            "env:".as_ref(),
+        ],
+        b"",
+        Some(PASSWORD),
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        engine::inspect(&encrypted).expect("inspect").algorithm,
+        AeadAlgorithm::Aes256Gcm
+    );
+
+    // reencrypt 同样接受 `--algorithm`（原地替换）
+    let output = common::run_cli(
+        [
+            "reencrypt".as_ref(),
+            encrypted.as_os_str(),
+            "env:".as_ref(),
+            "--algorithm".as_ref(),
+            "chacha20".as_ref(),
+        ],
+        b"",
+        Some(PASSWORD),
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert_eq!(
+        engine::inspect(&encrypted).expect("inspect").algorithm,
+        AeadAlgorithm::ChaCha20Poly1305
+    );
+    engine::decrypt(&encrypted, &decrypted, PASSWORD).expect("decrypt");
+    assert_eq!(
+        fs::read(&decrypted).expect("read output"),
+        b"algorithm flag payload"
+    );
+
+    let rejected: [&[&std::ffi::OsStr]; 3] = [
+        &[
+            "encrypt".as_ref(),
+            input.as_os_str(),
+            encrypted.as_os_str(),
+            "env:".as_ref(),
+            "xchacha20".as_ref(),
+            "--algorithm".as_ref(),
+            "aes256gcm".as_ref(),
+        ],
+        &[
+            "decrypt".as_ref(),
+            encrypted.as_os_str(),
+            decrypted.as_os_str(),
+            "env:".as_ref(),
+            "--algorithm".as_ref(),
+            "aes256gcm".as_ref(),
+        ],
+        &[
+            "encrypt".as_ref(),
+            input.as_os_str(),
+            encrypted.as_os_str(),
+            "env:".as_ref(),
+            "--algorithm".as_ref(),
+            "rot13".as_ref(),
+        ],
+    ];
+    for args in rejected {
+        let output = common::run_cli(args, b"", Some(PASSWORD));
+        assert!(!output.status.success(), "{output:?}");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("algorithm"), "{stderr}");
+    }
+    // 失败的命令不改动已有输出
+    assert_eq!(
+        engine::inspect(&encrypted).expect("inspect").algorithm,
+        AeadAlgorithm::ChaCha20Poly1305
+    );
+}
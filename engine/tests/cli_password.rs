@@ -0,0 +1,205 @@
+//! SealVault CLI 密码输入测试
+//!
+//! 通过管道向 CLI 的 stdin 写入密码，模拟交互输入（`-` 占位参数）；
+//! 以及通过 `SEALVAULT_PASSWORD` 环境变量提供密码。
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::tempdir;
+
+fn os(path: &Path) -> &std::ffi::OsStr {
+    path.as_os_str()
+}
+
+#[test]
+fn cli_reads_password_from_stdin_when_dash_is_given() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let encrypted_path = temp_dir.path().join("input.txt.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.txt");
+    fs::write(&input_path, b"prompted password payload").expect("write input");
+
+    // 加密：输入两次密码确认
+    let output = common::run_cli(
+        [
+            "encrypt".as_ref(),
+            os(&input_path),
+            os(&encrypted_path),
+            "-".as_ref(),
+        ],
+        b"stdin-secret\nstdin-secret\n",
+        None,
+    );
+    assert!(output.status.success(), "encrypt failed: {output:?}");
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("Warning"));
+
+    // 与库接口互通，证明读到的正是 stdin 中的密码
+    engine::decrypt(&encrypted_path, &decrypted_path, "stdin-secret").expect("decrypt file");
+    assert_eq!(
+        fs::read(&decrypted_path).expect("read decrypted"),
+        b"prompted password payload"
+    );
+
+    // 解密：省略密码参数时同样从 stdin 读取（只读一次）
+    fs::remove_file(&input_path).expect("remove original");
+    let output = common::run_cli(
+        ["decrypt".as_ref(), os(&encrypted_path)],
+        b"stdin-secret\n",
+        None,
+    );
+    assert!(output.status.success(), "decrypt failed: {output:?}");
+    assert_eq!(
+        fs::read(&input_path).expect("read default output"),
+        b"prompted password payload"
+    );
+}
+
+#[test]
+fn cli_rejects_mismatched_password_confirmation() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let encrypted_path = temp_dir.path().join("input.txt.svlt");
+    fs::write(&input_path, b"payload").expect("write input");
+
+    let output = common::run_cli(
+        [
+            "encrypt".as_ref(),
+            os(&input_path),
+            os(&encrypted_path),
+            "-".as_ref(),
+        ],
+        b"first-secret\nsecond-secret\n",
+        None,
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("passwords do not match"));
+    assert!(!encrypted_path.exists());
+}
+
+/// 测试二次确认使用的常量时间比较：相同、内容不同、长度不同、前缀
+#[test]
+fn passwords_equal_compares_full_contents() {
+    use engine::crypto::password::passwords_equal;
+
+    assert!(passwords_equal(b"correct horse", b"correct horse"));
+    assert!(passwords_equal(b"", b""));
+    assert!(!passwords_equal(b"correct horse", b"correct house"));
+    assert!(!passwords_equal(b"correct horse", b"correct horse "));
+    assert!(!passwords_equal(b"correct", b"correct horse"));
+    assert!(!passwords_equal("pässword".as_bytes(), b"password"));
+}
+
+/// 测试参数顺序写错、密码落在算法位置时，错误信息不回显该参数
+#[test]
+fn cli_does_not_echo_unrecognized_algorithm_argument() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let encrypted_path = temp_dir.path().join("input.txt.svlt");
+    fs::write(&input_path, b"payload").expect("write input");
+
+    let output = common::run_cli(
+        [
+            "encrypt".as_ref(),
+            os(&input_path),
+            os(&encrypted_path),
+            "env:".as_ref(),
+            "misplaced-secret".as_ref(),
+        ],
+        b"",
+        Some("env-password"),
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("unsupported algorithm"), "{stderr}");
+    assert!(!stderr.contains("misplaced-secret"), "{stderr}");
+    assert!(!encrypted_path.exists());
+}
+
+#[test]
+fn cli_warns_about_positional_password() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let missing_path = temp_dir.path().join("missing.svlt");
+    let output_path = temp_dir.path().join("out.txt");
+
+    let output = common::run_cli(
+        [
+            "decrypt".as_ref(),
+            os(&missing_path),
+            os(&output_path),
+            "argv-secret".as_ref(),
+        ],
+        b"",
+        None,
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Warning"));
+}
+
+#[test]
+fn cli_reads_password_from_env_without_prompting() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let encrypted_path = temp_dir.path().join("input.txt.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.txt");
+    fs::write(&input_path, b"ci pipeline payload").expect("write input");
+
+    // `env:` 显式从环境变量读取；stdin 为空，若仍提示输入则会失败
+    let output = common::run_cli(
+        [
+            "encrypt".as_ref(),
+            os(&input_path),
+            os(&encrypted_path),
+            "env:".as_ref(),
+        ],
+        b"",
+        Some("env-secret"),
+    );
+    assert!(output.status.success(), "encrypt failed: {output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("Warning") && !stderr.contains("Password"));
+
+    engine::decrypt(&encrypted_path, &decrypted_path, "env-secret").expect("decrypt file");
+    assert_eq!(
+        fs::read(&decrypted_path).expect("read decrypted"),
+        b"ci pipeline payload"
+    );
+
+    // 省略密码参数时，已设置的环境变量优先于交互输入
+    fs::remove_file(&input_path).expect("remove original");
+    let output = common::run_cli(
+        ["decrypt".as_ref(), os(&encrypted_path)],
+        b"",
+        Some("env-secret"),
+    );
+    assert!(output.status.success(), "decrypt failed: {output:?}");
+    assert_eq!(
+        fs::read(&input_path).expect("read default output"),
+        b"ci pipeline payload"
+    );
+}
+
+#[test]
+fn cli_env_sentinel_requires_variable() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let encrypted_path = temp_dir.path().join("input.txt.svlt");
+    fs::write(&input_path, b"payload").expect("write input");
+
+    let output = common::run_cli(
+        [
+            "encrypt".as_ref(),
+            os(&input_path),
+            os(&encrypted_path),
+            "env:".as_ref(),
+        ],
+        b"",
+        None,
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(common::PASSWORD_ENV_VAR));
+    assert!(!encrypted_path.exists());
+}
@@ -0,0 +1,98 @@
+//! SealVault CLI 管道测试
+//!
+//! input / output 为 `-` 时经 stdin / stdout 读写原始字节，密码通过 `SEALVAULT_PASSWORD` 提供。
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::fs;
+
+use tempfile::tempdir;
+
+/// 含 `\r\n`、`\n`、NUL 与非 UTF-8 字节，跨越多个 chunk
+fn binary_plaintext() -> Vec<u8> {
+    let mut data: Vec<u8> = (0..200_000u32).map(|i| (i % 256) as u8).collect();
+    data.extend_from_slice(b"\r\n\n\r\x00\xff\xfe");
+    data
+}
+
+#[test]
+fn cli_pipes_binary_data_through_encrypt_and_decrypt() {
+    let plaintext = binary_plaintext();
+
+    let encrypted = common::run_cli(["encrypt", "-", "-", "env:"], &plaintext, Some("pipe-pw"));
+    assert!(
+        encrypted.status.success(),
+        "encrypt failed: {}",
+        String::from_utf8_lossy(&encrypted.stderr)
+    );
+    assert_ne!(encrypted.stdout, plaintext);
+    assert_eq!(
+        engine::decrypt_bytes(&encrypted.stdout, "pipe-pw").expect("decrypt piped output"),
+        plaintext
+    );
+
+    let decrypted = common::run_cli(
+        ["decrypt", "-", "-", "env:"],
+        &encrypted.stdout,
+        Some("pipe-pw"),
+    );
+    assert!(
+        decrypted.status.success(),
+        "decrypt failed: {}",
+        String::from_utf8_lossy(&decrypted.stderr)
+    );
+    assert_eq!(decrypted.stdout, plaintext);
+
+    // 错误密码：非 0 退出，不输出明文
+    let failed = common::run_cli(
+        ["decrypt", "-", "-", "env:"],
+        &encrypted.stdout,
+        Some("wrong-pw"),
+    );
+    assert!(!failed.status.success());
+    assert!(failed.stdout.is_empty());
+}
+
+#[test]
+fn cli_mixes_stdio_with_files() {
+    let temp = tempdir().expect("create temp dir");
+    let encrypted_path = temp.path().join("piped.svlt");
+    let encrypted_arg = encrypted_path.to_str().expect("utf-8 path");
+    let plaintext = binary_plaintext();
+
+    // stdin -> 文件（位置参数密码）
+    let output = common::run_cli(
+        ["encrypt", "-", encrypted_arg, "file-pw", "aes-256-gcm"],
+        &plaintext,
+        None,
+    );
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        engine::inspect(&encrypted_path).expect("inspect").algorithm,
+        engine::AeadAlgorithm::Aes256Gcm
+    );
+
+    // 文件 -> stdout
+    let output = common::run_cli(["decrypt", encrypted_arg, "-", "file-pw"], b"", None);
+    assert!(output.status.success());
+    assert_eq!(output.stdout, plaintext);
+    assert!(
+        !fs::read_dir(temp.path())
+            .expect("list")
+            .any(|entry| entry.expect("entry").file_name() == "-")
+    );
+}
+
+#[test]
+fn cli_refuses_to_read_password_from_data_stdin() {
+    let output = common::run_cli(["encrypt", "-", "-", "-"], b"secret\nplaintext\n", None);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains(common::PASSWORD_ENV_VAR));
+
+    let output = common::run_cli(["decrypt", "-"], b"not a svlt file", None);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+}
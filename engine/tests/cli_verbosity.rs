@@ -0,0 +1,175 @@
+//! SealVault CLI `--quiet` / `--verbose` 输出测试
+#![cfg(feature = "cli")]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::tempdir;
+
+const PASSWORD: &str = "verbosity-secret-2026";
+
+fn os(path: &Path) -> &std::ffi::OsStr {
+    path.as_os_str()
+}
+
+/// 测试 `--quiet` 成功时 stdout 与 stderr 均为空，出错时只打印错误、不附用法
+#[test]
+fn quiet_prints_nothing_on_success() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.txt");
+    let encrypted = temp.path().join("input.txt.svlt");
+    let decrypted = temp.path().join("decrypted.txt");
+    fs::write(&input, b"quiet payload").expect("write input");
+
+    for args in [
+        vec![
+            "--quiet".as_ref(),
+            "encrypt".as_ref(),
+            os(&input),
+            os(&encrypted),
+            "env:".as_ref(),
+        ],
+        vec![
+            "decrypt".as_ref(),
+            os(&encrypted),
+            os(&decrypted),
+            "env:".as_ref(),
+            "--quiet".as_ref(),
+        ],
+    ] {
+        let output = common::run_cli(&args, b"", Some(PASSWORD));
+        assert!(output.status.success(), "{output:?}");
+        assert!(output.stdout.is_empty(), "{output:?}");
+        assert!(output.stderr.is_empty(), "{output:?}");
+    }
+    assert_eq!(fs::read(&decrypted).expect("read output"), b"quiet payload");
+
+    // 目录命令也不打印统计
+    let folder = temp.path().join("folder");
+    let encrypted_folder = temp.path().join("folder.svlt");
+    fs::create_dir(&folder).expect("create folder");
+    fs::write(folder.join("a.txt"), b"a").expect("write a");
+    let output = common::run_cli(
+        [
+            "--quiet".as_ref(),
+            "ef".as_ref(),
+            os(&folder),
+            os(&encrypted_folder),
+            "env:".as_ref(),
+        ],
+        b"",
+        Some(PASSWORD),
+    );
+    assert!(output.status.success(), "{output:?}");
+    assert!(
+        output.stdout.is_empty() && output.stderr.is_empty(),
+        "{output:?}"
+    );
+
+    // 参数错误时仍以非 0 退出，但不打印用法
+    let output = common::run_cli(["--quiet", "encrypt"], b"", Some(PASSWORD));
+    assert!(!output.status.success());
+    assert!(output.stderr.is_empty(), "{output:?}");
+    let output = common::run_cli(["--quiet", "--verbose", "e"], b"", Some(PASSWORD));
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("cannot be used together"));
+}
+
+/// 测试 `--verbose` 打印算法与 chunk 大小，且输出中不出现密码
+#[test]
+fn verbose_prints_algorithm_without_password() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.txt");
+    let encrypted = temp.path().join("input.txt.svlt");
+    let decrypted = temp.path().join("decrypted.txt");
+    fs::write(&input, b"verbose payload").expect("write input");
+
+    let output = common::run_cli(
+        [
+            "--verbose".as_ref(),
+            "encrypt".as_ref(),
+            os(&input),
+            os(&encrypted),
+            "env:".as_ref(),
+            "aes-256-gcm".as_ref(),
+        ],
+        b"",
+        Some(PASSWORD),
+    );
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Algorithm: AES-256-GCM"), "{stderr}");
+    assert!(stderr.contains("Chunk size: "), "{stderr}");
+    assert!(!stderr.contains(PASSWORD));
+
+    // 解密时算法取自 Header
+    let output = common::run_cli(
+        [
+            "decrypt".as_ref(),
+            os(&encrypted),
+            os(&decrypted),
+            "env:".as_ref(),
+            "--verbose".as_ref(),
+        ],
+        b"",
+        Some(PASSWORD),
+    );
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Algorithm: AES-256-GCM"), "{stderr}");
+    assert!(!stderr.contains(PASSWORD));
+    assert!(output.stdout.is_empty());
+}
+
+/// 测试目录命令在 `--verbose` 下逐个打印文件进度
+#[test]
+fn verbose_prints_per_file_progress_for_folders() {
+    let temp = tempdir().expect("create temp dir");
+    let folder = temp.path().join("folder");
+    let encrypted = temp.path().join("encrypted");
+    let decrypted = temp.path().join("decrypted");
+    fs::create_dir_all(folder.join("sub")).expect("create folder");
+    fs::write(folder.join("a.txt"), b"a").expect("write a");
+    fs::write(folder.join("sub").join("b.txt"), b"b").expect("write b");
+
+    let output = common::run_cli(
+        [
+            "--verbose".as_ref(),
+            "ef".as_ref(),
+            os(&folder),
+            os(&encrypted),
+            "env:".as_ref(),
+        ],
+        b"",
+        Some(PASSWORD),
+    );
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Algorithm: XChaCha20-Poly1305"), "{stderr}");
+    assert!(stderr.contains("Encrypting [1/2] "), "{stderr}");
+    assert!(stderr.contains("Encrypting [2/2] "), "{stderr}");
+    assert!(stderr.contains("Encrypted 2 file(s)"), "{stderr}");
+
+    let output = common::run_cli(
+        [
+            "--verbose".as_ref(),
+            "df".as_ref(),
+            os(&encrypted),
+            os(&decrypted),
+            "env:".as_ref(),
+        ],
+        b"",
+        Some(PASSWORD),
+    );
+    assert!(output.status.success(), "{output:?}");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Decrypting [1/2] "), "{stderr}");
+    assert!(stderr.contains("Decrypting [2/2] "), "{stderr}");
+    assert!(!stderr.contains(PASSWORD));
+    assert_eq!(
+        fs::read(decrypted.join("sub").join("b.txt")).expect("read b"),
+        b"b"
+    );
+}
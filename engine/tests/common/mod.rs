@@ -0,0 +1,76 @@
+//! 集成测试共用的辅助函数
+//!
+//! 各测试文件通过 `mod common;` 引入，通常只用到其中一部分。
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::Path;
+
+use engine::crypto::kdf::{KdfAlgorithm, ScryptParams};
+use engine::{AeadAlgorithm, EncryptOptions};
+
+/// CLI 读取密码的环境变量
+pub const PASSWORD_ENV_VAR: &str = "SEALVAULT_PASSWORD";
+
+/// 低成本 scrypt 参数（N = 2^4），只为保持测试速度，不影响分帧
+pub const FAST_SCRYPT: ScryptParams = ScryptParams {
+    log_n: 4,
+    r: 8,
+    p: 1,
+};
+
+/// 使用 `FAST_SCRYPT` 与 1 KiB chunk 的加密选项，其余为指定算法的默认值
+pub fn fast_options(algorithm: AeadAlgorithm) -> EncryptOptions {
+    EncryptOptions {
+        chunk_size: 1024,
+        kdf: KdfAlgorithm::Scrypt(FAST_SCRYPT),
+        ..EncryptOptions::with_algorithm(algorithm)
+    }
+}
+
+/// 列出 `dir` 中 `write_atomic` 留下的临时文件名
+pub fn tmp_files(dir: &Path) -> Vec<String> {
+    fs::read_dir(dir)
+        .expect("read dir")
+        .map(|entry| entry.expect("dir entry").file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .filter(|name| name.contains(".tmp-"))
+        .collect()
+}
+
+/// 运行 CLI，把 `stdin` 原样写入其标准输入；`env_password` 为 `Some` 时设置 `SEALVAULT_PASSWORD`，
+/// 否则清除继承的该变量
+#[cfg(feature = "cli")]
+pub fn run_cli<I, S>(args: I, stdin: &[u8], env_password: Option<&str>) -> std::process::Output
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_engine"));
+    match env_password {
+        Some(password) => command.env(PASSWORD_ENV_VAR, password),
+        None => command.env_remove(PASSWORD_ENV_VAR),
+    };
+
+    let mut child = command
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("spawn cli");
+
+    // 在独立线程中写入，避免输出管道写满时互相阻塞；
+    // CLI 提前失败退出时不会读完 stdin，写入端的 BrokenPipe 可以忽略
+    let mut child_stdin = child.stdin.take().expect("child stdin");
+    let stdin = stdin.to_vec();
+    let writer = std::thread::spawn(move || {
+        let _ = child_stdin.write_all(&stdin);
+    });
+    let output = child.wait_with_output().expect("wait for cli");
+    writer.join().expect("join writer");
+    output
+}
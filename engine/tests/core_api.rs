@@ -0,0 +1,70 @@
+//! 核心特性集（`--no-default-features`）下的内存与流式接口测试
+//!
+//! 只使用不依赖 `fs`、`zstd`、`cli` 特性的接口，也不使用临时目录，
+//! 可单独以 `cargo test --no-default-features --test core_api` 构建运行，
+//! 用于确认浏览器（`wasm` 特性）等环境可用的接口集合保持完整。
+
+mod common;
+
+use std::io::{Read, Write};
+
+use common::fast_options;
+use engine::format::header::Header;
+use engine::{AeadAlgorithm, SealVaultError, SealVaultReader, SealVaultWriter};
+
+const PASSWORD: &str = "core-api-password";
+
+/// 测试内存接口与流式接口互通：`encrypt_bytes` 的输出可流式解密，反之亦然
+#[test]
+fn bytes_and_stream_apis_interoperate() {
+    let plaintext = b"core feature set payload ".repeat(100);
+
+    for algorithm in [AeadAlgorithm::XChaCha20Poly1305, AeadAlgorithm::Aes256Gcm] {
+        let encrypted = engine::encrypt_bytes(&plaintext, PASSWORD, algorithm).expect("encrypt");
+        let mut decrypted = Vec::new();
+        engine::decrypt_stream(&encrypted[..], &mut decrypted, PASSWORD).expect("decrypt stream");
+        assert_eq!(decrypted, plaintext);
+
+        let mut encrypted = Vec::new();
+        engine::encrypt_stream(&plaintext[..], &mut encrypted, PASSWORD, algorithm)
+            .expect("encrypt stream");
+        assert_eq!(
+            engine::decrypt_bytes(&encrypted, PASSWORD).expect("decrypt bytes"),
+            plaintext
+        );
+        let err = engine::decrypt_bytes(&encrypted, "wrong-password").expect_err("wrong password");
+        assert!(
+            matches!(err, SealVaultError::InvalidPasswordOrFile),
+            "{err:?}"
+        );
+    }
+}
+
+/// 测试增量 writer 与 reader 往返，`decrypt_body` 可在单独读取 Header 后解密其余数据
+#[test]
+fn writer_reader_and_body_roundtrip() {
+    let plaintext = b"incremental line\n".repeat(200);
+
+    let mut writer = SealVaultWriter::with_options(
+        Vec::new(),
+        PASSWORD,
+        &fast_options(AeadAlgorithm::Aes256Gcm),
+    )
+    .expect("create writer");
+    for line in plaintext.chunks(17) {
+        writer.write_all(line).expect("write");
+    }
+    let encrypted = writer.finish().expect("finish");
+
+    let mut reader = SealVaultReader::new(&encrypted[..], PASSWORD).expect("create reader");
+    assert_eq!(reader.header().algorithm, AeadAlgorithm::Aes256Gcm);
+    let mut decrypted = Vec::new();
+    reader.read_to_end(&mut decrypted).expect("read");
+    assert_eq!(decrypted, plaintext);
+
+    let mut body = &encrypted[..];
+    let header = Header::read(&mut body).expect("read header");
+    let mut decrypted = Vec::new();
+    engine::decrypt_body(body, &header, PASSWORD, &mut decrypted).expect("decrypt body");
+    assert_eq!(decrypted, plaintext);
+}
@@ -0,0 +1,226 @@
+//! SealVault 断点续解密测试
+//!
+//! 通过截断的密文模拟中途失败，再用完整密文从 checkpoint 继续。
+#![cfg(feature = "fs")]
+
+use std::fs;
+use std::io::Write;
+
+use engine::format::checkpoint::Checkpoint;
+use tempfile::tempdir;
+
+const CHUNK: usize = 64 * 1024;
+const HEADER_LEN: usize = 8 + 1 + 1 + 16 + 24 + 4 + 1 + 12 + 1 + 1 + 8 + 16;
+const FRAME_LEN: usize = 4 + CHUNK + 16;
+
+/// 测试从中途的 checkpoint 恢复解密
+#[test]
+fn decrypt_resumes_from_mid_file_checkpoint() {
+    let temp = tempdir().expect("create temp dir");
+    let input_path = temp.path().join("big.bin");
+    let full_path = temp.path().join("big.full.svlt");
+    let encrypted_path = temp.path().join("big.svlt");
+    let output_path = temp.path().join("restored.bin");
+    let partial_path = temp.path().join("restored.bin.svpart");
+    let checkpoint_path = temp.path().join("restored.bin.svckpt");
+
+    let plaintext: Vec<u8> = (0..(5 * CHUNK + 123) as u32)
+        .map(|i| (i % 253) as u8)
+        .collect();
+    fs::write(&input_path, &plaintext).expect("write input");
+    engine::encrypt(&input_path, &full_path, "resume-password").expect("encrypt");
+    let ciphertext = fs::read(&full_path).expect("read ciphertext");
+
+    let options = engine::CheckpointOptions {
+        interval_chunks: 1,
+        resume: true,
+    };
+
+    // 第一次：密文在第 4 个 chunk 中间被截断，模拟中途失败
+    fs::write(
+        &encrypted_path,
+        &ciphertext[..HEADER_LEN + 3 * FRAME_LEN + 100],
+    )
+    .expect("write truncated ciphertext");
+    let result =
+        engine::decrypt_with_checkpoint(&encrypted_path, &output_path, "resume-password", &options);
+    assert!(result.is_err(), "expected truncated input to fail");
+    assert!(!output_path.exists());
+
+    let checkpoint = Checkpoint::load(&checkpoint_path)
+        .expect("load checkpoint")
+        .expect("checkpoint exists");
+    assert_eq!(checkpoint.chunk_index, 3);
+    assert_eq!(checkpoint.output_offset, (3 * CHUNK) as u64);
+
+    // checkpoint 之后残留的内容应在恢复时被丢弃
+    fs::OpenOptions::new()
+        .append(true)
+        .open(&partial_path)
+        .expect("open partial")
+        .write_all(b"garbage after checkpoint")
+        .expect("append garbage");
+
+    // 第二次：使用完整密文恢复
+    fs::write(&encrypted_path, &ciphertext).expect("write full ciphertext");
+    engine::decrypt_with_checkpoint(&encrypted_path, &output_path, "resume-password", &options)
+        .expect("resume decrypt");
+
+    assert_eq!(fs::read(&output_path).expect("read output"), plaintext);
+    assert!(!partial_path.exists());
+    assert!(!checkpoint_path.exists());
+}
+
+/// 测试 checkpoint 不属于当前文件时从头开始
+#[test]
+fn decrypt_ignores_checkpoint_from_another_file() {
+    let temp = tempdir().expect("create temp dir");
+    let input_path = temp.path().join("small.txt");
+    let encrypted_path = temp.path().join("small.svlt");
+    let output_path = temp.path().join("small.out");
+
+    fs::write(&input_path, b"checkpoint mismatch payload").expect("write input");
+    engine::encrypt(&input_path, &encrypted_path, "resume-password").expect("encrypt");
+
+    let foreign = Checkpoint {
+        salt: [0xaa; 16],
+        base_nonce: [0xbb; 24],
+        chunk_index: 1,
+        output_offset: 4,
+        input: None,
+    };
+    foreign
+        .store(&temp.path().join("small.out.svckpt"))
+        .expect("store checkpoint");
+    fs::write(temp.path().join("small.out.svpart"), b"junk").expect("write partial");
+
+    engine::decrypt_with_checkpoint(
+        &encrypted_path,
+        &output_path,
+        "resume-password",
+        &engine::CheckpointOptions::default(),
+    )
+    .expect("decrypt");
+
+    assert_eq!(
+        fs::read(&output_path).expect("read output"),
+        b"checkpoint mismatch payload"
+    );
+}
+
+/// 测试压缩过的文件不支持断点续解密
+#[test]
+fn decrypt_with_checkpoint_rejects_compressed_file() {
+    let temp = tempdir().expect("create temp dir");
+    let input_path = temp.path().join("log.txt");
+    let encrypted_path = temp.path().join("log.svlt");
+    let output_path = temp.path().join("restored.txt");
+    fs::write(&input_path, b"compressible ".repeat(1000)).expect("write input");
+
+    let options = engine::EncryptOptions {
+        compression: Some(engine::Compression::Zstd(3)),
+        ..Default::default()
+    };
+    engine::encrypt_with_options(&input_path, &encrypted_path, "pw", &options).expect("encrypt");
+
+    let err = engine::decrypt_with_checkpoint(
+        &encrypted_path,
+        &output_path,
+        "pw",
+        &engine::CheckpointOptions::default(),
+    )
+    .expect_err("compressed file must be rejected");
+    assert!(
+        matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+        "unexpected error: {err:?}"
+    );
+    assert!(!output_path.exists());
+}
+
+/// 测试预先放在 `.svpart` 位置的符号链接不会被跟随：链接目标保持不变，解密照常完成
+#[cfg(unix)]
+#[test]
+fn decrypt_checkpoint_does_not_follow_planted_partial_symlink() {
+    let temp = tempdir().expect("create temp dir");
+    let input_path = temp.path().join("small.txt");
+    let encrypted_path = temp.path().join("small.svlt");
+    let output_path = temp.path().join("small.out");
+    let victim = temp.path().join("victim.txt");
+
+    fs::write(&input_path, b"symlink payload").expect("write input");
+    engine::encrypt(&input_path, &encrypted_path, "resume-password").expect("encrypt");
+    fs::write(&victim, b"do not overwrite").expect("write victim");
+    std::os::unix::fs::symlink(&victim, temp.path().join("small.out.svpart"))
+        .expect("plant symlink");
+
+    engine::decrypt_with_checkpoint(
+        &encrypted_path,
+        &output_path,
+        "resume-password",
+        &engine::CheckpointOptions::default(),
+    )
+    .expect("decrypt");
+
+    assert_eq!(
+        fs::read(&output_path).expect("read output"),
+        b"symlink payload"
+    );
+    assert_eq!(fs::read(&victim).expect("read victim"), b"do not overwrite");
+}
+
+/// 测试带明文摘要的文件：从头解密与恢复都比对摘要，恢复时保留的前缀被改动即视为损坏
+#[test]
+fn decrypt_checkpoint_checks_plaintext_digest() {
+    let temp = tempdir().expect("create temp dir");
+    let input_path = temp.path().join("big.bin");
+    let full_path = temp.path().join("big.full.svlt");
+    let encrypted_path = temp.path().join("big.svlt");
+    let output_path = temp.path().join("restored.bin");
+    let partial_path = temp.path().join("restored.bin.svpart");
+
+    let plaintext: Vec<u8> = (0..(5 * CHUNK + 123) as u32)
+        .map(|i| (i % 251) as u8)
+        .collect();
+    fs::write(&input_path, &plaintext).expect("write input");
+    let encrypt_options = engine::EncryptOptions {
+        plaintext_digest: true,
+        ..Default::default()
+    };
+    engine::encrypt_with_options(&input_path, &full_path, "pw", &encrypt_options).expect("encrypt");
+    let ciphertext = fs::read(&full_path).expect("read ciphertext");
+
+    // 从头解密：摘要一致
+    let fresh = engine::CheckpointOptions {
+        interval_chunks: 1,
+        resume: false,
+    };
+    engine::decrypt_with_checkpoint(&full_path, &output_path, "pw", &fresh).expect("fresh decrypt");
+    assert_eq!(fs::read(&output_path).expect("read output"), plaintext);
+    fs::remove_file(&output_path).expect("remove output");
+
+    // 中途失败后改动 `.svpart` 中已记录的前缀，恢复时摘要不一致
+    let options = engine::CheckpointOptions {
+        interval_chunks: 1,
+        resume: true,
+    };
+    fs::write(
+        &encrypted_path,
+        &ciphertext[..HEADER_LEN + 3 * FRAME_LEN + 100],
+    )
+    .expect("write truncated ciphertext");
+    assert!(
+        engine::decrypt_with_checkpoint(&encrypted_path, &output_path, "pw", &options).is_err()
+    );
+    let mut partial = fs::read(&partial_path).expect("read partial");
+    partial[10] ^= 0xff;
+    fs::write(&partial_path, &partial).expect("tamper partial");
+
+    fs::write(&encrypted_path, &ciphertext).expect("write full ciphertext");
+    let err = engine::decrypt_with_checkpoint(&encrypted_path, &output_path, "pw", &options)
+        .expect_err("tampered prefix must be rejected");
+    assert!(
+        matches!(err, engine::SealVaultError::CorruptedData),
+        "unexpected error: {err:?}"
+    );
+    assert!(!output_path.exists());
+}
@@ -0,0 +1,302 @@
+//! `DecryptOptions` 测试
+#![cfg(feature = "fs")]
+
+use std::fs;
+
+use engine::crypto::kdf::{KdfAlgorithm, KdfParams, ScryptParams};
+use engine::format::header::Header;
+use engine::{AeadAlgorithm, Compression, DecryptOptions, EncryptOptions, SealVaultError};
+use tempfile::tempdir;
+
+fn limited(max_output_bytes: u64) -> DecryptOptions {
+    DecryptOptions {
+        max_output_bytes: Some(max_output_bytes),
+        ..DecryptOptions::default()
+    }
+}
+
+/// 测试明文超过上限时中止解密，不留下输出与临时文件；恰好等于上限时正常解密
+#[test]
+fn max_output_bytes_aborts_and_removes_partial_output() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    let output_dir = temp.path().join("out");
+    let output = output_dir.join("output.bin");
+    let plaintext = vec![0x5au8; 10_000];
+    fs::write(&input, &plaintext).expect("write input");
+    fs::create_dir(&output_dir).expect("create output dir");
+
+    let options = EncryptOptions {
+        chunk_size: 1024,
+        ..EncryptOptions::default()
+    };
+    engine::encrypt_with_options(&input, &encrypted, "limit-password", &options).expect("encrypt");
+
+    let err = engine::decrypt_with_options(&encrypted, &output, "limit-password", &limited(4096))
+        .expect_err("limit exceeded");
+    assert!(
+        matches!(err, SealVaultError::OutputLimitExceeded),
+        "{err:?}"
+    );
+    assert_eq!(
+        fs::read_dir(&output_dir).expect("read output dir").count(),
+        0,
+        "partial output left behind"
+    );
+
+    engine::decrypt_with_options(&encrypted, &output, "limit-password", &limited(10_000))
+        .expect("decrypt at the limit");
+    assert_eq!(fs::read(&output).expect("read output"), plaintext);
+}
+
+/// 测试上限按解压后的明文计算，可拦截高压缩比的输入
+#[test]
+fn max_output_bytes_counts_decompressed_plaintext() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("zeros.bin");
+    let encrypted = temp.path().join("zeros.svlt");
+    let output = temp.path().join("zeros.out");
+    fs::write(&input, vec![0u8; 4 << 20]).expect("write input");
+
+    let options = EncryptOptions {
+        compression: Some(Compression::Zstd(19)),
+        ..EncryptOptions::default()
+    };
+    engine::encrypt_with_options(&input, &encrypted, "limit-password", &options).expect("encrypt");
+    assert!(fs::metadata(&encrypted).expect("metadata").len() < 64 * 1024);
+
+    let err =
+        engine::decrypt_with_options(&encrypted, &output, "limit-password", &limited(64 * 1024))
+            .expect_err("limit exceeded");
+    assert!(
+        matches!(err, SealVaultError::OutputLimitExceeded),
+        "{err:?}"
+    );
+    assert!(!output.exists());
+
+    // 默认不限制
+    engine::decrypt_with_options(
+        &encrypted,
+        &output,
+        "limit-password",
+        &DecryptOptions::default(),
+    )
+    .expect("decrypt without limit");
+    assert_eq!(fs::metadata(&output).expect("metadata").len(), 4 << 20);
+}
+
+/// 测试 `expected_algorithm` 不一致时在派生密钥之前报错（密码错误也先报告算法不符），一致时正常解密
+#[test]
+fn expected_algorithm_mismatch_fails_before_key_derivation() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    let output = temp.path().join("output.bin");
+    fs::write(&input, b"algorithm pinned").expect("write input");
+
+    engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        "algorithm-password",
+        &EncryptOptions::with_algorithm(AeadAlgorithm::Aes256Gcm),
+    )
+    .expect("encrypt");
+
+    let expect_xchacha = DecryptOptions {
+        expected_algorithm: Some(AeadAlgorithm::XChaCha20Poly1305),
+        ..DecryptOptions::default()
+    };
+    for password in ["algorithm-password", "wrong-password"] {
+        let err = engine::decrypt_with_options(&encrypted, &output, password, &expect_xchacha)
+            .expect_err("unexpected algorithm");
+        assert!(
+            matches!(
+                err,
+                SealVaultError::UnexpectedAlgorithm {
+                    expected: AeadAlgorithm::XChaCha20Poly1305,
+                    found: AeadAlgorithm::Aes256Gcm,
+                }
+            ),
+            "{err:?}"
+        );
+        assert!(!output.exists());
+    }
+
+    let expect_aes = DecryptOptions {
+        expected_algorithm: Some(AeadAlgorithm::Aes256Gcm),
+        ..DecryptOptions::default()
+    };
+    engine::decrypt_with_options(&encrypted, &output, "algorithm-password", &expect_aes)
+        .expect("expected algorithm");
+    assert_eq!(fs::read(&output).expect("read output"), b"algorithm pinned");
+}
+
+/// 测试 `reject_trailing_data`：final chunk 之后追加的数据默认被忽略，开启后报告 `CorruptedData`
+#[test]
+fn reject_trailing_data_detects_appended_bytes() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    let output = temp.path().join("output.bin");
+    let plaintext = b"strict ending ".repeat(200);
+    fs::write(&input, &plaintext).expect("write input");
+
+    let options = EncryptOptions {
+        chunk_size: 1024,
+        plaintext_digest: true,
+        ..EncryptOptions::default()
+    };
+    engine::encrypt_with_options(&input, &encrypted, "trailing-password", &options)
+        .expect("encrypt");
+    let strict = DecryptOptions {
+        reject_trailing_data: true,
+        ..DecryptOptions::default()
+    };
+
+    // 没有多余数据时严格模式正常解密
+    engine::decrypt_with_options(&encrypted, &output, "trailing-password", &strict)
+        .expect("decrypt strict");
+    assert_eq!(fs::read(&output).expect("read output"), plaintext);
+    fs::remove_file(&output).expect("remove output");
+
+    let mut data = fs::read(&encrypted).expect("read encrypted");
+    data.extend_from_slice(b"appended junk");
+    fs::write(&encrypted, &data).expect("write appended");
+
+    engine::decrypt_with_options(
+        &encrypted,
+        &output,
+        "trailing-password",
+        &DecryptOptions::default(),
+    )
+    .expect("trailing data ignored by default");
+    assert_eq!(fs::read(&output).expect("read output"), plaintext);
+    fs::remove_file(&output).expect("remove output");
+
+    let err = engine::decrypt_with_options(&encrypted, &output, "trailing-password", &strict)
+        .expect_err("trailing data");
+    assert!(matches!(err, SealVaultError::CorruptedData), "{err:?}");
+    assert!(!output.exists());
+}
+
+/// 测试解密默认与加密一样逐级创建不存在的输出目录；`create_parents` 为 `false` 时
+/// 在派生密钥之前返回 `NotFound`（密码错误也一样），不建出任何目录
+#[test]
+fn missing_output_parents_are_created_unless_disabled() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("nested").join("enc").join("input.svlt");
+    let output = temp.path().join("a").join("b").join("c").join("output.bin");
+    fs::write(&input, b"nested output payload").expect("write input");
+
+    engine::encrypt(&input, &encrypted, "parents-password").expect("encrypt");
+    engine::decrypt(&encrypted, &output, "parents-password").expect("decrypt");
+    assert_eq!(
+        fs::read(&output).expect("read output"),
+        b"nested output payload"
+    );
+
+    let strict = DecryptOptions {
+        create_parents: false,
+        ..DecryptOptions::default()
+    };
+    let missing = temp.path().join("x").join("y").join("output.bin");
+    for password in ["parents-password", "wrong-password"] {
+        let err = engine::decrypt_with_options(&encrypted, &missing, password, &strict)
+            .expect_err("missing parent");
+        assert!(
+            matches!(&err, SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::NotFound),
+            "{err:?}"
+        );
+    }
+    assert!(!temp.path().join("x").exists());
+
+    // 父目录已存在时不受影响
+    fs::remove_file(&output).expect("remove output");
+    engine::decrypt_with_options(&encrypted, &output, "parents-password", &strict)
+        .expect("decrypt into existing directory");
+    assert_eq!(
+        fs::read(&output).expect("read output"),
+        b"nested output payload"
+    );
+}
+
+/// 测试 KDF 参数超过 `max_kdf_memory` / `max_kdf_time` 时在派生密钥之前报告 `KdfResourceExhausted`
+/// （密码错误也先报告超限）；恰好等于上限时正常解密，默认上限拒绝声称需要 2 GiB 内存的 Header
+#[test]
+fn kdf_limits_are_checked_before_key_derivation() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    let output = temp.path().join("output.bin");
+    fs::write(&input, b"kdf limited").expect("write input");
+
+    // 128 * r * N = 1 MiB，p = 2
+    let kdf = KdfAlgorithm::Scrypt(ScryptParams {
+        log_n: 10,
+        r: 8,
+        p: 2,
+    });
+    assert_eq!(kdf.memory_bytes(), 1024 * 1024);
+    let options = EncryptOptions {
+        kdf,
+        ..EncryptOptions::default()
+    };
+    engine::encrypt_with_options(&input, &encrypted, "kdf-password", &options).expect("encrypt");
+
+    let too_little_memory = DecryptOptions {
+        max_kdf_memory: 1024 * 1024 - 1,
+        ..DecryptOptions::default()
+    };
+    let too_few_passes = DecryptOptions {
+        max_kdf_time: 1,
+        ..DecryptOptions::default()
+    };
+    for limits in [&too_little_memory, &too_few_passes] {
+        for password in ["kdf-password", "wrong-password"] {
+            let err = engine::decrypt_with_options(&encrypted, &output, password, limits)
+                .expect_err("kdf over limit");
+            assert!(
+                matches!(err, SealVaultError::KdfResourceExhausted),
+                "{err:?}"
+            );
+            assert!(!output.exists());
+        }
+    }
+
+    let exact = DecryptOptions {
+        max_kdf_memory: 1024 * 1024,
+        max_kdf_time: 2,
+        ..DecryptOptions::default()
+    };
+    engine::decrypt_with_options(&encrypted, &output, "kdf-password", &exact)
+        .expect("kdf within limit");
+    assert_eq!(fs::read(&output).expect("read output"), b"kdf limited");
+
+    // 伪造 Header 要求 2 GiB 的 Argon2id：默认上限在分配内存之前拒绝
+    let original = fs::read(&encrypted).expect("read encrypted");
+    let header = Header::read(&original[..]).expect("read header");
+    let mut encoded = Vec::new();
+    header.write(&mut encoded).expect("write header");
+    let mut forged = Vec::new();
+    Header {
+        kdf: KdfAlgorithm::Argon2id(KdfParams {
+            m_cost: 2 * 1024 * 1024,
+            t_cost: 3,
+            p_cost: 1,
+        }),
+        ..header
+    }
+    .write(&mut forged)
+    .expect("write forged header");
+    forged.extend_from_slice(&original[encoded.len()..]);
+
+    let err =
+        engine::decrypt_bytes_with_options(&forged, "kdf-password", &DecryptOptions::default())
+            .expect_err("default kdf limit");
+    assert!(
+        matches!(err, SealVaultError::KdfResourceExhausted),
+        "{err:?}"
+    );
+}
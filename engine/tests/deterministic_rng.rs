@@ -0,0 +1,75 @@
+//! 注入随机数源（`encrypt_with_rng`）测试
+#![cfg(feature = "fs")]
+
+mod common;
+
+use std::fs;
+
+use common::fast_options;
+use engine::AeadAlgorithm;
+use rand::rngs::OsRng;
+use rand_chacha::ChaCha20Rng;
+use rand_chacha::rand_core::SeedableRng;
+use tempfile::tempdir;
+
+/// 测试相同种子的 RNG 两次加密得到逐字节相同的输出，且输出可正常解密
+#[test]
+fn seeded_rng_produces_identical_ciphertext() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let first = temp.path().join("first.svlt");
+    let second = temp.path().join("second.svlt");
+    let plaintext = b"deterministic ".repeat(500);
+    fs::write(&input, &plaintext).expect("write input");
+
+    for output in [&first, &second] {
+        let mut rng = ChaCha20Rng::seed_from_u64(42);
+        engine::encrypt_with_rng(
+            &input,
+            output,
+            "rng-password",
+            &fast_options(AeadAlgorithm::XChaCha20Poly1305),
+            &mut rng,
+        )
+        .expect("encrypt");
+    }
+    assert_eq!(
+        fs::read(&first).expect("read first"),
+        fs::read(&second).expect("read second")
+    );
+
+    let decrypted = temp.path().join("decrypted.bin");
+    engine::decrypt(&first, &decrypted, "rng-password").expect("decrypt");
+    assert_eq!(fs::read(&decrypted).expect("read decrypted"), plaintext);
+}
+
+/// 测试不同种子与 `OsRng` 下两次加密的输出均不同
+#[test]
+fn different_rngs_produce_different_ciphertext() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let first = temp.path().join("first.svlt");
+    let second = temp.path().join("second.svlt");
+    fs::write(&input, b"same plaintext").expect("write input");
+
+    let options = fast_options(AeadAlgorithm::XChaCha20Poly1305);
+    engine::encrypt_with_rng(&input, &first, "rng-password", &options, &mut OsRng)
+        .expect("encrypt first");
+    engine::encrypt_with_rng(&input, &second, "rng-password", &options, &mut OsRng)
+        .expect("encrypt second");
+    assert_ne!(
+        fs::read(&first).expect("read first"),
+        fs::read(&second).expect("read second")
+    );
+
+    let mut rng = ChaCha20Rng::seed_from_u64(1);
+    engine::encrypt_with_rng(&input, &first, "rng-password", &options, &mut rng)
+        .expect("encrypt seed 1");
+    let mut rng = ChaCha20Rng::seed_from_u64(2);
+    engine::encrypt_with_rng(&input, &second, "rng-password", &options, &mut rng)
+        .expect("encrypt seed 2");
+    assert_ne!(
+        fs::read(&first).expect("read first"),
+        fs::read(&second).expect("read second")
+    );
+}
@@ -0,0 +1,332 @@
+//! SealVault 断点续加密测试
+//!
+//! 把完整密文截断到第 N 个 chunk 并写入对应的 checkpoint，模拟中途中断后再恢复。
+#![cfg(feature = "fs")]
+
+mod common;
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use common::{FAST_SCRYPT, fast_options};
+use engine::crypto::kdf::derive_key_scrypt;
+use engine::format::checkpoint::{Checkpoint, InputFingerprint, input_prefix_digest};
+use engine::format::header::Header;
+use tempfile::tempdir;
+
+const CHUNK: usize = 1024;
+const FRAME_LEN: usize = 4 + CHUNK + 16;
+/// 最后一个不完整的数据 chunk（`TAIL` 字节明文）与 final chunk 的长度
+const TAIL: usize = 123;
+const TRAILER_LEN: usize = (4 + TAIL + 16) + (4 + 16);
+
+/// 模拟在写完 `chunks` 个数据 chunk 后中断：`.svpart` 截断到该位置（并残留垃圾），
+/// `.svckpt` 记录该位置与 `input` 当前的指纹
+fn simulate_interruption(
+    complete: &[u8],
+    input: &Path,
+    password: &str,
+    output: &Path,
+    chunks: usize,
+) {
+    let header = Header::read(complete).expect("read header");
+    let header_len = complete.len() - 5 * FRAME_LEN - TRAILER_LEN;
+    let offset = header_len + chunks * FRAME_LEN;
+    let key =
+        derive_key_scrypt(password.as_bytes(), &header.salt, &FAST_SCRYPT).expect("derive key");
+    let plaintext = fs::read(input).expect("read input");
+    let fingerprint = InputFingerprint::new(
+        &fs::metadata(input).expect("input metadata"),
+        input_prefix_digest(&key[..], &plaintext[..chunks * CHUNK]),
+    );
+
+    let partial = output.with_extension("svlt.svpart");
+    let mut file = fs::File::create(&partial).expect("create partial");
+    file.write_all(&complete[..offset]).expect("write partial");
+    file.write_all(b"half-written chunk")
+        .expect("write garbage");
+
+    Checkpoint {
+        salt: header.salt,
+        base_nonce: header.base_nonce,
+        chunk_index: chunks as u64,
+        output_offset: offset as u64,
+        input: Some(fingerprint),
+    }
+    .store(&output.with_extension("svlt.svckpt"))
+    .expect("store checkpoint");
+}
+
+/// 测试中断后从 checkpoint 恢复，结果与一次完成的加密逐字节相同
+#[test]
+fn encrypt_resumes_from_mid_file_checkpoint() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("big.bin");
+    let output = temp.path().join("big.svlt");
+    let partial = temp.path().join("big.svlt.svpart");
+    let checkpoint_path = temp.path().join("big.svlt.svckpt");
+
+    let plaintext: Vec<u8> = (0..(5 * CHUNK + TAIL) as u32)
+        .map(|i| (i % 253) as u8)
+        .collect();
+    fs::write(&input, &plaintext).expect("write input");
+
+    let checkpoint = engine::CheckpointOptions {
+        interval_chunks: 1,
+        resume: true,
+    };
+    engine::encrypt_with_checkpoint(
+        &input,
+        &output,
+        "resume-pw",
+        &fast_options(engine::AeadAlgorithm::XChaCha20Poly1305),
+        &checkpoint,
+    )
+    .expect("encrypt");
+    assert!(!partial.exists());
+    assert!(!checkpoint_path.exists());
+    let complete = fs::read(&output).expect("read output");
+    fs::remove_file(&output).expect("remove output");
+
+    // 写完 3 个 chunk 后中断，恢复时沿用原 Header，只加密剩余的 chunk
+    simulate_interruption(&complete, &input, "resume-pw", &output, 3);
+    engine::encrypt_with_checkpoint(
+        &input,
+        &output,
+        "resume-pw",
+        &fast_options(engine::AeadAlgorithm::XChaCha20Poly1305),
+        &checkpoint,
+    )
+    .expect("resume encrypt");
+
+    assert_eq!(fs::read(&output).expect("read resumed"), complete);
+    assert!(!partial.exists());
+    assert!(!checkpoint_path.exists());
+    assert_eq!(
+        engine::decrypt_bytes(&complete, "resume-pw").expect("decrypt"),
+        plaintext
+    );
+}
+
+/// 测试恢复时密码错误不会续写，且 `.svpart` 保持不变
+#[test]
+fn encrypt_resume_with_wrong_password_fails() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("big.bin");
+    let output = temp.path().join("big.svlt");
+    let partial = temp.path().join("big.svlt.svpart");
+
+    fs::write(&input, vec![7u8; 5 * CHUNK + TAIL]).expect("write input");
+    engine::encrypt_with_options(
+        &input,
+        &output,
+        "resume-pw",
+        &fast_options(engine::AeadAlgorithm::XChaCha20Poly1305),
+    )
+    .expect("encrypt");
+    let complete = fs::read(&output).expect("read output");
+    fs::remove_file(&output).expect("remove output");
+
+    simulate_interruption(&complete, &input, "resume-pw", &output, 2);
+    let before = fs::read(&partial).expect("read partial");
+
+    let err = engine::encrypt_with_checkpoint(
+        &input,
+        &output,
+        "wrong-pw",
+        &fast_options(engine::AeadAlgorithm::XChaCha20Poly1305),
+        &engine::CheckpointOptions::default(),
+    )
+    .expect_err("wrong password must fail");
+    assert!(matches!(err, engine::SealVaultError::InvalidPasswordOrFile));
+    assert!(!output.exists());
+    assert_eq!(fs::read(&partial).expect("read partial"), before);
+
+    // 不恢复时从头开始，输出使用新的 salt
+    engine::encrypt_with_checkpoint(
+        &input,
+        &output,
+        "resume-pw",
+        &fast_options(engine::AeadAlgorithm::XChaCha20Poly1305),
+        &engine::CheckpointOptions {
+            resume: false,
+            ..Default::default()
+        },
+    )
+    .expect("fresh encrypt");
+    assert_ne!(
+        engine::inspect(&output).expect("inspect").salt,
+        Header::read(&complete[..]).expect("read header").salt
+    );
+}
+
+/// 测试输入在两次运行之间改变时拒绝恢复（否则会以同一 nonce 加密不同明文），
+/// `.svpart` 与 checkpoint 保持不变；不记录输入指纹的旧 checkpoint 同样拒绝
+#[test]
+fn encrypt_resume_rejects_changed_input() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("big.bin");
+    let output = temp.path().join("big.svlt");
+    let partial = temp.path().join("big.svlt.svpart");
+    let checkpoint_path = temp.path().join("big.svlt.svckpt");
+
+    let plaintext = vec![0x42u8; 5 * CHUNK + TAIL];
+    fs::write(&input, &plaintext).expect("write input");
+    engine::encrypt_with_options(
+        &input,
+        &output,
+        "resume-pw",
+        &fast_options(engine::AeadAlgorithm::XChaCha20Poly1305),
+    )
+    .expect("encrypt");
+    let complete = fs::read(&output).expect("read output");
+    fs::remove_file(&output).expect("remove output");
+
+    let assert_refused = || {
+        let before = fs::read(&partial).expect("read partial");
+        let err = engine::encrypt_with_checkpoint(
+            &input,
+            &output,
+            "resume-pw",
+            &fast_options(engine::AeadAlgorithm::XChaCha20Poly1305),
+            &engine::CheckpointOptions::default(),
+        )
+        .expect_err("changed input must not be resumed");
+        assert!(
+            matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidData),
+            "{err:?}"
+        );
+        assert!(!output.exists());
+        assert_eq!(fs::read(&partial).expect("read partial"), before);
+        assert!(checkpoint_path.exists());
+    };
+
+    // 已加密前缀中的一个字节被改动，长度与修改时间均恢复原状，只能由前缀摘要发现
+    simulate_interruption(&complete, &input, "resume-pw", &output, 3);
+    let modified = fs::metadata(&input)
+        .expect("input metadata")
+        .modified()
+        .expect("mtime");
+    let mut edited = plaintext.clone();
+    edited[CHUNK + 7] ^= 0x01;
+    fs::write(&input, &edited).expect("edit input");
+    fs::File::options()
+        .write(true)
+        .open(&input)
+        .expect("open input")
+        .set_modified(modified)
+        .expect("restore mtime");
+    assert_refused();
+
+    // 只改动尚未加密的部分：由长度发现
+    fs::write(&input, &plaintext).expect("restore input");
+    simulate_interruption(&complete, &input, "resume-pw", &output, 3);
+    fs::OpenOptions::new()
+        .append(true)
+        .open(&input)
+        .expect("open input")
+        .write_all(b"appended")
+        .expect("append");
+    assert_refused();
+
+    // checkpoint 未记录输入指纹
+    fs::write(&input, &plaintext).expect("restore input");
+    simulate_interruption(&complete, &input, "resume-pw", &output, 3);
+    let mut checkpoint = Checkpoint::load(&checkpoint_path)
+        .expect("load checkpoint")
+        .expect("checkpoint exists");
+    checkpoint.input = None;
+    checkpoint
+        .store(&checkpoint_path)
+        .expect("store checkpoint");
+    assert_refused();
+}
+
+/// 测试预先放在 `.svpart` 位置的符号链接不会被跟随：从头开始时替换链接本身，
+/// 恢复时拒绝链接，链接目标始终保持不变
+#[cfg(unix)]
+#[test]
+fn encrypt_checkpoint_does_not_follow_planted_partial_symlink() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("data.bin");
+    let output = temp.path().join("data.svlt");
+    let partial = temp.path().join("data.svlt.svpart");
+    let victim = temp.path().join("victim.txt");
+    fs::write(&input, vec![9u8; 3 * CHUNK]).expect("write input");
+    fs::write(&victim, b"do not overwrite").expect("write victim");
+
+    std::os::unix::fs::symlink(&victim, &partial).expect("plant symlink");
+    engine::encrypt_with_checkpoint(
+        &input,
+        &output,
+        "resume-pw",
+        &fast_options(engine::AeadAlgorithm::XChaCha20Poly1305),
+        &engine::CheckpointOptions::default(),
+    )
+    .expect("fresh encrypt");
+    assert_eq!(fs::read(&victim).expect("read victim"), b"do not overwrite");
+    assert_eq!(
+        engine::decrypt_bytes(&fs::read(&output).expect("read output"), "resume-pw")
+            .expect("decrypt"),
+        vec![9u8; 3 * CHUNK]
+    );
+
+    // 存在匹配的 checkpoint 时，`.svpart` 为符号链接则拒绝恢复
+    let header = Header::read(&fs::read(&output).expect("read output")[..]).expect("header");
+    Checkpoint::new(&header, 1, 0)
+        .store(&temp.path().join("data.svlt.svckpt"))
+        .expect("store checkpoint");
+    std::os::unix::fs::symlink(&victim, &partial).expect("plant symlink");
+    let err = engine::encrypt_with_checkpoint(
+        &input,
+        &output,
+        "resume-pw",
+        &fast_options(engine::AeadAlgorithm::XChaCha20Poly1305),
+        &engine::CheckpointOptions::default(),
+    )
+    .expect_err("symlinked partial");
+    assert!(
+        matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+        "{err:?}"
+    );
+    assert_eq!(fs::read(&victim).expect("read victim"), b"do not overwrite");
+}
+
+/// 测试不支持断点续做的选项被拒绝
+#[test]
+fn encrypt_checkpoint_rejects_unsupported_options() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("data.bin");
+    let output = temp.path().join("data.svlt");
+    fs::write(&input, b"data").expect("write input");
+
+    let unsupported = [
+        engine::EncryptOptions {
+            compression: Some(engine::Compression::Zstd(3)),
+            ..fast_options(engine::AeadAlgorithm::XChaCha20Poly1305)
+        },
+        engine::EncryptOptions {
+            padding: Some(engine::Padding::ToBlock(4096)),
+            ..fast_options(engine::AeadAlgorithm::XChaCha20Poly1305)
+        },
+        engine::EncryptOptions {
+            plaintext_digest: true,
+            ..fast_options(engine::AeadAlgorithm::XChaCha20Poly1305)
+        },
+    ];
+    for options in &unsupported {
+        let err = engine::encrypt_with_checkpoint(
+            &input,
+            &output,
+            "pw",
+            options,
+            &engine::CheckpointOptions::default(),
+        )
+        .expect_err("unsupported option");
+        assert!(
+            matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput)
+        );
+    }
+    assert!(!output.exists());
+}
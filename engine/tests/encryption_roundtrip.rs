@@ -8,6 +8,9 @@
 //! 3. 解密加密文件，并验证解密结果与输入文件相同。
 //! 4. 使用错误的密码解密加密文件，并验证解密失败。
 //! 5. 测试解密失败时的错误处理。
+#![cfg(feature = "fs")]
+
+mod common;
 
 use std::fs;
 use std::io::Write;
@@ -64,6 +67,75 @@ fn encrypt_decrypt_roundtrip_with_aes_256_gcm() {
     assert_eq!(decrypted, plaintext);
 }
 
+/// 测试加密解密流程(ChaCha20-Poly1305 IETF)
+#[test]
+fn encrypt_decrypt_roundtrip_with_chacha20_poly1305() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.bin");
+    let encrypted_path = temp_dir.path().join("output_chacha.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.bin");
+
+    // 跨越多个 chunk，覆盖 nonce 计数器
+    let plaintext: Vec<u8> = (0..150_000u32).map(|i| (i % 233) as u8).collect();
+    fs::write(&input_path, &plaintext).expect("write plaintext");
+
+    engine::encrypt_with_algorithm(
+        &input_path,
+        &encrypted_path,
+        "test-password",
+        engine::AeadAlgorithm::ChaCha20Poly1305,
+    )
+    .expect("encrypt file with chacha20");
+
+    let header = engine::format::header::Header::read(
+        fs::File::open(&encrypted_path).expect("open encrypted"),
+    )
+    .expect("read header");
+    assert_eq!(header.algorithm, engine::AeadAlgorithm::ChaCha20Poly1305);
+
+    engine::decrypt(&encrypted_path, &decrypted_path, "test-password").expect("decrypt file");
+
+    let decrypted = fs::read(&decrypted_path).expect("read decrypted");
+    assert_eq!(decrypted, plaintext);
+}
+
+/// 测试 AES-128-GCM：Header 记录算法 ID 4，解密按 Header 选择 16 字节密钥
+#[test]
+fn aes128gcm_encrypt_decrypt_roundtrip() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.bin");
+    let encrypted_path = temp_dir.path().join("output_aes128.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.bin");
+
+    let plaintext: Vec<u8> = (0..150_000u32).map(|i| (i % 227) as u8).collect();
+    fs::write(&input_path, &plaintext).expect("write plaintext");
+
+    engine::encrypt_with_algorithm(
+        &input_path,
+        &encrypted_path,
+        "test-password",
+        engine::AeadAlgorithm::Aes128Gcm,
+    )
+    .expect("encrypt file with aes-128-gcm");
+
+    let header = engine::format::header::Header::read(
+        fs::File::open(&encrypted_path).expect("open encrypted"),
+    )
+    .expect("read header");
+    assert_eq!(header.algorithm, engine::AeadAlgorithm::Aes128Gcm);
+    assert_eq!(header.algorithm.to_u8(), 4);
+    assert_eq!(header.algorithm.key_len(), 16);
+
+    engine::decrypt(&encrypted_path, &decrypted_path, "test-password").expect("decrypt file");
+    assert_eq!(
+        fs::read(&decrypted_path).expect("read decrypted"),
+        plaintext
+    );
+
+    let err = engine::verify(&encrypted_path, "wrong-password").expect_err("wrong password");
+    assert!(matches!(err, engine::SealVaultError::InvalidPasswordOrFile));
+}
+
 /// 测试解密失败(missing key)时的错误处理
 #[test]
 fn decrypt_with_wrong_password_fails() {
@@ -81,8 +153,13 @@ fn decrypt_with_wrong_password_fails() {
 
     engine::encrypt(&input_path, &encrypted_path, "correct-password").expect("encrypt file");
 
-    let result = engine::decrypt(&encrypted_path, &decrypted_path, "wrong-password");
-    assert!(result.is_err(), "expected decrypt to fail");
+    let err = engine::decrypt(&encrypted_path, &decrypted_path, "wrong-password")
+        .expect_err("expected decrypt to fail");
+    assert!(
+        matches!(err, engine::SealVaultError::InvalidPasswordOrFile),
+        "unexpected error: {err:?}"
+    );
+    assert!(!decrypted_path.exists());
 }
 
 /// 测试错误密码解密失败时，不覆盖已有输出文件
@@ -105,6 +182,42 @@ fn decrypt_with_wrong_password_keeps_existing_output_file() {
     assert_eq!(existing, b"do-not-overwrite");
 }
 
+/// 测试中间 chunk 损坏时不留下任何部分明文（包括临时文件）
+#[test]
+fn decrypt_with_corrupted_middle_chunk_leaves_no_output() {
+    const HEADER_LEN: usize = 93;
+    const FRAME_LEN: usize = 4 + 64 * 1024 + 16;
+
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.bin");
+    let encrypted_path = temp_dir.path().join("input.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.bin");
+
+    let plaintext: Vec<u8> = (0..5 * 64 * 1024u32).map(|i| (i % 241) as u8).collect();
+    fs::write(&input_path, &plaintext).expect("write input");
+    engine::encrypt(&input_path, &encrypted_path, "test-password").expect("encrypt file");
+
+    // 翻转第 3 个 chunk 密文中的一个字节，前两个 chunk 仍能通过认证
+    let mut encrypted = fs::read(&encrypted_path).expect("read encrypted");
+    encrypted[HEADER_LEN + 2 * FRAME_LEN + 100] ^= 0x01;
+    fs::write(&encrypted_path, &encrypted).expect("write corrupted");
+
+    let err = engine::decrypt(&encrypted_path, &decrypted_path, "test-password")
+        .expect_err("corrupted chunk must fail");
+    assert!(
+        matches!(err, engine::SealVaultError::CorruptedData),
+        "unexpected error: {err:?}"
+    );
+    assert!(!decrypted_path.exists());
+
+    let leftovers: Vec<_> = fs::read_dir(temp_dir.path())
+        .expect("list temp dir")
+        .map(|entry| entry.expect("dir entry").file_name())
+        .filter(|name| name.to_string_lossy().contains(".tmp-"))
+        .collect();
+    assert!(leftovers.is_empty(), "temp files left: {leftovers:?}");
+}
+
 /// 测试解密失败(invalid header magic)时的错误处理
 #[test]
 fn decrypt_rejects_invalid_header_magic() {
@@ -114,6 +227,969 @@ fn decrypt_rejects_invalid_header_magic() {
 
     fs::write(&bad_path, b"not a sealvault file").expect("write bad file");
 
-    let result = engine::decrypt(&bad_path, &output_path, "password");
-    assert!(result.is_err(), "expected invalid header");
+    let err =
+        engine::decrypt(&bad_path, &output_path, "password").expect_err("expected invalid header");
+    assert!(
+        matches!(err, engine::SealVaultError::NotSealVaultFile),
+        "unexpected error: {err:?}"
+    );
+
+    // 比 magic 还短的文件同样不是 SealVault 文件，而不是密码错误
+    fs::write(&bad_path, b"SVLT").expect("write short file");
+    let err = engine::verify(&bad_path, "password").expect_err("expected short file to fail");
+    assert!(
+        matches!(err, engine::SealVaultError::NotSealVaultFile),
+        "unexpected error: {err:?}"
+    );
+    assert!(!output_path.exists());
+}
+
+/// 测试未知版本、缺失输入文件分别映射为对应的错误变体
+#[test]
+fn decrypt_reports_unsupported_version_and_io_errors() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let future_path = temp_dir.path().join("future.svlt");
+    let output_path = temp_dir.path().join("output.txt");
+
+    let mut future = engine::format::header::MAGIC.to_vec();
+    future.push(0xff);
+    future.extend_from_slice(&[0u8; 64]);
+    fs::write(&future_path, future).expect("write future file");
+
+    let err = engine::decrypt(&future_path, &output_path, "password")
+        .expect_err("expected unsupported version");
+    assert!(
+        matches!(err, engine::SealVaultError::UnsupportedVersion),
+        "unexpected error: {err:?}"
+    );
+
+    let err = engine::decrypt(
+        &temp_dir.path().join("missing.svlt"),
+        &output_path,
+        "password",
+    )
+    .expect_err("expected missing input");
+    assert!(
+        matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::NotFound),
+        "unexpected error: {err:?}"
+    );
+    // 底层 I/O 错误经 `source()` 暴露给错误链，不重复写入 `Display`
+    let source = std::error::Error::source(&err).expect("io source");
+    assert_eq!(
+        source
+            .downcast_ref::<std::io::Error>()
+            .expect("io::Error source")
+            .kind(),
+        std::io::ErrorKind::NotFound
+    );
+    assert_eq!(err.to_string(), "I/O error");
+}
+
+/// 测试 `EncryptOptions::checksum_sidecar` 走普通加密流程：`overwrite`、chunk 大小、压缩与填充
+/// 照常生效，sidecar 按压缩前的明文计算；加密被拒绝时不写 sidecar
+#[test]
+fn checksum_sidecar_option_respects_other_options() {
+    use sha2::{Digest, Sha256};
+
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("report.txt");
+    let encrypted_path = temp_dir.path().join("report.txt.svlt");
+    let sidecar_path = temp_dir.path().join("report.txt.svlt.sha256");
+    let decrypted_path = temp_dir.path().join("decrypted.txt");
+
+    let plaintext = b"checksum sidecar with options ".repeat(3_000);
+    fs::write(&input_path, &plaintext).expect("write input");
+    fs::write(&encrypted_path, b"existing").expect("write existing output");
+
+    let options = engine::EncryptOptions {
+        chunk_size: 4096,
+        overwrite: false,
+        checksum_sidecar: true,
+        compression: Some(engine::Compression::Zstd(3)),
+        padding: Some(engine::Padding::ToBlock(1024)),
+        ..engine::EncryptOptions::default()
+    };
+    let err = engine::encrypt_with_options(&input_path, &encrypted_path, "test-password", &options)
+        .expect_err("existing output");
+    assert!(
+        matches!(err, engine::SealVaultError::AlreadyExists),
+        "{err:?}"
+    );
+    assert!(!sidecar_path.exists());
+
+    let options = engine::EncryptOptions {
+        overwrite: true,
+        ..options
+    };
+    engine::encrypt_with_options(&input_path, &encrypted_path, "test-password", &options)
+        .expect("encrypt with sidecar");
+    let info = engine::inspect(&encrypted_path).expect("inspect");
+    assert_eq!(info.chunk_size, 4096);
+    assert!(fs::metadata(&encrypted_path).expect("metadata").len() < plaintext.len() as u64);
+
+    let expected_hex: String = Sha256::digest(&plaintext)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    let sidecar = fs::read_to_string(&sidecar_path).expect("read sidecar");
+    assert_eq!(sidecar, format!("{expected_hex}  report.txt\n"));
+
+    engine::decrypt(&encrypted_path, &decrypted_path, "test-password").expect("decrypt file");
+    assert_eq!(
+        fs::read(&decrypted_path).expect("read decrypted"),
+        plaintext
+    );
+}
+
+/// 测试明文校验和 sidecar 与独立计算的 SHA-256 一致
+#[test]
+fn checksum_sidecar_matches_plaintext_hash() {
+    use sha2::{Digest, Sha256};
+
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("report.txt");
+    let encrypted_path = temp_dir.path().join("report.txt.svlt");
+
+    let plaintext: Vec<u8> = (0..150_000u32).map(|i| (i * 7 % 256) as u8).collect();
+    fs::write(&input_path, &plaintext).expect("write input");
+
+    engine::encrypt_with_options(
+        &input_path,
+        &encrypted_path,
+        "test-password",
+        &engine::EncryptOptions {
+            checksum_sidecar: true,
+            ..engine::EncryptOptions::default()
+        },
+    )
+    .expect("encrypt with sidecar");
+
+    let expected_hex: String = Sha256::digest(&plaintext)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+
+    let sidecar =
+        fs::read_to_string(temp_dir.path().join("report.txt.svlt.sha256")).expect("read sidecar");
+    assert_eq!(sidecar, format!("{expected_hex}  report.txt\n"));
+
+    let decrypted_path = temp_dir.path().join("decrypted.txt");
+    engine::decrypt(&encrypted_path, &decrypted_path, "test-password").expect("decrypt file");
+    assert_eq!(
+        fs::read(&decrypted_path).expect("read decrypted"),
+        plaintext
+    );
+}
+
+/// 测试使用非 UTF-8 字节序列作为密码
+#[test]
+fn encrypt_decrypt_roundtrip_with_non_utf8_password_bytes() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let encrypted_path = temp_dir.path().join("output.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.txt");
+
+    // 0xff / 0xfe / 0x80 在 UTF-8 中均不合法
+    let password: &[u8] = &[0xff, 0xfe, b'p', 0x80, b'w', 0x00, 0xc3];
+
+    fs::write(&input_path, b"non-utf8 password payload").expect("write input");
+
+    engine::encrypt_with_password_bytes(
+        &input_path,
+        &encrypted_path,
+        password,
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt file");
+
+    engine::decrypt_with_password_bytes(&encrypted_path, &decrypted_path, password)
+        .expect("decrypt file");
+    assert_eq!(
+        fs::read(&decrypted_path).expect("read decrypted"),
+        b"non-utf8 password payload"
+    );
+
+    let wrong = engine::decrypt_with_password_bytes(
+        &encrypted_path,
+        &decrypted_path,
+        &[0xff, 0xfe, b'p', 0x80, b'w', 0x00, 0xc4],
+    );
+    assert!(wrong.is_err(), "expected different bytes to fail");
+}
+
+/// 测试写后校验：正常输出通过，被篡改的输出导致加密报错且不替换目标文件；
+/// 校验沿用调用方的其他选项（算法、chunk 大小、线程数与填充）
+#[test]
+fn verify_after_write_detects_corrupted_output() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let good_path = temp_dir.path().join("good.svlt");
+    let bad_path = temp_dir.path().join("bad.svlt");
+
+    fs::write(&input_path, b"verify after write payload ".repeat(200)).expect("write input");
+    let options = engine::EncryptOptions {
+        threads: 2,
+        padding: Some(engine::Padding::ToBlock(512)),
+        ..common::fast_options(engine::AeadAlgorithm::Aes256Gcm)
+    };
+
+    let mut hook_calls = 0;
+    engine::encrypt_with_verify_hook(&input_path, &good_path, "test-password", &options, |_| {
+        hook_calls += 1;
+        Ok(())
+    })
+    .expect("verified encrypt");
+    assert_eq!(hook_calls, 1);
+    let info = engine::inspect(&good_path).expect("inspect");
+    assert_eq!(info.algorithm, engine::AeadAlgorithm::Aes256Gcm);
+    assert_eq!(info.chunk_size, 1024);
+
+    fs::write(&bad_path, b"previous content").expect("write existing output");
+    let result = engine::encrypt_with_verify_hook(
+        &input_path,
+        &bad_path,
+        "test-password",
+        &options,
+        |written| {
+            // 翻转最后一个字节（chunk tag），模拟写出损坏
+            let mut bytes = fs::read(written)?;
+            let last = bytes.len() - 1;
+            bytes[last] ^= 0x01;
+            fs::write(written, bytes)
+        },
+    );
+
+    // 校验失败保留解密时的具体错误，而不是笼统的 I/O 错误
+    assert!(
+        matches!(
+            result,
+            Err(engine::SealVaultError::InvalidPasswordOrFile
+                | engine::SealVaultError::CorruptedData)
+        ),
+        "{result:?}"
+    );
+    assert_eq!(
+        fs::read(&bad_path).expect("read existing output"),
+        b"previous content"
+    );
+}
+
+/// 测试 `EncryptOptions::verify_after_write` 可与并行加密、keyfile、填充和文件名帧同时使用
+#[test]
+fn verify_after_write_option_combines_with_other_options() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.bin");
+    let keyfile = temp_dir.path().join("key.bin");
+    let encrypted_path = temp_dir.path().join("output.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.bin");
+
+    let plaintext = b"verify after write with options ".repeat(2_000);
+    fs::write(&input_path, &plaintext).expect("write input");
+    fs::write(&keyfile, [0x42u8; 64]).expect("write keyfile");
+
+    let options = engine::EncryptOptions {
+        chunk_size: 4096,
+        threads: 4,
+        verify_after_write: true,
+        keyfile: Some(keyfile.clone()),
+        padding: Some(engine::Padding::ToBlock(1024)),
+        store_file_name: true,
+        ..engine::EncryptOptions::default()
+    };
+    engine::encrypt_with_options(&input_path, &encrypted_path, "test-password", &options)
+        .expect("verified encrypt");
+
+    engine::decrypt_with_keyfile(
+        &encrypted_path,
+        &decrypted_path,
+        Some("test-password"),
+        &keyfile,
+    )
+    .expect("decrypt");
+    assert_eq!(fs::read(&decrypted_path).expect("read output"), plaintext);
+}
+
+/// 回归测试：解密必须使用 Header 中记录的算法，而不是默认算法
+#[test]
+fn decrypt_uses_header_algorithm_not_default() {
+    use engine::format::stream::StreamDecryptor;
+
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let encrypted_path = temp_dir.path().join("output_aes.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.txt");
+
+    fs::write(&input_path, b"aes header regression").expect("write input");
+    engine::encrypt_with_algorithm(
+        &input_path,
+        &encrypted_path,
+        "test-password",
+        engine::AeadAlgorithm::Aes256Gcm,
+    )
+    .expect("encrypt file with aes");
+
+    engine::decrypt(&encrypted_path, &decrypted_path, "test-password").expect("decrypt file");
+    assert_eq!(
+        fs::read(&decrypted_path).expect("read decrypted"),
+        b"aes header regression"
+    );
+
+    // 同样的 key 与 base_nonce，若按默认的 XChaCha20-Poly1305 解密则必然认证失败
+    let mut reader = std::io::BufReader::new(fs::File::open(&encrypted_path).expect("open"));
+    let (header, _) =
+        engine::open_for_decrypt(&mut reader, b"test-password").expect("open for decrypt");
+    assert_eq!(header.algorithm, engine::AeadAlgorithm::Aes256Gcm);
+
+    let key =
+        engine::crypto::kdf::derive_key_raw(b"test-password", &header.salt).expect("derive key");
+    let result = StreamDecryptor::new(
+        &key,
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        header.base_nonce,
+        header.digest(),
+    )
+    .decrypt(&mut reader, std::io::sink());
+    assert!(
+        result.is_err(),
+        "default algorithm must not decrypt aes file"
+    );
+}
+
+/// 测试在 chunk 边界处被截断的密文会被拒绝
+#[test]
+fn decrypt_rejects_truncated_ciphertext() {
+    const HEADER_LEN: usize = 93;
+    const FRAME_LEN: usize = 4 + 64 * 1024 + 16;
+    const FINAL_FRAME_LEN: usize = 4 + 16;
+
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.bin");
+    let encrypted_path = temp_dir.path().join("input.svlt");
+    let truncated_path = temp_dir.path().join("truncated.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.bin");
+
+    // 恰好 3 个满 chunk，截掉 final chunk 后仍停在 chunk 边界
+    let plaintext: Vec<u8> = (0..3 * 64 * 1024u32).map(|i| (i % 251) as u8).collect();
+    fs::write(&input_path, &plaintext).expect("write input");
+
+    engine::encrypt(&input_path, &encrypted_path, "test-password").expect("encrypt file");
+    let encrypted = fs::read(&encrypted_path).expect("read encrypted");
+    assert_eq!(
+        encrypted.len(),
+        HEADER_LEN + 3 * FRAME_LEN + FINAL_FRAME_LEN
+    );
+
+    for truncated_len in [
+        // 仅缺少 final chunk
+        encrypted.len() - FINAL_FRAME_LEN,
+        // 缺少最后一个数据 chunk 与 final chunk
+        encrypted.len() - FINAL_FRAME_LEN - FRAME_LEN,
+    ] {
+        fs::write(&truncated_path, &encrypted[..truncated_len]).expect("write truncated");
+
+        let err = engine::decrypt(&truncated_path, &decrypted_path, "test-password")
+            .expect_err("expected truncated ciphertext to fail");
+        assert!(
+            matches!(err, engine::SealVaultError::CorruptedData),
+            "unexpected error: {err:?}"
+        );
+        assert!(!decrypted_path.exists());
+    }
+}
+
+/// 测试空文件以单个认证过的 final chunk 表示，可与只剩 Header 的截断文件区分
+#[test]
+fn empty_file_roundtrip_and_header_only_file_rejected() {
+    // 空文件不记录数据 chunk 总数
+    const HEADER_LEN: usize = 85;
+    const FINAL_FRAME_LEN: usize = 4 + 16;
+
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("empty.txt");
+    let encrypted_path = temp_dir.path().join("empty.svlt");
+    let truncated_path = temp_dir.path().join("header-only.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.txt");
+    fs::write(&input_path, b"").expect("write empty input");
+
+    for algorithm in [
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        engine::AeadAlgorithm::Aes256Gcm,
+        engine::AeadAlgorithm::ChaCha20Poly1305,
+    ] {
+        engine::encrypt_with_algorithm(&input_path, &encrypted_path, "pw", algorithm)
+            .expect("encrypt empty file");
+        let encrypted = fs::read(&encrypted_path).expect("read encrypted");
+        assert_eq!(
+            encrypted.len(),
+            HEADER_LEN + FINAL_FRAME_LEN,
+            "{algorithm:?}"
+        );
+
+        engine::decrypt(&encrypted_path, &decrypted_path, "pw").expect("decrypt empty file");
+        assert!(
+            fs::read(&decrypted_path)
+                .expect("read decrypted")
+                .is_empty()
+        );
+        fs::remove_file(&decrypted_path).expect("remove decrypted");
+
+        // final chunk 同样经过认证：空文件也能发现错误密码
+        let err = engine::decrypt(&encrypted_path, &decrypted_path, "wrong")
+            .expect_err("wrong password on empty file");
+        assert!(
+            matches!(err, engine::SealVaultError::InvalidPasswordOrFile),
+            "unexpected error: {err:?}"
+        );
+
+        fs::write(&truncated_path, &encrypted[..HEADER_LEN]).expect("write header-only file");
+        let err = engine::decrypt(&truncated_path, &decrypted_path, "pw")
+            .expect_err("header-only file must be rejected");
+        assert!(
+            matches!(err, engine::SealVaultError::CorruptedData),
+            "unexpected error: {err:?}"
+        );
+        assert!(!decrypted_path.exists());
+    }
+}
+
+/// 测试通过 EncryptOptions 配置 16 KiB 与 1 MiB chunk 的 round-trip
+#[test]
+fn encrypt_with_options_roundtrip_custom_chunk_sizes() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.bin");
+    let plaintext: Vec<u8> = (0..2_500_000u32).map(|i| (i % 241) as u8).collect();
+    fs::write(&input_path, &plaintext).expect("write input");
+
+    for (chunk_size, algorithm) in [
+        (16 * 1024, engine::AeadAlgorithm::XChaCha20Poly1305),
+        (1024 * 1024, engine::AeadAlgorithm::Aes256Gcm),
+    ] {
+        let encrypted_path = temp_dir.path().join(format!("{chunk_size}.svlt"));
+        let decrypted_path = temp_dir.path().join(format!("{chunk_size}.bin"));
+
+        let options = engine::EncryptOptions {
+            algorithm,
+            chunk_size,
+            ..Default::default()
+        };
+        engine::encrypt_with_options(&input_path, &encrypted_path, "test-password", &options)
+            .expect("encrypt with options");
+
+        let header = engine::format::header::Header::read(
+            fs::File::open(&encrypted_path).expect("open encrypted"),
+        )
+        .expect("read header");
+        assert_eq!(header.chunk_size as usize, chunk_size);
+        assert_eq!(header.algorithm, algorithm);
+
+        engine::decrypt(&encrypted_path, &decrypted_path, "test-password").expect("decrypt");
+        assert_eq!(
+            fs::read(&decrypted_path).expect("read decrypted"),
+            plaintext
+        );
+    }
+}
+
+/// 测试 EncryptOptions 拒绝 0 与超过 1 GiB 的 chunk 大小
+#[test]
+fn encrypt_with_options_rejects_out_of_range_chunk_size() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let encrypted_path = temp_dir.path().join("input.svlt");
+    fs::write(&input_path, b"payload").expect("write input");
+
+    for chunk_size in [0, engine::EncryptOptions::MAX_CHUNK_SIZE + 1] {
+        let options = engine::EncryptOptions {
+            chunk_size,
+            ..Default::default()
+        };
+        let err = engine::encrypt_with_options(&input_path, &encrypted_path, "pw", &options)
+            .expect_err("expected invalid chunk size to fail");
+        assert!(
+            matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+            "unexpected error: {err:?}"
+        );
+        assert!(!encrypted_path.exists());
+    }
+}
+
+/// 测试自定义 I/O 缓冲区容量（小于、等于、大于 chunk）不影响 round-trip 与密文长度
+#[test]
+fn encrypt_with_options_custom_io_buffer_size_roundtrip() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.bin");
+    let encrypted_path = temp_dir.path().join("input.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.bin");
+
+    let plaintext: Vec<u8> = (0..300_001u32).map(|i| (i % 233) as u8).collect();
+    fs::write(&input_path, &plaintext).expect("write input");
+
+    let mut lengths = Vec::new();
+    for io_buffer_size in [1, 1000, 64 * 1024, 4 * 1024 * 1024] {
+        let options = engine::EncryptOptions {
+            io_buffer_size,
+            ..Default::default()
+        };
+        engine::encrypt_with_options(&input_path, &encrypted_path, "pw", &options)
+            .expect("encrypt with custom io buffer");
+        lengths.push(fs::metadata(&encrypted_path).expect("stat").len());
+
+        engine::decrypt(&encrypted_path, &decrypted_path, "pw").expect("decrypt");
+        assert_eq!(
+            fs::read(&decrypted_path).expect("read decrypted"),
+            plaintext
+        );
+    }
+    // 缓冲区容量与 chunk 大小无关：密文布局不变
+    assert!(lengths.windows(2).all(|w| w[0] == w[1]), "{lengths:?}");
+
+    for io_buffer_size in [0, engine::EncryptOptions::MAX_IO_BUFFER_SIZE + 1] {
+        let options = engine::EncryptOptions {
+            io_buffer_size,
+            ..Default::default()
+        };
+        let err = engine::encrypt_with_options(&input_path, &encrypted_path, "pw", &options)
+            .expect_err("expected invalid io buffer size to fail");
+        assert!(
+            matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+            "unexpected error: {err:?}"
+        );
+    }
+}
+
+/// 测试 EncryptOptions::threads 并行加密单个文件，并按顺序报告进度
+#[test]
+fn encrypt_with_options_parallel_threads_roundtrip() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("disk.img");
+    let encrypted_path = temp_dir.path().join("disk.img.svlt");
+    let decrypted_path = temp_dir.path().join("disk.dec.img");
+
+    let plaintext: Vec<u8> = (0..(20 * 16 * 1024 + 99) as u32)
+        .map(|i| (i * 31 % 256) as u8)
+        .collect();
+    fs::write(&input_path, &plaintext).expect("write input");
+
+    let options = engine::EncryptOptions {
+        chunk_size: 16 * 1024,
+        threads: 4,
+        ..Default::default()
+    };
+    let mut events = Vec::new();
+    engine::encrypt_with_progress(&input_path, &encrypted_path, "pw", &options, &mut |event| {
+        events.push(event)
+    })
+    .expect("parallel encrypt");
+
+    engine::decrypt(&encrypted_path, &decrypted_path, "pw").expect("decrypt");
+    assert_eq!(
+        fs::read(&decrypted_path).expect("read decrypted"),
+        plaintext
+    );
+
+    let done: Vec<u64> = events
+        .iter()
+        .filter_map(|event| match event {
+            engine::ProgressEvent::BytesProcessed { done, .. } => Some(*done),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(done.len(), 21);
+    assert!(done.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(done.last().copied(), Some(plaintext.len() as u64));
+}
+
+/// 测试内存映射加密的 round-trip，以及空文件回退到流式路径
+#[test]
+fn encrypt_mmap_roundtrip_and_empty_fallback() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let encrypted_path = temp_dir.path().join("mapped.svlt");
+    let streamed_path = temp_dir.path().join("streamed.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.bin");
+
+    let large: Vec<u8> = (0..(3 * 64 * 1024 + 17) as u32)
+        .map(|i| (i % 211) as u8)
+        .collect();
+    for (name, plaintext) in [("large.bin", large), ("empty.bin", Vec::new())] {
+        let input_path = temp_dir.path().join(name);
+        fs::write(&input_path, &plaintext).expect("write input");
+
+        let options = engine::EncryptOptions::default();
+        engine::encrypt_mmap(&input_path, &encrypted_path, "pw", &options).expect("mmap encrypt");
+        engine::encrypt_with_options(&input_path, &streamed_path, "pw", &options)
+            .expect("streaming encrypt");
+        assert_eq!(
+            fs::metadata(&encrypted_path).expect("stat").len(),
+            fs::metadata(&streamed_path).expect("stat").len(),
+            "{name}"
+        );
+
+        engine::decrypt(&encrypted_path, &decrypted_path, "pw").expect("decrypt");
+        assert_eq!(
+            fs::read(&decrypted_path).expect("read decrypted"),
+            plaintext
+        );
+    }
+}
+
+/// 测试 overwrite = false 时不覆盖已存在的目标文件
+#[test]
+fn encrypt_with_options_no_clobber_keeps_existing_output() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let encrypted_path = temp_dir.path().join("input.svlt");
+    fs::write(&input_path, b"new payload").expect("write input");
+    fs::write(&encrypted_path, b"existing archive").expect("write existing output");
+
+    let options = engine::EncryptOptions {
+        overwrite: false,
+        ..Default::default()
+    };
+    let err = engine::encrypt_with_options(&input_path, &encrypted_path, "pw", &options)
+        .expect_err("expected existing output to be kept");
+
+    assert!(
+        matches!(err, engine::SealVaultError::AlreadyExists),
+        "unexpected error: {err:?}"
+    );
+    assert_eq!(
+        fs::read(&encrypted_path).expect("read existing output"),
+        b"existing archive"
+    );
+}
+
+/// 测试 overwrite = true（默认）时原子替换已存在的目标文件
+#[test]
+fn encrypt_with_options_overwrite_replaces_existing_output() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.txt");
+    let encrypted_path = temp_dir.path().join("input.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.txt");
+    fs::write(&input_path, b"new payload").expect("write input");
+    fs::write(&encrypted_path, b"existing archive").expect("write existing output");
+
+    let options = engine::EncryptOptions::default();
+    assert!(options.overwrite);
+    engine::encrypt_with_options(&input_path, &encrypted_path, "pw", &options)
+        .expect("overwrite existing output");
+
+    engine::decrypt(&encrypted_path, &decrypted_path, "pw").expect("decrypt replaced output");
+    assert_eq!(
+        fs::read(&decrypted_path).expect("read decrypted"),
+        b"new payload"
+    );
+}
+
+#[test]
+fn encrypt_with_progress_reports_each_chunk() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("plain.bin");
+    let encrypted = temp.path().join("plain.bin.svlt");
+
+    let chunk_size = 16 * 1024;
+    let plaintext = vec![0x5a; chunk_size * 2 + 5];
+    fs::write(&input, &plaintext).expect("write plaintext");
+
+    let options = engine::EncryptOptions {
+        chunk_size,
+        ..Default::default()
+    };
+    let mut events = Vec::new();
+    engine::encrypt_with_progress(&input, &encrypted, "pw", &options, &mut |e| events.push(e))
+        .expect("encrypt with progress");
+
+    let total = plaintext.len() as u64;
+    assert_eq!(
+        events,
+        vec![
+            engine::ProgressEvent::BytesProcessed {
+                done: chunk_size as u64,
+                total
+            },
+            engine::ProgressEvent::BytesProcessed {
+                done: 2 * chunk_size as u64,
+                total
+            },
+            engine::ProgressEvent::BytesProcessed { done: total, total },
+        ]
+    );
+}
+
+/// 测试开启 zstd 压缩后，可压缩数据的密文明显变小且能正确还原
+#[test]
+fn encrypt_with_zstd_compression_roundtrip() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("log.txt");
+    let encrypted = temp.path().join("log.txt.svlt");
+    let decrypted = temp.path().join("log.restored.txt");
+
+    let plaintext = "2026-01-01 INFO request handled in 3ms\n"
+        .repeat(20_000)
+        .into_bytes();
+    fs::write(&input, &plaintext).expect("write plaintext");
+
+    let options = engine::EncryptOptions {
+        compression: Some(engine::Compression::Zstd(3)),
+        ..Default::default()
+    };
+    engine::encrypt_with_options(&input, &encrypted, "pw", &options).expect("encrypt compressed");
+
+    let ciphertext_len = fs::metadata(&encrypted).expect("stat encrypted").len();
+    assert!(ciphertext_len * 10 < plaintext.len() as u64);
+
+    engine::decrypt(&encrypted, &decrypted, "pw").expect("decrypt compressed");
+    assert_eq!(fs::read(&decrypted).expect("read decrypted"), plaintext);
+}
+
+/// 测试非法的压缩级别在加密前被拒绝
+#[test]
+fn encrypt_with_options_rejects_invalid_compression_level() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("plain.txt");
+    let encrypted = temp.path().join("plain.txt.svlt");
+    fs::write(&input, b"payload").expect("write plaintext");
+
+    let options = engine::EncryptOptions {
+        compression: Some(engine::Compression::Zstd(1000)),
+        ..Default::default()
+    };
+    let err = engine::encrypt_with_options(&input, &encrypted, "pw", &options)
+        .expect_err("invalid level must be rejected");
+    assert!(
+        matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+        "unexpected error: {err:?}"
+    );
+    assert!(!encrypted.exists());
+}
+
+/// 测试使用 scrypt 加密后，普通解密路径按 Header 中的 KDF 解密
+#[test]
+fn encrypt_with_scrypt_kdf_roundtrip() {
+    use engine::crypto::kdf::{KdfAlgorithm, ScryptParams};
+
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("embedded.bin");
+    let encrypted = temp.path().join("embedded.bin.svlt");
+    let decrypted = temp.path().join("embedded.restored.bin");
+
+    let plaintext: Vec<u8> = (0..100_000u32).map(|i| (i % 211) as u8).collect();
+    fs::write(&input, &plaintext).expect("write plaintext");
+
+    let params = ScryptParams {
+        log_n: 10,
+        r: 8,
+        p: 1,
+    };
+    let options = engine::EncryptOptions {
+        kdf: KdfAlgorithm::Scrypt(params),
+        ..Default::default()
+    };
+    engine::encrypt_with_options(&input, &encrypted, "pw", &options).expect("encrypt with scrypt");
+
+    let header = engine::format::header::Header::read(fs::File::open(&encrypted).expect("open"))
+        .expect("read header");
+    assert_eq!(header.kdf, KdfAlgorithm::Scrypt(params));
+
+    engine::decrypt(&encrypted, &decrypted, "pw").expect("decrypt scrypt file");
+    assert_eq!(fs::read(&decrypted).expect("read decrypted"), plaintext);
+
+    let err = engine::decrypt(&encrypted, &decrypted, "wrong").expect_err("wrong password");
+    assert!(
+        matches!(err, engine::SealVaultError::InvalidPasswordOrFile),
+        "unexpected error: {err:?}"
+    );
+}
+
+/// 测试超出上限的 scrypt 参数在加密前被拒绝
+#[test]
+fn encrypt_with_options_rejects_unsupported_scrypt_params() {
+    use engine::crypto::kdf::{KdfAlgorithm, ScryptParams};
+
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("plain.txt");
+    let encrypted = temp.path().join("plain.txt.svlt");
+    fs::write(&input, b"payload").expect("write plaintext");
+
+    for params in [
+        ScryptParams {
+            log_n: 0,
+            ..Default::default()
+        },
+        // 128 * 8 * 2^23 = 8 GiB，超过 4 GiB 上限
+        ScryptParams {
+            log_n: 23,
+            ..Default::default()
+        },
+    ] {
+        let options = engine::EncryptOptions {
+            kdf: KdfAlgorithm::Scrypt(params),
+            ..Default::default()
+        };
+        let err = engine::encrypt_with_options(&input, &encrypted, "pw", &options)
+            .expect_err("unsupported scrypt params must be rejected");
+        assert!(
+            matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+            "unexpected error: {err:?}"
+        );
+        assert!(!encrypted.exists());
+    }
+}
+
+/// 测试 EncryptOptions::auto_for 按输入大小选择的 chunk 大小落在预期分档内
+#[test]
+fn encrypt_options_auto_for_scales_chunk_size_with_input_len() {
+    const KIB: u64 = 1024;
+    const MIB: u64 = 1024 * KIB;
+    const GIB: u64 = 1024 * MIB;
+
+    for (input_len, min_chunk, max_chunk) in [
+        (0, 4 * KIB, 64 * KIB),
+        (10 * KIB, 4 * KIB, 64 * KIB),
+        (10 * MIB, 16 * KIB, 256 * KIB),
+        (500 * MIB, 64 * KIB, MIB),
+        (4 * GIB, 256 * KIB, MIB),
+        (u64::MAX, MIB, MIB),
+    ] {
+        let options = engine::EncryptOptions::auto_for(input_len);
+        let chunk_size = options.chunk_size as u64;
+        assert!(
+            (min_chunk..=max_chunk).contains(&chunk_size),
+            "input {input_len}: chunk size {chunk_size} outside {min_chunk}..={max_chunk}"
+        );
+        assert_eq!(options.algorithm, engine::AeadAlgorithm::XChaCha20Poly1305);
+    }
+
+    // 分档单调不减
+    let sizes: Vec<usize> = [0, MIB, 64 * MIB, GIB, 16 * GIB]
+        .into_iter()
+        .map(|len| engine::EncryptOptions::auto_for(len).chunk_size)
+        .collect();
+    assert!(sizes.windows(2).all(|w| w[0] <= w[1]), "{sizes:?}");
+
+    // 自动选出的选项可直接用于加密
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.bin");
+    let encrypted_path = temp_dir.path().join("input.svlt");
+    let decrypted_path = temp_dir.path().join("decrypted.bin");
+    let plaintext = vec![0x5au8; 40_000];
+    fs::write(&input_path, &plaintext).expect("write input");
+
+    let options = engine::EncryptOptions::auto_for(plaintext.len() as u64);
+    engine::encrypt_with_options(&input_path, &encrypted_path, "auto-password", &options)
+        .expect("encrypt auto");
+    engine::decrypt(&encrypted_path, &decrypted_path, "auto-password").expect("decrypt");
+    assert_eq!(
+        fs::read(&decrypted_path).expect("read decrypted"),
+        plaintext
+    );
+}
+
+/// 测试输入与输出指向同一文件（同一路径、`..` 或符号链接）时加密与解密均报 InvalidInput，且输入不变
+#[test]
+fn encrypt_and_decrypt_reject_same_input_and_output() {
+    let temp_dir = tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("secret.txt");
+    let encrypted_path = temp_dir.path().join("secret.svlt");
+    fs::write(&input_path, b"do not clobber").expect("write input");
+    engine::encrypt(&input_path, &encrypted_path, "pw").expect("encrypt");
+    let ciphertext = fs::read(&encrypted_path).expect("read encrypted");
+
+    let mut aliases = vec![
+        (input_path.clone(), input_path.clone()),
+        (
+            input_path.clone(),
+            temp_dir.path().join("sub/../secret.txt"),
+        ),
+    ];
+    fs::create_dir_all(temp_dir.path().join("sub")).expect("create sub");
+    #[cfg(unix)]
+    {
+        let link = temp_dir.path().join("link.txt");
+        std::os::unix::fs::symlink(&input_path, &link).expect("create symlink");
+        aliases.push((input_path.clone(), link));
+    }
+
+    let is_invalid_input = |err: &engine::SealVaultError| matches!(err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput);
+    for (input, output) in &aliases {
+        let err = engine::encrypt(input, output, "pw").expect_err("same file encrypt");
+        assert!(is_invalid_input(&err), "unexpected error: {err:?}");
+        let err =
+            engine::encrypt_with_options(input, output, "pw", &engine::EncryptOptions::default())
+                .expect_err("same file encrypt with options");
+        assert!(is_invalid_input(&err), "unexpected error: {err:?}");
+    }
+    assert_eq!(
+        fs::read(&input_path).expect("read input"),
+        b"do not clobber"
+    );
+
+    let err =
+        engine::decrypt(&encrypted_path, &encrypted_path, "pw").expect_err("same file decrypt");
+    assert!(is_invalid_input(&err), "unexpected error: {err:?}");
+    assert_eq!(
+        fs::read(&encrypted_path).expect("read encrypted"),
+        ciphertext
+    );
+}
+
+/// 测试加密与解密返回的统计与多 chunk 输入一致（串行、并行与压缩路径）
+#[test]
+fn encrypt_and_decrypt_report_stats() {
+    use engine::{Compression, EncryptOptions, Stats};
+
+    let temp_dir = tempdir().expect("create temp dir");
+    let input = temp_dir.path().join("input.bin");
+    let plaintext: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+    fs::write(&input, &plaintext).expect("write input");
+
+    for (name, threads, compression) in [
+        ("serial", 1, None),
+        ("parallel", 4, None),
+        ("zstd", 1, Some(Compression::Zstd(3))),
+    ] {
+        let encrypted = temp_dir.path().join(format!("{name}.svlt"));
+        let decrypted = temp_dir.path().join(format!("{name}.out"));
+        let options = EncryptOptions {
+            chunk_size: 1024,
+            threads,
+            compression,
+            store_file_name: true,
+            ..EncryptOptions::default()
+        };
+
+        let stats = engine::encrypt_with_stats(&input, &encrypted, "stats-password", &options)
+            .expect("encrypt");
+        let ciphertext_bytes = fs::metadata(&encrypted).expect("metadata").len();
+        assert_eq!(stats.plaintext_bytes, 10_000, "{name}");
+        assert_eq!(stats.ciphertext_bytes, ciphertext_bytes, "{name}");
+        if compression.is_none() {
+            // 9 个满 chunk 加 1 个 784 字节的 chunk；文件名帧与 final chunk 不计入
+            assert_eq!(stats.chunks, 10, "{name}");
+        }
+
+        let decrypt_stats =
+            engine::decrypt_with_stats(&encrypted, &decrypted, "stats-password").expect("decrypt");
+        assert_eq!(decrypt_stats, stats, "{name}");
+        assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+    }
+
+    // 空文件：没有数据 chunk
+    let empty = temp_dir.path().join("empty.bin");
+    let encrypted = temp_dir.path().join("empty.svlt");
+    fs::write(&empty, b"").expect("write empty");
+    let stats = engine::encrypt_with_stats(
+        &empty,
+        &encrypted,
+        "stats-password",
+        &EncryptOptions::default(),
+    )
+    .expect("encrypt empty");
+    assert_eq!(
+        stats,
+        Stats {
+            plaintext_bytes: 0,
+            ciphertext_bytes: fs::metadata(&encrypted).expect("metadata").len(),
+            chunks: 0,
+        }
+    );
 }
@@ -0,0 +1,182 @@
+//! 原始文件名（`EncryptOptions::store_file_name`）测试
+#![cfg(feature = "fs")]
+
+use std::fs;
+
+use engine::format::stream::{MAX_FILE_NAME_LEN, StreamEncryptor};
+use engine::{AeadAlgorithm, Compression, EncryptOptions, Padding, SealVaultError};
+use tempfile::tempdir;
+
+fn options_with_file_name() -> EncryptOptions {
+    EncryptOptions {
+        chunk_size: 1024,
+        store_file_name: true,
+        ..EncryptOptions::default()
+    }
+}
+
+/// 测试加密时保存文件名，解密到目录时还原为原名，普通解密不受影响
+#[test]
+fn decrypt_to_dir_restores_original_file_name() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("report.pdf");
+    let encrypted = temp.path().join("out.svlt");
+    let restore_dir = temp.path().join("restored");
+    let plaintext = b"%PDF-1.7 quarterly report ".repeat(200);
+    fs::write(&input, &plaintext).expect("write input");
+    fs::create_dir(&restore_dir).expect("create restore dir");
+
+    engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        "name-password",
+        &options_with_file_name(),
+    )
+    .expect("encrypt");
+    assert!(engine::inspect(&encrypted).expect("inspect").has_file_name);
+
+    let restored =
+        engine::decrypt_to_dir(&encrypted, &restore_dir, "name-password").expect("decrypt to dir");
+    assert_eq!(restored, restore_dir.join("report.pdf"));
+    assert_eq!(fs::read(&restored).expect("read restored"), plaintext);
+
+    let plain_output = temp.path().join("plain.bin");
+    engine::decrypt(&encrypted, &plain_output, "name-password").expect("decrypt");
+    assert_eq!(fs::read(&plain_output).expect("read output"), plaintext);
+
+    let err = engine::decrypt_to_dir(&encrypted, &restore_dir, "wrong-password")
+        .expect_err("wrong password");
+    assert!(matches!(err, SealVaultError::InvalidPasswordOrFile));
+}
+
+/// 测试默认不保存文件名：Header 不带标志，`decrypt_to_dir` 在派生密钥之前报错
+#[test]
+fn file_name_is_not_stored_by_default() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("secret-plans.txt");
+    let encrypted = temp.path().join("out.svlt");
+    fs::write(&input, b"private").expect("write input");
+
+    engine::encrypt(&input, &encrypted, "name-password").expect("encrypt");
+    assert!(!engine::inspect(&encrypted).expect("inspect").has_file_name);
+
+    let ciphertext = fs::read(&encrypted).expect("read ciphertext");
+    assert!(
+        !ciphertext
+            .windows(b"secret-plans".len())
+            .any(|w| w == b"secret-plans")
+    );
+
+    let err = engine::decrypt_to_dir(&encrypted, temp.path(), "name-password")
+        .expect_err("no stored file name");
+    assert!(
+        matches!(&err, SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+        "{err:?}"
+    );
+}
+
+/// 测试文件名与压缩、填充、明文摘要共存，并在 rekey 与 reencrypt 后保留
+#[test]
+fn file_name_survives_options_rekey_and_reencrypt() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("data.tar.gz");
+    let encrypted = temp.path().join("data.svlt");
+    let restore_dir = temp.path().join("restored");
+    let plaintext = b"archive bytes ".repeat(1000);
+    fs::write(&input, &plaintext).expect("write input");
+    fs::create_dir(&restore_dir).expect("create restore dir");
+
+    let options = EncryptOptions {
+        compression: Some(Compression::Zstd(3)),
+        padding: Some(Padding::ToBlock(4096)),
+        plaintext_digest: true,
+        ..options_with_file_name()
+    };
+    engine::encrypt_with_options(&input, &encrypted, "old-password", &options).expect("encrypt");
+
+    engine::rekey(&encrypted, &encrypted, "old-password", "new-password").expect("rekey");
+    let restored =
+        engine::decrypt_to_dir(&encrypted, &restore_dir, "new-password").expect("decrypt rekeyed");
+    assert_eq!(restored, restore_dir.join("data.tar.gz"));
+    assert_eq!(fs::read(&restored).expect("read restored"), plaintext);
+
+    let reencrypted = temp.path().join("reencrypted.svlt");
+    let new_options = EncryptOptions {
+        algorithm: AeadAlgorithm::Aes256Gcm,
+        ..options_with_file_name()
+    };
+    engine::reencrypt(&encrypted, &reencrypted, "new-password", &new_options).expect("reencrypt");
+    fs::remove_file(&restored).expect("remove restored");
+    let restored = engine::decrypt_to_dir(&reencrypted, &restore_dir, "new-password")
+        .expect("decrypt reencrypted");
+    assert_eq!(restored, restore_dir.join("data.tar.gz"));
+    assert_eq!(fs::read(&restored).expect("read restored"), plaintext);
+
+    // 未开启 store_file_name 时 reencrypt 丢弃文件名
+    engine::reencrypt(
+        &reencrypted,
+        &reencrypted,
+        "new-password",
+        &EncryptOptions::default(),
+    )
+    .expect("reencrypt without name");
+    assert!(
+        !engine::inspect(&reencrypted)
+            .expect("inspect")
+            .has_file_name
+    );
+}
+
+/// 测试非 UTF-8 文件名按原始字节保存与还原
+#[cfg(unix)]
+#[test]
+fn decrypt_to_dir_restores_non_utf8_file_name() {
+    use std::ffi::OsStr;
+    use std::os::unix::ffi::OsStrExt;
+
+    let temp = tempdir().expect("create temp dir");
+    let name = OsStr::from_bytes(b"caf\xe9.txt");
+    let input = temp.path().join(name);
+    let encrypted = temp.path().join("out.svlt");
+    let restore_dir = temp.path().join("restored");
+    fs::write(&input, b"latin-1 name").expect("write input");
+    fs::create_dir(&restore_dir).expect("create restore dir");
+
+    engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        "name-password",
+        &options_with_file_name(),
+    )
+    .expect("encrypt");
+    let restored =
+        engine::decrypt_to_dir(&encrypted, &restore_dir, "name-password").expect("decrypt to dir");
+    assert_eq!(restored.file_name(), Some(name));
+    assert_eq!(fs::read(&restored).expect("read restored"), b"latin-1 name");
+}
+
+/// 测试文件名长度上限：超长文件名在加密时被拒绝
+#[test]
+fn file_name_length_is_capped() {
+    let mut encryptor = StreamEncryptor::new(
+        &[7u8; 32],
+        AeadAlgorithm::XChaCha20Poly1305,
+        [1u8; 24],
+        1024,
+        [0u8; 32],
+    )
+    .expect("create encryptor")
+    .with_file_name();
+
+    let mut output = Vec::new();
+    // 写出文件名帧之前不能写数据 chunk
+    assert!(encryptor.write_chunk(b"data", &mut output).is_err());
+
+    let err = encryptor
+        .write_file_name(&vec![b'a'; MAX_FILE_NAME_LEN + 1], &mut output)
+        .expect_err("name too long");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    encryptor
+        .write_file_name(&vec![b'a'; MAX_FILE_NAME_LEN], &mut output)
+        .expect("name at the limit");
+}
@@ -1,4 +1,5 @@
 //! 目录加密/解密最小可运行测试
+#![cfg(feature = "fs")]
 
 use std::fs;
 use std::io::Write;
@@ -20,24 +21,23 @@ fn encrypt_decrypt_folder_roundtrip() {
     let mut f2 = fs::File::create(input_dir.join("a/b/nested.log")).expect("create nested file");
     f2.write_all(b"hello nested").expect("write nested file");
 
-    engine::encrypt_folder(
+    let encrypted = engine::encrypt_folder(
         &input_dir,
         &encrypted_dir,
         "folder-password",
         engine::AeadAlgorithm::XChaCha20Poly1305,
     )
     .expect("encrypt folder");
+    assert_eq!(encrypted.processed, 2);
+    assert!(encrypted.skipped.is_empty());
 
     assert!(encrypted_dir.join("root.txt.svlt").exists());
     assert!(encrypted_dir.join("a/b/nested.log.svlt").exists());
 
-    engine::decrypt_folder(
-        &encrypted_dir,
-        &decrypted_dir,
-        "folder-password",
-        engine::AeadAlgorithm::XChaCha20Poly1305,
-    )
-    .expect("decrypt folder");
+    let decrypted = engine::decrypt_folder(&encrypted_dir, &decrypted_dir, "folder-password")
+        .expect("decrypt folder");
+    assert_eq!(decrypted.processed, 2);
+    assert!(decrypted.skipped.is_empty());
 
     assert_eq!(
         fs::read(decrypted_dir.join("root.txt")).expect("read root"),
@@ -67,12 +67,7 @@ fn decrypt_folder_with_wrong_password_fails() {
     )
     .expect("encrypt folder");
 
-    let result = engine::decrypt_folder(
-        &encrypted_dir,
-        &decrypted_dir,
-        "wrong-password",
-        engine::AeadAlgorithm::Aes256Gcm,
-    );
+    let result = engine::decrypt_folder(&encrypted_dir, &decrypted_dir, "wrong-password");
 
     assert!(result.is_err(), "expected wrong password to fail");
 }
@@ -100,13 +95,8 @@ fn folder_roundtrip_preserves_trailing_svlt_file_names() {
     )
     .expect("encrypt folder");
 
-    engine::decrypt_folder(
-        &encrypted_dir,
-        &decrypted_dir,
-        "folder-password",
-        engine::AeadAlgorithm::XChaCha20Poly1305,
-    )
-    .expect("decrypt folder");
+    engine::decrypt_folder(&encrypted_dir, &decrypted_dir, "folder-password")
+        .expect("decrypt folder");
 
     assert_eq!(
         fs::read(decrypted_dir.join("report")).expect("read report"),
@@ -143,13 +133,8 @@ fn folder_roundtrip_supports_non_utf8_file_names() {
     )
     .expect("encrypt folder");
 
-    engine::decrypt_folder(
-        &encrypted_dir,
-        &decrypted_dir,
-        "folder-password",
-        engine::AeadAlgorithm::Aes256Gcm,
-    )
-    .expect("decrypt folder");
+    engine::decrypt_folder(&encrypted_dir, &decrypted_dir, "folder-password")
+        .expect("decrypt folder");
 
     let decrypted_path = decrypted_dir.join(&original_name);
     assert!(
@@ -161,3 +146,1027 @@ fn folder_roundtrip_supports_non_utf8_file_names() {
         b"non-utf8 name payload"
     );
 }
+
+/// 构造一个包含深层 `.svlt`、普通文件与双后缀文件的加密目录。
+fn build_mixed_encrypted_tree(root: &std::path::Path) -> std::path::PathBuf {
+    let input_dir = root.join("plain");
+    let encrypted_dir = root.join("encrypted");
+
+    fs::create_dir_all(input_dir.join("odd.dir.svlt/x.y/deep")).expect("create input dir");
+    fs::write(
+        input_dir.join("odd.dir.svlt/x.y/deep/archive.tar"),
+        b"deep payload",
+    )
+    .expect("write deep file");
+
+    engine::encrypt_folder(
+        &input_dir,
+        &encrypted_dir,
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt folder");
+
+    // 加密后再混入未加密文件：普通文件与 `.svlt` 不是最后一个后缀的文件。
+    fs::write(encrypted_dir.join("README.md"), b"plain readme").expect("write readme");
+    fs::write(
+        encrypted_dir.join("odd.dir.svlt/data.svlt.bak"),
+        b"not really encrypted",
+    )
+    .expect("write double-extension file");
+
+    encrypted_dir
+}
+
+#[test]
+fn decrypt_folder_skips_non_svlt_files_by_default() {
+    let temp = tempdir().expect("create temp dir");
+    let encrypted_dir = build_mixed_encrypted_tree(temp.path());
+    let decrypted_dir = temp.path().join("decrypted");
+
+    engine::decrypt_folder_with_options(
+        &encrypted_dir,
+        &decrypted_dir,
+        "folder-password",
+        &engine::FolderOptions::default(),
+    )
+    .expect("decrypt folder");
+
+    assert_eq!(
+        fs::read(decrypted_dir.join("odd.dir.svlt/x.y/deep/archive.tar")).expect("read deep"),
+        b"deep payload"
+    );
+    assert!(!decrypted_dir.join("README.md").exists());
+    assert!(!decrypted_dir.join("odd.dir.svlt/data.svlt.bak").exists());
+    assert!(!decrypted_dir.join("odd.dir.svlt/data.svlt").exists());
+    assert!(!decrypted_dir.join("odd.dir").exists());
+}
+
+#[test]
+fn decrypt_folder_copies_non_svlt_files_with_copy_policy() {
+    let temp = tempdir().expect("create temp dir");
+    let encrypted_dir = build_mixed_encrypted_tree(temp.path());
+    let decrypted_dir = temp.path().join("decrypted");
+
+    let options = engine::FolderOptions {
+        non_encrypted_files: engine::NonEncryptedFilePolicy::Copy,
+        ..Default::default()
+    };
+    engine::decrypt_folder_with_options(
+        &encrypted_dir,
+        &decrypted_dir,
+        "folder-password",
+        &options,
+    )
+    .expect("decrypt folder");
+
+    assert_eq!(
+        fs::read(decrypted_dir.join("odd.dir.svlt/x.y/deep/archive.tar")).expect("read deep"),
+        b"deep payload"
+    );
+    assert_eq!(
+        fs::read(decrypted_dir.join("README.md")).expect("read readme"),
+        b"plain readme"
+    );
+    assert_eq!(
+        fs::read(decrypted_dir.join("odd.dir.svlt/data.svlt.bak")).expect("read bak"),
+        b"not really encrypted"
+    );
+}
+
+#[test]
+fn decrypt_folder_reports_skipped_files_when_nothing_matches() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let decrypted_dir = temp.path().join("decrypted");
+    fs::create_dir_all(input_dir.join("docs")).expect("create input dir");
+    fs::write(input_dir.join("notes.txt"), b"plain notes").expect("write notes");
+    fs::write(input_dir.join("docs/report.pdf"), b"plain report").expect("write report");
+
+    // 指向了未加密的目录：不报错，但结果中没有解密任何文件，且列出跳过的文件
+    let result = engine::decrypt_folder(&input_dir, &decrypted_dir, "folder-password")
+        .expect("decrypt folder");
+    assert_eq!(result.processed, 0);
+    let mut skipped = result.skipped;
+    skipped.sort();
+    assert_eq!(
+        skipped,
+        vec![
+            std::path::PathBuf::from("docs/report.pdf"),
+            std::path::PathBuf::from("notes.txt"),
+        ]
+    );
+    assert!(!decrypted_dir.join("notes.txt").exists());
+
+    // 复制策略下文件被复制而不是跳过
+    let options = engine::FolderOptions {
+        non_encrypted_files: engine::NonEncryptedFilePolicy::Copy,
+        ..Default::default()
+    };
+    let result = engine::decrypt_folder_with_options(
+        &input_dir,
+        &temp.path().join("copied"),
+        "folder-password",
+        &options,
+    )
+    .expect("decrypt folder with copy");
+    assert_eq!(result, engine::FolderResult::default());
+}
+
+#[test]
+fn folder_info_reports_mixed_algorithms() {
+    use engine::format::header::VERSION;
+
+    let temp = tempdir().expect("create temp dir");
+    let plain_path = temp.path().join("plain.txt");
+    let encrypted_dir = temp.path().join("encrypted");
+    fs::create_dir_all(encrypted_dir.join("sub")).expect("create encrypted dir");
+    fs::write(&plain_path, b"mixed algorithm payload").expect("write plain");
+
+    for (name, algorithm) in [
+        ("a.txt.svlt", engine::AeadAlgorithm::XChaCha20Poly1305),
+        ("sub/b.txt.svlt", engine::AeadAlgorithm::XChaCha20Poly1305),
+        ("sub/c.txt.svlt", engine::AeadAlgorithm::Aes256Gcm),
+    ] {
+        engine::encrypt_with_algorithm(
+            &plain_path,
+            &encrypted_dir.join(name),
+            "folder-password",
+            algorithm,
+        )
+        .expect("encrypt file");
+    }
+    fs::write(encrypted_dir.join("broken.svlt"), b"not a header").expect("write broken");
+    fs::write(encrypted_dir.join("notes.txt"), b"ignored").expect("write notes");
+
+    let info = engine::folder_info(&encrypted_dir).expect("folder info");
+
+    assert_eq!(info.files, 3);
+    assert_eq!(info.formats.len(), 2);
+    assert_eq!(
+        info.formats[&(VERSION, engine::AeadAlgorithm::XChaCha20Poly1305)],
+        2
+    );
+    assert_eq!(
+        info.formats[&(VERSION, engine::AeadAlgorithm::Aes256Gcm)],
+        1
+    );
+    assert_eq!(info.chunk_sizes.len(), 1);
+    assert_eq!(info.invalid, vec![std::path::PathBuf::from("broken.svlt")]);
+    assert!(info.is_mixed());
+}
+
+#[test]
+fn folder_operations_reject_output_inside_input() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("data");
+    fs::create_dir_all(&input_dir).expect("create input dir");
+    fs::write(input_dir.join("file.txt"), b"payload").expect("write file");
+
+    for output_dir in [
+        input_dir.join("enc"),
+        input_dir.join("../data/nested/enc"),
+        input_dir.clone(),
+    ] {
+        let err = engine::encrypt_folder(
+            &input_dir,
+            &output_dir,
+            "folder-password",
+            engine::AeadAlgorithm::XChaCha20Poly1305,
+        )
+        .expect_err("expected nested output to be rejected");
+        assert!(
+            matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+            "unexpected error: {err:?}"
+        );
+
+        let err = engine::decrypt_folder(&input_dir, &output_dir, "folder-password")
+            .expect_err("expected nested output to be rejected");
+        assert!(
+            matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    assert!(!input_dir.join("enc").exists());
+    assert!(!input_dir.join("nested").exists());
+    assert!(!input_dir.join("file.txt.svlt").exists());
+}
+
+/// 测试 CLI 中常见的 `encrypt-folder ./data ./data/encrypted` 写法，以及经符号链接指向输入内部的输出目录
+#[cfg(feature = "cli")]
+#[test]
+fn folder_commands_reject_nested_output_from_cli_and_symlink() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("data");
+    fs::create_dir_all(&input_dir).expect("create input dir");
+    fs::write(input_dir.join("file.txt"), b"payload").expect("write file");
+
+    for command in ["encrypt-folder", "decrypt-folder"] {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_engine"))
+            .current_dir(temp.path())
+            .env("SEALVAULT_PASSWORD", "folder-password")
+            .args([command, "./data", "./data/encrypted", "env:"])
+            .output()
+            .expect("run cli");
+        assert!(!output.status.success(), "{command} should fail");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains("输出目录不能位于输入目录内部"), "{stderr}");
+    }
+    assert!(!input_dir.join("encrypted").exists());
+
+    #[cfg(unix)]
+    {
+        let alias = temp.path().join("alias");
+        std::os::unix::fs::symlink(&input_dir, &alias).expect("create symlink");
+        let err = engine::encrypt_folder_parallel(
+            &input_dir,
+            &alias.join("enc"),
+            "folder-password",
+            engine::AeadAlgorithm::XChaCha20Poly1305,
+            2,
+        )
+        .expect_err("expected symlinked nested output to be rejected");
+        assert!(
+            matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+            "unexpected error: {err:?}"
+        );
+        assert!(!input_dir.join("enc").exists());
+    }
+    assert!(!input_dir.join("file.txt.svlt").exists());
+}
+
+/// 测试 CLI `encrypt-folder --threads 4` 并行加密的目录可以正常解密，且拒绝 0 个线程
+#[cfg(feature = "cli")]
+#[test]
+fn cli_encrypt_folder_with_threads_is_decryptable() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+    let decrypted_dir = temp.path().join("decrypted");
+    fs::create_dir_all(input_dir.join("nested")).expect("create input dir");
+    for i in 0..8 {
+        fs::write(
+            input_dir.join(format!("file{i}.txt")),
+            format!("threaded payload {i}").repeat(100 * i + 1),
+        )
+        .expect("write file");
+    }
+    fs::write(input_dir.join("nested/deep.bin"), [7u8; 70_000]).expect("write nested file");
+
+    let run = |threads: &str| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_engine"))
+            .env("SEALVAULT_PASSWORD", "threads-password")
+            .arg("encrypt-folder")
+            .arg(&input_dir)
+            .arg(&encrypted_dir)
+            .args(["env:", "--threads", threads])
+            .output()
+            .expect("run cli")
+    };
+
+    let output = run("0");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--threads must be at least 1"), "{stderr}");
+    assert!(!encrypted_dir.exists());
+
+    let output = run("4");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(
+        count_files(&encrypted_dir, |name| name.ends_with(".svlt")),
+        9
+    );
+
+    engine::decrypt_folder(&encrypted_dir, &decrypted_dir, "threads-password")
+        .expect("decrypt folder");
+    for i in 0..8 {
+        let name = format!("file{i}.txt");
+        assert_eq!(
+            fs::read(decrypted_dir.join(&name)).expect("read decrypted"),
+            fs::read(input_dir.join(&name)).expect("read original")
+        );
+    }
+    assert_eq!(
+        fs::read(decrypted_dir.join("nested/deep.bin")).expect("read nested"),
+        vec![7u8; 70_000]
+    );
+}
+
+/// 测试 CLI `decrypt-folder --passthrough-plaintext` 复制非 `.svlt` 文件，默认跳过并提示该选项
+#[cfg(feature = "cli")]
+#[test]
+fn cli_decrypt_folder_passthrough_plaintext_copies_plain_files() {
+    let temp = tempdir().expect("create temp dir");
+    let encrypted_dir = build_mixed_encrypted_tree(temp.path());
+
+    let run = |output_dir: &str, extra: &[&str]| {
+        std::process::Command::new(env!("CARGO_BIN_EXE_engine"))
+            .env("SEALVAULT_PASSWORD", "folder-password")
+            .arg("decrypt-folder")
+            .arg(&encrypted_dir)
+            .arg(temp.path().join(output_dir))
+            .arg("env:")
+            .args(extra)
+            .output()
+            .expect("run cli")
+    };
+
+    let output = run("skipped", &[]);
+    assert!(output.status.success(), "{output:?}");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("--passthrough-plaintext"),
+        "{output:?}"
+    );
+    assert!(!temp.path().join("skipped/README.md").exists());
+
+    let output = run("copied", &["--passthrough-plaintext"]);
+    assert!(output.status.success(), "{output:?}");
+    let copied = temp.path().join("copied");
+    assert_eq!(
+        fs::read(copied.join("README.md")).expect("read readme"),
+        b"plain readme"
+    );
+    assert_eq!(
+        fs::read(copied.join("odd.dir.svlt/x.y/deep/archive.tar")).expect("read deep"),
+        b"deep payload"
+    );
+
+    // 只适用于 decrypt-folder
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_engine"))
+        .env("SEALVAULT_PASSWORD", "folder-password")
+        .arg("encrypt-folder")
+        .arg(&encrypted_dir)
+        .arg(temp.path().join("again"))
+        .args(["env:", "--passthrough-plaintext"])
+        .output()
+        .expect("run cli");
+    assert!(!output.status.success());
+    assert!(!temp.path().join("again").exists());
+}
+
+fn count_files(dir: &std::path::Path, pred: impl Fn(&str) -> bool) -> usize {
+    walkdir::WalkDir::new(dir)
+        .into_iter()
+        .map(|entry| entry.expect("walk entry"))
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| pred(&entry.file_name().to_string_lossy()))
+        .count()
+}
+
+#[test]
+fn encrypt_folder_stops_when_cancel_flag_is_set() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+
+    fs::create_dir_all(&input_dir).expect("create input dir");
+    for i in 0..4 {
+        fs::write(input_dir.join(format!("file{i}.txt")), b"cancel payload").expect("write file");
+    }
+
+    let cancel = Arc::new(AtomicBool::new(false));
+
+    // 第一个输出文件出现后立即请求取消
+    let watcher = {
+        let cancel = Arc::clone(&cancel);
+        let encrypted_dir = encrypted_dir.clone();
+        std::thread::spawn(move || {
+            while !encrypted_dir.exists()
+                || count_files(&encrypted_dir, |name| name.ends_with(".svlt")) == 0
+            {
+                std::thread::sleep(std::time::Duration::from_millis(2));
+            }
+            cancel.store(true, Ordering::SeqCst);
+        })
+    };
+
+    let options = engine::FolderOptions {
+        cancel: Some(Arc::clone(&cancel)),
+        ..Default::default()
+    };
+    let err = engine::encrypt_folder_with_options(
+        &input_dir,
+        &encrypted_dir,
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        &options,
+    )
+    .expect_err("expected cancellation");
+    watcher.join().expect("join watcher");
+
+    let finished = count_files(&encrypted_dir, |name| name.ends_with(".svlt"));
+    assert!(
+        matches!(err, engine::SealVaultError::Cancelled { completed } if completed == finished),
+        "unexpected error: {err:?}"
+    );
+    assert!((1..4).contains(&finished), "finished {finished} files");
+    assert_eq!(
+        count_files(&encrypted_dir, |name| name.contains(".tmp-")),
+        0
+    );
+}
+
+/// 测试解密目录中途取消：已完成的文件数随错误返回，且不留下临时文件
+#[test]
+fn decrypt_folder_stops_when_cancel_flag_is_set() {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+    let decrypted_dir = temp.path().join("decrypted");
+
+    fs::create_dir_all(&input_dir).expect("create input dir");
+    for i in 0..4 {
+        fs::write(input_dir.join(format!("file{i}.txt")), b"cancel payload").expect("write file");
+    }
+    engine::encrypt_folder(
+        &input_dir,
+        &encrypted_dir,
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt folder");
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    let watcher = {
+        let cancel = Arc::clone(&cancel);
+        let decrypted_dir = decrypted_dir.clone();
+        std::thread::spawn(move || {
+            while !decrypted_dir.exists()
+                || count_files(&decrypted_dir, |name| name.ends_with(".txt")) == 0
+            {
+                std::thread::sleep(std::time::Duration::from_millis(2));
+            }
+            cancel.store(true, Ordering::SeqCst);
+        })
+    };
+
+    let options = engine::FolderOptions {
+        cancel: Some(Arc::clone(&cancel)),
+        ..Default::default()
+    };
+    let err = engine::decrypt_folder_with_options(
+        &encrypted_dir,
+        &decrypted_dir,
+        "folder-password",
+        &options,
+    )
+    .expect_err("expected cancellation");
+    watcher.join().expect("join watcher");
+
+    let finished = count_files(&decrypted_dir, |name| name.ends_with(".txt"));
+    assert!(
+        matches!(err, engine::SealVaultError::Cancelled { completed } if completed == finished),
+        "unexpected error: {err:?}"
+    );
+    assert!((1..4).contains(&finished), "finished {finished} files");
+    assert_eq!(
+        count_files(&decrypted_dir, |name| name.contains(".tmp-")),
+        0
+    );
+}
+
+/// 测试 CLI `encrypt-folder` 收到 SIGINT 后在文件之间停止，报告完成数并以 130 退出
+#[cfg(all(unix, feature = "cli"))]
+#[test]
+fn cli_encrypt_folder_stops_cleanly_on_sigint() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+    fs::create_dir_all(&input_dir).expect("create input dir");
+    for i in 0..100 {
+        fs::write(
+            input_dir.join(format!("file{i:03}.bin")),
+            vec![i as u8; 256 * 1024],
+        )
+        .expect("write file");
+    }
+
+    let child = std::process::Command::new(env!("CARGO_BIN_EXE_engine"))
+        .env("SEALVAULT_PASSWORD", "sigint-password")
+        .arg("encrypt-folder")
+        .arg(&input_dir)
+        .arg(&encrypted_dir)
+        .arg("env:")
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .expect("spawn cli");
+
+    // 第一个输出文件出现时信号处理已安装
+    while !encrypted_dir.exists()
+        || count_files(&encrypted_dir, |name| name.ends_with(".svlt")) == 0
+    {
+        std::thread::sleep(std::time::Duration::from_millis(2));
+    }
+    let status = std::process::Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .expect("send SIGINT");
+    assert!(status.success());
+
+    let output = child.wait_with_output().expect("wait for cli");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert_eq!(output.status.code(), Some(130), "{stderr}");
+
+    let finished = count_files(&encrypted_dir, |name| name.ends_with(".svlt"));
+    assert!(
+        stderr.contains(&format!("Cancelled: {finished} file(s) completed")),
+        "{stderr}"
+    );
+    assert!((1..100).contains(&finished), "finished {finished} files");
+    assert_eq!(
+        count_files(&encrypted_dir, |name| name.contains(".tmp-")),
+        0
+    );
+}
+
+#[test]
+fn decrypt_folder_handles_mixed_algorithms_in_one_call() {
+    let temp = tempdir().expect("create temp dir");
+    let plain_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+    let decrypted_dir = temp.path().join("decrypted");
+
+    fs::create_dir_all(&plain_dir).expect("create plain dir");
+    fs::create_dir_all(encrypted_dir.join("sub")).expect("create encrypted dir");
+
+    let files = [
+        ("aes.txt", engine::AeadAlgorithm::Aes256Gcm),
+        ("sub/xchacha.txt", engine::AeadAlgorithm::XChaCha20Poly1305),
+    ];
+    for (name, algorithm) in files {
+        let plain_path = plain_dir.join(name.replace('/', "_"));
+        fs::write(&plain_path, format!("payload of {name}")).expect("write plain");
+        engine::encrypt_with_algorithm(
+            &plain_path,
+            &encrypted_dir.join(format!("{name}.svlt")),
+            "folder-password",
+            algorithm,
+        )
+        .expect("encrypt file");
+    }
+
+    let info = engine::folder_info(&encrypted_dir).expect("folder info");
+    assert!(info.is_mixed());
+
+    engine::decrypt_folder(&encrypted_dir, &decrypted_dir, "folder-password")
+        .expect("decrypt mixed folder");
+
+    for (name, _) in files {
+        assert_eq!(
+            fs::read_to_string(decrypted_dir.join(name)).expect("read decrypted"),
+            format!("payload of {name}")
+        );
+    }
+}
+
+#[test]
+fn encrypt_folder_parallel_roundtrip_many_files() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+    let decrypted_dir = temp.path().join("decrypted");
+
+    let mut expected = Vec::new();
+    for i in 0..100 {
+        let rel = std::path::PathBuf::from(format!("d{}/s{}/file-{i}.bin", i % 5, i % 3));
+        let content = format!("payload #{i} ").repeat(i + 1).into_bytes();
+        fs::create_dir_all(input_dir.join(rel.parent().unwrap())).expect("create dir");
+        fs::write(input_dir.join(&rel), &content).expect("write file");
+        expected.push((rel, content));
+    }
+
+    engine::encrypt_folder_parallel(
+        &input_dir,
+        &encrypted_dir,
+        "parallel-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        4,
+    )
+    .expect("encrypt folder in parallel");
+
+    assert_eq!(count_files(&encrypted_dir, |n| n.ends_with(".svlt")), 100);
+
+    engine::decrypt_folder(&encrypted_dir, &decrypted_dir, "parallel-password")
+        .expect("decrypt folder");
+
+    for (rel, content) in expected {
+        assert_eq!(
+            fs::read(decrypted_dir.join(&rel)).expect("read decrypted"),
+            content,
+            "{}",
+            rel.display()
+        );
+    }
+}
+
+#[test]
+fn encrypt_folder_with_progress_reports_every_file() {
+    use engine::ProgressEvent;
+
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+
+    fs::create_dir_all(input_dir.join("sub")).expect("create input dir");
+    let files = [
+        ("a.txt", 10usize),
+        ("sub/b.txt", 200 * 1024),
+        ("sub/empty.txt", 0),
+    ];
+    for (name, len) in files {
+        fs::write(input_dir.join(name), vec![7u8; len]).expect("write file");
+    }
+
+    let mut events = Vec::new();
+    engine::encrypt_folder_with_progress(
+        &input_dir,
+        &encrypted_dir,
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        &engine::FolderOptions::default(),
+        &mut |e| events.push(e),
+    )
+    .expect("encrypt folder with progress");
+
+    let started: Vec<_> = events
+        .iter()
+        .filter_map(|e| match e {
+            ProgressEvent::FileStarted { path, index, total } => Some((path, *index, *total)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(started.len(), files.len());
+    for (i, (_, index, total)) in started.iter().enumerate() {
+        assert_eq!((*index, *total), (i, files.len()));
+    }
+
+    let finished = events
+        .iter()
+        .filter(|e| matches!(e, ProgressEvent::FileFinished { .. }))
+        .count();
+    assert_eq!(finished, files.len());
+
+    // 每个文件最后一次 BytesProcessed 的 done 等于其长度（空文件没有数据 chunk）
+    let mut current = None;
+    let mut last_done = std::collections::HashMap::new();
+    for e in &events {
+        match e {
+            ProgressEvent::FileStarted { path, .. } => current = Some(path.clone()),
+            ProgressEvent::BytesProcessed { done, total } => {
+                let path = current.clone().expect("bytes outside of a file");
+                assert_eq!(*total, fs::metadata(&path).expect("metadata").len());
+                last_done.insert(path, *done);
+            }
+            ProgressEvent::FileFinished { path } => {
+                assert_eq!(Some(path), current.as_ref());
+                let len = fs::metadata(path).expect("metadata").len();
+                assert_eq!(last_done.get(path).copied().unwrap_or(0), len);
+            }
+        }
+    }
+}
+
+#[test]
+fn decrypt_folder_verifies_manifest() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+
+    fs::create_dir_all(input_dir.join("sub")).expect("create input dir");
+    fs::write(input_dir.join("a.txt"), b"original a").expect("write a");
+    fs::write(input_dir.join("sub/b.txt"), b"original b").expect("write b");
+
+    engine::encrypt_folder(
+        &input_dir,
+        &encrypted_dir,
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt folder");
+    assert!(encrypted_dir.join("manifest.svmf").exists());
+
+    let verify = engine::FolderOptions {
+        verify_manifest: true,
+        non_encrypted_files: engine::NonEncryptedFilePolicy::Copy,
+        ..Default::default()
+    };
+    let decrypt = |name: &str| {
+        engine::decrypt_folder_with_options(
+            &encrypted_dir,
+            &temp.path().join(name),
+            "folder-password",
+            &verify,
+        )
+    };
+
+    decrypt("ok").expect("untampered folder verifies");
+    assert!(!temp.path().join("ok/manifest.svmf").exists());
+
+    // 用同一密码加密的其他内容替换：单个文件可以正常解密，但与 manifest 不符
+    let tampered = encrypted_dir.join("sub/b.txt.svlt");
+    let original = fs::read(&tampered).expect("read original");
+    let replacement = temp.path().join("replacement.txt");
+    fs::write(&replacement, b"forged b").expect("write replacement");
+    engine::encrypt(&replacement, &tampered, "folder-password").expect("encrypt replacement");
+
+    let err = decrypt("tampered").expect_err("tampered file must fail verification");
+    assert!(
+        matches!(&err, engine::SealVaultError::ManifestMismatch(reason) if reason.contains("b.txt")),
+        "unexpected error: {err:?}"
+    );
+    fs::write(&tampered, original).expect("restore original");
+
+    // 多出的文件
+    let extra = encrypted_dir.join("extra.txt.svlt");
+    fs::copy(encrypted_dir.join("a.txt.svlt"), &extra).expect("copy extra");
+    let err = decrypt("extra").expect_err("extra file must fail verification");
+    assert!(
+        matches!(&err, engine::SealVaultError::ManifestMismatch(reason) if reason.contains("extra.txt")),
+        "unexpected error: {err:?}"
+    );
+    fs::remove_file(&extra).expect("remove extra");
+
+    // 缺少的文件
+    fs::remove_file(encrypted_dir.join("a.txt.svlt")).expect("remove a");
+    let err = decrypt("missing").expect_err("missing file must fail verification");
+    assert!(
+        matches!(&err, engine::SealVaultError::ManifestMismatch(reason) if reason.contains("a.txt")),
+        "unexpected error: {err:?}"
+    );
+
+    // 缺少 manifest
+    fs::remove_file(encrypted_dir.join("manifest.svmf")).expect("remove manifest");
+    let err = decrypt("no-manifest").expect_err("missing manifest must fail verification");
+    assert!(
+        matches!(err, engine::SealVaultError::ManifestMismatch(_)),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn folder_roundtrip_preserves_mode_mtime_and_symlinks() {
+    use std::os::unix::fs::PermissionsExt;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+    let decrypted_dir = temp.path().join("decrypted");
+
+    fs::create_dir_all(input_dir.join("bin")).expect("create input dir");
+    let script = input_dir.join("bin/run.sh");
+    fs::write(&script, b"#!/bin/sh\necho hi\n").expect("write script");
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).expect("chmod script");
+
+    let readonly = input_dir.join("readonly.txt");
+    fs::write(&readonly, b"do not touch").expect("write readonly");
+    let mtime = UNIX_EPOCH + Duration::new(1_600_000_000, 123_000_000);
+    fs::File::options()
+        .write(true)
+        .open(&readonly)
+        .expect("open readonly")
+        .set_modified(mtime)
+        .expect("set mtime");
+    fs::set_permissions(&readonly, fs::Permissions::from_mode(0o444)).expect("chmod readonly");
+
+    std::os::unix::fs::symlink("bin/run.sh", input_dir.join("run")).expect("create symlink");
+
+    engine::encrypt_folder(
+        &input_dir,
+        &encrypted_dir,
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt folder");
+    assert!(!encrypted_dir.join("run").exists());
+    assert!(!encrypted_dir.join("run.svlt").exists());
+
+    engine::decrypt_folder(&encrypted_dir, &decrypted_dir, "folder-password")
+        .expect("decrypt folder");
+
+    let mode =
+        |path: &std::path::Path| fs::metadata(path).expect("metadata").permissions().mode() & 0o777;
+    assert_eq!(mode(&decrypted_dir.join("bin/run.sh")), 0o755);
+    assert_eq!(mode(&decrypted_dir.join("readonly.txt")), 0o444);
+    assert_eq!(
+        fs::metadata(decrypted_dir.join("readonly.txt"))
+            .expect("metadata")
+            .modified()
+            .expect("mtime"),
+        mtime
+    );
+
+    let link = decrypted_dir.join("run");
+    assert!(
+        fs::symlink_metadata(&link)
+            .expect("link metadata")
+            .file_type()
+            .is_symlink()
+    );
+    assert_eq!(
+        fs::read_link(&link).expect("read link"),
+        std::path::Path::new("bin/run.sh")
+    );
+    assert_eq!(
+        fs::read(&link).expect("read through link"),
+        b"#!/bin/sh\necho hi\n"
+    );
+}
+
+/// 测试未要求校验时，损坏的 manifest 视同不存在，文件照常解密；要求校验时报告错误
+#[test]
+fn corrupted_manifest_only_fails_when_verification_is_requested() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+    let decrypted_dir = temp.path().join("decrypted");
+
+    fs::create_dir_all(&input_dir).expect("create input dir");
+    fs::write(input_dir.join("a.txt"), b"manifest payload").expect("write a");
+
+    engine::encrypt_folder(
+        &input_dir,
+        &encrypted_dir,
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt folder");
+
+    let manifest_path = encrypted_dir.join("manifest.svmf");
+    let mut manifest = fs::read(&manifest_path).expect("read manifest");
+    let last = manifest.len() - 1;
+    manifest[last] ^= 0xff;
+    fs::write(&manifest_path, manifest).expect("corrupt manifest");
+
+    engine::decrypt_folder(&encrypted_dir, &decrypted_dir, "folder-password")
+        .expect("decrypt without verification");
+    assert_eq!(
+        fs::read(decrypted_dir.join("a.txt")).expect("read a"),
+        b"manifest payload"
+    );
+
+    let verify = engine::FolderOptions {
+        verify_manifest: true,
+        ..Default::default()
+    };
+    engine::decrypt_folder_with_options(
+        &encrypted_dir,
+        &temp.path().join("verified"),
+        "folder-password",
+        &verify,
+    )
+    .expect_err("corrupted manifest must fail verification");
+}
+
+/// 测试 manifest 中记录的 setuid 等特殊权限位不会被还原
+#[cfg(unix)]
+#[test]
+fn folder_decrypt_drops_special_mode_bits_from_manifest() {
+    use engine::format::manifest::Manifest;
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+    let decrypted_dir = temp.path().join("decrypted");
+
+    fs::create_dir_all(&input_dir).expect("create input dir");
+    fs::write(input_dir.join("tool"), b"#!/bin/sh\n").expect("write tool");
+
+    engine::encrypt_folder(
+        &input_dir,
+        &encrypted_dir,
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt folder");
+
+    // 以同一密码重写 manifest，使其记录 setuid 权限
+    let manifest_path = encrypted_dir.join("manifest.svmf");
+    let plaintext = engine::decrypt_bytes(
+        &fs::read(&manifest_path).expect("read manifest"),
+        "folder-password",
+    )
+    .expect("decrypt manifest");
+    let mut manifest = Manifest::from_bytes(&plaintext).expect("parse manifest");
+    manifest.entries[0].metadata.mode = 0o4755;
+    let ciphertext = engine::encrypt_bytes(
+        &manifest.to_bytes().expect("serialize manifest"),
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt manifest");
+    fs::write(&manifest_path, ciphertext).expect("write manifest");
+
+    engine::decrypt_folder(&encrypted_dir, &decrypted_dir, "folder-password")
+        .expect("decrypt folder");
+
+    let mode = fs::metadata(decrypted_dir.join("tool"))
+        .expect("metadata")
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o7777, 0o755);
+}
+
+/// 测试 `verify_folder` 不写出明文，校验全部 `.svlt` 文件并汇总通过、失败与跳过的文件
+#[test]
+fn verify_folder_reports_good_and_corrupted_files() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let encrypted_dir = temp.path().join("encrypted");
+    fs::create_dir_all(input_dir.join("sub")).expect("create input dir");
+    fs::write(input_dir.join("good.txt"), b"intact backup").expect("write good");
+    fs::write(input_dir.join("sub/bad.bin"), vec![0x42u8; 200 * 1024]).expect("write bad");
+
+    engine::encrypt_folder(
+        &input_dir,
+        &encrypted_dir,
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt folder");
+    fs::write(encrypted_dir.join("README.md"), b"plain readme").expect("write readme");
+
+    // 损坏最后一个数据 chunk，第一个 chunk 仍能通过认证
+    let bad = encrypted_dir.join("sub/bad.bin.svlt");
+    let mut bytes = fs::read(&bad).expect("read bad");
+    let offset = bytes.len() - 100;
+    bytes[offset] ^= 0x01;
+    fs::write(&bad, bytes).expect("write corrupted");
+
+    let before = count_files(temp.path(), |_| true);
+    let result = engine::verify_folder(&encrypted_dir, "folder-password").expect("verify folder");
+    assert_eq!(result.processed, 1);
+    assert_eq!(
+        result.failed,
+        [std::path::PathBuf::from("sub/bad.bin.svlt")]
+    );
+    assert_eq!(result.skipped, [std::path::PathBuf::from("README.md")]);
+    assert_eq!(count_files(temp.path(), |_| true), before);
+
+    let result = engine::verify_folder(&encrypted_dir, "wrong-password").expect("verify folder");
+    assert_eq!(result.processed, 0);
+    assert_eq!(result.failed.len(), 2);
+
+    let options = engine::FolderOptions {
+        stop_on_first_failure: true,
+        ..engine::FolderOptions::default()
+    };
+    let err = engine::verify_folder_with_options(&encrypted_dir, "folder-password", &options)
+        .expect_err("stop on first failure");
+    assert!(
+        matches!(err, engine::SealVaultError::CorruptedData),
+        "{err:?}"
+    );
+}
+
+/// 测试遍历时无权读取的子目录以 `PermissionDenied` 报告，而不是笼统的 `Other`
+#[cfg(unix)]
+#[test]
+fn unreadable_subdirectory_reports_permission_denied() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    let locked = input_dir.join("locked");
+    fs::create_dir_all(&locked).expect("create input dir");
+    fs::write(locked.join("secret.txt"), b"hidden").expect("write secret");
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).expect("chmod locked");
+
+    // root 等不受目录权限限制时无法构造该错误
+    if fs::read_dir(&locked).is_ok() {
+        fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).expect("restore locked");
+        return;
+    }
+
+    let err = engine::encrypt_folder(
+        &input_dir,
+        &temp.path().join("encrypted"),
+        "folder-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect_err("unreadable subdirectory");
+    let verify_err =
+        engine::verify_folder(&input_dir, "folder-password").expect_err("unreadable subdirectory");
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).expect("restore locked");
+
+    for err in [err, verify_err] {
+        assert!(
+            matches!(&err, engine::SealVaultError::Io(e)
+                if e.kind() == std::io::ErrorKind::PermissionDenied),
+            "{err:?}"
+        );
+    }
+}
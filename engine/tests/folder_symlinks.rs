@@ -0,0 +1,140 @@
+//! 目录加解密的符号链接策略测试（仅 Unix）
+#![cfg(all(unix, feature = "fs"))]
+
+use std::fs;
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
+
+use tempfile::tempdir;
+
+const PASSWORD: &str = "symlink-password";
+
+/// 构造含普通文件、指向文件的链接与指向目录的链接的输入目录
+fn build_tree(root: &Path) -> PathBuf {
+    let input_dir = root.join("plain");
+    fs::create_dir_all(input_dir.join("data")).expect("create input dir");
+    fs::write(input_dir.join("data/real.txt"), b"real payload").expect("write file");
+    symlink("data/real.txt", input_dir.join("file-link")).expect("create file link");
+    symlink("data", input_dir.join("dir-link")).expect("create dir link");
+    input_dir
+}
+
+fn roundtrip(root: &Path, policy: engine::SymlinkPolicy) -> (PathBuf, PathBuf) {
+    let input_dir = build_tree(root);
+    let encrypted_dir = root.join("encrypted");
+    let decrypted_dir = root.join("decrypted");
+    let options = engine::FolderOptions {
+        symlinks: policy,
+        ..Default::default()
+    };
+
+    engine::encrypt_folder_with_options(
+        &input_dir,
+        &encrypted_dir,
+        PASSWORD,
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        &options,
+    )
+    .expect("encrypt folder");
+    engine::decrypt_folder_with_options(&encrypted_dir, &decrypted_dir, PASSWORD, &options)
+        .expect("decrypt folder");
+
+    (encrypted_dir, decrypted_dir)
+}
+
+fn is_symlink(path: &Path) -> bool {
+    fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink())
+}
+
+#[test]
+fn skip_policy_drops_links() {
+    let temp = tempdir().expect("create temp dir");
+    let (encrypted_dir, decrypted_dir) = roundtrip(temp.path(), engine::SymlinkPolicy::Skip);
+
+    assert!(!encrypted_dir.join("file-link.svlt").exists());
+    assert_eq!(
+        fs::read(decrypted_dir.join("data/real.txt")).expect("read file"),
+        b"real payload"
+    );
+    assert!(fs::symlink_metadata(decrypted_dir.join("file-link")).is_err());
+    assert!(fs::symlink_metadata(decrypted_dir.join("dir-link")).is_err());
+}
+
+#[test]
+fn preserve_policy_recreates_file_and_dir_links() {
+    let temp = tempdir().expect("create temp dir");
+    let (encrypted_dir, decrypted_dir) =
+        roundtrip(temp.path(), engine::SymlinkPolicy::PreserveAsLink);
+
+    // 链接只记录在加密的 manifest 中，不产生 .svlt 文件
+    assert!(!encrypted_dir.join("file-link.svlt").exists());
+    assert!(!encrypted_dir.join("dir-link").exists());
+
+    for (link, target) in [("file-link", "data/real.txt"), ("dir-link", "data")] {
+        let link = decrypted_dir.join(link);
+        assert!(is_symlink(&link), "{link:?} should be a symlink");
+        assert_eq!(fs::read_link(&link).expect("read link"), Path::new(target));
+    }
+    assert_eq!(
+        fs::read(decrypted_dir.join("dir-link/real.txt")).expect("read through dir link"),
+        b"real payload"
+    );
+}
+
+#[test]
+fn follow_files_policy_encrypts_link_targets() {
+    let temp = tempdir().expect("create temp dir");
+    let (encrypted_dir, decrypted_dir) = roundtrip(temp.path(), engine::SymlinkPolicy::FollowFiles);
+
+    // 指向文件的链接以链接名加密目标内容，解密后是普通文件
+    assert!(encrypted_dir.join("file-link.svlt").is_file());
+    let restored = decrypted_dir.join("file-link");
+    assert!(!is_symlink(&restored));
+    assert_eq!(fs::read(&restored).expect("read file"), b"real payload");
+
+    // 指向目录的链接不跟随，仍作为链接还原
+    assert!(!encrypted_dir.join("dir-link").exists());
+    assert!(is_symlink(&decrypted_dir.join("dir-link")));
+}
+
+#[test]
+fn decrypt_refuses_links_nested_under_restored_links() {
+    use engine::format::manifest::{MANIFEST_FILE_NAME, Manifest, ManifestSymlink};
+
+    let temp = tempdir().expect("create temp dir");
+    let encrypted_dir = temp.path().join("encrypted");
+    let decrypted_dir = temp.path().join("decrypted");
+    let outside = temp.path().join("outside");
+    fs::create_dir_all(&encrypted_dir).expect("create encrypted dir");
+    fs::create_dir_all(&outside).expect("create outside dir");
+
+    // 伪造的 manifest：先还原 `escape -> <outside>`，再经由它在外部创建链接
+    let manifest = Manifest::new(
+        Vec::new(),
+        vec![
+            ManifestSymlink {
+                path: PathBuf::from("escape"),
+                target: outside.clone(),
+            },
+            ManifestSymlink {
+                path: PathBuf::from("escape/planted"),
+                target: PathBuf::from("/etc/passwd"),
+            },
+        ],
+    );
+    let ciphertext = engine::encrypt_bytes(
+        &manifest.to_bytes().expect("serialize"),
+        PASSWORD,
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt manifest");
+    fs::write(encrypted_dir.join(MANIFEST_FILE_NAME), ciphertext).expect("write manifest");
+
+    let err = engine::decrypt_folder(&encrypted_dir, &decrypted_dir, PASSWORD)
+        .expect_err("nested link must be rejected");
+    assert!(
+        matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidData),
+        "unexpected error: {err:?}"
+    );
+    assert!(fs::symlink_metadata(outside.join("planted")).is_err());
+}
@@ -0,0 +1,156 @@
+//! Header 注释（`EncryptOptions::header_comment`）测试
+#![cfg(feature = "fs")]
+
+use std::fs;
+
+use engine::format::header::{Header, MAX_COMMENT_LEN};
+use engine::{EncryptOptions, SealVaultError};
+use tempfile::tempdir;
+
+const COMMENT: &[u8] = b"backup-job-2026-10-16#42";
+
+fn options_with_comment(comment: &[u8]) -> EncryptOptions {
+    EncryptOptions {
+        chunk_size: 1024,
+        header_comment: comment.to_vec(),
+        ..EncryptOptions::default()
+    }
+}
+
+/// 测试注释无需密码即可读取、不影响解密，并在 rekey 后保留
+#[test]
+fn header_comment_roundtrips_and_survives_rekey() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    let decrypted = temp.path().join("output.bin");
+    let plaintext = b"labelled backup ".repeat(300);
+    fs::write(&input, &plaintext).expect("write input");
+
+    engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        "comment-password",
+        &options_with_comment(COMMENT),
+    )
+    .expect("encrypt");
+    assert_eq!(
+        engine::inspect(&encrypted).expect("inspect").header_comment,
+        COMMENT
+    );
+
+    engine::decrypt(&encrypted, &decrypted, "comment-password").expect("decrypt");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+
+    engine::rekey(&encrypted, &encrypted, "comment-password", "new-password").expect("rekey");
+    assert_eq!(
+        engine::inspect(&encrypted).expect("inspect").header_comment,
+        COMMENT
+    );
+    engine::decrypt(&encrypted, &decrypted, "new-password").expect("decrypt rekeyed");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+
+    // 默认不写注释
+    engine::encrypt(&input, &encrypted, "comment-password").expect("encrypt");
+    assert!(
+        engine::inspect(&encrypted)
+            .expect("inspect")
+            .header_comment
+            .is_empty()
+    );
+}
+
+/// 测试改动注释中的任一字节都会使解密失败：直接改动由 Header 校验值发现，
+/// 连同校验值一起重写的伪造 Header 由第一个 chunk 的认证发现
+#[test]
+fn tampered_header_comment_fails_decryption() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    let decrypted = temp.path().join("output.bin");
+    fs::write(&input, b"do not relabel me").expect("write input");
+
+    engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        "comment-password",
+        &options_with_comment(COMMENT),
+    )
+    .expect("encrypt");
+
+    let data = fs::read(&encrypted).expect("read encrypted");
+    let offset = data
+        .windows(COMMENT.len())
+        .position(|w| w == COMMENT)
+        .expect("comment stored in plain");
+    let mut tampered = data.clone();
+    tampered[offset + COMMENT.len() - 1] ^= 0x01;
+    fs::write(&encrypted, &tampered).expect("write tampered");
+
+    let err = engine::inspect(&encrypted).expect_err("tampered comment");
+    assert!(matches!(err, SealVaultError::CorruptedData), "{err:?}");
+    let err =
+        engine::decrypt(&encrypted, &decrypted, "comment-password").expect_err("tampered comment");
+    assert!(matches!(err, SealVaultError::CorruptedData), "{err:?}");
+    assert!(!decrypted.exists());
+
+    // 重新序列化改过注释的 Header，校验值随之更新
+    let mut header = Header::read(&data[..]).expect("read header");
+    header.comment = b"backup-job-2026-10-16#43".to_vec();
+    let mut forged = Vec::new();
+    header.write(&mut forged).expect("write forged header");
+    forged.extend_from_slice(&data[forged.len()..]);
+    fs::write(&encrypted, &forged).expect("write forged");
+
+    assert_eq!(
+        engine::inspect(&encrypted).expect("inspect").header_comment,
+        b"backup-job-2026-10-16#43"
+    );
+    let err =
+        engine::decrypt(&encrypted, &decrypted, "comment-password").expect_err("forged comment");
+    assert!(
+        matches!(err, SealVaultError::InvalidPasswordOrFile),
+        "{err:?}"
+    );
+    assert!(!decrypted.exists());
+}
+
+/// 测试注释长度上限：超长注释在加密时被拒绝，恰好等于上限时可用
+#[test]
+fn header_comment_length_is_capped() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    fs::write(&input, b"data").expect("write input");
+
+    let err = engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        "comment-password",
+        &options_with_comment(&[b'c'; MAX_COMMENT_LEN + 1]),
+    )
+    .expect_err("comment too long");
+    assert!(
+        matches!(&err, SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+        "{err:?}"
+    );
+    assert!(!encrypted.exists());
+
+    let comment = [b'c'; MAX_COMMENT_LEN];
+    engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        "comment-password",
+        &options_with_comment(&comment),
+    )
+    .expect("comment at the limit");
+    assert_eq!(
+        engine::inspect(&encrypted).expect("inspect").header_comment,
+        comment
+    );
+    assert_eq!(
+        engine::decrypt_bytes(&fs::read(&encrypted).expect("read"), "comment-password")
+            .expect("decrypt"),
+        b"data"
+    );
+}
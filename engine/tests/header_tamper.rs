@@ -0,0 +1,248 @@
+//! Header 篡改检测测试：Header 校验值与 `header_digest` 认证
+#![cfg(feature = "fs")]
+
+use std::fs;
+use std::ops::Range;
+
+use engine::crypto::kdf::{KdfAlgorithm, ScryptParams};
+use engine::format::header::{HEADER_CHECK_SIZE, Header, TOTAL_CHUNKS_SIZE, VERSION_V1};
+use engine::{AeadAlgorithm, EncryptOptions, Padding, SealVaultError};
+use sha2::{Digest, Sha256};
+use tempfile::tempdir;
+
+const PASSWORD: &str = "header-tamper-password";
+const COMMENT: &[u8] = b"nightly";
+
+/// 两种样本都带 scrypt、明文摘要与注释：带填充的样本不记录 chunk 总数，
+/// 不带填充的样本记录 chunk 总数，合起来覆盖 Header 的全部可选字段
+const PADDED_SAMPLES: [bool; 2] = [true, false];
+
+/// 写出样本文件，返回密文
+fn encrypt_sample(dir: &std::path::Path, padded: bool) -> Vec<u8> {
+    let input = dir.join("input.bin");
+    let encrypted = dir.join("input.svlt");
+    fs::write(&input, b"header fields under test ".repeat(100)).expect("write input");
+    let options = EncryptOptions {
+        chunk_size: 1024,
+        kdf: KdfAlgorithm::Scrypt(ScryptParams {
+            log_n: 4,
+            r: 8,
+            p: 1,
+        }),
+        padding: padded.then_some(Padding::ToBlock(512)),
+        plaintext_digest: true,
+        header_comment: COMMENT.to_vec(),
+        ..EncryptOptions::with_algorithm(AeadAlgorithm::Aes256Gcm)
+    };
+    engine::encrypt_with_options(&input, &encrypted, PASSWORD, &options).expect("encrypt");
+    fs::read(&encrypted).expect("read encrypted")
+}
+
+/// 样本 Header 中各字段的字节范围
+fn header_fields(padded: bool) -> Vec<(&'static str, Range<usize>)> {
+    let mut fields = vec![
+        ("algorithm", 9..10),
+        ("salt", 10..26),
+        ("base_nonce", 26..50),
+        ("chunk_size", 50..54),
+        ("kdf_id", 54..55),
+        ("kdf_params", 55..64),
+        ("codec", 64..65),
+        ("flags", 65..66),
+    ];
+    let mut offset = 66;
+    if padded {
+        fields.push(("padding_block", offset..offset + 4));
+        offset += 4;
+    }
+    fields.push(("comment_len", offset..offset + 1));
+    fields.push(("comment", offset + 1..offset + 1 + COMMENT.len()));
+    offset += 1 + COMMENT.len();
+    if !padded {
+        fields.push(("total_chunks", offset..offset + TOTAL_CHUNKS_SIZE));
+        offset += TOTAL_CHUNKS_SIZE;
+    }
+    fields.push(("header_check", offset..offset + HEADER_CHECK_SIZE));
+    fields
+}
+
+/// 样本 Header 中指定字段的字节范围
+fn field(padded: bool, name: &str) -> Option<Range<usize>> {
+    header_fields(padded)
+        .into_iter()
+        .find(|(field, _)| *field == name)
+        .map(|(_, range)| range)
+}
+
+/// 改写为 v1：v1 Header 只有固定部分，KDF 参数、标志位、扩展区与校验值随之成为 Stream 的开头
+fn downgrade_to_v1(data: &[u8]) -> Vec<u8> {
+    let mut downgraded = data.to_vec();
+    downgraded[8] = VERSION_V1;
+    downgraded
+}
+
+/// 测试翻转 Header 任一字段中的任一位，`inspect` 与解密都在派生密钥前报告 `CorruptedData`，
+/// magic 与版本号分别报告 `NotSealVaultFile` 与 `UnsupportedVersion`
+#[test]
+fn flipping_any_header_field_is_reported_before_key_derivation() {
+    for padded in PADDED_SAMPLES {
+        flip_every_header_bit(padded);
+    }
+}
+
+fn flip_every_header_bit(padded: bool) {
+    let temp = tempdir().expect("create temp dir");
+    let original = encrypt_sample(temp.path(), padded);
+    let tampered_path = temp.path().join("tampered.svlt");
+    let output = temp.path().join("output.bin");
+
+    // 字段范围恰好覆盖整个 Header
+    let header = Header::read(&original[..]).expect("read header");
+    let mut encoded = Vec::new();
+    header.write(&mut encoded).expect("write header");
+    assert_eq!(
+        header_fields(padded).last().expect("fields").1.end,
+        encoded.len()
+    );
+    assert_eq!(original[..encoded.len()], encoded);
+
+    let check = |tampered: &[u8], expected: fn(&SealVaultError) -> bool, what: &str| {
+        fs::write(&tampered_path, tampered).expect("write tampered");
+        let err = engine::inspect(&tampered_path).expect_err(what);
+        assert!(expected(&err), "inspect {what}: {err:?}");
+        let err = engine::decrypt(&tampered_path, &output, PASSWORD).expect_err(what);
+        assert!(expected(&err), "decrypt {what}: {err:?}");
+        assert!(!output.exists());
+    };
+
+    let mut tampered = original.clone();
+    tampered[0] ^= 0x01;
+    check(
+        &tampered,
+        |e| matches!(e, SealVaultError::NotSealVaultFile),
+        "magic",
+    );
+
+    let mut tampered = original.clone();
+    tampered[8] = 0xFF;
+    check(
+        &tampered,
+        |e| matches!(e, SealVaultError::UnsupportedVersion),
+        "version",
+    );
+
+    for (name, range) in header_fields(padded) {
+        for offset in range {
+            for bit in [0x01, 0x80] {
+                let mut tampered = original.clone();
+                tampered[offset] ^= bit;
+                check(
+                    &tampered,
+                    |e| matches!(e, SealVaultError::CorruptedData),
+                    &format!("{name} byte {offset} bit {bit:#04x} (padded: {padded})"),
+                );
+            }
+        }
+    }
+}
+
+/// 测试连同校验值一起重写的伪造 Header（改算法、chunk 大小或 salt）仍因 `header_digest`
+/// 不匹配而在第一个 chunk 认证失败；降级为无校验值的 v1 同样无法解密
+#[test]
+fn forged_header_with_recomputed_check_fails_authentication() {
+    for padded in PADDED_SAMPLES {
+        forge_header_fields(padded);
+    }
+}
+
+fn forge_header_fields(padded: bool) {
+    let temp = tempdir().expect("create temp dir");
+    let original = encrypt_sample(temp.path(), padded);
+    let header = Header::read(&original[..]).expect("read header");
+    let mut encoded = Vec::new();
+    header.write(&mut encoded).expect("write header");
+    let stream = &original[encoded.len()..];
+
+    let mut forgeries = vec![
+        (
+            "algorithm",
+            Header {
+                algorithm: AeadAlgorithm::XChaCha20Poly1305,
+                ..header.clone()
+            },
+        ),
+        (
+            "chunk_size",
+            Header {
+                chunk_size: 4096,
+                ..header.clone()
+            },
+        ),
+        (
+            "salt",
+            Header {
+                salt: [0x5a; 16],
+                ..header.clone()
+            },
+        ),
+    ];
+    if let Some(total_chunks) = header.total_chunks {
+        forgeries.push((
+            "total_chunks",
+            Header {
+                total_chunks: Some(total_chunks - 1),
+                ..header.clone()
+            },
+        ));
+    }
+    for (name, forged_header) in forgeries {
+        let mut forged = Vec::new();
+        forged_header
+            .write(&mut forged)
+            .expect("write forged header");
+        forged.extend_from_slice(stream);
+
+        Header::read(&forged[..]).expect("forged header parses");
+        let err = engine::decrypt_bytes(&forged, PASSWORD).expect_err(name);
+        assert!(
+            matches!(err, SealVaultError::InvalidPasswordOrFile),
+            "{name}: {err:?}"
+        );
+    }
+
+    // v1 没有校验值：把版本号改为 1 后，v2 追加的字段被当作 Stream 的开头
+    let downgraded = downgrade_to_v1(&original);
+    assert_eq!(
+        Header::read(&downgraded[..]).expect("parses as v1").version,
+        VERSION_V1
+    );
+    assert!(engine::decrypt_bytes(&downgraded, PASSWORD).is_err());
+}
+
+/// 测试校验值正确但算法 ID 未知的 Header（如更新版本写出的文件）报告 `UnsupportedAlgorithm`，
+/// 不与损坏混淆
+#[test]
+fn unknown_algorithm_id_is_a_typed_error() {
+    let temp = tempdir().expect("create temp dir");
+    let original = encrypt_sample(temp.path(), false);
+    let input = temp.path().join("future.svlt");
+    let output = temp.path().join("output.bin");
+    let check_range = field(false, "header_check").expect("header check");
+
+    let mut future = original.clone();
+    future[9] = 0x7F;
+    let check = Sha256::digest(&future[..check_range.start]);
+    future[check_range.clone()].copy_from_slice(&check[..HEADER_CHECK_SIZE]);
+    fs::write(&input, &future).expect("write future file");
+
+    let is_unsupported =
+        |err: &SealVaultError| matches!(err, SealVaultError::UnsupportedAlgorithm(0x7F));
+    let err = engine::inspect(&input).expect_err("unknown algorithm");
+    assert!(is_unsupported(&err), "inspect: {err:?}");
+    let err = engine::decrypt(&input, &output, PASSWORD).expect_err("unknown algorithm");
+    assert!(is_unsupported(&err), "decrypt: {err:?}");
+    assert!(!output.exists());
+    let err = engine::decrypt_bytes(&future, PASSWORD).expect_err("unknown algorithm");
+    assert!(is_unsupported(&err), "decrypt_bytes: {err:?}");
+    assert!(err.to_string().contains("id 127"), "{err}");
+}
@@ -0,0 +1,98 @@
+//! 无需密码读取 Header 元数据的 `inspect` 测试
+#![cfg(feature = "fs")]
+
+use std::fs;
+
+use engine::format::header::{MAGIC, VERSION};
+use tempfile::tempdir;
+
+#[test]
+fn inspect_reports_header_fields() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("photo.jpg");
+    let encrypted = temp.path().join("photo.jpg.svlt");
+    fs::write(&input, vec![1u8; 10_000]).expect("write input");
+
+    let options = engine::EncryptOptions {
+        chunk_size: 4096,
+        ..engine::EncryptOptions::with_algorithm(engine::AeadAlgorithm::Aes256Gcm)
+    };
+    engine::encrypt_with_options(&input, &encrypted, "pw", &options).expect("encrypt");
+
+    let info = engine::inspect(&encrypted).expect("inspect");
+    assert_eq!(info.version, VERSION);
+    assert_eq!(info.algorithm, engine::AeadAlgorithm::Aes256Gcm);
+    assert_eq!(info.chunk_size, 4096);
+
+    // salt 紧跟 magic、version 与 algorithm
+    let bytes = fs::read(&encrypted).expect("read encrypted");
+    assert_eq!(info.salt[..], bytes[10..26]);
+}
+
+#[test]
+fn inspect_rejects_non_sealvault_inputs() {
+    let temp = tempdir().expect("create temp dir");
+
+    let cases: [(&str, Vec<u8>); 3] = [
+        ("empty.bin", Vec::new()),
+        (
+            "text.txt",
+            b"just a regular text file, nothing to see here".to_vec(),
+        ),
+        ("short.svlt", MAGIC[..4].to_vec()),
+    ];
+    for (name, content) in cases {
+        let path = temp.path().join(name);
+        fs::write(&path, content).expect("write input");
+        let err = engine::inspect(&path).expect_err("not a SealVault file");
+        assert!(
+            matches!(err, engine::SealVaultError::NotSealVaultFile),
+            "{name}: unexpected error: {err:?}"
+        );
+    }
+
+    let mut future = MAGIC.to_vec();
+    future.push(99);
+    future.extend_from_slice(&[0u8; 64]);
+    let path = temp.path().join("future.svlt");
+    fs::write(&path, future).expect("write input");
+    let err = engine::inspect(&path).expect_err("unknown version");
+    assert!(
+        matches!(err, engine::SealVaultError::UnsupportedVersion),
+        "unexpected error: {err:?}"
+    );
+}
+
+/// 测试 crate 根导出的格式常量与文件字节一致，算法 ID 可双向映射
+#[test]
+fn crate_root_exposes_format_constants() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    fs::write(&input, b"interop").expect("write input");
+    engine::encrypt(&input, &encrypted, "pw").expect("encrypt");
+
+    let bytes = fs::read(&encrypted).expect("read encrypted");
+    assert_eq!(&bytes[..8], engine::MAGIC);
+    assert_eq!(bytes[8], engine::format_version());
+    assert_eq!(engine::format_version(), engine::VERSION);
+    let algorithm = engine::AeadAlgorithm::from_u8(bytes[9]).expect("known algorithm id");
+    assert_eq!(
+        algorithm,
+        engine::inspect(&encrypted).expect("inspect").algorithm
+    );
+    assert!(bytes.len() > engine::HEADER_SIZE);
+
+    for algorithm in [
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        engine::AeadAlgorithm::Aes256Gcm,
+        engine::AeadAlgorithm::ChaCha20Poly1305,
+        engine::AeadAlgorithm::Aes128Gcm,
+    ] {
+        assert_eq!(
+            engine::AeadAlgorithm::from_u8(algorithm.to_u8()),
+            Some(algorithm)
+        );
+    }
+    assert_eq!(engine::AeadAlgorithm::from_u8(0), None);
+}
@@ -0,0 +1,73 @@
+//! Argon2id 参数校准（`kdf::calibrate`）测试
+//!
+//! 计时受机器与负载影响，容差取得很宽，只验证搜索方向与量级正确。
+#![cfg(feature = "fs")]
+
+use std::fs;
+use std::time::{Duration, Instant};
+
+use engine::EncryptOptions;
+use engine::crypto::kdf::{
+    self, CALIBRATE_MAX_M_COST, CALIBRATE_MIN_M_COST, KdfAlgorithm, KdfParams,
+};
+use tempfile::tempdir;
+
+/// 取三次派生耗时的中位数，降低偶发抖动的影响
+fn measure(params: &KdfParams) -> Duration {
+    let mut samples: Vec<Duration> = (0..3)
+        .map(|_| {
+            let start = Instant::now();
+            kdf::derive_key_raw_with_params(b"measure", &[7u8; 16], params).expect("derive");
+            start.elapsed()
+        })
+        .collect();
+    samples.sort();
+    samples[1]
+}
+
+/// 测试校准结果的实测耗时落在目标附近的宽容差区间内，且参数可直接用于加密
+#[test]
+fn calibrate_hits_target_within_loose_tolerance() {
+    let target = Duration::from_millis(120);
+    let params = kdf::calibrate(target).expect("calibrate");
+    assert!(params.is_supported(), "{params:?}");
+    assert_eq!(params.p_cost, 1);
+    assert!((CALIBRATE_MIN_M_COST..=CALIBRATE_MAX_M_COST).contains(&params.m_cost));
+
+    let elapsed = measure(&params);
+    // 已取最小参数时无法更快，已取最大内存成本与迭代上限时无法更慢
+    if params.m_cost > CALIBRATE_MIN_M_COST || params.t_cost > 1 {
+        assert!(elapsed <= target * 3, "{params:?} took {elapsed:?}");
+    }
+    if params.m_cost < CALIBRATE_MAX_M_COST {
+        assert!(elapsed >= target / 4, "{params:?} took {elapsed:?}");
+    }
+
+    let options = EncryptOptions {
+        kdf: KdfAlgorithm::Argon2id(params),
+        ..EncryptOptions::default()
+    };
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    fs::write(&input, b"calibrated").expect("write input");
+    engine::encrypt_with_options(&input, &encrypted, "pw", &options)
+        .expect("encrypt with calibrated params");
+    assert_eq!(
+        engine::decrypt_bytes(&fs::read(&encrypted).expect("read encrypted"), "pw")
+            .expect("decrypt"),
+        b"calibrated"
+    );
+}
+
+/// 测试更长的目标耗时不会得到更弱的参数
+#[test]
+fn calibrate_is_monotonic_in_target() {
+    let fast = kdf::calibrate(Duration::from_millis(30)).expect("calibrate fast");
+    let slow = kdf::calibrate(Duration::from_millis(240)).expect("calibrate slow");
+    assert!(
+        u64::from(slow.m_cost) * u64::from(slow.t_cost)
+            >= u64::from(fast.m_cost) * u64::from(fast.t_cost),
+        "fast {fast:?}, slow {slow:?}"
+    );
+}
@@ -0,0 +1,107 @@
+//! 目录共享 salt 时的密钥复用测试
+//!
+//! `derive_key_invocations` 是进程级计数器，本文件只放一个测试，
+//! 避免与其他并行运行的测试互相干扰。计数器只在 `test-hooks` feature 下存在。
+#![cfg(all(feature = "fs", feature = "test-hooks"))]
+
+use std::fs;
+
+use engine::crypto::kdf::derive_key_invocations;
+use tempfile::tempdir;
+
+const FILES: usize = 12;
+
+fn derivations_during(f: impl FnOnce()) -> usize {
+    let before = derive_key_invocations();
+    f();
+    derive_key_invocations() - before
+}
+
+#[test]
+fn shared_salt_derives_one_key_per_folder_run() {
+    let temp = tempdir().expect("create temp dir");
+    let input_dir = temp.path().join("plain");
+    fs::create_dir_all(input_dir.join("nested")).expect("create input dir");
+    for i in 0..FILES {
+        let dir = if i % 2 == 0 { "" } else { "nested" };
+        fs::write(
+            input_dir.join(dir).join(format!("f{i}.txt")),
+            format!("file {i}"),
+        )
+        .expect("write file");
+    }
+
+    // 默认：每个文件独立 salt，逐个派生；manifest 另需一次
+    let per_file_dir = temp.path().join("per-file");
+    let n = derivations_during(|| {
+        engine::encrypt_folder(
+            &input_dir,
+            &per_file_dir,
+            "pw",
+            engine::AeadAlgorithm::XChaCha20Poly1305,
+        )
+        .expect("encrypt folder");
+    });
+    assert_eq!(n, FILES + 1);
+
+    // 共享 salt：整次运行只派生一次，校验写出与 manifest 也复用同一密钥
+    let shared = engine::FolderOptions {
+        shared_salt: true,
+        verify_after_write: true,
+        ..Default::default()
+    };
+    let shared_dir = temp.path().join("shared");
+    let n = derivations_during(|| {
+        engine::encrypt_folder_with_options(
+            &input_dir,
+            &shared_dir,
+            "pw",
+            engine::AeadAlgorithm::XChaCha20Poly1305,
+            &shared,
+        )
+        .expect("encrypt folder with shared salt");
+    });
+    assert_eq!(n, 1);
+
+    let aes_dir = temp.path().join("aes");
+    let n = derivations_during(|| {
+        engine::encrypt_folder_with_options(
+            &input_dir,
+            &aes_dir,
+            "pw",
+            engine::AeadAlgorithm::Aes256Gcm,
+            &engine::FolderOptions {
+                shared_salt: true,
+                ..Default::default()
+            },
+        )
+        .expect("encrypt folder with shared salt");
+    });
+    assert_eq!(n, 1);
+
+    // 解密自动识别共享 salt，无需任何选项
+    let decrypted_dir = temp.path().join("decrypted");
+    let n = derivations_during(|| {
+        engine::decrypt_folder(&shared_dir, &decrypted_dir, "pw").expect("decrypt folder");
+    });
+    assert_eq!(n, 1);
+    for i in 0..FILES {
+        let dir = if i % 2 == 0 { "" } else { "nested" };
+        assert_eq!(
+            fs::read(decrypted_dir.join(dir).join(format!("f{i}.txt"))).expect("read"),
+            format!("file {i}").into_bytes()
+        );
+    }
+
+    // 逐文件 salt 的目录仍按文件派生（另加读取 manifest 的一次）
+    let n = derivations_during(|| {
+        engine::decrypt_folder(&per_file_dir, &temp.path().join("decrypted-2"), "pw")
+            .expect("decrypt folder");
+    });
+    assert_eq!(n, FILES + 1);
+
+    // 共享 salt 的文件仍可单独解密
+    let single = temp.path().join("single.txt");
+    engine::decrypt(&shared_dir.join("f0.txt.svlt"), &single, "pw").expect("decrypt single");
+    assert_eq!(fs::read(&single).expect("read"), b"file 0");
+}
@@ -0,0 +1,65 @@
+//! 密钥材料生命周期测试
+//!
+//! 派生密钥在 stream 中的流转：
+//! 1. KDF 输出 `Zeroizing<[u8; 32]>`（`DerivedKey.key`），随 DerivedKey drop 清零
+//! 2. StreamEncryptor / StreamDecryptor 复制一份，保存在 `Zeroizing` 字段中，随其 drop 清零
+//! 3. 每个 chunk 临时构造 AEAD 实例，实例内部的密钥状态在下列类型 drop 时清零
+//!
+//! 无法在安全代码中观察已释放的内存，这里在编译期断言第 3 步依赖的类型实现了
+//! `ZeroizeOnDrop`：若有人移除 Cargo.toml 中开启 `zeroize` 特性的依赖，本测试无法编译。
+
+use zeroize::ZeroizeOnDrop;
+
+fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+
+#[test]
+fn aead_key_schedules_are_zeroized_on_drop() {
+    // XChaCha20-Poly1305 / ChaCha20-Poly1305：AEAD 实例保存的原始 key
+    assert_zeroize_on_drop::<chacha20poly1305::XChaCha20Poly1305>();
+    assert_zeroize_on_drop::<chacha20poly1305::ChaCha20Poly1305>();
+    // 加解密时内部使用的 ChaCha20 keystream 状态
+    assert_zeroize_on_drop::<chacha20::XChaCha20>();
+    assert_zeroize_on_drop::<chacha20::ChaCha20>();
+
+    // AES-256-GCM：AES 轮密钥（aes-gcm 0.10 的 AesGcm 本身不清零，依赖内部 Aes256）
+    assert_zeroize_on_drop::<aes::Aes256>();
+}
+
+#[test]
+fn stream_roundtrip_after_key_source_is_dropped() {
+    use engine::format::stream::{StreamDecryptor, StreamEncryptor};
+    use std::io::Cursor;
+    use zeroize::Zeroizing;
+
+    let plaintext = b"key material outlives its source only inside the stream".to_vec();
+    let base_nonce = [8u8; 24];
+    let digest = [9u8; 32];
+
+    // key 的来源在构造后立即 drop 并清零，stream 仍持有自己的副本
+    let mut encryptor = {
+        let key = Zeroizing::new([1u8; 32]);
+        StreamEncryptor::new(
+            &key,
+            engine::AeadAlgorithm::Aes256Gcm,
+            base_nonce,
+            16,
+            digest,
+        )
+        .expect("create encryptor")
+    };
+    let mut encrypted = Vec::new();
+    encryptor
+        .encrypt(Cursor::new(&plaintext), &mut encrypted)
+        .expect("encrypt");
+    drop(encryptor);
+
+    let mut decryptor = {
+        let key = Zeroizing::new([1u8; 32]);
+        StreamDecryptor::new(&key, engine::AeadAlgorithm::Aes256Gcm, base_nonce, digest)
+    };
+    let mut decrypted = Vec::new();
+    decryptor
+        .decrypt(Cursor::new(encrypted), &mut decrypted)
+        .expect("decrypt");
+    assert_eq!(decrypted, plaintext);
+}
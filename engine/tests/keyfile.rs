@@ -0,0 +1,141 @@
+//! keyfile 加密测试：密码 + keyfile、仅 keyfile、缺少或错误的 keyfile
+#![cfg(feature = "fs")]
+
+use std::fs;
+
+use tempfile::tempdir;
+
+#[test]
+fn password_and_keyfile_roundtrip() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("secret.txt");
+    let keyfile = temp.path().join("key.bin");
+    let encrypted = temp.path().join("secret.txt.svlt");
+    let decrypted = temp.path().join("secret.dec.txt");
+    let plaintext: Vec<u8> = (0..150_000u32).map(|i| (i % 241) as u8).collect();
+    fs::write(&input, &plaintext).expect("write input");
+    fs::write(&keyfile, [0x5au8; 4096]).expect("write keyfile");
+
+    engine::encrypt_with_keyfile(
+        &input,
+        &encrypted,
+        Some("pw"),
+        &keyfile,
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt");
+    engine::decrypt_with_keyfile(&encrypted, &decrypted, Some("pw"), &keyfile).expect("decrypt");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+
+    // 只有密码、没有 keyfile 不够
+    let err = engine::decrypt_with_keyfile(&encrypted, &decrypted, Some("other"), &keyfile)
+        .expect_err("wrong password");
+    assert!(
+        matches!(err, engine::SealVaultError::InvalidPasswordOrFile),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn keyfile_only_roundtrip() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("notes.md");
+    let keyfile = temp.path().join("key.bin");
+    let encrypted = temp.path().join("notes.md.svlt");
+    let decrypted = temp.path().join("notes.dec.md");
+    fs::write(&input, b"keyfile only").expect("write input");
+    fs::write(&keyfile, b"any file works as a keyfile").expect("write keyfile");
+
+    engine::encrypt_with_keyfile(
+        &input,
+        &encrypted,
+        None,
+        &keyfile,
+        engine::AeadAlgorithm::Aes256Gcm,
+    )
+    .expect("encrypt");
+    engine::decrypt_with_keyfile(&encrypted, &decrypted, None, &keyfile).expect("decrypt");
+    assert_eq!(fs::read(&decrypted).expect("read output"), b"keyfile only");
+}
+
+#[test]
+fn missing_or_wrong_keyfile_fails() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("data.bin");
+    let keyfile = temp.path().join("key.bin");
+    let other_keyfile = temp.path().join("other.bin");
+    let encrypted = temp.path().join("data.bin.svlt");
+    let decrypted = temp.path().join("data.dec.bin");
+    fs::write(&input, b"protected").expect("write input");
+    fs::write(&keyfile, b"right keyfile").expect("write keyfile");
+    fs::write(&other_keyfile, b"wrong keyfile").expect("write other keyfile");
+
+    engine::encrypt_with_keyfile(
+        &input,
+        &encrypted,
+        Some("pw"),
+        &keyfile,
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt");
+
+    let err = engine::decrypt(&encrypted, &decrypted, "pw").expect_err("missing keyfile");
+    assert!(
+        matches!(err, engine::SealVaultError::KeyfileRequired),
+        "unexpected error: {err:?}"
+    );
+
+    let err = engine::decrypt_with_keyfile(&encrypted, &decrypted, Some("pw"), &other_keyfile)
+        .expect_err("wrong keyfile");
+    assert!(
+        matches!(err, engine::SealVaultError::InvalidPasswordOrFile),
+        "unexpected error: {err:?}"
+    );
+    assert!(!decrypted.exists());
+}
+
+#[test]
+fn keyfile_rejected_for_password_only_file() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("plain.txt");
+    let keyfile = temp.path().join("key.bin");
+    let encrypted = temp.path().join("plain.txt.svlt");
+    let decrypted = temp.path().join("plain.dec.txt");
+    fs::write(&input, b"password only").expect("write input");
+    fs::write(&keyfile, b"unneeded").expect("write keyfile");
+
+    engine::encrypt(&input, &encrypted, "pw").expect("encrypt");
+
+    let err = engine::decrypt_with_keyfile(&encrypted, &decrypted, Some("pw"), &keyfile)
+        .expect_err("file does not use a keyfile");
+    assert!(
+        matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn keyfile_with_blank_password_is_rejected() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("data.bin");
+    let keyfile = temp.path().join("key.bin");
+    let encrypted = temp.path().join("data.bin.svlt");
+    fs::write(&input, b"protected").expect("write input");
+    fs::write(&keyfile, b"keyfile").expect("write keyfile");
+
+    for password in ["", "   "] {
+        let err = engine::encrypt_with_keyfile(
+            &input,
+            &encrypted,
+            Some(password),
+            &keyfile,
+            engine::AeadAlgorithm::XChaCha20Poly1305,
+        )
+        .expect_err("blank password must be rejected");
+        assert!(
+            matches!(err, engine::SealVaultError::WeakPassword),
+            "unexpected error: {err:?}"
+        );
+        assert!(!encrypted.exists());
+    }
+}
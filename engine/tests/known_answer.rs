@@ -0,0 +1,90 @@
+//! 已知答案测试：锁定 nonce 派生、AAD 与分帧格式
+#![cfg(feature = "fs")]
+
+mod kat;
+
+use std::fs;
+
+use engine::EncryptOptions;
+use kat::{KDF, KnownAnswer, ReplayRng, VECTORS, from_hex, to_hex};
+use tempfile::tempdir;
+
+fn encrypt_vector(vector: &KnownAnswer) -> Vec<u8> {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let output = temp.path().join("output.svlt");
+    fs::write(&input, vector.plaintext).expect("write input");
+
+    let options = EncryptOptions {
+        chunk_size: vector.chunk_size,
+        kdf: KDF,
+        ..EncryptOptions::with_algorithm(vector.algorithm)
+    };
+    let mut rng = ReplayRng::new(vector);
+    engine::encrypt_with_rng(&input, &output, vector.password, &options, &mut rng)
+        .expect("encrypt");
+    assert!(
+        rng.is_exhausted(),
+        "{}: salt or base_nonce not consumed",
+        vector.name
+    );
+    fs::read(&output).expect("read output")
+}
+
+/// 测试固定输入加密得到的密文与向量逐字节一致
+#[test]
+fn encryption_matches_known_answers() {
+    for vector in VECTORS {
+        let ciphertext = encrypt_vector(vector);
+        assert_eq!(to_hex(&ciphertext), vector.ciphertext, "{}", vector.name);
+    }
+}
+
+/// 测试向量中的密文可解密回原明文，错误密码被拒绝
+#[test]
+fn known_answer_ciphertexts_decrypt() {
+    for vector in VECTORS {
+        let ciphertext = from_hex(vector.ciphertext);
+        let plaintext = engine::decrypt_bytes(&ciphertext, vector.password).expect(vector.name);
+        assert_eq!(plaintext, vector.plaintext, "{}", vector.name);
+
+        assert!(engine::decrypt_bytes(&ciphertext, "not-the-password").is_err());
+    }
+}
+
+/// 测试 v2 的 Argon2id 派生直接以原始 salt 字节为输入，结果与向量及直接调用 argon2 一致，
+/// 且不同于 v1 以 base64 文本为 salt 的派生
+#[test]
+fn argon2id_raw_salt_derivation_matches_known_answer() {
+    use argon2::password_hash::SaltString;
+    use argon2::{Algorithm, Argon2, Params, Version};
+    use engine::crypto::kdf::{KdfParams, derive_key_raw_with_params, derive_key_with_params};
+
+    const PASSWORD: &[u8] = b"kat-password";
+    const SALT: &[u8; 16] = b"kat-salt-argon2!";
+    const RAW_KEY: &str = "2df4045213f0c7db619fac888a2c5232be50734c0422195c670908212112e449";
+    const LEGACY_KEY: &str = "2b59eedf33a2f09712d645b5160b41da1bd26a25cdcbf0a59c3b0c9795feed3d";
+    let params = KdfParams {
+        m_cost: 64,
+        t_cost: 2,
+        p_cost: 1,
+    };
+
+    let key = derive_key_raw_with_params(PASSWORD, SALT, &params).expect("derive raw");
+    assert_eq!(to_hex(&key[..]), RAW_KEY);
+
+    let mut expected = [0u8; 32];
+    Argon2::new(
+        Algorithm::Argon2id,
+        Version::V0x13,
+        Params::new(64, 2, 1, Some(32)).expect("argon2 params"),
+    )
+    .hash_password_into(PASSWORD, SALT, &mut expected)
+    .expect("argon2");
+    assert_eq!(key[..], expected);
+
+    let salt_string = SaltString::encode_b64(SALT).expect("encode salt");
+    assert_eq!(salt_string.as_str(), "a2F0LXNhbHQtYXJnb24yIQ");
+    let legacy = derive_key_with_params(PASSWORD, &salt_string, &params).expect("derive legacy");
+    assert_eq!(to_hex(&legacy[..]), LEGACY_KEY);
+}
@@ -0,0 +1,140 @@
+//! 跨越多个 chunk 的大文件测试：chunk 序号参与 nonce 派生，边界长度的分帧
+#![cfg(feature = "fs")]
+
+use std::fs;
+use std::path::Path;
+
+use engine::format::stream::{DEFAULT_CHUNK_SIZE, TAG_SIZE};
+use engine::{AeadAlgorithm, EncryptOptions, SealVaultError};
+use rand::RngCore;
+use rand_chacha::ChaCha20Rng;
+use rand_chacha::rand_core::SeedableRng;
+use tempfile::tempdir;
+
+const HEADER_LEN: usize = 93;
+const LEN_PREFIX: usize = 4;
+const FINAL_FRAME_LEN: usize = LEN_PREFIX + TAG_SIZE;
+const ALGORITHMS: [AeadAlgorithm; 2] = [AeadAlgorithm::XChaCha20Poly1305, AeadAlgorithm::Aes256Gcm];
+
+/// 固定种子生成的伪随机明文，失败时可复现
+fn seeded_plaintext(len: usize, seed: u64) -> Vec<u8> {
+    let mut data = vec![0u8; len];
+    ChaCha20Rng::seed_from_u64(seed).fill_bytes(&mut data);
+    data
+}
+
+/// 按默认 chunk 大小加密 `plaintext`，校验统计与密文长度后解密并比对
+fn roundtrip(dir: &Path, plaintext: &[u8], algorithm: AeadAlgorithm) -> Vec<u8> {
+    let input = dir.join("input.bin");
+    let encrypted = dir.join("input.svlt");
+    let decrypted = dir.join("decrypted.bin");
+    fs::write(&input, plaintext).expect("write input");
+
+    let stats = engine::encrypt_with_stats(
+        &input,
+        &encrypted,
+        "large-password",
+        &EncryptOptions::with_algorithm(algorithm),
+    )
+    .expect("encrypt");
+    let chunks = plaintext.len().div_ceil(DEFAULT_CHUNK_SIZE);
+    assert_eq!(stats.chunks, chunks as u64);
+    assert_eq!(stats.plaintext_bytes, plaintext.len() as u64);
+
+    let ciphertext = fs::read(&encrypted).expect("read encrypted");
+    assert_eq!(
+        ciphertext.len(),
+        HEADER_LEN + plaintext.len() + chunks * (LEN_PREFIX + TAG_SIZE) + FINAL_FRAME_LEN
+    );
+    assert_eq!(stats.ciphertext_bytes, ciphertext.len() as u64);
+
+    let stats =
+        engine::decrypt_with_stats(&encrypted, &decrypted, "large-password").expect("decrypt");
+    assert_eq!(stats.chunks, chunks as u64);
+    assert_eq!(fs::read(&decrypted).expect("read decrypted"), plaintext);
+    fs::remove_file(&decrypted).expect("remove decrypted");
+    ciphertext
+}
+
+/// 测试数 MiB 的伪随机数据在两种算法下均能跨越数十个 chunk 正确往返
+#[test]
+fn multi_mib_random_roundtrip_with_both_algorithms() {
+    let temp = tempdir().expect("create temp dir");
+    let plaintext = seeded_plaintext(3 * 1024 * 1024 + 12_345, 320);
+
+    for algorithm in ALGORITHMS {
+        roundtrip(temp.path(), &plaintext, algorithm);
+    }
+
+    // 全零明文下各 chunk 明文相同，密文仍须各不相同（每个 chunk 的 nonce 不同）
+    let zeros = vec![0u8; 4 * DEFAULT_CHUNK_SIZE];
+    let ciphertext = roundtrip(temp.path(), &zeros, AeadAlgorithm::XChaCha20Poly1305);
+    let frame = LEN_PREFIX + DEFAULT_CHUNK_SIZE + TAG_SIZE;
+    let frames: Vec<_> = ciphertext[HEADER_LEN..HEADER_LEN + 4 * frame]
+        .chunks(frame)
+        .collect();
+    for (i, a) in frames.iter().enumerate() {
+        for b in &frames[i + 1..] {
+            assert_ne!(a, b);
+        }
+    }
+}
+
+/// 测试明文长度恰为 chunk 大小整数倍及前后各差一个字节时的分帧，
+/// 以及去掉 final chunk 后均被识别为截断
+#[test]
+fn chunk_boundary_sizes_roundtrip_and_detect_truncation() {
+    let temp = tempdir().expect("create temp dir");
+    let truncated = temp.path().join("truncated.svlt");
+    let output = temp.path().join("truncated.bin");
+
+    for algorithm in ALGORITHMS {
+        for len in [
+            16 * DEFAULT_CHUNK_SIZE - 1,
+            16 * DEFAULT_CHUNK_SIZE,
+            16 * DEFAULT_CHUNK_SIZE + 1,
+        ] {
+            let plaintext = seeded_plaintext(len, len as u64);
+            let ciphertext = roundtrip(temp.path(), &plaintext, algorithm);
+
+            fs::write(
+                &truncated,
+                &ciphertext[..ciphertext.len() - FINAL_FRAME_LEN],
+            )
+            .expect("write truncated");
+            let err = engine::decrypt(&truncated, &output, "large-password")
+                .expect_err("missing final chunk");
+            assert!(
+                matches!(err, SealVaultError::CorruptedData),
+                "{algorithm:?} len {len}: {err:?}"
+            );
+            assert!(!output.exists());
+        }
+    }
+}
+
+/// 测试交换两个数据 chunk 的位置会使解密失败（nonce 绑定 chunk 序号）
+#[test]
+fn reordered_chunks_fail_authentication() {
+    let temp = tempdir().expect("create temp dir");
+    let encrypted = temp.path().join("input.svlt");
+    let output = temp.path().join("reordered.bin");
+    let plaintext = seeded_plaintext(6 * DEFAULT_CHUNK_SIZE, 6);
+
+    for algorithm in ALGORITHMS {
+        let mut ciphertext = roundtrip(temp.path(), &plaintext, algorithm);
+        let frame = LEN_PREFIX + DEFAULT_CHUNK_SIZE + TAG_SIZE;
+        let (second, third) =
+            ciphertext[HEADER_LEN + frame..HEADER_LEN + 3 * frame].split_at_mut(frame);
+        second.swap_with_slice(third);
+        fs::write(&encrypted, &ciphertext).expect("write reordered");
+
+        let err =
+            engine::decrypt(&encrypted, &output, "large-password").expect_err("reordered chunks");
+        assert!(
+            matches!(err, SealVaultError::CorruptedData),
+            "{algorithm:?}: {err:?}"
+        );
+        assert!(!output.exists());
+    }
+}
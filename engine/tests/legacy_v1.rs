@@ -0,0 +1,92 @@
+//! v1 旧文件解密兼容测试
+//!
+//! `tests/fixtures` 中的文件按 v1 规则手工构造：默认 Argon2id 参数（以 salt 的 base64 文本为输入）、
+//! 64 KiB chunk、AAD 仅为 chunk_index、无 final chunk。v1 Header 有两种布局，版本号相同：
+//! - `v1_without_algorithm.svlt`：最早的布局，version 后直接是 salt（算法固定为 XChaCha20-Poly1305）；
+//!   salt 首字节为 `0x01`，与 XChaCha20-Poly1305 的算法 ID 相同
+//! - `v1_with_algorithm.svlt`：version 后带 algorithm 字节（AES-256-GCM）
+#![cfg(feature = "fs")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use engine::format::header::{LEGACY_V1_CHUNK_SIZE, VERSION_V1};
+use engine::{AeadAlgorithm, SealVaultError};
+use tempfile::tempdir;
+
+const PASSWORD: &str = "legacy-v1-password";
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+/// 测试没有 algorithm 字节的 v1 文件按默认算法解密，salt 首字节恰为合法算法 ID 也不会误判
+#[test]
+fn v1_file_without_algorithm_byte_decrypts() {
+    let temp = tempdir().expect("create temp dir");
+    let input = fixture("v1_without_algorithm.svlt");
+    let output = temp.path().join("output.txt");
+
+    let info = engine::inspect(&input).expect("inspect");
+    assert_eq!(info.version, VERSION_V1);
+    assert_eq!(info.algorithm, AeadAlgorithm::XChaCha20Poly1305);
+    assert_eq!(info.chunk_size, LEGACY_V1_CHUNK_SIZE);
+    assert_eq!(&info.salt, b"\x01legacy-v1-salt!");
+
+    engine::decrypt(&input, &output, PASSWORD).expect("decrypt");
+    assert_eq!(
+        fs::read(&output).expect("read output"),
+        b"Written by SealVault v1 before the header carried an algorithm byte."
+    );
+    assert_eq!(
+        engine::decrypt_bytes(&fs::read(&input).expect("read fixture"), PASSWORD)
+            .expect("decrypt bytes"),
+        fs::read(&output).expect("read output")
+    );
+
+    // 仓库早期提交的示例文件同样没有 algorithm 字节
+    let asset = Path::new(env!("CARGO_MANIFEST_DIR")).join("../assets/text.txt.svlt");
+    let info = engine::inspect(&asset).expect("inspect asset");
+    assert_eq!(info.algorithm, AeadAlgorithm::XChaCha20Poly1305);
+    assert_eq!(info.chunk_size, LEGACY_V1_CHUNK_SIZE);
+}
+
+/// 测试带 algorithm 字节的 v1 文件按 Header 中的算法解密
+#[test]
+fn v1_file_with_algorithm_byte_decrypts() {
+    let temp = tempdir().expect("create temp dir");
+    let input = fixture("v1_with_algorithm.svlt");
+    let output = temp.path().join("output.txt");
+
+    let info = engine::inspect(&input).expect("inspect");
+    assert_eq!(info.version, VERSION_V1);
+    assert_eq!(info.algorithm, AeadAlgorithm::Aes256Gcm);
+    assert_eq!(info.chunk_size, LEGACY_V1_CHUNK_SIZE);
+    assert_eq!(&info.salt, b"legacy-v1-aesgcm");
+
+    engine::decrypt(&input, &output, PASSWORD).expect("decrypt");
+    assert_eq!(
+        fs::read(&output).expect("read output"),
+        b"Written by SealVault v1 with an algorithm byte after the version."
+    );
+}
+
+/// 测试 v1 文件密码错误时报告 `InvalidPasswordOrFile`，且不留下输出
+#[test]
+fn v1_file_with_wrong_password_fails() {
+    let temp = tempdir().expect("create temp dir");
+    let output = temp.path().join("output.txt");
+
+    for name in ["v1_without_algorithm.svlt", "v1_with_algorithm.svlt"] {
+        let err =
+            engine::decrypt(&fixture(name), &output, "wrong-password").expect_err("wrong password");
+        assert!(
+            matches!(err, SealVaultError::InvalidPasswordOrFile),
+            "{name}: {err:?}"
+        );
+        assert!(!output.exists());
+    }
+}
@@ -0,0 +1,799 @@
+//! SealVault 低层 API 测试
+//!
+//! 直接使用 `open_for_encrypt` / `open_for_decrypt` 驱动 chunk 循环，
+//! 不经过基于路径的便捷封装。
+#![cfg(feature = "fs")]
+
+use std::io::Cursor;
+
+/// 测试手动驱动 chunk 循环完成 round-trip
+#[test]
+fn open_for_encrypt_and_decrypt_roundtrip() {
+    let plaintext: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+
+    let mut encrypted = Vec::new();
+    let (header, mut encryptor) = engine::open_for_encrypt(
+        &mut encrypted,
+        b"low-level-password",
+        engine::AeadAlgorithm::Aes256Gcm,
+    )
+    .expect("open for encrypt");
+
+    for chunk in plaintext.chunks(encryptor.chunk_size()) {
+        encryptor
+            .write_chunk(chunk, &mut encrypted)
+            .expect("write chunk");
+    }
+    encryptor.finish(&mut encrypted).expect("finish stream");
+
+    let mut reader = Cursor::new(encrypted);
+    let (read_header, mut decryptor) =
+        engine::open_for_decrypt(&mut reader, b"low-level-password").expect("open for decrypt");
+
+    assert_eq!(read_header.algorithm, header.algorithm);
+    assert_eq!(read_header.salt, header.salt);
+    assert_eq!(read_header.base_nonce, header.base_nonce);
+    assert_eq!(read_header.chunk_size, header.chunk_size);
+
+    let mut decrypted = Vec::new();
+    while let Some(chunk) = decryptor.read_chunk(&mut reader).expect("read chunk") {
+        decrypted.extend_from_slice(&chunk);
+    }
+
+    assert_eq!(decrypted, plaintext);
+}
+
+/// 测试超出长度前缀范围的 chunk_size 在构造时被拒绝
+#[test]
+fn stream_encryptor_rejects_out_of_range_chunk_size() {
+    use engine::format::stream::{MAX_CHUNK_SIZE, StreamEncryptor};
+
+    let key = [7u8; 32];
+    let base_nonce = [9u8; 24];
+
+    let too_large = StreamEncryptor::new(
+        &key,
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        base_nonce,
+        MAX_CHUNK_SIZE + 1,
+        [0u8; 32],
+    );
+    assert!(too_large.is_err(), "expected oversized chunk size to fail");
+
+    let zero = StreamEncryptor::new(
+        &key,
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        base_nonce,
+        0,
+        [0u8; 32],
+    );
+    assert!(zero.is_err(), "expected zero chunk size to fail");
+
+    assert!(
+        StreamEncryptor::new(
+            &key,
+            engine::AeadAlgorithm::XChaCha20Poly1305,
+            base_nonce,
+            MAX_CHUNK_SIZE,
+            [0u8; 32],
+        )
+        .is_ok()
+    );
+}
+
+/// 测试 1 字节的极小 chunk 也能正确 round-trip
+#[test]
+fn stream_roundtrip_with_one_byte_chunks() {
+    use engine::format::stream::{StreamDecryptor, StreamEncryptor};
+
+    let key = [3u8; 32];
+    let base_nonce = [5u8; 24];
+    let header_digest = [6u8; 32];
+    let plaintext = b"tiny chunks still frame correctly";
+
+    for algorithm in [
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        engine::AeadAlgorithm::Aes256Gcm,
+    ] {
+        let mut encrypted = Vec::new();
+        StreamEncryptor::new(&key, algorithm, base_nonce, 1, header_digest)
+            .expect("create encryptor")
+            .encrypt(&plaintext[..], &mut encrypted)
+            .expect("encrypt");
+
+        let mut decrypted = Vec::new();
+        StreamDecryptor::new(&key, algorithm, base_nonce, header_digest)
+            .decrypt(Cursor::new(encrypted), &mut decrypted)
+            .expect("decrypt");
+
+        assert_eq!(decrypted, plaintext);
+    }
+}
+
+/// 测试长度前缀声明远大于实际数据时，按实际数据报错而不是预先分配
+#[test]
+fn stream_decryptor_rejects_oversized_length_prefix_on_short_input() {
+    use engine::format::stream::StreamDecryptor;
+
+    let mut forged = Vec::new();
+    forged.extend_from_slice(&(1u32 << 30).to_be_bytes());
+    forged.extend_from_slice(&[0u8; 64]);
+
+    let result = StreamDecryptor::new(
+        &[1u8; 32],
+        engine::AeadAlgorithm::Aes256Gcm,
+        [2u8; 24],
+        [3u8; 32],
+    )
+    .decrypt(Cursor::new(forged), std::io::sink());
+
+    let err = result.expect_err("expected truncated chunk");
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+/// 测试 Header 声明超大 chunk_size 但实际 chunk 很小时，仍能正确解密
+#[test]
+fn decrypt_with_header_claiming_huge_chunk_size() {
+    use engine::format::header::Header;
+    use engine::format::stream::StreamEncryptor;
+
+    let temp_dir = tempfile::tempdir().expect("create temp dir");
+    let encrypted_path = temp_dir.path().join("huge_claim.svlt");
+    let decrypted_path = temp_dir.path().join("huge_claim.txt");
+
+    let algorithm = engine::AeadAlgorithm::XChaCha20Poly1305;
+    let salt = [4u8; 16];
+    let base_nonce = [6u8; 24];
+    let key = engine::crypto::kdf::derive_key_raw(b"password", &salt).expect("derive key");
+
+    let plaintext = vec![0x5au8; 3000];
+    let mut encrypted = Vec::new();
+    let header = Header::new(algorithm, salt, base_nonce, 64 * 1024 * 1024);
+    header.write(&mut encrypted).expect("write header");
+    StreamEncryptor::new(&key, algorithm, base_nonce, 1024, header.digest())
+        .expect("create encryptor")
+        .encrypt(&plaintext[..], &mut encrypted)
+        .expect("encrypt");
+    std::fs::write(&encrypted_path, encrypted).expect("write encrypted");
+
+    engine::decrypt(&encrypted_path, &decrypted_path, "password").expect("decrypt file");
+    assert_eq!(
+        std::fs::read(&decrypted_path).expect("read decrypted"),
+        plaintext
+    );
+}
+
+/// 测试 encrypt_stream / decrypt_stream 通过 Cursor round-trip
+#[test]
+fn stream_api_roundtrip_through_cursor() {
+    let plaintext: Vec<u8> = (0..100_000u32).map(|i| (i % 97) as u8).collect();
+
+    let mut encrypted = Vec::new();
+    engine::encrypt_stream(
+        Cursor::new(&plaintext),
+        &mut encrypted,
+        "stream-password",
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt stream");
+
+    let mut decrypted = Vec::new();
+    engine::decrypt_stream(Cursor::new(encrypted), &mut decrypted, "stream-password")
+        .expect("decrypt stream");
+
+    assert_eq!(decrypted, plaintext);
+}
+
+/// 测试 encrypt_stream / decrypt_stream 在不可 seek 的管道上工作
+#[test]
+fn stream_api_roundtrip_through_pipe() {
+    let plaintext: Vec<u8> = (0..150_000u32).map(|i| (i % 89) as u8).collect();
+
+    let (pipe_reader, pipe_writer) = std::io::pipe().expect("create pipe");
+
+    let producer = {
+        let plaintext = plaintext.clone();
+        std::thread::spawn(move || {
+            engine::encrypt_stream(
+                Cursor::new(plaintext),
+                pipe_writer,
+                "pipe-password",
+                engine::AeadAlgorithm::Aes256Gcm,
+            )
+        })
+    };
+
+    let mut decrypted = Vec::new();
+    engine::decrypt_stream(pipe_reader, &mut decrypted, "pipe-password").expect("decrypt stream");
+    producer
+        .join()
+        .expect("join producer")
+        .expect("encrypt stream");
+
+    assert_eq!(decrypted, plaintext);
+}
+
+/// 测试空输入只产生 final chunk，且能 round-trip
+#[test]
+fn empty_stream_contains_only_final_chunk() {
+    use engine::format::stream::{StreamDecryptor, StreamEncryptor, TAG_SIZE};
+
+    let key = [8u8; 32];
+    let base_nonce = [1u8; 24];
+    let header_digest = [9u8; 32];
+    let algorithm = engine::AeadAlgorithm::XChaCha20Poly1305;
+
+    let mut encrypted = Vec::new();
+    StreamEncryptor::new(&key, algorithm, base_nonce, 1024, header_digest)
+        .expect("create encryptor")
+        .encrypt(&[][..], &mut encrypted)
+        .expect("encrypt");
+    assert_eq!(encrypted.len(), 4 + TAG_SIZE);
+
+    let mut decrypted = Vec::new();
+    StreamDecryptor::new(&key, algorithm, base_nonce, header_digest)
+        .decrypt(Cursor::new(encrypted), &mut decrypted)
+        .expect("decrypt");
+    assert!(decrypted.is_empty());
+
+    // 连 final chunk 都没有的空 stream 视为截断
+    let err = StreamDecryptor::new(&key, algorithm, base_nonce, header_digest)
+        .decrypt(Cursor::new(Vec::new()), std::io::sink())
+        .expect_err("expected missing final chunk");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+/// 测试没有结束标记的 v1 旧格式 stream 仍可通过兼容模式解密
+#[test]
+fn legacy_v1_stream_decrypts_without_final_chunk() {
+    use engine::algorithm::xchacha20_poly1305;
+    use engine::format::stream::{StreamDecryptor, TAG_SIZE};
+
+    let key = [2u8; 32];
+    let base_nonce = [4u8; 24];
+    let algorithm = engine::AeadAlgorithm::XChaCha20Poly1305;
+    let plaintext = b"written before final chunks existed";
+
+    // 按 v1 规则手工构造：AAD 仅为 chunk_index，无 final chunk
+    let mut encrypted = Vec::new();
+    for (index, chunk) in plaintext.chunks(16).enumerate() {
+        let index = index as u64;
+        let ciphertext = xchacha20_poly1305::encrypt_chunk(
+            &key,
+            &base_nonce,
+            index,
+            chunk,
+            &index.to_be_bytes(),
+        )
+        .expect("encrypt chunk");
+        let body_len = ciphertext.len() - TAG_SIZE;
+        encrypted.extend_from_slice(&(body_len as u32).to_be_bytes());
+        encrypted.extend_from_slice(&ciphertext);
+    }
+
+    let mut decrypted = Vec::new();
+    StreamDecryptor::new_legacy_v1(&key, algorithm, base_nonce)
+        .decrypt(Cursor::new(&encrypted), &mut decrypted)
+        .expect("decrypt legacy stream");
+    assert_eq!(decrypted, plaintext);
+
+    // 当前格式的解密器不接受 v1 stream
+    assert!(
+        StreamDecryptor::new(&key, algorithm, base_nonce, [0u8; 32])
+            .decrypt(Cursor::new(&encrypted), std::io::sink())
+            .is_err()
+    );
+}
+
+/// 测试相同 key 下，来自其他文件的 chunk 无法拼接进当前文件
+#[test]
+fn chunks_cannot_be_spliced_across_files() {
+    use engine::format::header::Header;
+    use engine::format::stream::{StreamDecryptor, StreamEncryptor, TAG_SIZE};
+
+    const CHUNK: usize = 16;
+    const FRAME_LEN: usize = 4 + CHUNK + TAG_SIZE;
+
+    // 两个文件共用 key 与 base_nonce，仅 Header 中的 salt 不同
+    let key = [5u8; 32];
+    let base_nonce = [7u8; 24];
+    let algorithm = engine::AeadAlgorithm::XChaCha20Poly1305;
+    let header_a = Header::new(algorithm, [1u8; 16], base_nonce, CHUNK as u32);
+    let header_b = Header::new(algorithm, [2u8; 16], base_nonce, CHUNK as u32);
+
+    let encrypt = |header: &Header, plaintext: &[u8]| {
+        let mut encrypted = Vec::new();
+        StreamEncryptor::new(&key, algorithm, base_nonce, CHUNK, header.digest())
+            .expect("create encryptor")
+            .encrypt(plaintext, &mut encrypted)
+            .expect("encrypt");
+        encrypted
+    };
+    let stream_a = encrypt(&header_a, &[b'a'; 3 * CHUNK]);
+    let stream_b = encrypt(&header_b, &[b'b'; 3 * CHUNK]);
+
+    // 用 B 的 chunk 1 替换 A 的 chunk 1
+    let mut spliced = stream_a.clone();
+    spliced[FRAME_LEN..2 * FRAME_LEN].copy_from_slice(&stream_b[FRAME_LEN..2 * FRAME_LEN]);
+
+    let mut decrypted = Vec::new();
+    StreamDecryptor::for_header(&key, &header_a)
+        .decrypt(Cursor::new(&stream_a), &mut decrypted)
+        .expect("decrypt untouched stream");
+    assert_eq!(decrypted, [b'a'; 3 * CHUNK]);
+
+    let result = StreamDecryptor::for_header(&key, &header_a)
+        .decrypt(Cursor::new(&spliced), std::io::sink());
+    assert!(result.is_err(), "spliced chunk must not authenticate");
+}
+
+/// 测试 Header 中保存的非默认 KDF 参数会在解密时被使用
+#[test]
+fn decrypt_uses_kdf_params_stored_in_header() {
+    use engine::crypto::kdf::{KdfAlgorithm, KdfParams, derive_key_raw_with_params};
+    use engine::format::header::Header;
+    use engine::format::stream::{DEFAULT_CHUNK_SIZE, StreamEncryptor};
+
+    let temp_dir = tempfile::tempdir().expect("create temp dir");
+    let encrypted_path = temp_dir.path().join("custom_kdf.svlt");
+    let decrypted_path = temp_dir.path().join("custom_kdf.txt");
+
+    let algorithm = engine::AeadAlgorithm::Aes256Gcm;
+    let salt = [11u8; 16];
+    let base_nonce = [12u8; 24];
+    let mut header = Header::new(algorithm, salt, base_nonce, DEFAULT_CHUNK_SIZE as u32);
+    let params = KdfParams {
+        m_cost: 8 * 1024,
+        t_cost: 1,
+        p_cost: 2,
+    };
+    assert_ne!(params, KdfParams::default());
+    header.kdf = KdfAlgorithm::Argon2id(params);
+
+    let key = derive_key_raw_with_params(b"password", &salt, &params).expect("derive key");
+
+    let plaintext = b"encrypted with non-default argon2 parameters";
+    let mut encrypted = Vec::new();
+    header.write(&mut encrypted).expect("write header");
+    StreamEncryptor::new(
+        &key,
+        algorithm,
+        base_nonce,
+        DEFAULT_CHUNK_SIZE,
+        header.digest(),
+    )
+    .expect("create encryptor")
+    .encrypt(&plaintext[..], &mut encrypted)
+    .expect("encrypt");
+    std::fs::write(&encrypted_path, &encrypted).expect("write encrypted");
+
+    let read_header = Header::read(Cursor::new(&encrypted)).expect("read header");
+    assert_eq!(read_header.kdf, header.kdf);
+
+    engine::decrypt(&encrypted_path, &decrypted_path, "password").expect("decrypt file");
+    assert_eq!(
+        std::fs::read(&decrypted_path).expect("read decrypted"),
+        plaintext
+    );
+}
+
+/// 测试 Header 中超出上限的 KDF 参数在派生密钥前被拒绝
+#[test]
+fn header_rejects_unsupported_kdf_params() {
+    use engine::crypto::kdf::{KdfAlgorithm, KdfParams, MAX_M_COST};
+    use engine::format::header::Header;
+
+    let mut header = Header::new(
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        [1u8; 16],
+        [2u8; 24],
+        1024,
+    );
+    header.kdf = KdfAlgorithm::Argon2id(KdfParams {
+        m_cost: MAX_M_COST + 1,
+        ..KdfParams::default()
+    });
+
+    let mut encoded = Vec::new();
+    header.write(&mut encoded).expect("write header");
+
+    let err = Header::read(Cursor::new(encoded)).expect_err("expected oversized m_cost to fail");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+/// 测试内存成本过大时 KDF 返回 KdfResourceExhausted，而不是因分配失败中止进程
+#[test]
+fn oversized_kdf_memory_cost_is_a_typed_error() {
+    use argon2::password_hash::SaltString;
+    use engine::crypto::kdf::{KdfAlgorithm, KdfParams, derive_key_with_params};
+
+    let params = KdfParams {
+        m_cost: u32::MAX,
+        t_cost: 1,
+        p_cost: 1,
+    };
+    let salt = SaltString::encode_b64(&[3u8; 16]).expect("encode salt");
+    let err = derive_key_with_params(b"password", &salt, &params).expect_err("m_cost too large");
+    assert!(matches!(err, engine::SealVaultError::KdfResourceExhausted));
+
+    // 库接口在加密前校验参数，同样返回该错误且不写出任何文件
+    let temp_dir = tempfile::tempdir().expect("create temp dir");
+    let input = temp_dir.path().join("input.txt");
+    let output = temp_dir.path().join("input.txt.svlt");
+    std::fs::write(&input, b"data").expect("write input");
+    let options = engine::EncryptOptions {
+        kdf: KdfAlgorithm::Argon2id(params),
+        ..engine::EncryptOptions::default()
+    };
+    let err = engine::encrypt_with_options(&input, &output, "password", &options)
+        .expect_err("m_cost too large");
+    assert!(matches!(err, engine::SealVaultError::KdfResourceExhausted));
+    assert!(!output.exists());
+}
+
+/// 测试声明长度超过 Header chunk_size 的 chunk 被拒绝
+#[test]
+fn decryptor_rejects_chunk_longer_than_header_chunk_size() {
+    use engine::format::header::Header;
+    use engine::format::stream::{StreamDecryptor, StreamEncryptor};
+
+    let key = [13u8; 32];
+    let base_nonce = [14u8; 24];
+    let algorithm = engine::AeadAlgorithm::XChaCha20Poly1305;
+
+    // Header 声明 16 字节 chunk，实际写入 32 字节 chunk
+    let header = Header::new(algorithm, [15u8; 16], base_nonce, 16);
+    let mut encrypted = Vec::new();
+    StreamEncryptor::new(&key, algorithm, base_nonce, 32, header.digest())
+        .expect("create encryptor")
+        .encrypt(&[0x42u8; 64][..], &mut encrypted)
+        .expect("encrypt");
+
+    let err = StreamDecryptor::for_header(&key, &header)
+        .decrypt(Cursor::new(encrypted), std::io::sink())
+        .expect_err("expected oversized chunk to fail");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+/// 测试 Header 声明小 chunk_size 时，伪造的超大长度前缀立即被拒绝
+#[test]
+fn decrypt_rejects_length_prefix_larger_than_header_chunk_size() {
+    use engine::format::header::Header;
+
+    let temp_dir = tempfile::tempdir().expect("create temp dir");
+    let encrypted_path = temp_dir.path().join("bogus_length.svlt");
+    let decrypted_path = temp_dir.path().join("bogus_length.txt");
+
+    let header = Header::new(
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        [16u8; 16],
+        [17u8; 24],
+        1024,
+    );
+    let mut forged = Vec::new();
+    header.write(&mut forged).expect("write header");
+    // 声明约 4 GiB 的 chunk，后面只跟少量字节
+    forged.extend_from_slice(&u32::MAX.to_be_bytes());
+    forged.extend_from_slice(&[0u8; 32]);
+    std::fs::write(&encrypted_path, forged).expect("write forged");
+
+    let err = engine::decrypt(&encrypted_path, &decrypted_path, "password")
+        .expect_err("expected oversized length prefix to fail");
+    assert!(
+        matches!(err, engine::SealVaultError::CorruptedData),
+        "unexpected error: {err:?}"
+    );
+    assert!(!decrypted_path.exists());
+}
+
+/// 测试 v1 Header 只有固定部分，读取时按默认 Argon2id 参数；v2 才有的字段不能写成 v1
+#[test]
+fn v1_header_reads_as_default_argon2id() {
+    use engine::crypto::kdf::{KdfAlgorithm, KdfParams};
+    use engine::format::header::{HEADER_SIZE, Header, VERSION_V1};
+
+    let mut header = Header::new(engine::AeadAlgorithm::Aes256Gcm, [3u8; 16], [4u8; 24], 4096);
+    header.version = VERSION_V1;
+
+    let mut encoded = Vec::new();
+    header.write(&mut encoded).expect("write header");
+    assert_eq!(encoded.len(), HEADER_SIZE);
+
+    let read_header = Header::read(Cursor::new(encoded)).expect("read v1 header");
+    assert_eq!(read_header.version, VERSION_V1);
+    assert_eq!(read_header.algorithm, engine::AeadAlgorithm::Aes256Gcm);
+    assert_eq!(read_header.kdf, KdfAlgorithm::default());
+
+    header.kdf = KdfAlgorithm::Argon2id(KdfParams {
+        m_cost: 16 * 1024,
+        t_cost: 2,
+        p_cost: 1,
+    });
+    let err = header
+        .write(Vec::new())
+        .expect_err("v1 cannot record KDF params");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+/// 测试标志位：keyfile 标志可往返，未知标志位视为损坏
+#[test]
+fn header_flags_roundtrip_and_reject_unknown_bits() {
+    use engine::format::header::{FLAG_KEYFILE, HEADER_CHECK_SIZE, Header};
+
+    let mut header = Header::new(
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        [5u8; 16],
+        [6u8; 24],
+        4096,
+    );
+    header.keyfile_required = true;
+
+    let mut encoded = Vec::new();
+    header.write(&mut encoded).expect("write header");
+    // 标志位之后只有校验值
+    let flags = encoded.len() - HEADER_CHECK_SIZE - 1;
+    assert_eq!(encoded[flags], FLAG_KEYFILE);
+
+    let read_header = Header::read(Cursor::new(encoded.clone())).expect("read header");
+    assert!(read_header.keyfile_required);
+
+    encoded[flags] |= 0x80;
+    let err = Header::read(Cursor::new(encoded)).expect_err("unknown flag bit");
+    assert!(
+        matches!(
+            engine::SealVaultError::from(err),
+            engine::SealVaultError::CorruptedData
+        ),
+        "unknown flag bits must be rejected"
+    );
+}
+
+/// 测试复用的明文缓冲区：多个满 chunk 加不满的末尾 chunk，`read_chunk` 返回清零包装的明文
+#[test]
+fn stream_roundtrip_through_zeroizing_chunk_buffers() {
+    use engine::format::stream::{StreamDecryptor, StreamEncryptor};
+    use zeroize::Zeroizing;
+
+    let key = [3u8; 32];
+    let base_nonce = [4u8; 24];
+    let digest = [5u8; 32];
+    let chunk_size = 1000;
+    let plaintext: Vec<u8> = (0..3 * chunk_size as u32 + 123)
+        .map(|i| (i % 253) as u8)
+        .collect();
+
+    let mut encrypted = Vec::new();
+    StreamEncryptor::new(
+        &key,
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        base_nonce,
+        chunk_size,
+        digest,
+    )
+    .expect("create encryptor")
+    .encrypt(Cursor::new(&plaintext), &mut encrypted)
+    .expect("encrypt stream");
+
+    let mut decryptor = StreamDecryptor::new(
+        &key,
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        base_nonce,
+        digest,
+    );
+    let mut reader = Cursor::new(encrypted);
+    let mut lengths = Vec::new();
+    let mut decrypted = Vec::new();
+    loop {
+        let chunk: Option<Zeroizing<Vec<u8>>> = decryptor.read_chunk(&mut reader).expect("read");
+        let Some(chunk) = chunk else { break };
+        lengths.push(chunk.len());
+        decrypted.extend_from_slice(&chunk);
+    }
+
+    assert_eq!(lengths, [chunk_size, chunk_size, chunk_size, 123]);
+    assert_eq!(decrypted, plaintext);
+    assert!(decryptor.is_finished());
+}
+
+/// 测试先读取 Header、检查字段，再通过 `decrypt_body` 解密正文
+#[test]
+fn header_then_decrypt_body_roundtrip() {
+    use engine::format::header::{Header, VERSION};
+
+    let temp_dir = tempfile::tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("input.bin");
+    let encrypted_path = temp_dir.path().join("input.bin.svlt");
+    let plaintext: Vec<u8> = (0..100_000u32).map(|i| (i % 199) as u8).collect();
+    std::fs::write(&input_path, &plaintext).expect("write input");
+
+    let options = engine::EncryptOptions {
+        chunk_size: 8192,
+        ..engine::EncryptOptions::with_algorithm(engine::AeadAlgorithm::ChaCha20Poly1305)
+    };
+    engine::encrypt_with_options(&input_path, &encrypted_path, "split-password", &options)
+        .expect("encrypt");
+
+    let mut reader =
+        std::io::BufReader::new(std::fs::File::open(&encrypted_path).expect("open encrypted"));
+    let header = Header::read(&mut reader).expect("read header");
+    assert_eq!(header.version, VERSION);
+    assert_eq!(header.algorithm, engine::AeadAlgorithm::ChaCha20Poly1305);
+    assert_eq!(header.chunk_size, 8192);
+
+    let mut decrypted = Vec::new();
+    engine::decrypt_body(&mut reader, &header, "split-password", &mut decrypted)
+        .expect("decrypt body");
+    assert_eq!(decrypted, plaintext);
+
+    // 错误密码在第一个 chunk 即被发现
+    let mut reader = std::io::BufReader::new(std::fs::File::open(&encrypted_path).expect("open"));
+    let header = Header::read(&mut reader).expect("read header");
+    let err = engine::decrypt_body(&mut reader, &header, "wrong", std::io::sink())
+        .expect_err("wrong password");
+    assert!(
+        matches!(err, engine::SealVaultError::InvalidPasswordOrFile),
+        "unexpected error: {err:?}"
+    );
+}
+
+/// 测试并行加密多 chunk 数据：输出与串行路径逐字节相同，并可由串行解密器解密
+#[test]
+fn parallel_stream_encryption_matches_sequential_output() {
+    use engine::format::stream::{StreamDecryptor, StreamEncryptor};
+
+    let key = [6u8; 32];
+    let base_nonce = [7u8; 24];
+    let digest = [8u8; 32];
+    let chunk_size = 4096;
+    let plaintext: Vec<u8> = (0..(37 * chunk_size + 1234) as u32)
+        .map(|i| (i % 251) as u8)
+        .collect();
+
+    for algorithm in [
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        engine::AeadAlgorithm::Aes256Gcm,
+        engine::AeadAlgorithm::ChaCha20Poly1305,
+    ] {
+        let new_encryptor = || {
+            StreamEncryptor::new(&key, algorithm, base_nonce, chunk_size, digest)
+                .expect("create encryptor")
+        };
+
+        let mut sequential = Vec::new();
+        new_encryptor()
+            .encrypt(Cursor::new(&plaintext), &mut sequential)
+            .expect("sequential encrypt");
+
+        for threads in [0, 2, 5] {
+            let mut parallel = Vec::new();
+            new_encryptor()
+                .encrypt_parallel(Cursor::new(&plaintext), &mut parallel, threads)
+                .expect("parallel encrypt");
+            assert_eq!(parallel, sequential, "{algorithm:?} with {threads} threads");
+
+            let mut decrypted = Vec::new();
+            StreamDecryptor::new(&key, algorithm, base_nonce, digest)
+                .decrypt(Cursor::new(parallel), &mut decrypted)
+                .expect("sequential decrypt");
+            assert_eq!(decrypted, plaintext);
+        }
+    }
+}
+
+/// 测试并行加密中途读取失败时返回错误，工作线程正常退出而不是死锁
+#[test]
+fn parallel_stream_encryption_propagates_read_error() {
+    use engine::format::stream::StreamEncryptor;
+    use std::io::Read;
+
+    /// 读出 `limit` 字节后报错的 reader
+    struct FailingReader {
+        remaining: usize,
+    }
+
+    impl Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.remaining == 0 {
+                return Err(std::io::Error::other("disk read failed"));
+            }
+            let n = buf.len().min(self.remaining);
+            buf[..n].fill(0xab);
+            self.remaining -= n;
+            Ok(n)
+        }
+    }
+
+    let mut encryptor = StreamEncryptor::new(
+        &[1u8; 32],
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        [2u8; 24],
+        1024,
+        [3u8; 32],
+    )
+    .expect("create encryptor");
+    let err = encryptor
+        .encrypt_parallel(
+            FailingReader {
+                remaining: 50 * 1024 + 10,
+            },
+            std::io::sink(),
+            3,
+        )
+        .expect_err("read error must propagate");
+    assert_eq!(err.to_string(), "disk read failed");
+}
+
+/// 测试对内存映射输入切片加密，与相同 key / nonce 下的流式加密逐字节相同
+#[test]
+fn mmap_slice_encryption_matches_streaming_output() {
+    use engine::format::stream::StreamEncryptor;
+
+    let temp_dir = tempfile::tempdir().expect("create temp dir");
+    let input_path = temp_dir.path().join("image.bin");
+    let plaintext: Vec<u8> = (0..(5 * 64 * 1024 + 4321) as u32)
+        .map(|i| (i % 247) as u8)
+        .collect();
+    std::fs::write(&input_path, &plaintext).expect("write input");
+
+    let new_encryptor = || {
+        StreamEncryptor::new(
+            &[4u8; 32],
+            engine::AeadAlgorithm::Aes256Gcm,
+            [5u8; 24],
+            64 * 1024,
+            [6u8; 32],
+        )
+        .expect("create encryptor")
+    };
+
+    let mut streamed = Vec::new();
+    new_encryptor()
+        .encrypt(
+            std::fs::File::open(&input_path).expect("open input"),
+            &mut streamed,
+        )
+        .expect("streaming encrypt");
+
+    let file = std::fs::File::open(&input_path).expect("open input");
+    // SAFETY: 测试独占该临时文件，映射期间不会被修改
+    let map = unsafe { memmap2::Mmap::map(&file) }.expect("map input");
+    let mut mapped = Vec::new();
+    new_encryptor()
+        .encrypt_slice(&map, &mut mapped)
+        .expect("mmap encrypt");
+
+    assert_eq!(mapped, streamed);
+}
+
+/// 测试 chunk_index 达到算法上限时加密返回错误，而不是让 nonce 计数器回绕
+#[test]
+fn stream_encryptor_rejects_chunk_index_beyond_algorithm_limit() {
+    use engine::format::stream::StreamEncryptor;
+
+    for algorithm in [
+        engine::AeadAlgorithm::Aes256Gcm,
+        engine::AeadAlgorithm::ChaCha20Poly1305,
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+    ] {
+        let max_chunks = algorithm.max_chunks();
+        let mut encryptor = StreamEncryptor::new(&[9u8; 32], algorithm, [8u8; 24], 64, [7u8; 32])
+            .expect("create encryptor")
+            .starting_at_chunk(max_chunks - 2);
+        let mut output = Vec::new();
+
+        // 倒数第二个 index 仍可写数据 chunk
+        encryptor
+            .write_chunk(b"last data chunk", &mut output)
+            .expect("chunk below the limit");
+
+        // 最后一个 index 只留给 final chunk
+        let err = encryptor
+            .write_chunk(b"one too many", &mut output)
+            .expect_err("chunk at the limit");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput, "{algorithm}");
+
+        encryptor.finish(&mut output).expect("final chunk fits");
+    }
+
+    assert_eq!(engine::AeadAlgorithm::Aes256Gcm.max_chunks(), 1 << 32);
+}
@@ -0,0 +1,183 @@
+//! 长度隐藏填充测试：密文长度按块量化，解密精确还原原始字节
+#![cfg(feature = "fs")]
+
+use std::fs;
+use std::path::Path;
+
+use tempfile::tempdir;
+
+const BLOCK: u32 = 4096;
+
+fn padded_options() -> engine::EncryptOptions {
+    engine::EncryptOptions {
+        padding: Some(engine::Padding::ToBlock(BLOCK)),
+        ..engine::EncryptOptions::default()
+    }
+}
+
+/// 按 `options` 加密 `plaintext`，检查解密结果一致，返回密文长度
+fn encrypted_len(dir: &Path, plaintext: &[u8], options: &engine::EncryptOptions) -> u64 {
+    let input = dir.join("input.bin");
+    let encrypted = dir.join("input.bin.svlt");
+    let decrypted = dir.join("input.dec.bin");
+    fs::write(&input, plaintext).expect("write input");
+
+    engine::encrypt_with_options(&input, &encrypted, "pw", options).expect("encrypt");
+    engine::decrypt(&encrypted, &decrypted, "pw").expect("decrypt");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+
+    fs::metadata(&encrypted).expect("stat").len()
+}
+
+#[test]
+fn padded_ciphertext_size_is_quantized_to_blocks() {
+    let temp = tempdir().expect("create temp dir");
+    let options = padded_options();
+
+    // 空输入、单字节与恰好一个块都占一个块
+    let one_block = encrypted_len(temp.path(), b"y", &options);
+    for len in [0usize, 2, 3, 100, BLOCK as usize - 1, BLOCK as usize] {
+        let plaintext = vec![b'n'; len];
+        assert_eq!(
+            encrypted_len(temp.path(), &plaintext, &options),
+            one_block,
+            "len {len}"
+        );
+    }
+
+    // 多出一个字节即进入下一个块
+    let two_blocks = encrypted_len(temp.path(), &vec![7u8; BLOCK as usize + 1], &options);
+    assert_eq!(two_blocks, one_block + u64::from(BLOCK));
+    assert_eq!(
+        encrypted_len(temp.path(), &vec![7u8; 2 * BLOCK as usize], &options),
+        two_blocks
+    );
+
+    // 未填充时长度随明文变化
+    let plain = engine::EncryptOptions::default();
+    assert_ne!(
+        encrypted_len(temp.path(), b"yes", &plain),
+        encrypted_len(temp.path(), b"no", &plain)
+    );
+}
+
+#[test]
+fn padding_spanning_several_chunks_is_stripped_exactly() {
+    let temp = tempdir().expect("create temp dir");
+    let plaintext: Vec<u8> = (0..5_000u32).map(|i| (i % 251) as u8).collect();
+
+    // 填充（8192 - 5000 字节）跨越多个 1 KiB chunk，并与压缩、明文摘要、多线程组合
+    let options = engine::EncryptOptions {
+        chunk_size: 1024,
+        ..padded_options()
+    };
+    let small = encrypted_len(temp.path(), &plaintext, &options);
+    let combined = engine::EncryptOptions {
+        compression: Some(engine::Compression::Zstd(3)),
+        plaintext_digest: true,
+        threads: 3,
+        ..options.clone()
+    };
+    encrypted_len(temp.path(), &plaintext, &combined);
+
+    // 数据 chunk 的长度前缀全部为完整 chunk，不暴露最后一个 chunk 的真实长度
+    let encrypted = temp.path().join("input.bin.svlt");
+    engine::encrypt_with_options(&temp.path().join("input.bin"), &encrypted, "pw", &options)
+        .expect("encrypt");
+    assert_eq!(fs::metadata(&encrypted).expect("stat").len(), small);
+    let info = engine::inspect(&encrypted).expect("inspect");
+    assert_eq!(info.padding, Some(engine::Padding::ToBlock(BLOCK)));
+
+    // rekey 保留填充，reencrypt 可去除填充
+    let rekeyed = temp.path().join("rekeyed.svlt");
+    engine::rekey(&encrypted, &rekeyed, "pw", "new-pw").expect("rekey");
+    assert_eq!(fs::metadata(&rekeyed).expect("stat").len(), small);
+    let unpadded = temp.path().join("unpadded.svlt");
+    engine::reencrypt(
+        &rekeyed,
+        &unpadded,
+        "new-pw",
+        &engine::EncryptOptions::default(),
+    )
+    .expect("reencrypt");
+    assert_eq!(engine::inspect(&unpadded).expect("inspect").padding, None);
+
+    let decrypted = temp.path().join("final.bin");
+    engine::decrypt(&unpadded, &decrypted, "new-pw").expect("decrypt");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+}
+
+#[test]
+fn padding_block_size_is_validated() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.bin.svlt");
+    fs::write(&input, b"data").expect("write input");
+
+    for block in [0, engine::padding::MAX_PADDING_BLOCK + 1] {
+        let options = engine::EncryptOptions {
+            padding: Some(engine::Padding::ToBlock(block)),
+            ..engine::EncryptOptions::default()
+        };
+        let err = engine::encrypt_with_options(&input, &encrypted, "pw", &options)
+            .expect_err("invalid block size");
+        assert!(
+            matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput)
+        );
+        assert!(!encrypted.exists());
+    }
+}
+
+#[test]
+fn forged_padding_length_is_rejected() {
+    use engine::crypto::kdf::{KdfAlgorithm, ScryptParams, derive_key_scrypt};
+    use engine::format::header::{FLAG_PADDED, HEADER_CHECK_SIZE, Header};
+    use engine::format::stream::StreamEncryptor;
+
+    let params = ScryptParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+    let mut header = Header::new(
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        [1u8; 16],
+        [2u8; 24],
+        1024,
+    );
+    header.kdf = KdfAlgorithm::Scrypt(params);
+    header.padding = Some(engine::Padding::ToBlock(16));
+    let key = derive_key_scrypt(b"pw", &header.salt, &params).expect("derive key");
+
+    let mut encoded = Vec::new();
+    header.write(&mut encoded).expect("write header");
+    // 标志位之后是大端的块大小，最后是校验值
+    let fields = encoded.len() - HEADER_CHECK_SIZE;
+    assert_eq!(encoded[fields - 5], FLAG_PADDED);
+    assert_eq!(encoded[fields - 4..fields], 16u32.to_be_bytes());
+
+    let mut encryptor = StreamEncryptor::new(
+        &key,
+        header.algorithm,
+        header.base_nonce,
+        1024,
+        header.digest(),
+    )
+    .expect("encryptor")
+    .with_padding();
+    // 必须以填充长度结束
+    assert!(encryptor.finish(Vec::new()).is_err());
+
+    // 持有密钥的一方声明了超过实际数据的填充长度
+    let mut stream = Vec::new();
+    encryptor
+        .write_chunk(&[0u8; 16], &mut stream)
+        .expect("write chunk");
+    encryptor
+        .finish_with_trailer(&mut stream, None, Some(17))
+        .expect("finish");
+
+    encoded.extend_from_slice(&stream);
+    let err = engine::decrypt_bytes(&encoded, "pw").expect_err("padding exceeds data");
+    assert!(matches!(err, engine::SealVaultError::CorruptedData));
+}
@@ -0,0 +1,160 @@
+//! 加密时的密码最低要求（`PasswordPolicy`）测试
+#![cfg(feature = "fs")]
+
+mod common;
+
+use std::fs;
+
+use common::fast_options;
+use engine::{AeadAlgorithm, EncryptOptions, FolderOptions, PasswordPolicy, SealVaultError};
+use tempfile::tempdir;
+
+fn assert_weak(result: Result<impl std::fmt::Debug, SealVaultError>) {
+    let err = result.expect_err("weak password accepted");
+    assert!(matches!(err, SealVaultError::WeakPassword), "{err:?}");
+}
+
+/// 测试默认拒绝空密码与只含空白的密码：文件、内存、目录（含并行）与 rekey 均不产生输出
+#[test]
+fn empty_and_blank_passwords_are_rejected_by_default() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.txt");
+    let encrypted = temp.path().join("input.txt.svlt");
+    let folder = temp.path().join("folder");
+    let encrypted_folder = temp.path().join("folder.enc");
+    fs::write(&input, b"secret").expect("write input");
+    fs::create_dir(&folder).expect("create folder");
+    fs::write(folder.join("a.txt"), b"a").expect("write a");
+
+    for password in ["", " ", "\t \n", "\u{3000}"] {
+        assert_weak(engine::encrypt(&input, &encrypted, password));
+        assert_weak(engine::encrypt_with_options(
+            &input,
+            &encrypted,
+            password,
+            &fast_options(AeadAlgorithm::XChaCha20Poly1305),
+        ));
+        assert_weak(engine::encrypt_with_options(
+            &input,
+            &encrypted,
+            password,
+            &EncryptOptions {
+                verify_after_write: true,
+                ..fast_options(AeadAlgorithm::XChaCha20Poly1305)
+            },
+        ));
+        assert_weak(engine::encrypt_bytes(
+            b"secret",
+            password,
+            AeadAlgorithm::XChaCha20Poly1305,
+        ));
+        assert!(!encrypted.exists());
+
+        assert_weak(engine::encrypt_folder(
+            &folder,
+            &encrypted_folder,
+            password,
+            AeadAlgorithm::XChaCha20Poly1305,
+        ));
+        assert_weak(engine::encrypt_folder_parallel_with_options(
+            &folder,
+            &encrypted_folder,
+            password,
+            AeadAlgorithm::XChaCha20Poly1305,
+            &FolderOptions::default(),
+            2,
+        ));
+        assert!(!encrypted_folder.exists());
+    }
+
+    // rekey 不能把密码换成空密码
+    engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        "old-password",
+        &fast_options(AeadAlgorithm::XChaCha20Poly1305),
+    )
+    .expect("encrypt");
+    let before = fs::read(&encrypted).expect("read encrypted");
+    assert_weak(engine::rekey(&encrypted, &encrypted, "old-password", ""));
+    assert_eq!(fs::read(&encrypted).expect("read encrypted"), before);
+}
+
+/// 测试可配置的最小长度按字符计数
+#[test]
+fn minimum_length_is_counted_in_characters() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.txt");
+    let encrypted = temp.path().join("input.txt.svlt");
+    fs::write(&input, b"secret").expect("write input");
+
+    let options = EncryptOptions {
+        password_policy: PasswordPolicy { min_len: 8 },
+        ..fast_options(AeadAlgorithm::XChaCha20Poly1305)
+    };
+    assert_weak(engine::encrypt_with_options(
+        &input, &encrypted, "short", &options,
+    ));
+    // 7 个字符（21 字节）仍不够
+    assert_weak(engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        "密码太短了吧",
+        &options,
+    ));
+    engine::encrypt_with_options(&input, &encrypted, "密码足够长了八个字", &options)
+        .expect("long enough");
+    engine::decrypt_bytes(
+        &fs::read(&encrypted).expect("read encrypted"),
+        "密码足够长了八个字",
+    )
+    .expect("decrypt");
+}
+
+/// 测试 `PasswordPolicy::ALLOW_ANY` 允许空密码加密文件与目录，解密不受策略影响
+#[test]
+fn allow_any_policy_permits_empty_password() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.txt");
+    let encrypted = temp.path().join("input.txt.svlt");
+    fs::write(&input, b"deliberately unprotected").expect("write input");
+
+    engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        "",
+        &EncryptOptions {
+            password_policy: PasswordPolicy::ALLOW_ANY,
+            ..fast_options(AeadAlgorithm::XChaCha20Poly1305)
+        },
+    )
+    .expect("encrypt with empty password");
+    assert_eq!(
+        engine::decrypt_bytes(&fs::read(&encrypted).expect("read encrypted"), "")
+            .expect("decrypt with empty password"),
+        b"deliberately unprotected"
+    );
+
+    let folder = temp.path().join("folder");
+    let encrypted_folder = temp.path().join("folder.enc");
+    let decrypted_folder = temp.path().join("folder.dec");
+    fs::create_dir(&folder).expect("create folder");
+    fs::write(folder.join("a.txt"), b"a").expect("write a");
+    let options = FolderOptions {
+        password_policy: PasswordPolicy::ALLOW_ANY,
+        ..FolderOptions::default()
+    };
+    engine::encrypt_folder_with_options(
+        &folder,
+        &encrypted_folder,
+        "",
+        AeadAlgorithm::XChaCha20Poly1305,
+        &options,
+    )
+    .expect("encrypt folder with empty password");
+    engine::decrypt_folder(&encrypted_folder, &decrypted_folder, "").expect("decrypt folder");
+    assert_eq!(
+        fs::read(decrypted_folder.join("a.txt")).expect("read a"),
+        b"a"
+    );
+}
@@ -0,0 +1,167 @@
+//! 明文摘要测试：final chunk 中认证的 BLAKE3 摘要与原始输入的 `blake3::hash` 一致
+#![cfg(feature = "fs")]
+
+use std::fs;
+
+use tempfile::tempdir;
+
+fn sample_plaintext() -> Vec<u8> {
+    (0..300_000u32).map(|i| (i % 239) as u8).collect()
+}
+
+fn digest_options() -> engine::EncryptOptions {
+    engine::EncryptOptions {
+        chunk_size: 32 * 1024,
+        plaintext_digest: true,
+        ..engine::EncryptOptions::default()
+    }
+}
+
+#[test]
+fn plaintext_digest_matches_blake3_of_input() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("release.tar");
+    let encrypted = temp.path().join("release.tar.svlt");
+    let decrypted = temp.path().join("release.dec.tar");
+    let plaintext = sample_plaintext();
+    fs::write(&input, &plaintext).expect("write input");
+
+    engine::encrypt_with_options(&input, &encrypted, "pw", &digest_options()).expect("encrypt");
+
+    // 只读 Header 时只能知道摘要存在，摘要本身在密文中
+    let info = engine::inspect(&encrypted).expect("inspect");
+    assert!(info.has_plaintext_digest);
+    assert_eq!(info.plaintext_digest, None);
+
+    let info = engine::decrypt_with_info(&encrypted, &decrypted, "pw").expect("decrypt");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+    assert_eq!(
+        info.plaintext_digest,
+        Some(*blake3::hash(&plaintext).as_bytes())
+    );
+
+    // 普通解密与校验路径同样接受携带摘要的文件
+    engine::verify(&encrypted, "pw").expect("verify");
+}
+
+#[test]
+fn plaintext_digest_covers_uncompressed_input_for_every_encrypt_path() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("notes.txt");
+    let decrypted = temp.path().join("notes.dec.txt");
+    let plaintext = b"digest me before compression\n".repeat(8_000);
+    fs::write(&input, &plaintext).expect("write input");
+    let expected = Some(*blake3::hash(&plaintext).as_bytes());
+
+    let compressed = engine::EncryptOptions {
+        compression: Some(engine::Compression::Zstd(3)),
+        ..digest_options()
+    };
+    let parallel = engine::EncryptOptions {
+        threads: 3,
+        ..digest_options()
+    };
+
+    for (name, options) in [("compressed", compressed), ("parallel", parallel)] {
+        let encrypted = temp.path().join(format!("{name}.svlt"));
+        engine::encrypt_with_options(&input, &encrypted, "pw", &options).expect("encrypt");
+        let info = engine::decrypt_with_info(&encrypted, &decrypted, "pw").expect("decrypt");
+        assert_eq!(info.plaintext_digest, expected, "{name}");
+        assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+    }
+
+    let encrypted = temp.path().join("mmap.svlt");
+    engine::encrypt_mmap(&input, &encrypted, "pw", &digest_options()).expect("encrypt mmap");
+    let info = engine::decrypt_with_info(&encrypted, &decrypted, "pw").expect("decrypt");
+    assert_eq!(info.plaintext_digest, expected);
+
+    // rekey 原样保留摘要
+    let rekeyed = temp.path().join("rekeyed.svlt");
+    engine::rekey(&encrypted, &rekeyed, "pw", "new-pw").expect("rekey");
+    let info = engine::decrypt_with_info(&rekeyed, &decrypted, "new-pw").expect("decrypt");
+    assert_eq!(info.plaintext_digest, expected);
+}
+
+#[test]
+fn plaintext_digest_is_absent_by_default_and_for_empty_input() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("data.bin");
+    let encrypted = temp.path().join("data.bin.svlt");
+    let decrypted = temp.path().join("data.dec.bin");
+    fs::write(&input, sample_plaintext()).expect("write input");
+
+    engine::encrypt(&input, &encrypted, "pw").expect("encrypt");
+    let info = engine::decrypt_with_info(&encrypted, &decrypted, "pw").expect("decrypt");
+    assert!(!info.has_plaintext_digest);
+    assert_eq!(info.plaintext_digest, None);
+
+    fs::write(&input, b"").expect("write empty input");
+    engine::encrypt_with_options(&input, &encrypted, "pw", &digest_options()).expect("encrypt");
+    let info = engine::decrypt_with_info(&encrypted, &decrypted, "pw").expect("decrypt");
+    assert_eq!(info.plaintext_digest, Some(*blake3::hash(b"").as_bytes()));
+    assert!(fs::read(&decrypted).expect("read output").is_empty());
+}
+
+#[test]
+fn forged_plaintext_digest_is_rejected() {
+    use engine::crypto::kdf::{KdfAlgorithm, ScryptParams, derive_key_scrypt};
+    use engine::format::header::{FLAG_PLAINTEXT_DIGEST, HEADER_CHECK_SIZE, Header};
+    use engine::format::stream::{StreamDecryptor, StreamEncryptor};
+
+    let params = ScryptParams {
+        log_n: 4,
+        r: 8,
+        p: 1,
+    };
+    let mut header = Header::new(
+        engine::AeadAlgorithm::XChaCha20Poly1305,
+        [1u8; 16],
+        [2u8; 24],
+        1024,
+    );
+    header.kdf = KdfAlgorithm::Scrypt(params);
+    header.plaintext_digest = true;
+    let key = derive_key_scrypt(b"pw", &header.salt, &params).expect("derive key");
+
+    let mut encoded = Vec::new();
+    header.write(&mut encoded).expect("write header");
+    assert_eq!(
+        encoded[encoded.len() - HEADER_CHECK_SIZE - 1],
+        FLAG_PLAINTEXT_DIGEST
+    );
+
+    let mut encryptor = StreamEncryptor::new(
+        &key,
+        header.algorithm,
+        header.base_nonce,
+        1024,
+        header.digest(),
+    )
+    .expect("encryptor")
+    .with_plaintext_digest();
+    // 必须以摘要结束
+    assert!(encryptor.finish(Vec::new()).is_err());
+
+    // 持有密钥的一方写入了与明文不符的摘要：每个 chunk 都能通过认证
+    let claimed = *blake3::hash(b"claimed contents").as_bytes();
+    let mut stream = Vec::new();
+    encryptor
+        .write_chunk(b"actual contents", &mut stream)
+        .expect("write chunk");
+    encryptor
+        .finish_with_digest(&mut stream, &claimed)
+        .expect("finish");
+
+    let mut decryptor = StreamDecryptor::for_header(&key, &header);
+    let mut output = Vec::new();
+    decryptor
+        .decrypt(stream.as_slice(), &mut output)
+        .expect("decrypt stream");
+    assert_eq!(output, b"actual contents");
+    assert_eq!(decryptor.plaintext_digest(), Some(claimed));
+
+    // 但完整解密会比对明文与摘要，不一致即视为损坏
+    encoded.extend_from_slice(&stream);
+    let err = engine::decrypt_bytes(&encoded, "pw").expect_err("digest mismatch");
+    assert!(matches!(err, engine::SealVaultError::CorruptedData));
+}
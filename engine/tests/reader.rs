@@ -0,0 +1,58 @@
+//! SealVault 流式解密 reader 测试
+
+use std::io::{Cursor, Read};
+
+use engine::{AeadAlgorithm, EncryptOptions, SealVaultReader, SealVaultWriter};
+
+/// 测试通过 `std::io::read_to_string` 读取 `Cursor` 中的 .svlt 数据
+#[test]
+fn reader_reads_plaintext_from_cursor() {
+    let plaintext = "name,score\n".to_owned() + &"alice,42\nbob,7\n".repeat(10_000);
+    let ciphertext = engine::encrypt_bytes(
+        plaintext.as_bytes(),
+        "reader-password",
+        AeadAlgorithm::XChaCha20Poly1305,
+    )
+    .expect("encrypt bytes");
+
+    let reader =
+        SealVaultReader::new(Cursor::new(&ciphertext), "reader-password").expect("open reader");
+    assert_eq!(reader.header().algorithm, AeadAlgorithm::XChaCha20Poly1305);
+    assert_eq!(std::io::read_to_string(reader).expect("read"), plaintext);
+
+    let err = SealVaultReader::new(Cursor::new(&ciphertext), "wrong-password")
+        .and_then(|mut reader| Ok(reader.read(&mut [0u8; 16])?))
+        .expect_err("wrong password");
+    assert!(matches!(err, engine::SealVaultError::InvalidPasswordOrFile));
+}
+
+/// 测试被篡改的 chunk：之前的 chunk 正常读出，篡改的 chunk 不交出任何明文并报 `InvalidData`
+#[test]
+fn reader_stops_at_tampered_chunk() {
+    let chunk_size = 1000;
+    let plaintext: Vec<u8> = (0..10_000u32).map(|i| (i % 251) as u8).collect();
+
+    let options = EncryptOptions {
+        chunk_size,
+        ..EncryptOptions::with_algorithm(AeadAlgorithm::Aes256Gcm)
+    };
+    let mut writer =
+        SealVaultWriter::with_options(Vec::new(), "tamper", &options).expect("new writer");
+    std::io::Write::write_all(&mut writer, &plaintext).expect("write");
+    let mut ciphertext = writer.finish().expect("finish");
+
+    // 篡改第 4 个 chunk 的密文：帧为 4 字节长度 + 密文 + 16 字节 tag
+    let header_len = ciphertext.len() - (10 * (4 + chunk_size + 16) + 4 + 16);
+    let tampered = header_len + 3 * (4 + chunk_size + 16) + 4 + 10;
+    ciphertext[tampered] ^= 0x01;
+
+    let mut reader = SealVaultReader::new(Cursor::new(&ciphertext), "tamper").expect("open");
+    let mut read = Vec::new();
+    let err = reader.read_to_end(&mut read).expect_err("tampered chunk");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(read, &plaintext[..3 * chunk_size]);
+
+    // 出错后继续读取仍返回错误
+    let err = reader.read(&mut [0u8; 16]).expect_err("still failed");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
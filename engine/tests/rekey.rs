@@ -0,0 +1,174 @@
+//! 更换密码测试：rekey 后只有新密码可解密，明文与算法参数保持不变；
+//! reencrypt 以同一密码按新选项重新加密
+#![cfg(feature = "fs")]
+
+use std::fs;
+
+use tempfile::tempdir;
+
+fn sample_plaintext() -> Vec<u8> {
+    (0..200_000u32).map(|i| (i % 251) as u8).collect()
+}
+
+#[test]
+fn rekey_to_new_output_only_new_password_decrypts() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("data.bin");
+    let encrypted = temp.path().join("data.bin.svlt");
+    let rekeyed = temp.path().join("data.rekeyed.svlt");
+    let decrypted = temp.path().join("data.dec.bin");
+    let plaintext = sample_plaintext();
+    fs::write(&input, &plaintext).expect("write input");
+
+    let options = engine::EncryptOptions {
+        chunk_size: 16 * 1024,
+        ..engine::EncryptOptions::with_algorithm(engine::AeadAlgorithm::Aes256Gcm)
+    };
+    engine::encrypt_with_options(&input, &encrypted, "old-pw", &options).expect("encrypt");
+
+    engine::rekey(&encrypted, &rekeyed, "old-pw", "new-pw").expect("rekey");
+
+    // 新文件沿用算法与 chunk 大小，但 salt 已更换
+    let before = engine::inspect(&encrypted).expect("inspect original");
+    let after = engine::inspect(&rekeyed).expect("inspect rekeyed");
+    assert_eq!(after.algorithm, engine::AeadAlgorithm::Aes256Gcm);
+    assert_eq!(after.chunk_size, 16 * 1024);
+    assert_ne!(after.salt, before.salt);
+
+    engine::decrypt(&rekeyed, &decrypted, "new-pw").expect("decrypt with new password");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+
+    let err = engine::verify(&rekeyed, "old-pw").expect_err("old password must fail");
+    assert!(matches!(err, engine::SealVaultError::InvalidPasswordOrFile));
+
+    // 原文件不受影响
+    engine::verify(&encrypted, "old-pw").expect("original still valid");
+}
+
+#[test]
+fn rekey_in_place_and_compressed_file() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("notes.txt");
+    let encrypted = temp.path().join("notes.txt.svlt");
+    let decrypted = temp.path().join("notes.dec.txt");
+    let plaintext = b"compressible line\n".repeat(10_000);
+    fs::write(&input, &plaintext).expect("write input");
+
+    let options = engine::EncryptOptions {
+        compression: Some(engine::Compression::Zstd(3)),
+        ..engine::EncryptOptions::default()
+    };
+    engine::encrypt_with_options(&input, &encrypted, "old-pw", &options).expect("encrypt");
+
+    // 输出与输入相同：原子替换原文件
+    engine::rekey(&encrypted, &encrypted, "old-pw", "new-pw").expect("rekey in place");
+
+    engine::decrypt(&encrypted, &decrypted, "new-pw").expect("decrypt with new password");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+    assert!(engine::verify(&encrypted, "old-pw").is_err());
+}
+
+#[test]
+fn rekey_with_wrong_old_password_writes_nothing() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("data.bin");
+    let encrypted = temp.path().join("data.bin.svlt");
+    let rekeyed = temp.path().join("data.rekeyed.svlt");
+    fs::write(&input, sample_plaintext()).expect("write input");
+    engine::encrypt(&input, &encrypted, "old-pw").expect("encrypt");
+    let original = fs::read(&encrypted).expect("read encrypted");
+
+    let err = engine::rekey(&encrypted, &rekeyed, "wrong-pw", "new-pw").expect_err("must fail");
+    assert!(matches!(err, engine::SealVaultError::InvalidPasswordOrFile));
+    assert!(!rekeyed.exists());
+
+    // 原地 rekey 失败时原文件保持不变
+    engine::rekey(&encrypted, &encrypted, "wrong-pw", "new-pw").expect_err("must fail");
+    assert_eq!(fs::read(&encrypted).expect("read encrypted"), original);
+}
+
+#[test]
+fn reencrypt_shrinks_chunk_size_and_still_decrypts() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("data.bin");
+    let encrypted = temp.path().join("data.bin.svlt");
+    let reencrypted = temp.path().join("data.small.svlt");
+    let decrypted = temp.path().join("data.dec.bin");
+    let plaintext = sample_plaintext();
+    fs::write(&input, &plaintext).expect("write input");
+
+    let options = engine::EncryptOptions {
+        chunk_size: 256 * 1024,
+        ..engine::EncryptOptions::default()
+    };
+    engine::encrypt_with_options(&input, &encrypted, "pw", &options).expect("encrypt");
+
+    let new_options = engine::EncryptOptions {
+        chunk_size: 4 * 1024,
+        ..engine::EncryptOptions::with_algorithm(engine::AeadAlgorithm::Aes256Gcm)
+    };
+    engine::reencrypt(&encrypted, &reencrypted, "pw", &new_options).expect("reencrypt");
+
+    let before = engine::inspect(&encrypted).expect("inspect original");
+    let after = engine::inspect(&reencrypted).expect("inspect reencrypted");
+    assert_eq!(after.chunk_size, 4 * 1024);
+    assert_eq!(after.algorithm, engine::AeadAlgorithm::Aes256Gcm);
+    assert_ne!(after.salt, before.salt);
+
+    engine::decrypt(&reencrypted, &decrypted, "pw").expect("decrypt");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+}
+
+#[test]
+fn reencrypt_in_place_changes_compression_and_keeps_digest() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("notes.txt");
+    let encrypted = temp.path().join("notes.txt.svlt");
+    let decrypted = temp.path().join("notes.dec.txt");
+    let plaintext = b"compressible line\n".repeat(10_000);
+    fs::write(&input, &plaintext).expect("write input");
+
+    let compressed = engine::EncryptOptions {
+        compression: Some(engine::Compression::Zstd(3)),
+        plaintext_digest: true,
+        ..engine::EncryptOptions::default()
+    };
+    engine::encrypt_with_options(&input, &encrypted, "pw", &compressed).expect("encrypt");
+
+    // 压缩文件原地改为不压缩、多线程加密，摘要覆盖的仍是原始明文
+    let new_options = engine::EncryptOptions {
+        chunk_size: 16 * 1024,
+        threads: 3,
+        plaintext_digest: true,
+        ..engine::EncryptOptions::default()
+    };
+    engine::reencrypt(&encrypted, &encrypted, "pw", &new_options).expect("reencrypt in place");
+    assert!(fs::metadata(&encrypted).expect("stat").len() > plaintext.len() as u64);
+
+    let info = engine::decrypt_with_info(&encrypted, &decrypted, "pw").expect("decrypt");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+    assert_eq!(
+        info.plaintext_digest,
+        Some(*blake3::hash(&plaintext).as_bytes())
+    );
+}
+
+#[test]
+fn reencrypt_with_wrong_password_writes_nothing() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("data.bin");
+    let encrypted = temp.path().join("data.bin.svlt");
+    fs::write(&input, sample_plaintext()).expect("write input");
+    engine::encrypt(&input, &encrypted, "pw").expect("encrypt");
+    let original = fs::read(&encrypted).expect("read encrypted");
+
+    let err = engine::reencrypt(
+        &encrypted,
+        &encrypted,
+        "wrong-pw",
+        &engine::EncryptOptions::default(),
+    )
+    .expect_err("must fail");
+    assert!(matches!(err, engine::SealVaultError::InvalidPasswordOrFile));
+    assert_eq!(fs::read(&encrypted).expect("read encrypted"), original);
+}
@@ -0,0 +1,118 @@
+//! 安全删除（`fs::secure_delete`）与 `encrypt_and_wipe` 测试
+#![cfg(feature = "fs")]
+
+use std::fs;
+use std::io;
+
+use engine::fs::secure_delete;
+use engine::{AeadAlgorithm, EncryptOptions, SealVaultError};
+use tempfile::tempdir;
+
+const PASSWORD: &str = "wipe-password-2026";
+
+/// 测试 `secure_delete` 删除文件前覆写其内容：预先建立的硬链接与原文件共享数据，
+/// 删除后仍可读取，其内容长度不变但已不是原始字节
+#[test]
+fn secure_delete_overwrites_before_removing() {
+    let temp = tempdir().expect("create temp dir");
+    let path = temp.path().join("secret.txt");
+    let witness = temp.path().join("witness.txt");
+    let original = b"top secret plaintext ".repeat(10_000);
+    fs::write(&path, &original).expect("write secret");
+    fs::hard_link(&path, &witness).expect("create hard link");
+
+    secure_delete(&path).expect("secure delete");
+    assert!(!path.exists());
+
+    let remaining = fs::read(&witness).expect("read witness");
+    assert_eq!(remaining.len(), original.len());
+    assert_ne!(remaining, original);
+    assert!(
+        !remaining
+            .windows(b"top secret".len())
+            .any(|w| w == b"top secret")
+    );
+
+    // 空文件同样可以删除
+    let empty = temp.path().join("empty.txt");
+    fs::write(&empty, b"").expect("write empty");
+    secure_delete(&empty).expect("secure delete empty");
+    assert!(!empty.exists());
+}
+
+/// 测试 `secure_delete` 只处理普通文件：目录与不存在的路径报错且不改动任何内容
+#[test]
+fn secure_delete_rejects_non_regular_files() {
+    let temp = tempdir().expect("create temp dir");
+    let dir = temp.path().join("dir");
+    fs::create_dir(&dir).expect("create dir");
+
+    let err = secure_delete(&dir).expect_err("directory");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert!(dir.is_dir());
+
+    let err = secure_delete(&temp.path().join("missing")).expect_err("missing file");
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+/// 测试 `secure_delete` 不跟随符号链接：指向哨兵文件的链接报告 `InvalidInput`，
+/// 哨兵文件的内容逐字节不变，链接本身也保留
+#[cfg(unix)]
+#[test]
+fn secure_delete_does_not_overwrite_symlink_target() {
+    let temp = tempdir().expect("create temp dir");
+    let sentinel = temp.path().join("sentinel.bin");
+    let link = temp.path().join("link.bin");
+    let contents: Vec<u8> = (0..=255u8).cycle().take(100_000).collect();
+    fs::write(&sentinel, &contents).expect("write sentinel");
+    std::os::unix::fs::symlink(&sentinel, &link).expect("create symlink");
+
+    let err = secure_delete(&link).expect_err("symlink");
+    assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    assert_eq!(fs::read(&sentinel).expect("read sentinel"), contents);
+    assert!(link.symlink_metadata().expect("link kept").is_symlink());
+}
+
+/// 测试 `encrypt_and_wipe` 按选项加密、在密文可解密后删除明文输入；
+/// 加密失败（路径相同、`overwrite: false` 时目标已存在）时输入保持不变
+#[test]
+fn encrypt_and_wipe_removes_input_only_after_success() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.txt");
+    let encrypted = temp.path().join("out").join("input.txt.svlt");
+    let decrypted = temp.path().join("decrypted.txt");
+    let plaintext = b"encrypt then wipe ".repeat(500);
+    fs::write(&input, &plaintext).expect("write input");
+
+    let options = EncryptOptions {
+        chunk_size: 1024,
+        threads: 2,
+        overwrite: false,
+        ..EncryptOptions::with_algorithm(AeadAlgorithm::Aes256Gcm)
+    };
+
+    // 输出与输入相同时拒绝，不覆写输入
+    let err = engine::encrypt_and_wipe(&input, &input, PASSWORD, &options).expect_err("same path");
+    assert!(
+        matches!(&err, SealVaultError::Io(e) if e.kind() == io::ErrorKind::InvalidInput),
+        "{err:?}"
+    );
+    assert_eq!(fs::read(&input).expect("read input"), plaintext);
+
+    // 选项同样生效：不允许覆盖已有输出时失败，输入不变
+    let existing = temp.path().join("existing.svlt");
+    fs::write(&existing, b"keep").expect("write existing");
+    let err = engine::encrypt_and_wipe(&input, &existing, PASSWORD, &options)
+        .expect_err("existing output");
+    assert!(matches!(err, SealVaultError::AlreadyExists), "{err:?}");
+    assert_eq!(fs::read(&input).expect("read input"), plaintext);
+
+    engine::encrypt_and_wipe(&input, &encrypted, PASSWORD, &options).expect("encrypt and wipe");
+    assert!(!input.exists());
+
+    let info = engine::inspect(&encrypted).expect("inspect");
+    assert_eq!(info.algorithm, AeadAlgorithm::Aes256Gcm);
+    assert_eq!(info.chunk_size, 1024);
+    engine::decrypt(&encrypted, &decrypted, PASSWORD).expect("decrypt");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+}
@@ -0,0 +1,284 @@
+//! Header 中的数据 chunk 总数（标志位 `FLAG_TOTAL_CHUNKS`）测试
+#![cfg(feature = "fs")]
+
+mod common;
+
+use std::fs;
+
+use common::{FAST_SCRYPT, fast_options};
+use engine::crypto::kdf::{KdfAlgorithm, derive_key_scrypt};
+use engine::format::header::{Header, VERSION_V1};
+use engine::format::stream::{StreamEncryptor, TAG_SIZE};
+use engine::{AeadAlgorithm, Compression, EncryptOptions, Padding, SealVaultError};
+use tempfile::tempdir;
+
+const PASSWORD: &str = "total-chunks-password";
+const CHUNK: usize = 1024;
+const FRAME_LEN: usize = 4 + CHUNK + TAG_SIZE;
+const FINAL_FRAME_LEN: usize = 4 + TAG_SIZE;
+
+fn assert_corrupted<T: std::fmt::Debug>(result: Result<T, SealVaultError>, what: &str) {
+    let err = result.expect_err(what);
+    assert!(
+        matches!(err, SealVaultError::CorruptedData),
+        "{what}: {err:?}"
+    );
+}
+
+/// 测试文件输入按长度记录 chunk 总数并正常往返；压缩、填充、空文件与内存、流式接口
+/// 不记录，仍由 final chunk 发现截断
+#[test]
+fn file_inputs_record_total_chunks() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    let decrypted = temp.path().join("output.bin");
+
+    let cases = [
+        // 长度为 0 时与 `/proc` 伪文件无法区分，不记录
+        (0, fast_options(AeadAlgorithm::XChaCha20Poly1305), None),
+        (
+            5 * CHUNK,
+            fast_options(AeadAlgorithm::XChaCha20Poly1305),
+            Some(5),
+        ),
+        (
+            5 * CHUNK + 1,
+            fast_options(AeadAlgorithm::XChaCha20Poly1305),
+            Some(6),
+        ),
+        (
+            100,
+            EncryptOptions {
+                padding: Some(Padding::ToBlock(4 * CHUNK as u32)),
+                ..fast_options(AeadAlgorithm::XChaCha20Poly1305)
+            },
+            // 空输入不记录，填充时一律不记录，Header 长度才不暴露输入是否为空
+            None,
+        ),
+        (
+            5 * CHUNK,
+            EncryptOptions {
+                compression: Some(Compression::Zstd(3)),
+                ..fast_options(AeadAlgorithm::XChaCha20Poly1305)
+            },
+            None,
+        ),
+    ];
+    for (len, options, expected) in cases {
+        let plaintext: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+        fs::write(&input, &plaintext).expect("write input");
+
+        engine::encrypt_with_options(&input, &encrypted, PASSWORD, &options).expect("encrypt");
+        let info = engine::inspect(&encrypted).expect("inspect");
+        assert_eq!(info.total_chunks, expected, "len {len}");
+        assert_eq!(info.version, engine::VERSION);
+
+        engine::decrypt(&encrypted, &decrypted, PASSWORD).expect("decrypt");
+        assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+        engine::verify(&encrypted, PASSWORD).expect("verify");
+    }
+
+    let encrypted = engine::encrypt_bytes(&[7u8; 3 * CHUNK], PASSWORD, AeadAlgorithm::Aes256Gcm)
+        .expect("encrypt bytes");
+    let header = Header::read(&encrypted[..]).expect("read header");
+    assert_eq!(header.total_chunks, None);
+    assert_corrupted(
+        engine::decrypt_bytes(&encrypted[..encrypted.len() - FINAL_FRAME_LEN], PASSWORD),
+        "stream without final chunk",
+    );
+}
+
+/// 测试在 chunk 边界或 chunk 中间截断的文件在派生密钥之前即被拒绝：
+/// 即使密码错误也报告 `CorruptedData`，且不留下任何输出
+#[test]
+fn truncated_file_is_rejected_before_key_derivation() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    let truncated = temp.path().join("truncated.svlt");
+    let output = temp.path().join("output.bin");
+    fs::write(&input, vec![0x5a; 4 * CHUNK + 10]).expect("write input");
+    engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        PASSWORD,
+        &fast_options(AeadAlgorithm::XChaCha20Poly1305),
+    )
+    .expect("encrypt");
+    let complete = fs::read(&encrypted).expect("read encrypted");
+    let last_frame = 4 + 10 + TAG_SIZE;
+
+    for cut in [
+        // 整个最后一个数据 chunk 与 final chunk，恰在 chunk 边界
+        last_frame + FINAL_FRAME_LEN,
+        // 最后两个数据 chunk 与 final chunk
+        FRAME_LEN + last_frame + FINAL_FRAME_LEN,
+        // 只剩 Header
+        4 * FRAME_LEN + last_frame + FINAL_FRAME_LEN,
+        // 最后一个数据 chunk 中间
+        last_frame / 2 + FINAL_FRAME_LEN,
+    ] {
+        fs::write(&truncated, &complete[..complete.len() - cut]).expect("write truncated");
+        for password in [PASSWORD, "wrong-password"] {
+            assert_corrupted(
+                engine::decrypt(&truncated, &output, password),
+                &format!("decrypt cut {cut}"),
+            );
+            assert!(!output.exists());
+            assert_corrupted(
+                engine::verify(&truncated, password),
+                &format!("verify cut {cut}"),
+            );
+        }
+        // 不经过文件长度检查的内存接口仍在读到缺失的 chunk 时失败
+        assert_corrupted(
+            engine::decrypt_bytes(&complete[..complete.len() - cut], PASSWORD),
+            &format!("decrypt_bytes cut {cut}"),
+        );
+    }
+}
+
+/// 测试持有密钥的一方写出的 chunk 数与 Header 不符时解密失败，
+/// 以及加密器拒绝以不符的 chunk 数结束 stream
+#[test]
+fn chunk_count_must_match_header() {
+    let mut header = Header::new(AeadAlgorithm::XChaCha20Poly1305, [3u8; 16], [4u8; 24], 16);
+    header.kdf = KdfAlgorithm::Scrypt(FAST_SCRYPT);
+    let key =
+        derive_key_scrypt(PASSWORD.as_bytes(), &header.salt, &FAST_SCRYPT).expect("derive key");
+
+    let encrypt_claiming = |claimed: u64, chunks: usize| {
+        let header = Header {
+            total_chunks: Some(claimed),
+            ..header.clone()
+        };
+        let mut encrypted = Vec::new();
+        header.write(&mut encrypted).expect("write header");
+        // 不调用 `with_total_chunks`，模拟不检查数量的加密方
+        let mut encryptor = StreamEncryptor::new(
+            &key,
+            header.algorithm,
+            header.base_nonce,
+            16,
+            header.digest(),
+        )
+        .expect("encryptor");
+        for _ in 0..chunks {
+            encryptor
+                .write_chunk(&[0x11; 16], &mut encrypted)
+                .expect("write chunk");
+        }
+        encryptor.finish(&mut encrypted).expect("finish");
+        encrypted
+    };
+
+    assert_eq!(
+        engine::decrypt_bytes(&encrypt_claiming(3, 3), PASSWORD).expect("matching count"),
+        [0x11; 48]
+    );
+    assert_corrupted(
+        engine::decrypt_bytes(&encrypt_claiming(3, 2), PASSWORD),
+        "fewer chunks than the header",
+    );
+    assert_corrupted(
+        engine::decrypt_bytes(&encrypt_claiming(3, 4), PASSWORD),
+        "more chunks than the header",
+    );
+
+    let mut encryptor = StreamEncryptor::new(
+        &key,
+        header.algorithm,
+        header.base_nonce,
+        16,
+        header.digest(),
+    )
+    .expect("encryptor")
+    .with_total_chunks(2);
+    encryptor
+        .write_chunk(&[0x22; 16], Vec::new())
+        .expect("write chunk");
+    let err = encryptor.finish(Vec::new()).expect_err("count mismatch");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    // v1 Header 不能记录 chunk 总数
+    let v1 = Header {
+        version: VERSION_V1,
+        kdf: KdfAlgorithm::default(),
+        total_chunks: Some(1),
+        ..header
+    };
+    let err = v1
+        .write(Vec::new())
+        .expect_err("v1 header with total chunks");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+/// 测试 FIFO 等非普通文件的元数据长度不可信，不记录 chunk 总数，照常加密全部数据
+#[cfg(unix)]
+#[test]
+fn non_regular_inputs_fall_back_to_final_chunk() {
+    use std::io::Write;
+
+    let temp = tempdir().expect("create temp dir");
+    let fifo = temp.path().join("input.fifo");
+    let encrypted = temp.path().join("fifo.svlt");
+    let decrypted = temp.path().join("fifo.out");
+    let status = std::process::Command::new("mkfifo")
+        .arg(&fifo)
+        .status()
+        .expect("run mkfifo");
+    assert!(status.success());
+
+    let plaintext: Vec<u8> = (0..3 * CHUNK + 17).map(|i| (i % 241) as u8).collect();
+    let writer = {
+        let fifo = fifo.clone();
+        let plaintext = plaintext.clone();
+        std::thread::spawn(move || {
+            fs::OpenOptions::new()
+                .write(true)
+                .open(&fifo)
+                .expect("open fifo for writing")
+                .write_all(&plaintext)
+                .expect("write fifo");
+        })
+    };
+    engine::encrypt_with_options(
+        &fifo,
+        &encrypted,
+        PASSWORD,
+        &fast_options(AeadAlgorithm::XChaCha20Poly1305),
+    )
+    .expect("encrypt fifo");
+    writer.join().expect("writer thread");
+
+    assert_eq!(
+        engine::inspect(&encrypted).expect("inspect").total_chunks,
+        None
+    );
+    engine::decrypt(&encrypted, &decrypted, PASSWORD).expect("decrypt");
+    assert_eq!(fs::read(&decrypted).expect("read output"), plaintext);
+
+    // `/proc` 伪文件报告长度 0，但能读出内容
+    #[cfg(target_os = "linux")]
+    {
+        let status = std::path::Path::new("/proc/self/status");
+        engine::encrypt_with_options(
+            status,
+            &encrypted,
+            PASSWORD,
+            &fast_options(AeadAlgorithm::XChaCha20Poly1305),
+        )
+        .expect("encrypt proc file");
+        assert_eq!(
+            engine::inspect(&encrypted).expect("inspect").total_chunks,
+            None
+        );
+        engine::decrypt(&encrypted, &decrypted, PASSWORD).expect("decrypt");
+        assert!(
+            fs::read(&decrypted)
+                .expect("read output")
+                .starts_with(b"Name:")
+        );
+    }
+}
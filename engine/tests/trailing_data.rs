@@ -0,0 +1,142 @@
+//! final chunk 之后多余数据（`DecryptOptions::reject_trailing_data`）的检测测试
+#![cfg(feature = "fs")]
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::fast_options;
+use engine::{AeadAlgorithm, DecryptOptions, SealVaultError};
+use tempfile::tempdir;
+
+const PASSWORD: &str = "trailing-password";
+
+fn strict() -> DecryptOptions {
+    DecryptOptions {
+        reject_trailing_data: true,
+        ..DecryptOptions::default()
+    }
+}
+
+fn assert_corrupted<T: std::fmt::Debug>(result: Result<T, SealVaultError>) {
+    let err = result.expect_err("trailing data accepted");
+    assert!(matches!(err, SealVaultError::CorruptedData), "{err:?}");
+}
+
+/// 测试在合法 .svlt 之后追加任意长度的数据：严格模式下内存、流式与文件接口均报告
+/// `CorruptedData`，默认模式忽略这些数据
+#[test]
+fn appended_junk_is_rejected_in_strict_mode() {
+    let temp = tempdir().expect("create temp dir");
+    let input = temp.path().join("input.bin");
+    let encrypted = temp.path().join("input.svlt");
+    let output = temp.path().join("output.bin");
+    let plaintext = b"integrity sensitive ".repeat(150);
+    fs::write(&input, &plaintext).expect("write input");
+    engine::encrypt_with_options(
+        &input,
+        &encrypted,
+        PASSWORD,
+        &fast_options(AeadAlgorithm::XChaCha20Poly1305),
+    )
+    .expect("encrypt");
+    let valid = fs::read(&encrypted).expect("read encrypted");
+
+    assert_eq!(
+        engine::decrypt_bytes_with_options(&valid, PASSWORD, &strict()).expect("strict bytes"),
+        plaintext
+    );
+
+    // 不足一个长度前缀、恰好一个长度前缀、以及整段重复的密文
+    for junk in [
+        &b"\x00"[..],
+        b"\x00\x00\x00\x00",
+        b"garbage after the end",
+        &valid,
+    ] {
+        let mut data = valid.clone();
+        data.extend_from_slice(junk);
+
+        assert_eq!(
+            engine::decrypt_bytes(&data, PASSWORD).expect("lenient bytes"),
+            plaintext
+        );
+        assert_corrupted(engine::decrypt_bytes_with_options(
+            &data,
+            PASSWORD,
+            &strict(),
+        ));
+        assert_corrupted(engine::decrypt_stream_with_options(
+            &data[..],
+            std::io::sink(),
+            PASSWORD,
+            &strict(),
+        ));
+
+        fs::write(&encrypted, &data).expect("write appended");
+        assert_corrupted(engine::decrypt_with_options(
+            &encrypted,
+            &output,
+            PASSWORD,
+            &strict(),
+        ));
+        assert!(!output.exists());
+    }
+}
+
+/// 测试内存与流式接口同样支持 `max_output_bytes` 与 `expected_algorithm`
+#[test]
+fn stream_options_apply_output_limit_and_algorithm() {
+    let mut encrypted = Vec::new();
+    engine::encrypt_stream(
+        &[0x11u8; 4096][..],
+        &mut encrypted,
+        PASSWORD,
+        AeadAlgorithm::Aes256Gcm,
+    )
+    .expect("encrypt stream");
+
+    let err = engine::decrypt_bytes_with_options(
+        &encrypted,
+        PASSWORD,
+        &DecryptOptions {
+            max_output_bytes: Some(4095),
+            ..DecryptOptions::default()
+        },
+    )
+    .expect_err("limit exceeded");
+    assert!(
+        matches!(err, SealVaultError::OutputLimitExceeded),
+        "{err:?}"
+    );
+
+    let err = engine::decrypt_stream_with_options(
+        &encrypted[..],
+        std::io::sink(),
+        PASSWORD,
+        &DecryptOptions {
+            expected_algorithm: Some(AeadAlgorithm::XChaCha20Poly1305),
+            ..DecryptOptions::default()
+        },
+    )
+    .expect_err("unexpected algorithm");
+    assert!(
+        matches!(err, SealVaultError::UnexpectedAlgorithm { .. }),
+        "{err:?}"
+    );
+}
+
+/// 测试没有 final chunk 的 v1 旧文件：末尾不足一个长度前缀的多余字节不再被当作正常结束
+#[test]
+fn v1_file_with_partial_trailing_length_prefix_fails() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("v1_with_algorithm.svlt");
+    let mut data = fs::read(fixture).expect("read fixture");
+    engine::decrypt_bytes(&data, "legacy-v1-password").expect("decrypt v1");
+
+    data.extend_from_slice(b"\x00\x01");
+    assert_corrupted(engine::decrypt_bytes(&data, "legacy-v1-password"));
+}
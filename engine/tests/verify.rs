@@ -0,0 +1,116 @@
+//! 只校验、不写出明文的 `verify` 与 `check_password` 测试
+#![cfg(feature = "fs")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tempfile::tempdir;
+
+const HEADER_LEN: usize = 93;
+const FRAME_LEN: usize = 4 + 64 * 1024 + 16;
+const FINAL_FRAME_LEN: usize = 4 + 16;
+
+/// 加密一个跨越 3 个 chunk 的文件，返回密文路径
+fn encrypt_sample(dir: &Path) -> PathBuf {
+    let input = dir.join("backup.bin");
+    let encrypted = dir.join("backup.bin.svlt");
+    let plaintext: Vec<u8> = (0..(2 * 64 * 1024 + 777) as u32)
+        .map(|i| (i % 239) as u8)
+        .collect();
+    fs::write(&input, plaintext).expect("write plaintext");
+    engine::encrypt(&input, &encrypted, "verify-password").expect("encrypt");
+    encrypted
+}
+
+#[test]
+fn verify_accepts_intact_file() {
+    let temp = tempdir().expect("create temp dir");
+    let encrypted = encrypt_sample(temp.path());
+
+    engine::verify(&encrypted, "verify-password").expect("verify intact file");
+
+    // 不写出任何文件
+    assert_eq!(fs::read_dir(temp.path()).expect("list dir").count(), 2);
+}
+
+#[test]
+fn verify_rejects_wrong_password() {
+    let temp = tempdir().expect("create temp dir");
+    let encrypted = encrypt_sample(temp.path());
+
+    let err = engine::verify(&encrypted, "wrong-password").expect_err("wrong password");
+    assert!(
+        matches!(err, engine::SealVaultError::InvalidPasswordOrFile),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn verify_rejects_corrupted_chunk() {
+    let temp = tempdir().expect("create temp dir");
+    let encrypted = encrypt_sample(temp.path());
+
+    let mut bytes = fs::read(&encrypted).expect("read encrypted");
+    bytes[HEADER_LEN + FRAME_LEN + 10] ^= 0x80;
+    fs::write(&encrypted, bytes).expect("write corrupted");
+
+    let err = engine::verify(&encrypted, "verify-password").expect_err("corrupted chunk");
+    assert!(
+        matches!(err, engine::SealVaultError::CorruptedData),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn verify_rejects_missing_final_chunk() {
+    let temp = tempdir().expect("create temp dir");
+    let encrypted = encrypt_sample(temp.path());
+
+    let bytes = fs::read(&encrypted).expect("read encrypted");
+    fs::write(&encrypted, &bytes[..bytes.len() - FINAL_FRAME_LEN]).expect("write truncated");
+
+    let err = engine::verify(&encrypted, "verify-password").expect_err("missing final chunk");
+    assert!(
+        matches!(err, engine::SealVaultError::CorruptedData),
+        "unexpected error: {err:?}"
+    );
+}
+
+#[test]
+fn check_password_distinguishes_right_and_wrong_passwords() {
+    let temp = tempdir().expect("create temp dir");
+    let encrypted = encrypt_sample(temp.path());
+
+    assert!(engine::check_password(&encrypted, "verify-password").expect("right password"));
+    assert!(!engine::check_password(&encrypted, "wrong-password").expect("wrong password"));
+
+    // 只读取第一个 chunk：后续 chunk 损坏不影响结果
+    let mut bytes = fs::read(&encrypted).expect("read encrypted");
+    bytes[HEADER_LEN + FRAME_LEN + 10] ^= 0x80;
+    fs::write(&encrypted, &bytes).expect("write corrupted");
+    assert!(engine::check_password(&encrypted, "verify-password").expect("right password"));
+
+    // 空文件的第一个 chunk 即 final chunk
+    let empty = temp.path().join("empty.bin");
+    let empty_encrypted = temp.path().join("empty.bin.svlt");
+    fs::write(&empty, b"").expect("write empty");
+    engine::encrypt(&empty, &empty_encrypted, "verify-password").expect("encrypt empty");
+    assert!(engine::check_password(&empty_encrypted, "verify-password").expect("right password"));
+    assert!(!engine::check_password(&empty_encrypted, "wrong-password").expect("wrong password"));
+}
+
+#[test]
+fn check_password_reports_io_and_format_errors() {
+    let temp = tempdir().expect("create temp dir");
+
+    let err =
+        engine::check_password(&temp.path().join("missing.svlt"), "pw").expect_err("missing file");
+    assert!(
+        matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::NotFound),
+        "unexpected error: {err:?}"
+    );
+
+    let not_svlt = temp.path().join("plain.txt");
+    fs::write(&not_svlt, b"just some text, not a vault file").expect("write plain");
+    assert!(engine::check_password(&not_svlt, "pw").is_err());
+}
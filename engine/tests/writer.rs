@@ -0,0 +1,99 @@
+//! SealVault 增量加密 writer 测试
+
+use std::io::Write;
+
+use engine::{AeadAlgorithm, EncryptOptions, SealVaultWriter};
+
+/// 测试逐行写入大量小块明文（跨越多个 chunk），finish 后可用同步接口解密
+#[test]
+fn writer_encrypts_many_small_writes() {
+    let options = EncryptOptions {
+        chunk_size: 1000,
+        ..EncryptOptions::with_algorithm(AeadAlgorithm::Aes256Gcm)
+    };
+    let mut writer =
+        SealVaultWriter::with_options(Vec::new(), "writer-password", &options).expect("new");
+
+    let mut expected = Vec::new();
+    for i in 0..2000 {
+        let line = format!("log line {i}: something happened\n");
+        writer.write_all(line.as_bytes()).expect("write line");
+        expected.extend_from_slice(line.as_bytes());
+        if i % 100 == 0 {
+            writer.flush().expect("flush");
+        }
+    }
+    let ciphertext = writer.finish().expect("finish");
+
+    let header = engine::format::header::Header::read(ciphertext.as_slice()).expect("header");
+    assert_eq!(header.chunk_size, 1000);
+    assert_eq!(header.algorithm, AeadAlgorithm::Aes256Gcm);
+    assert_eq!(
+        engine::decrypt_bytes(&ciphertext, "writer-password").expect("decrypt"),
+        expected
+    );
+}
+
+/// 测试 Header 延迟到第一次写入时写出，未写入任何数据时 finish 得到空明文
+#[test]
+fn writer_defers_header_and_supports_empty_input() {
+    let writer =
+        SealVaultWriter::new(Vec::new(), "empty", AeadAlgorithm::XChaCha20Poly1305).expect("new");
+    let ciphertext = writer.finish().expect("finish");
+    assert!(
+        engine::decrypt_bytes(&ciphertext, "empty")
+            .expect("decrypt")
+            .is_empty()
+    );
+
+    let mut output = Vec::new();
+    {
+        let mut writer =
+            SealVaultWriter::new(&mut output, "lazy", AeadAlgorithm::XChaCha20Poly1305)
+                .expect("new");
+        writer.write_all(b"first").expect("write");
+        writer.finish().expect("finish");
+    }
+    assert_eq!(
+        engine::decrypt_bytes(&output, "lazy").expect("decrypt"),
+        b"first"
+    );
+}
+
+/// 测试未 finish 即 drop 的输出缺少结束标记，解密失败；不支持的选项被拒绝
+#[test]
+fn writer_dropped_without_finish_is_rejected() {
+    let mut output = Vec::new();
+    {
+        let mut writer =
+            SealVaultWriter::new(&mut output, "pw", AeadAlgorithm::XChaCha20Poly1305).expect("new");
+        writer.write_all(&[7u8; 200_000]).expect("write");
+        writer.flush().expect("flush");
+    }
+    assert!(!output.is_empty());
+    let err = engine::decrypt_bytes(&output, "pw").expect_err("truncated stream");
+    assert!(matches!(err, engine::SealVaultError::CorruptedData));
+
+    for options in [
+        EncryptOptions {
+            plaintext_digest: true,
+            ..Default::default()
+        },
+        EncryptOptions {
+            verify_after_write: true,
+            ..Default::default()
+        },
+        EncryptOptions {
+            checksum_sidecar: true,
+            ..Default::default()
+        },
+    ] {
+        let err = SealVaultWriter::with_options(Vec::new(), "pw", &options)
+            .err()
+            .expect("unsupported option");
+        assert!(
+            matches!(&err, engine::SealVaultError::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput),
+            "{options:?}: {err:?}"
+        );
+    }
+}